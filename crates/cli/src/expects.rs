@@ -0,0 +1,360 @@
+//! Choosing which top-level `expect`s `roc test` runs, and reporting on how they went.
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use regex::Regex;
+use roc_region::all::Region;
+
+/// What `roc test` knows about a top-level `expect` before running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectInfo<'a> {
+    pub module_name: &'a str,
+    pub path: &'a Path,
+    /// The line of the `expect` keyword, starting at 1.
+    pub line: usize,
+    /// The comment right above the `expect`, without the leading `#`s.
+    pub comment: String,
+    /// The source of the `expect` itself, from the `expect` keyword on.
+    pub source: &'a str,
+}
+
+impl<'a> ExpectInfo<'a> {
+    pub fn new(
+        module_name: &'a str,
+        path: &'a Path,
+        module_source: &'a str,
+        region: Region,
+    ) -> Self {
+        let start = region.start().byte_offset().min(module_source.len());
+        let end = region.end().byte_offset().clamp(start, module_source.len());
+
+        // The region usually starts at the comment above the `expect` already, but include any
+        // comment lines directly above it as well.
+        let mut comment_start = module_source[..start].rfind('\n').map_or(0, |i| i + 1);
+
+        while comment_start > 0 {
+            let previous_line_start = module_source[..comment_start - 1]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+
+            if is_comment(&module_source[previous_line_start..comment_start]) {
+                comment_start = previous_line_start;
+            } else {
+                break;
+            }
+        }
+
+        let mut comment_lines = Vec::new();
+        let mut source_start = comment_start;
+
+        for line in module_source[comment_start..end].split_inclusive('\n') {
+            if is_comment(line) {
+                comment_lines.push(line.trim().trim_start_matches('#').trim());
+                source_start += line.len();
+            } else if line.trim().is_empty() {
+                source_start += line.len();
+            } else {
+                break;
+            }
+        }
+
+        let source_start = source_start.min(end);
+        let line = module_source[..source_start].matches('\n').count() + 1;
+
+        Self {
+            module_name,
+            path,
+            line,
+            comment: comment_lines.join("\n"),
+            source: module_source[source_start..end].trim(),
+        }
+    }
+
+    /// A one-line description: the first line of the comment if there is one, and otherwise
+    /// the first line of the `expect`.
+    pub fn summary(&self) -> &str {
+        self.comment
+            .lines()
+            .find(|line| !line.is_empty())
+            .or_else(|| self.source.lines().next())
+            .unwrap_or_default()
+    }
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+/// The `--filter` of `roc test`. It is used as a regex when it is a valid one, and as a plain
+/// substring otherwise, and is matched against the module name, the comment above the `expect`,
+/// and the `expect` itself.
+#[derive(Debug)]
+pub enum ExpectFilter {
+    Regex(Regex),
+    Substring(String),
+}
+
+impl ExpectFilter {
+    pub fn new(pattern: &str) -> Self {
+        match Regex::new(pattern) {
+            Ok(regex) => ExpectFilter::Regex(regex),
+            Err(_) => ExpectFilter::Substring(pattern.to_string()),
+        }
+    }
+
+    pub fn matches(&self, info: &ExpectInfo) -> bool {
+        [info.module_name, info.comment.as_str(), info.source]
+            .into_iter()
+            .any(|text| match self {
+                ExpectFilter::Regex(regex) => regex.is_match(text),
+                ExpectFilter::Substring(substring) => text.contains(substring.as_str()),
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    pub name: String,
+    pub path: PathBuf,
+    pub line: usize,
+    pub duration: Duration,
+    /// What was printed when the `expect` failed, without colors.
+    pub failure: Option<String>,
+}
+
+/// The results of every `expect` that `roc test` ran, grouped by module, in the JUnit XML
+/// format that most CI systems can display.
+#[derive(Debug, Default)]
+pub struct JunitReport {
+    suites: Vec<(String, Vec<TestCase>)>,
+}
+
+impl JunitReport {
+    pub fn push(&mut self, module_name: &str, test_case: TestCase) {
+        match self.suites.iter_mut().find(|(name, _)| name == module_name) {
+            Some((_, test_cases)) => test_cases.push(test_case),
+            None => self.suites.push((module_name.to_string(), vec![test_case])),
+        }
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        let (tests, failures, duration) = totals(self.suites.iter().flat_map(|(_, cases)| cases));
+
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="roc test" tests="{tests}" failures="{failures}" time="{}">"#,
+            seconds(duration)
+        );
+
+        for (module_name, test_cases) in self.suites.iter() {
+            let (tests, failures, duration) = totals(test_cases.iter());
+
+            let _ = writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{tests}" failures="{failures}" time="{}">"#,
+                escape_xml(module_name),
+                seconds(duration)
+            );
+
+            for test_case in test_cases {
+                let _ = write!(
+                    xml,
+                    r#"    <testcase name="{}" classname="{}" file="{}" line="{}" time="{}""#,
+                    escape_xml(&test_case.name),
+                    escape_xml(module_name),
+                    escape_xml(&test_case.path.to_string_lossy()),
+                    test_case.line,
+                    seconds(test_case.duration)
+                );
+
+                match &test_case.failure {
+                    None => xml.push_str("/>\n"),
+                    Some(failure) => {
+                        let _ = writeln!(
+                            xml,
+                            ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                            escape_xml(failure.lines().next().unwrap_or_default().trim()),
+                            escape_xml(failure.trim_end())
+                        );
+                    }
+                }
+            }
+
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+
+        xml
+    }
+}
+
+fn totals<'a>(test_cases: impl Iterator<Item = &'a TestCase>) -> (usize, usize, Duration) {
+    test_cases.fold(
+        (0, 0, Duration::ZERO),
+        |(tests, failures, duration), test_case| {
+            (
+                tests + 1,
+                failures + test_case.failure.is_some() as usize,
+                duration + test_case.duration,
+            )
+        },
+    )
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // These are not allowed anywhere in XML 1.0, not even escaped.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use roc_region::all::Position;
+
+    const SOURCE: &str = indoc!(
+        r#"
+        module [add]
+
+        add = \a, b -> a + b
+
+        ## Adding zero changes nothing
+        expect add 1 0 == 1
+
+        expect
+            sum = add 2 2
+            sum == 4
+        "#
+    );
+
+    fn region_of(snippet: &str) -> Region {
+        let start = SOURCE.find(snippet).unwrap() as u32;
+        let end = start + snippet.len() as u32;
+
+        Region::new(Position::new(start), Position::new(end))
+    }
+
+    fn info(snippet: &str) -> ExpectInfo<'static> {
+        ExpectInfo::new("Add", Path::new("Add.roc"), SOURCE, region_of(snippet))
+    }
+
+    #[test]
+    fn expect_with_comment_above() {
+        let info = info("expect add 1 0 == 1");
+
+        assert_eq!(info.line, 6);
+        assert_eq!(info.comment, "Adding zero changes nothing");
+        assert_eq!(info.source, "expect add 1 0 == 1");
+        assert_eq!(info.summary(), "Adding zero changes nothing");
+    }
+
+    #[test]
+    fn expect_region_including_comment() {
+        let info = info("## Adding zero changes nothing\nexpect add 1 0 == 1");
+
+        assert_eq!(info.line, 6);
+        assert_eq!(info.comment, "Adding zero changes nothing");
+        assert_eq!(info.source, "expect add 1 0 == 1");
+    }
+
+    #[test]
+    fn expect_without_comment() {
+        let info = info("expect\n    sum = add 2 2\n    sum == 4");
+
+        assert_eq!(info.line, 8);
+        assert_eq!(info.comment, "");
+        assert_eq!(info.summary(), "expect");
+    }
+
+    #[test]
+    fn filters() {
+        let with_comment = info("expect add 1 0 == 1");
+        let without_comment = info("expect\n    sum = add 2 2\n    sum == 4");
+
+        let by_comment = ExpectFilter::new("zero");
+        assert!(by_comment.matches(&with_comment));
+        assert!(!by_comment.matches(&without_comment));
+
+        let by_source = ExpectFilter::new("sum == [0-9]");
+        assert!(matches!(by_source, ExpectFilter::Regex(_)));
+        assert!(!by_source.matches(&with_comment));
+        assert!(by_source.matches(&without_comment));
+
+        let by_module = ExpectFilter::new("^Add$");
+        assert!(by_module.matches(&with_comment));
+        assert!(by_module.matches(&without_comment));
+
+        let invalid_regex = ExpectFilter::new("add 1 (");
+        assert!(matches!(invalid_regex, ExpectFilter::Substring(_)));
+        assert!(!invalid_regex.matches(&with_comment));
+    }
+
+    #[test]
+    fn junit_xml() {
+        let mut report = JunitReport::default();
+
+        report.push(
+            "Add",
+            TestCase {
+                name: "Adding zero changes nothing".to_string(),
+                path: PathBuf::from("Add.roc"),
+                line: 6,
+                duration: Duration::from_millis(2),
+                failure: None,
+            },
+        );
+        report.push(
+            "Add",
+            TestCase {
+                name: "expect a < b".to_string(),
+                path: PathBuf::from("Add.roc"),
+                line: 8,
+                duration: Duration::from_millis(1),
+                failure: Some(
+                    "── EXPECT FAILED in Add.roc ──\n\nThis expectation failed:\n".to_string(),
+                ),
+            },
+        );
+
+        assert_eq!(
+            report.to_xml(),
+            indoc!(
+                r#"
+                <?xml version="1.0" encoding="UTF-8"?>
+                <testsuites name="roc test" tests="2" failures="1" time="0.003">
+                  <testsuite name="Add" tests="2" failures="1" time="0.003">
+                    <testcase name="Adding zero changes nothing" classname="Add" file="Add.roc" line="6" time="0.002"/>
+                    <testcase name="expect a &lt; b" classname="Add" file="Add.roc" line="8" time="0.001">
+                      <failure message="── EXPECT FAILED in Add.roc ──">── EXPECT FAILED in Add.roc ──
+
+                This expectation failed:</failure>
+                    </testcase>
+                  </testsuite>
+                </testsuites>
+                "#
+            )
+        );
+    }
+}
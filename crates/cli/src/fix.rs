@@ -0,0 +1,614 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use bumpalo::Bump;
+use roc_can::expr::Declarations;
+use roc_can::pattern::{DestructType, RecordDestruct};
+use roc_can::traverse::{walk_record_destruct, Visitor};
+use roc_error_macros::internal_error;
+use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadingProblem, Threading};
+use roc_module::symbol::Interns;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_problem::can::Problem;
+use roc_region::all::Region;
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::Target;
+
+/// Fixing one problem can uncover another (e.g. removing an unused def can leave a def that only
+/// it used), so `roc fix` reloads and fixes again until nothing changes, up to this many times.
+const MAX_FIX_PASSES: usize = 8;
+
+/// An automatic fix for a warning, as a replacement of a byte range of the module's source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixEdit {
+    pub title: String,
+    /// Where the problem this fixes was reported
+    pub problem: Region,
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Applies all the fixes for the modules of the project that `file` belongs to, i.e. those in its
+/// directory or below it. Returns how many fixes were applied.
+pub fn fix_files(arena: &Bump, file: PathBuf) -> Result<usize, LoadingProblem> {
+    let project_dir = file
+        .canonicalize()
+        .unwrap_or_else(|_| file.clone())
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let mut total = 0;
+
+    for _ in 0..MAX_FIX_PASSES {
+        let load_config = LoadConfig {
+            target: Target::default(),
+            function_kind: FunctionKind::from_env(),
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Check,
+            inline_budget: None,
+            simplify: false,
+            module_cache_dir: Some(cache::roc_cache_modules_dir()),
+        };
+
+        let loaded = roc_load::load_and_typecheck(
+            arena,
+            file.clone(),
+            None,
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            load_config,
+        )?;
+
+        let mut fixed = 0;
+
+        for (module_id, (path, src)) in loaded.sources.iter() {
+            let in_project = path
+                .canonicalize()
+                .is_ok_and(|path| path.starts_with(&project_dir));
+            let problems = loaded.can_problems.get(module_id);
+            let declarations = loaded.declarations_by_id.get(module_id);
+
+            let (Some(problems), Some(declarations)) =
+                (problems.filter(|_| in_project), declarations)
+            else {
+                continue;
+            };
+
+            let edits = fix_edits(src, problems, &loaded.interns, declarations);
+            let (fixed_src, applied) = apply_fix_edits(src, edits);
+
+            if applied > 0 {
+                std::fs::write(path, fixed_src).unwrap_or_else(|e| {
+                    internal_error!("failed to write fixed file to {path:?}: {e}")
+                });
+                fixed += applied;
+            }
+        }
+
+        if fixed == 0 {
+            break;
+        }
+
+        total += fixed;
+    }
+
+    Ok(total)
+}
+
+/// Applies `edits` to `src`, returning the new source and the number of edits applied.
+/// Edits that overlap an earlier edit are skipped; they are usually found again after reloading.
+pub fn apply_fix_edits(src: &str, mut edits: Vec<FixEdit>) -> (String, usize) {
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    let mut buffer = String::with_capacity(src.len());
+    let mut file_progress = 0;
+    let mut applied = 0;
+
+    for edit in edits {
+        if edit.range.start < file_progress {
+            continue;
+        }
+
+        buffer.push_str(&src[file_progress..edit.range.start]);
+        buffer.push_str(&edit.new_text);
+
+        file_progress = edit.range.end;
+        applied += 1;
+    }
+    buffer.push_str(&src[file_progress..]);
+
+    (buffer, applied)
+}
+
+/// The fixes for those of a module's `problems` that have one.
+pub fn fix_edits(
+    src: &str,
+    problems: &[Problem],
+    interns: &Interns,
+    declarations: &Declarations,
+) -> Vec<FixEdit> {
+    problems
+        .iter()
+        .filter_map(|problem| fix_edit(src, problem, interns, declarations))
+        .collect()
+}
+
+/// The fix for `problem`, if it has one and we can be sure the source looks like we expect.
+pub fn fix_edit(
+    src: &str,
+    problem: &Problem,
+    interns: &Interns,
+    declarations: &Declarations,
+) -> Option<FixEdit> {
+    match problem {
+        Problem::UnusedDef(_, region) => {
+            let name = region_str(src, *region)?;
+
+            Some(FixEdit {
+                title: format!("Remove unused definition `{name}`"),
+                problem: *region,
+                range: def_range(src, region.byte_range())?,
+                new_text: String::new(),
+            })
+        }
+        Problem::UnusedImport(_, region) => {
+            let name = region_str(src, *region)?;
+
+            Some(FixEdit {
+                title: format!("Remove unused import `{name}`"),
+                problem: *region,
+                range: exposed_name_range(src, region.byte_range())?,
+                new_text: String::new(),
+            })
+        }
+        Problem::UnusedModuleImport(module_id, region) => Some(FixEdit {
+            title: format!(
+                "Remove unused import of `{}`",
+                interns.module_name(*module_id)
+            ),
+            problem: *region,
+            range: whole_lines(src, region.byte_range())?,
+            new_text: String::new(),
+        }),
+        Problem::ExplicitBuiltinImport(module_id, region) => Some(FixEdit {
+            title: format!(
+                "Remove redundant import of builtin `{}`",
+                interns.module_name(*module_id)
+            ),
+            problem: *region,
+            range: whole_lines(src, region.byte_range())?,
+            new_text: String::new(),
+        }),
+        Problem::UnusedArgument(_, _, _, region) => {
+            let name = region_str(src, *region)?;
+
+            if is_record_field_shorthand(declarations, *region) {
+                return None;
+            }
+
+            let start = region.byte_range().start;
+
+            Some(FixEdit {
+                title: format!("Prefix unused argument `{name}` with `_`"),
+                problem: *region,
+                range: start..start,
+                new_text: "_".to_string(),
+            })
+        }
+        Problem::UnnecessaryOutputWildcard { region } => {
+            if src.get(region.byte_range())? != "*" {
+                return None;
+            }
+
+            Some(FixEdit {
+                title: "Remove unnecessary wildcard".to_string(),
+                problem: *region,
+                range: region.byte_range(),
+                new_text: String::new(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The identifier at `region`, or [None] if that's not what is there.
+fn region_str(src: &str, region: Region) -> Option<&str> {
+    let name = src.get(region.byte_range())?;
+    let ident = name.strip_suffix('!').unwrap_or(name);
+
+    let is_ident = ident.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && ident.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    is_ident.then_some(name)
+}
+
+/// The start of the line that `offset` is on.
+pub fn line_start(src: &str, offset: usize) -> usize {
+    src[..offset].rfind('\n').map_or(0, |pos| pos + 1)
+}
+
+/// The start of the line after the one that `offset` is on.
+pub fn line_end(src: &str, offset: usize) -> usize {
+    src[offset..]
+        .find('\n')
+        .map_or(src.len(), |pos| offset + pos + 1)
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Expands `range` to the full lines it is on, as long as it has nothing else on them.
+fn whole_lines(src: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let start = line_start(src, range.start);
+    let end = line_end(src, range.end);
+
+    let only_whitespace_around =
+        src[start..range.start].trim().is_empty() && src.get(range.end..end)?.trim().is_empty();
+
+    only_whitespace_around.then_some(start..end)
+}
+
+/// The lines of the def whose pattern starts at `pattern.start`, including its body (the lines
+/// indented further than the pattern), its annotation and its doc comment.
+///
+/// Returns [None] for anything but a plain `name =` or `name :` def, since removing a whole
+/// destructuring def could remove other, used, names along with the unused one.
+fn def_range(src: &str, pattern: Range<usize>) -> Option<Range<usize>> {
+    let name = &src[pattern.clone()];
+    let start = line_start(src, pattern.start);
+    let base_indent = pattern.start - start;
+
+    if !src[start..pattern.start].trim().is_empty() {
+        return None;
+    }
+
+    let def_kind = |line_start: usize| {
+        let line = &src[line_start..line_end(src, line_start)];
+        let after_name = line.trim_start().strip_prefix(name)?.trim_start();
+
+        if indent(line) != base_indent || after_name.starts_with("==") {
+            None
+        } else if after_name.starts_with('=') || after_name.starts_with(":=") {
+            Some(DefLine::Body)
+        } else if after_name.starts_with(':') {
+            Some(DefLine::Annotation)
+        } else {
+            None
+        }
+    };
+
+    // The line after the last one that belongs to the def starting on the line at `start`
+    let block_end = |start: usize| {
+        let mut end = line_end(src, start);
+        let mut next = end;
+
+        while next < src.len() {
+            let line = &src[next..line_end(src, next)];
+
+            if line.trim().is_empty() {
+                next = line_end(src, next);
+            } else if indent(line) > base_indent {
+                next = line_end(src, next);
+                end = next;
+            } else {
+                break;
+            }
+        }
+
+        end
+    };
+
+    let mut range = start..block_end(start);
+
+    match def_kind(start)? {
+        DefLine::Annotation => {
+            if range.end < src.len() && def_kind(range.end) == Some(DefLine::Body) {
+                range.end = block_end(range.end);
+            }
+        }
+        DefLine::Body => {
+            // Look for an annotation right above, skipping its continuation lines
+            let mut line = range.start;
+
+            while line > 0 {
+                line = line_start(src, line - 1);
+                let text = &src[line..line_end(src, line)];
+
+                if text.trim().is_empty() || indent(text) < base_indent {
+                    break;
+                } else if indent(text) == base_indent {
+                    if def_kind(line) == Some(DefLine::Annotation) {
+                        range.start = line;
+                    }
+
+                    break;
+                }
+            }
+        }
+    }
+
+    // Doc comments document the def, so they go with it
+    while range.start > 0 {
+        let line = line_start(src, range.start - 1);
+        let text = &src[line..range.start];
+
+        if indent(text) == base_indent && text.trim_start().starts_with("##") {
+            range.start = line;
+        } else {
+            break;
+        }
+    }
+
+    // Don't leave two blank lines where the def used to be
+    let blank_before = range.start == 0 || src[..range.start - 1].ends_with('\n');
+    let next_line = &src[range.end..line_end(src, range.end)];
+    if blank_before && !next_line.is_empty() && next_line.trim().is_empty() {
+        range.end += next_line.len();
+    }
+
+    Some(range)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DefLine {
+    Annotation,
+    Body,
+}
+
+/// The range to remove to take `name` out of an `exposing [...]` list, along with a comma next to
+/// it. When it is the only name, the whole `exposing [...]` goes.
+fn exposed_name_range(src: &str, name: Range<usize>) -> Option<Range<usize>> {
+    let before = src[..name.start].trim_end();
+    let after_start = name.end + (src[name.end..].len() - src[name.end..].trim_start().len());
+    let after = &src[after_start..];
+
+    if before.ends_with(',') {
+        // `a, name` loses `, name`
+        Some(before.len() - 1..name.end)
+    } else if let Some(after_comma) = after.strip_prefix(',') {
+        let next = after_start + 1 + (after_comma.len() - after_comma.trim_start().len());
+
+        if src[next..].starts_with(']') {
+            // `[name,]`, a list of one with a trailing comma
+            remove_exposing(before, next)
+        } else {
+            // `name, b` loses `name, `
+            Some(name.start..next)
+        }
+    } else if before.ends_with('[') && after.starts_with(']') {
+        remove_exposing(before, after_start)
+    } else {
+        None
+    }
+}
+
+/// Removes ` exposing [...]` from an import, given the source before the name and the offset of
+/// the closing bracket.
+fn remove_exposing(before: &str, close_bracket: usize) -> Option<Range<usize>> {
+    let before_bracket = before.strip_suffix('[')?.trim_end();
+    let before_exposing = before_bracket.strip_suffix("exposing")?.trim_end();
+
+    Some(before_exposing.len()..close_bracket + 1)
+}
+
+/// Whether `region` is a field of a record destructure like `{ x, y }` or `{ x ? 0 }`, where
+/// renaming `x` to `_x` would change which field is destructured.
+fn is_record_field_shorthand(declarations: &Declarations, region: Region) -> bool {
+    let mut visitor = RecordFieldShorthandVisitor {
+        region,
+        found: false,
+    };
+    visitor.visit_decls(declarations);
+
+    visitor.found
+}
+
+struct RecordFieldShorthandVisitor {
+    region: Region,
+    found: bool,
+}
+
+impl Visitor for RecordFieldShorthandVisitor {
+    fn should_visit(&mut self, region: Region) -> bool {
+        !self.found && region.contains(&self.region)
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+        match destruct.typ {
+            DestructType::Required | DestructType::Optional(..) if region == self.region => {
+                self.found = true;
+            }
+            _ if self.should_visit(region) => walk_record_destruct(self, destruct),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::fs::read_to_string;
+    use tempfile::tempdir;
+
+    fn fix_string(before: &str) -> (usize, String) {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("Test.roc");
+        std::fs::write(&file_path, before).unwrap();
+
+        let arena = Bump::new();
+        let fixed = fix_files(&arena, file_path.clone()).unwrap();
+
+        (fixed, read_to_string(file_path).unwrap())
+    }
+
+    #[test]
+    fn test_fix_unused_def_and_argument() {
+        let before = indoc! {r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            main =
+                unused = 10
+                \x -> "Hello, World!"
+            "#};
+
+        let after = indoc! {r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            main =
+                \_x -> "Hello, World!"
+            "#};
+
+        assert_eq!(fix_string(before), (2, after.to_string()));
+    }
+
+    #[test]
+    fn test_fix_nothing() {
+        let before = indoc! {r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            main = "Hello, World!"
+            "#};
+
+        assert_eq!(fix_string(before), (0, before.to_string()));
+    }
+
+    #[test]
+    fn test_fix_unused_argument_in_record_pattern() {
+        let before = indoc! {r#"
+            module [main, rename]
+
+            main = \{ x, y } -> y
+
+            rename = \{ x: renamed } -> 1
+            "#};
+
+        let after = indoc! {r#"
+            module [main, rename]
+
+            main = \{ x, y } -> y
+
+            rename = \{ x: _renamed } -> 1
+            "#};
+
+        assert_eq!(fix_string(before), (1, after.to_string()));
+    }
+
+    #[test]
+    fn test_fix_unused_exposed_import() {
+        let before = indoc! {r#"
+            module [main]
+
+            import Str exposing [concat, is_empty]
+
+            main = concat "Hello, " "World!"
+            "#};
+
+        let after = indoc! {r#"
+            module [main]
+
+            import Str exposing [concat]
+
+            main = concat "Hello, " "World!"
+            "#};
+
+        assert_eq!(fix_string(before), (1, after.to_string()));
+    }
+
+    #[test]
+    fn test_fix_explicit_builtin_import() {
+        let before = indoc! {r#"
+            module [main]
+
+            import Str
+            import List exposing [len]
+
+            main = Str.concat "Hello, " (Num.to_str (len [1]))
+            "#};
+
+        let after = indoc! {r#"
+            module [main]
+
+            import List exposing [len]
+
+            main = Str.concat "Hello, " (Num.to_str (len [1]))
+            "#};
+
+        assert_eq!(fix_string(before), (1, after.to_string()));
+    }
+
+    #[test]
+    fn test_fix_unnecessary_output_wildcard() {
+        let before = indoc! {r#"
+            module [color]
+
+            color : {} -> [Red, Green]*
+            color = \{} -> Red
+            "#};
+
+        let after = indoc! {r#"
+            module [color]
+
+            color : {} -> [Red, Green]
+            color = \{} -> Red
+            "#};
+
+        assert_eq!(fix_string(before), (1, after.to_string()));
+    }
+
+    #[test]
+    fn test_exposed_name_range() {
+        let remove = |src: &str, name: &str| {
+            let start = src.rfind(name).unwrap();
+            let range = exposed_name_range(src, start..start + name.len())?;
+
+            Some(format!("{}{}", &src[..range.start], &src[range.end..]))
+        };
+
+        assert_eq!(
+            remove("import Foo exposing [a, b, c]", "b").as_deref(),
+            Some("import Foo exposing [a, c]")
+        );
+        assert_eq!(
+            remove("import Foo exposing [a, b]", "a").as_deref(),
+            Some("import Foo exposing [b]")
+        );
+        assert_eq!(
+            remove("import Foo exposing [a, b]", "b").as_deref(),
+            Some("import Foo exposing [a]")
+        );
+        assert_eq!(
+            remove("import Foo exposing [a]", "a").as_deref(),
+            Some("import Foo")
+        );
+        assert_eq!(
+            remove("import Foo exposing [\n    a,\n]", "a").as_deref(),
+            Some("import Foo")
+        );
+        assert_eq!(remove("import Foo as a", "a"), None);
+    }
+
+    #[test]
+    fn test_whole_lines() {
+        let src = "import Foo\n    import Bar\nx = import\n";
+
+        let foo = src.find("import Foo").unwrap();
+        assert_eq!(whole_lines(src, foo..foo + 10), Some(0..11));
+
+        let bar = src.find("import Bar").unwrap();
+        assert_eq!(whole_lines(src, bar..bar + 10), Some(11..26));
+
+        // Something else is on the line, so it must stay
+        let import = src.rfind("import").unwrap();
+        assert_eq!(whole_lines(src, import..import + 6), None);
+    }
+}
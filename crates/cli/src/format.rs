@@ -313,6 +313,9 @@ pub fn annotate_file(arena: &Bump, file: PathBuf) -> Result<(), AnnotationProble
         palette: DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: Some(cache::roc_cache_modules_dir()),
     };
 
     let mut loaded = roc_load::load_and_typecheck(
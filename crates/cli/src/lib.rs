@@ -10,22 +10,25 @@ use clap::{
 };
 use roc_build::link::{LinkType, LinkingStrategy};
 use roc_build::program::{
-    handle_error_module, handle_loading_problem, standard_load_config, BuildFileError,
-    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
+    handle_error_module, handle_loading_problem, print_progress, standard_load_config,
+    BuildFileError, BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
 };
 #[cfg(not(windows))]
 use roc_collections::MutMap;
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{ExpectMetadata, Threading};
+use roc_load::{ExpectMetadata, LoadConfig, Threading};
 #[cfg(not(windows))]
 use roc_module::symbol::ModuleId;
 use roc_mono::ir::OptLevel;
-use roc_packaging::cache::RocCacheDir;
+use roc_packaging::cache::{self, RocCacheDir};
 use roc_packaging::tarball::Compression;
+use roc_packaging::vendor::{self, VendorProblem};
+use roc_reporting::report::RenderTarget;
 #[cfg(not(windows))]
 use roc_reporting::report::ANSI_STYLE_CODES;
+use roc_reporting::warnings::WarningConfig;
 use roc_target::{Architecture, Target};
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
@@ -41,11 +44,17 @@ use strum::IntoEnumIterator;
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+#[cfg(not(windows))]
+mod expects;
+mod fix;
 mod format;
+mod watch;
+pub use fix::{apply_fix_edits, fix_edit, fix_edits, fix_files, line_end, line_start, FixEdit};
 pub use format::{
     annotate_file, annotation_edit, annotation_edits, format_files, format_src, AnnotationProblem,
     FormatMode,
 };
+pub use watch::watch;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -56,6 +65,8 @@ pub const CMD_CHECK: &str = "check";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_FORMAT_ANNOTATE: &str = "annotate";
+pub const CMD_FIX: &str = "fix";
+pub const CMD_VENDOR: &str = "vendor";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_PREPROCESS_HOST: &str = "preprocess-host";
@@ -85,6 +96,15 @@ pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
 pub const FLAG_OUTPUT: &str = "output";
 pub const FLAG_FUZZ: &str = "fuzz";
 pub const FLAG_MAIN: &str = "main";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_LIST: &str = "list";
+pub const FLAG_REPORT: &str = "report";
+pub const FLAG_TIMEOUT: &str = "timeout";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_DENY_WARNINGS: &str = "deny-warnings";
+pub const FLAG_PROFILE_COMPILER: &str = "profile-compiler";
+pub const FLAG_OFFLINE: &str = "offline";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
@@ -99,6 +119,108 @@ pub const FLAG_DOCS_ROOT: &str = "root-dir";
 pub const VERSION: &str = env!("ROC_VERSION");
 const DEFAULT_GENERATED_DOCS_DIR: &str = "generated-docs";
 
+/// The render target for a value of `--format`.
+pub fn render_target(format: &str) -> RenderTarget {
+    match format {
+        "json" => RenderTarget::Json,
+        "sarif" => RenderTarget::Sarif,
+        _ => RenderTarget::ColorTerminal,
+    }
+}
+
+/// Parses the value of `--report`. Only `junit=<path>` is supported for now.
+fn parse_test_report(value: &str) -> Result<PathBuf, String> {
+    match value.split_once('=') {
+        Some(("junit", path)) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Err("expected a report like `junit=<path>`".to_string()),
+    }
+}
+
+/// The warning levels of the project whose root module is at `root_module`, with every warning
+/// denied if the subcommand was given `--deny-warnings`.
+pub fn warning_config(matches: &ArgMatches, root_module: &Path) -> WarningConfig {
+    let deny_warnings = matches!(
+        matches.try_get_one::<bool>(FLAG_DENY_WARNINGS),
+        Ok(Some(true))
+    );
+
+    WarningConfig::load(root_module, deny_warnings)
+        .unwrap_or_else(|err| user_error!("Invalid warning level setting in {err}"))
+}
+
+/// Where to find packages: the cache, plus the project's vendor directory with `--offline`.
+pub struct PackageDirs {
+    cache_dir: PathBuf,
+    opt_vendor_dir: Option<PathBuf>,
+}
+
+impl PackageDirs {
+    /// With `--offline`, this exits if the project's lock file doesn't agree with the project.
+    pub fn new(matches: &ArgMatches, root_module: &Path) -> Self {
+        let offline = matches!(matches.try_get_one::<bool>(FLAG_OFFLINE), Ok(Some(true)));
+
+        if offline {
+            if let Err(problem) = vendor::verify_lock_file(root_module) {
+                eprintln!("{}", vendor_problem_message(problem, root_module));
+                process::exit(1);
+            }
+        }
+
+        PackageDirs {
+            cache_dir: cache::roc_cache_packages_dir(),
+            opt_vendor_dir: offline.then(|| vendor::vendor_dir(root_module)),
+        }
+    }
+
+    pub fn roc_cache_dir(&self) -> RocCacheDir<'_> {
+        match &self.opt_vendor_dir {
+            Some(vendor_dir) => RocCacheDir::Offline {
+                cache_dir: &self.cache_dir,
+                vendor_dir,
+            },
+            None => RocCacheDir::Persistent(&self.cache_dir),
+        }
+    }
+}
+
+/// What to tell the user about a problem vendoring the packages of the project at root_module,
+/// or checking them with `--offline`.
+pub fn vendor_problem_message(problem: VendorProblem, root_module: &Path) -> String {
+    match problem {
+        VendorProblem::Package { url, problem } => {
+            roc_reporting::report::to_https_problem_report_string(
+                &url,
+                problem,
+                root_module.to_owned(),
+            )
+        }
+        VendorProblem::Io { path, error } => {
+            format!("I couldn't read or write {}: {error}", path.display())
+        }
+        VendorProblem::InvalidHeader(path) => {
+            format!("I couldn't parse the header of {}, so I don't know which packages it uses. Run `roc check` on it for details.", path.display())
+        }
+        VendorProblem::MissingLockFile(path) => {
+            format!(
+                "I couldn't find {}, which --offline needs to know which packages are vendored. Run `roc vendor` to write it.",
+                path.display()
+            )
+        }
+        VendorProblem::InvalidLockFile { path, line } => {
+            format!(
+                "Line {line} of {} isn't a package like `url hash vendor/tarball`. Run `roc vendor` to write it again.",
+                path.display()
+            )
+        }
+        VendorProblem::NotLocked { url, lock_file } => {
+            format!(
+                "This project uses the package {url}, but {} doesn't list it. Run `roc vendor` to vendor it.",
+                lock_file.display()
+            )
+        }
+    }
+}
+
 pub fn build_app() -> Command {
     let flag_optimize = Arg::new(FLAG_OPTIMIZE)
         .long(FLAG_OPTIMIZE)
@@ -178,6 +300,33 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(PathBuf))
         .required(false);
 
+    let flag_format = Arg::new(FLAG_FORMAT)
+        .long(FLAG_FORMAT)
+        .help("How to print problems\n(json and sarif print one document with every problem, for other tools to read.)")
+        .value_parser(["text", "json", "sarif"])
+        .default_value("text")
+        .required(false);
+
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help(
+            "Run again whenever one of the modules changes, including platform and package modules",
+        )
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_deny_warnings = Arg::new(FLAG_DENY_WARNINGS)
+        .long(FLAG_DENY_WARNINGS)
+        .help("Report every warning as an error\n(Warnings that are allowed in roc.warnings or by a `# roc-allow:` comment stay silent.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_offline = Arg::new(FLAG_OFFLINE)
+        .long(FLAG_OFFLINE)
+        .help("Don't download packages; only use the ones in the cache and the vendor directory\n(Run `roc vendor` to put every package the app uses in its vendor directory and write the roc.lock that the app is checked against.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .value_parser(value_parser!(PathBuf))
@@ -222,7 +371,17 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_offline.clone())
             .arg(flag_wasm_stack_size_kb)
+            .arg(flag_format.clone())
+            .arg(flag_deny_warnings.clone())
+            .arg(
+                Arg::new(FLAG_PROFILE_COMPILER)
+                    .long(FLAG_PROFILE_COMPILER)
+                    .help("Write a profile of how long each phase of the compiler took to this .json file\n(It's in the Chrome trace event format, which https://ui.perfetto.dev can show.)")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -282,6 +441,7 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_offline.clone())
             .arg(
                 Arg::new(FLAG_VERBOSE)
                     .long(FLAG_VERBOSE)
@@ -289,6 +449,37 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false)
             )
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the expects whose module name, source, or the comment above them matches this regex (or contains this text, if it isn't a valid regex)")
+                    .value_parser(value_parser!(String))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_LIST)
+                    .long(FLAG_LIST)
+                    .help("List the expects that would run, without running them")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_TIMEOUT)
+                    .long(FLAG_TIMEOUT)
+                    .help("Stop an expect that runs for longer than this many seconds, and report it as a failure")
+                    .value_parser(value_parser!(u64).range(1..))
+                    .default_value("60")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_REPORT)
+                    .long(FLAG_REPORT)
+                    .help("Also write the test results to a file, e.g. `--report junit=results.xml`")
+                    .value_parser(parse_test_report)
+                    .required(false)
+            )
+            .arg(flag_watch.clone())
+            .arg(flag_deny_warnings.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to test")
@@ -329,6 +520,7 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_offline.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -345,6 +537,12 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_offline.clone())
+            .arg(
+                flag_watch
+                    .clone()
+                    .help("Rebuild and restart the app whenever one of its modules changes, including platform and package modules")
+            )
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -396,6 +594,26 @@ pub fn build_app() -> Command {
                 )
             )
         )
+        .subcommand(Command::new(CMD_FIX)
+            .about("Automatically fix warnings that have an obvious fix, like unused imports and defs,\nin a .roc file and the other modules of its project")
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of the project to fix")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_VENDOR)
+            .about("Download every package a .roc file uses, including the packages those use, into a vendor\ndirectory next to it, and record their URLs and hashes in roc.lock\n(Then `--offline` builds don't need to download anything.)")
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of the app or package to vendor the packages of")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
         .subcommand(Command::new(CMD_VERSION)
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
         .subcommand(Command::new(CMD_LICENSES)
@@ -405,6 +623,10 @@ pub fn build_app() -> Command {
             .arg(flag_main.clone())
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_format)
+            .arg(flag_watch)
+            .arg(flag_deny_warnings)
+            .arg(flag_offline.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to check")
@@ -502,6 +724,7 @@ pub fn build_app() -> Command {
         .arg(flag_build_host)
         .arg(flag_suppress_build_host_warning)
         .arg(flag_fuzz)
+        .arg(flag_offline)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true))
 }
@@ -542,9 +765,34 @@ struct ModuleTestResults {
 
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    if matches.get_flag(FLAG_WATCH) {
+        let roots: Vec<PathBuf> = matches
+            .get_many::<PathBuf>(ROC_FILE)
+            .unwrap()
+            .cloned()
+            .collect();
+
+        watch::watch(&roots, true, |changed, source_paths| {
+            run_tests(matches, target, changed, source_paths)
+        })
+    } else {
+        run_tests(matches, target, None, &mut Vec::new())
+    }
+}
+
+/// Adds the path of every module that was loaded to `source_paths`, for `--watch`. If `changed`
+/// is given, only the tests of modules that those files affect are run.
+#[cfg(not(windows))]
+fn run_tests(
+    matches: &ArgMatches,
+    target: Target,
+    changed: Option<&[PathBuf]>,
+    source_paths: &mut Vec<PathBuf>,
+) -> io::Result<i32> {
+    use expects::{ExpectFilter, ExpectInfo, JunitReport, TestCase};
     use roc_build::program::report_problems_monomorphized;
-    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
-    use roc_packaging::cache;
+    use roc_load::{ExecutionMode, FunctionKind, LoadMonomorphizedError};
+    use roc_repl_expect::run::ExpectRunOptions;
 
     let start_time = Instant::now();
     let arena = Bump::new();
@@ -589,8 +837,28 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         flatten_paths
     };
 
+    // Every expect runs in a process of its own, and --max-threads limits how many run at once.
+    let expect_run_options = ExpectRunOptions {
+        max_workers: match threading {
+            Threading::Single => 1,
+            Threading::AtMost(n) => n,
+            Threading::AllAvailable => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        },
+        timeout: Duration::from_secs(*matches.get_one::<u64>(FLAG_TIMEOUT).unwrap()),
+    };
+
+    let filter = matches
+        .get_one::<String>(FLAG_FILTER)
+        .map(|pattern| ExpectFilter::new(pattern));
+    let list_only = matches.get_flag(FLAG_LIST);
+    let junit_path = matches.get_one::<PathBuf>(FLAG_REPORT);
+
     let mut all_files_total_failed_count = 0;
     let mut all_files_total_passed_count = 0;
+    let mut listed_count = 0;
+    let mut list_exit_code = 0;
+    let mut unaffected_count = 0;
+    let mut junit_report = JunitReport::default();
 
     for path in paths.iter() {
         let arena = &arena;
@@ -598,34 +866,110 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
 
         let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
 
+        // TODO: expose this from CLI?
+        let render = RenderTarget::ColorTerminal;
+        let warnings = warning_config(matches, path);
+        let package_dirs = PackageDirs::new(matches, path);
+
         // Step 1: compile the app and generate the .o file
         let load_config = LoadConfig {
             target,
             function_kind,
-            // TODO: expose this from CLI?
-            render: roc_reporting::report::RenderTarget::ColorTerminal,
+            render,
             palette: roc_reporting::report::DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Test,
+            inline_budget: None,
+            simplify: false,
+            module_cache_dir: Some(cache::roc_cache_modules_dir()),
         };
         let load_result = roc_load::load_and_monomorphize(
             arena,
             path.to_path_buf(),
             opt_main_path.cloned(),
-            RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+            package_dirs.roc_cache_dir(),
             load_config,
         );
 
         let mut loaded = match load_result {
             Ok(loaded) => loaded,
             Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
-                return handle_loading_problem(problem);
+                return handle_loading_problem(problem, render);
             }
             Err(LoadMonomorphizedError::ErrorModule(module)) => {
-                return handle_error_module(module, start_time.elapsed());
+                source_paths.extend(module.sources.values().map(|(path, _)| path.clone()));
+
+                return handle_error_module(module, start_time.elapsed(), render, &warnings);
             }
         };
-        let problems = report_problems_monomorphized(&mut loaded);
+        source_paths.extend(loaded.sources.values().map(|(path, _)| path.clone()));
+        let problems = report_problems_monomorphized(&mut loaded, render, &warnings);
+
+        // Warnings that are denied only become errors once they're reported.
+        if problems.errors > 0 {
+            problems.print_error_warning_count(start_time.elapsed());
+            println!(".\n");
+
+            return Ok(problems.exit_code());
+        }
+
+        if let Some(filter) = &filter {
+            for (module_id, expects) in loaded.toplevel_expects.iter_mut() {
+                let (module_path, module_source) = loaded.sources.get(module_id).unwrap();
+                let module_name = loaded.interns.module_name(*module_id).as_str();
+
+                expects.pure = std::mem::take(&mut expects.pure)
+                    .into_iter()
+                    .filter(|(_, region)| {
+                        let info =
+                            ExpectInfo::new(module_name, module_path, module_source, *region);
+                        filter.matches(&info)
+                    })
+                    .collect();
+            }
+
+            loaded
+                .toplevel_expects
+                .retain(|_, expects| !expects.pure.is_empty());
+        }
+
+        if let Some(changed) = changed {
+            let affected = watch::affected_modules(changed, &loaded.sources, &loaded.imports);
+            let module_count = loaded.toplevel_expects.len();
+
+            loaded
+                .toplevel_expects
+                .retain(|module_id, _| affected.contains(module_id));
+
+            unaffected_count += module_count - loaded.toplevel_expects.len();
+        }
+
+        if list_only {
+            let mut modules: Vec<_> = loaded.toplevel_expects.iter().collect();
+            modules.sort_by_key(|(module_id, _)| &loaded.sources.get(module_id).unwrap().0);
+
+            for (module_id, expects) in modules {
+                let (module_path, module_source) = loaded.sources.get(module_id).unwrap();
+                let module_name = loaded.interns.module_name(*module_id).as_str();
+
+                for region in expects.pure.values() {
+                    let info = ExpectInfo::new(module_name, module_path, module_source, *region);
+                    println!(
+                        "{}:{}: {}",
+                        module_path.display(),
+                        info.line,
+                        info.summary()
+                    );
+
+                    listed_count += 1;
+                }
+            }
+
+            // Listing doesn't run anything, but the warnings it reported still count.
+            list_exit_code = list_exit_code.max(problems.exit_code());
+
+            continue;
+        }
 
         let mut expectations = std::mem::take(&mut loaded.expectations);
 
@@ -633,13 +977,17 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         let sources = loaded.sources.clone();
 
         let (dyn_lib, expects_by_module, layout_interner) =
-            roc_repl_expect::run::expect_mono_module_to_dylib(
-                arena,
-                target,
-                loaded,
-                opt_level,
-                LlvmBackendMode::CliTest,
-            )
+            if matches!(opt_level, OptLevel::Development) {
+                roc_repl_expect::run::expect_mono_module_to_dylib_dev(arena, target, loaded)
+            } else {
+                roc_repl_expect::run::expect_mono_module_to_dylib(
+                    arena,
+                    target,
+                    loaded,
+                    opt_level,
+                    LlvmBackendMode::CliTest,
+                )
+            }
             .unwrap();
 
         // Print warnings before running tests.
@@ -671,7 +1019,7 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         for (module_id, expects) in expects_by_module.into_iter() {
             let test_start_time = Instant::now();
 
-            let (failed_count, passed_count) = roc_repl_expect::run::run_toplevel_expects(
+            let outcomes = roc_repl_expect::run::run_toplevel_expects(
                 &mut writer,
                 roc_reporting::report::RenderTarget::ColorTerminal,
                 arena,
@@ -680,11 +1028,40 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
                 &dyn_lib,
                 &mut expectations,
                 expects,
+                expect_run_options,
             )
             .unwrap();
 
             let tests_duration = test_start_time.elapsed();
 
+            let passed_count = outcomes.iter().filter(|outcome| outcome.passed()).count();
+            let failed_count = outcomes.len() - passed_count;
+
+            if junit_path.is_some() {
+                let (module_path, module_source) = sources.get(&module_id).unwrap();
+                let module_name = interns.module_name(module_id).as_str();
+
+                for outcome in outcomes {
+                    let info = ExpectInfo::new(
+                        module_name,
+                        module_path,
+                        module_source,
+                        outcome.expect.region,
+                    );
+
+                    junit_report.push(
+                        module_name,
+                        TestCase {
+                            name: info.summary().to_string(),
+                            path: module_path.clone(),
+                            line: info.line,
+                            duration: outcome.duration,
+                            failure: outcome.failure,
+                        },
+                    );
+                }
+            }
+
             results_by_module.push(ModuleTestResults {
                 module_id,
                 failed_count,
@@ -713,7 +1090,26 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             println!("{test_summary_str}");
         }
     }
-    if all_files_total_failed_count == 0 && all_files_total_passed_count == 0 {
+    if let Some(junit_path) = junit_path {
+        std::fs::write(junit_path, junit_report.to_xml())?;
+    }
+
+    if unaffected_count > 0 {
+        println!("Skipped the tests of {unaffected_count} modules that the changes don't affect.");
+    }
+
+    if list_only {
+        if listed_count == 0 {
+            println!("No expectations were found.");
+        }
+
+        Ok(list_exit_code)
+    } else if all_files_total_failed_count == 0
+        && all_files_total_passed_count == 0
+        && unaffected_count > 0
+    {
+        Ok(0)
+    } else if all_files_total_failed_count == 0 && all_files_total_passed_count == 0 {
         // TODO print this in a more nicely formatted way!
         println!("No expectations were found.");
 
@@ -810,7 +1206,7 @@ pub fn default_linking_strategy(
         Some("surgical") => match linker_support_level {
             roc_linker::SupportLevel::Full => LinkingStrategy::Surgical,
             roc_linker::SupportLevel::Wip => {
-                println!("Warning! Using an unfinished surgical linker for target {target}");
+                eprintln!("Warning! Using an unfinished surgical linker for target {target}");
                 LinkingStrategy::Surgical
             }
             roc_linker::SupportLevel::None => LinkingStrategy::Legacy,
@@ -929,85 +1325,22 @@ pub fn build(
     // so we don't want to spend time freeing these values
     let arena = ManuallyDrop::new(Bump::new());
 
-    let opt_level = opt_level_from_flags(matches);
-
-    let should_run_expects = matches!(opt_level, OptLevel::Development | OptLevel::Normal) &&
-        // TODO: once expect is decoupled from roc launching the executable, remove this part of the conditional.
-        matches!(
-            config,
-            BuildConfig::BuildAndRun | BuildConfig::BuildAndRunIfNoErrors
-        );
-
-    let code_gen_backend = if matches!(opt_level, OptLevel::Development) {
-        if matches!(target.architecture(), Architecture::Wasm32) {
-            CodeGenBackend::Wasm
-        } else {
-            CodeGenBackend::Assembly(AssemblyBackendMode::Binary)
-        }
-    } else {
-        let backend_mode = if should_run_expects {
-            LlvmBackendMode::BinaryWithExpect
-        } else {
-            LlvmBackendMode::Binary
-        };
-
-        CodeGenBackend::Llvm(backend_mode)
-    };
-
-    let emit_llvm_ir = matches.get_flag(FLAG_EMIT_LLVM_IR);
-    if emit_llvm_ir && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
-        user_error!("Cannot emit llvm ir while using a dev backend.");
-    }
-
-    let emit_debug_info = matches.get_flag(FLAG_PROFILING)
-        || matches!(opt_level, OptLevel::Development | OptLevel::Normal);
-    let emit_timings = matches.get_flag(FLAG_TIME);
-
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
-    };
-
-    let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
-
-    let linking_strategy = if wasm_dev_backend {
-        LinkingStrategy::Additive
-    } else {
-        default_linking_strategy(matches, link_type, target)
-    };
-
-    // All hosts should be prebuilt, this flag keeps the rebuilding behvaiour
-    // as required for internal tests
-    let build_host = matches.get_flag(FLAG_BUILD_HOST);
-    let suppress_build_host_warning = matches.get_flag(FLAG_SUPPRESS_BUILD_HOST_WARNING);
-
-    let fuzz = matches.get_flag(FLAG_FUZZ);
-    if fuzz && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
-        user_error!("Cannot instrument binary for fuzzing while using a dev backend.");
-    }
-
-    let wasm_dev_stack_bytes: Option<u32> = matches
-        .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
+    let BuildOptions {
+        code_gen_options,
+        emit_timings,
+        linking_strategy,
+        build_host,
+        suppress_build_host_warning,
+        wasm_dev_stack_bytes,
+        should_run_expects,
+        render,
+        load_config,
+    } = build_options(matches, &config, target, link_type);
+    let warnings = warning_config(matches, path);
+    let profile_path = matches
+        .try_get_one::<PathBuf>(FLAG_PROFILE_COMPILER)
         .ok()
-        .flatten()
-        .map(|x| x * 1024);
-
-    let build_ordering = match config {
-        BuildAndRunIfNoErrors | BuildOnly => BuildOrdering::BuildIfChecks,
-        _ => BuildOrdering::AlwaysBuild,
-    };
-
-    let code_gen_options = CodeGenOptions {
-        backend: code_gen_backend,
-        opt_level,
-        emit_debug_info,
-        emit_llvm_ir,
-        fuzz,
-    };
-
-    let load_config = standard_load_config(target, build_ordering, threading);
+        .flatten();
 
     let res_binary_path = roc_build::program::build_file(
         &arena,
@@ -1022,7 +1355,9 @@ pub fn build(
         wasm_dev_stack_bytes,
         roc_cache_dir,
         load_config,
+        &warnings,
         out_path,
+        profile_path.map(PathBuf::as_path),
         verbose,
     );
 
@@ -1032,6 +1367,7 @@ pub fn build(
             problems,
             total_time,
             expect_metadata,
+            source_paths: _,
         }) => {
             match config {
                 BuildOnly => {
@@ -1046,8 +1382,13 @@ pub fn build(
                     // since the process is about to exit anyway.
                     // std::mem::forget(arena);
 
-                    problems.print_error_warning_count(total_time);
-                    println!(" while successfully building:\n\n    {generated_filename}");
+                    print_progress(
+                        render,
+                        &format!(
+                            "{} while successfully building:\n\n    {generated_filename}\n",
+                            problems.error_warning_count(total_time)
+                        ),
+                    );
 
                     // Return a nonzero exit code if there were problems
                     Ok(problems.exit_code())
@@ -1101,10 +1442,17 @@ pub fn build(
                         // Return a nonzero exit code due to fatal problem
                         return Ok(problems.exit_code());
                     }
-                    debug_assert_eq!(
-                        problems.errors, 0,
-                        "if there are non-fatal errors, they should have been returned as an error variant"
-                    );
+                    // Other errors are returned as an error variant, but warnings that are
+                    // denied only become errors once they're reported.
+                    if problems.errors > 0 {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nCannot run program due to errors…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+
+                        return Ok(problems.exit_code());
+                    }
 
                     if problems.warnings > 0 {
                         problems.print_error_warning_count(total_time);
@@ -1136,9 +1484,234 @@ pub fn build(
             }
         }
         Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time)
+            handle_error_module(module, total_time, render, &warnings)
+        }
+        Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem, render),
+    }
+}
+
+/// `roc dev --watch`: build the app and run it, and then stop it, rebuild it and run it again
+/// whenever one of its modules changes. While there are errors, the app doesn't run.
+pub fn dev_watch(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let args: Vec<OsString> = matches
+        .get_many::<OsString>(ARGS_FOR_APP)
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+
+    let mut running_app: Option<process::Child> = None;
+
+    // The app is always rebuilt as a whole, but the module cache skips the unchanged modules.
+    watch::watch(&[path.clone()], true, |_changed, source_paths| {
+        // Stop the app before building, so that its executable can be replaced.
+        if let Some(mut app) = running_app.take() {
+            let _ = app.kill();
+            let _ = app.wait();
         }
-        Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
+
+        let arena = Bump::new();
+        let link_type = LinkType::Executable;
+
+        // Expects in the app are only checked when `roc` runs it in-process.
+        let BuildOptions {
+            code_gen_options,
+            emit_timings,
+            linking_strategy,
+            build_host,
+            suppress_build_host_warning,
+            wasm_dev_stack_bytes,
+            should_run_expects: _,
+            render,
+            load_config,
+        } = build_options(matches, &BuildConfig::BuildOnly, target, link_type);
+        let warnings = warning_config(matches, path);
+        let package_dirs = PackageDirs::new(matches, path);
+
+        let res_binary_path = roc_build::program::build_file(
+            &arena,
+            target,
+            path.to_owned(),
+            code_gen_options,
+            emit_timings,
+            link_type,
+            linking_strategy,
+            build_host,
+            suppress_build_host_warning,
+            wasm_dev_stack_bytes,
+            package_dirs.roc_cache_dir(),
+            load_config,
+            &warnings,
+            None,
+            None,
+            false,
+        );
+
+        match res_binary_path {
+            Ok(BuiltFile {
+                binary_path,
+                problems,
+                total_time,
+                expect_metadata: _,
+                source_paths: built_paths,
+            }) => {
+                source_paths.extend(built_paths);
+
+                if problems.fatally_errored {
+                    problems.print_error_warning_count(total_time);
+                    println!(".\n\nCannot run program due to fatal error…");
+
+                    return Ok(problems.exit_code());
+                }
+
+                // Warnings that are denied only become errors once they're reported.
+                if problems.errors > 0 {
+                    problems.print_error_warning_count(total_time);
+                    println!(".\n\nCannot run program due to errors…");
+
+                    return Ok(problems.exit_code());
+                }
+
+                if problems.warnings > 0 {
+                    problems.print_error_warning_count(total_time);
+                    println!(
+                        ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
+                        "─".repeat(80)
+                    );
+                }
+
+                running_app = Some(process::Command::new(&binary_path).args(&args).spawn()?);
+
+                Ok(0)
+            }
+            Err(BuildFileError::ErrorModule { module, total_time }) => {
+                source_paths.extend(module.sources.values().map(|(path, _)| path.clone()));
+
+                handle_error_module(module, total_time, render, &warnings)
+            }
+            Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem, render),
+        }
+    })
+}
+
+/// Everything `build` needs to know from the command line flags, besides the file to build.
+struct BuildOptions {
+    code_gen_options: CodeGenOptions,
+    emit_timings: bool,
+    linking_strategy: LinkingStrategy,
+    build_host: bool,
+    suppress_build_host_warning: bool,
+    wasm_dev_stack_bytes: Option<u32>,
+    should_run_expects: bool,
+    render: RenderTarget,
+    load_config: LoadConfig,
+}
+
+fn build_options(
+    matches: &ArgMatches,
+    config: &BuildConfig,
+    target: Target,
+    link_type: LinkType,
+) -> BuildOptions {
+    use BuildConfig::*;
+
+    let opt_level = opt_level_from_flags(matches);
+
+    let should_run_expects = matches!(opt_level, OptLevel::Development | OptLevel::Normal) &&
+        // TODO: once expect is decoupled from roc launching the executable, remove this part of the conditional.
+        matches!(
+            config,
+            BuildConfig::BuildAndRun | BuildConfig::BuildAndRunIfNoErrors
+        );
+
+    let code_gen_backend = if matches!(opt_level, OptLevel::Development) {
+        if matches!(target.architecture(), Architecture::Wasm32) {
+            CodeGenBackend::Wasm
+        } else {
+            CodeGenBackend::Assembly(AssemblyBackendMode::Binary)
+        }
+    } else {
+        let backend_mode = if should_run_expects {
+            LlvmBackendMode::BinaryWithExpect
+        } else {
+            LlvmBackendMode::Binary
+        };
+
+        CodeGenBackend::Llvm(backend_mode)
+    };
+
+    let emit_llvm_ir = matches.get_flag(FLAG_EMIT_LLVM_IR);
+    if emit_llvm_ir && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
+        user_error!("Cannot emit llvm ir while using a dev backend.");
+    }
+
+    let emit_debug_info = matches.get_flag(FLAG_PROFILING)
+        || matches!(opt_level, OptLevel::Development | OptLevel::Normal);
+    let emit_timings = matches.get_flag(FLAG_TIME);
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
+
+    let linking_strategy = if wasm_dev_backend {
+        LinkingStrategy::Additive
+    } else {
+        default_linking_strategy(matches, link_type, target)
+    };
+
+    // All hosts should be prebuilt, this flag keeps the rebuilding behvaiour
+    // as required for internal tests
+    let build_host = matches.get_flag(FLAG_BUILD_HOST);
+    let suppress_build_host_warning = matches.get_flag(FLAG_SUPPRESS_BUILD_HOST_WARNING);
+
+    let fuzz = matches.get_flag(FLAG_FUZZ);
+    if fuzz && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
+        user_error!("Cannot instrument binary for fuzzing while using a dev backend.");
+    }
+
+    let wasm_dev_stack_bytes: Option<u32> = matches
+        .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
+        .ok()
+        .flatten()
+        .map(|x| x * 1024);
+
+    let build_ordering = match config {
+        BuildAndRunIfNoErrors | BuildOnly => BuildOrdering::BuildIfChecks,
+        _ => BuildOrdering::AlwaysBuild,
+    };
+
+    let code_gen_options = CodeGenOptions {
+        backend: code_gen_backend,
+        opt_level,
+        emit_debug_info,
+        emit_llvm_ir,
+        fuzz,
+    };
+
+    let render = matches
+        .try_get_one::<String>(FLAG_FORMAT)
+        .ok()
+        .flatten()
+        .map_or(RenderTarget::ColorTerminal, |format| render_target(format));
+
+    let mut load_config = standard_load_config(target, build_ordering, threading);
+    load_config.render = render;
+
+    BuildOptions {
+        code_gen_options,
+        emit_timings,
+        linking_strategy,
+        build_host,
+        suppress_build_host_warning,
+        wasm_dev_stack_bytes,
+        should_run_expects,
+        render,
+        load_config,
     }
 }
 
@@ -1,15 +1,17 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
 use bumpalo::Bump;
 use roc_build::link::LinkType;
-use roc_build::program::{check_file, CodeGenBackend};
+use roc_build::program::{check_file, print_loading_report, CheckedFile, CodeGenBackend};
 use roc_cli::{
-    annotate_file, build_app, default_linking_strategy, format_files, format_src, test,
-    AnnotationProblem, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS,
+    annotate_file, build_app, default_linking_strategy, dev_watch, fix_files, format_files,
+    format_src, render_target, test, vendor_problem_message, warning_config, AnnotationProblem,
+    BuildConfig, FormatMode, PackageDirs, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_FIX,
     CMD_FORMAT, CMD_FORMAT_ANNOTATE, CMD_GLUE, CMD_LICENSES, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_DOCS_ROOT,
-    FLAG_LIB, FLAG_MAIN, FLAG_MIGRATE, FLAG_NO_COLOR, FLAG_NO_HEADER, FLAG_NO_LINK, FLAG_OUTPUT,
-    FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME,
-    FLAG_VERBOSE, GLUE_DIR, GLUE_SPEC, ROC_FILE, VERSION,
+    CMD_RUN, CMD_TEST, CMD_VENDOR, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV,
+    FLAG_DOCS_ROOT, FLAG_FORMAT, FLAG_LIB, FLAG_MAIN, FLAG_MIGRATE, FLAG_NO_COLOR, FLAG_NO_HEADER,
+    FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM, FLAG_STDIN,
+    FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, FLAG_VERBOSE, FLAG_WATCH, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    VERSION,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::{internal_error, user_error};
@@ -17,6 +19,9 @@ use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_load::{LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_packaging::vendor;
+use roc_reporting::report::RenderTarget;
+use roc_reporting::warnings::WarningConfig;
 use roc_target::Target;
 use std::fs::{self, FileType};
 use std::io::BufRead;
@@ -48,13 +53,16 @@ fn main() -> io::Result<()> {
     let exit_code = match matches.subcommand() {
         None => {
             if matches.contains_id(ROC_FILE) {
+                let package_dirs =
+                    PackageDirs::new(&matches, matches.get_one::<PathBuf>(ROC_FILE).unwrap());
+
                 build(
                     &matches,
                     &subcommands,
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host().into(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                    package_dirs.roc_cache_dir(),
                     LinkType::Executable,
                     false,
                 )
@@ -64,13 +72,16 @@ fn main() -> io::Result<()> {
         }
         Some((CMD_RUN, matches)) => {
             if matches.contains_id(ROC_FILE) {
+                let package_dirs =
+                    PackageDirs::new(matches, matches.get_one::<PathBuf>(ROC_FILE).unwrap());
+
                 build(
                     matches,
                     &subcommands,
                     BuildConfig::BuildAndRun,
                     Triple::host().into(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                    package_dirs.roc_cache_dir(),
                     LinkType::Executable,
                     false,
                 )
@@ -90,14 +101,19 @@ fn main() -> io::Result<()> {
             }
         }
         Some((CMD_DEV, matches)) => {
-            if matches.contains_id(ROC_FILE) {
+            if matches.contains_id(ROC_FILE) && matches.get_flag(FLAG_WATCH) {
+                dev_watch(matches, Triple::host().into())
+            } else if matches.contains_id(ROC_FILE) {
+                let package_dirs =
+                    PackageDirs::new(matches, matches.get_one::<PathBuf>(ROC_FILE).unwrap());
+
                 build(
                     matches,
                     &subcommands,
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host().into(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                    package_dirs.roc_cache_dir(),
                     LinkType::Executable,
                     false,
                 )
@@ -201,6 +217,8 @@ fn main() -> io::Result<()> {
                 .get_one::<OsString>(FLAG_OUTPUT)
                 .map(OsString::as_ref);
             let verbose = matches.get_flag(FLAG_VERBOSE);
+            let package_dirs =
+                PackageDirs::new(matches, matches.get_one::<PathBuf>(ROC_FILE).unwrap());
 
             Ok(build(
                 matches,
@@ -208,14 +226,12 @@ fn main() -> io::Result<()> {
                 BuildConfig::BuildOnly,
                 target,
                 out_path,
-                RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                package_dirs.roc_cache_dir(),
                 link_type,
                 verbose,
             )?)
         }
         Some((CMD_CHECK, matches)) => {
-            let arena = Bump::new();
-
             let emit_timings = matches.get_flag(FLAG_TIME);
             let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
             let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
@@ -226,94 +242,72 @@ fn main() -> io::Result<()> {
             };
 
             let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+            let render = render_target(matches.get_one::<String>(FLAG_FORMAT).unwrap());
 
-            match roc_file_path.extension().and_then(OsStr::to_str) {
-                Some("md") => {
-                    // Extract the blocks of roc code
-                    let file = fs::File::open(roc_file_path.as_path())?;
-                    let markdown_file_reader = io::BufReader::new(file);
-                    let mut roc_blocks: Vec<String> = Vec::new();
-                    let mut in_roc_block: bool = false;
-                    let mut current_block = String::new();
-
-                    for line in markdown_file_reader.lines() {
-                        let line = line.unwrap();
-                        if line == "```roc" {
-                            in_roc_block = true;
-                        } else if (line == "```") & in_roc_block {
-                            in_roc_block = false;
-                            roc_blocks.push(current_block);
-                            current_block = String::new();
-                        } else if in_roc_block {
-                            current_block.push_str(&line);
-                            current_block.push('\n');
-                        }
-                    }
-
-                    // now check each block, we exit early if any single block does not check
-                    let mut exit_code = 0;
-
-                    for block in roc_blocks.iter() {
-                        let mut file = Builder::new().suffix(".roc").tempfile()?;
-                        write!(file, "{}", block)?;
+            let check = |source_paths: &mut Vec<PathBuf>| -> io::Result<i32> {
+                let arena = Bump::new();
+                let warnings = warning_config(matches, roc_file_path);
+                let package_dirs = PackageDirs::new(matches, roc_file_path);
 
+                match roc_file_path.extension().and_then(OsStr::to_str) {
+                    Some("md") => check_markdown_file(
+                        &arena,
+                        roc_file_path,
+                        opt_main_path,
+                        emit_timings,
+                        package_dirs.roc_cache_dir(),
+                        threading,
+                        render,
+                        &warnings,
+                    ),
+                    _ => {
                         match check_file(
                             &arena,
-                            file.path().to_owned(),
+                            roc_file_path.to_owned(),
                             opt_main_path.cloned(),
                             emit_timings,
-                            RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                            package_dirs.roc_cache_dir(),
                             threading,
+                            render,
+                            &warnings,
                         ) {
-                            Ok((problems, total_time)) => {
-                                problems.print_error_warning_count(total_time);
-                                println!(".\n");
+                            Ok(CheckedFile {
+                                problems,
+                                total_time,
+                                source_paths: checked_paths,
+                            }) => {
+                                source_paths.extend(checked_paths);
+
+                                if !render.is_machine_readable() {
+                                    problems.print_error_warning_count(total_time);
+                                    println!(".\n");
+                                }
 
-                                exit_code = problems.exit_code();
+                                Ok(problems.exit_code())
                             }
 
                             Err(LoadingProblem::FormattedReport(report, _)) => {
-                                print!("{report}");
+                                print_loading_report(report, render);
 
-                                exit_code = 1;
+                                Ok(1)
                             }
                             Err(other) => {
                                 panic!("build_file failed with error:\n{other:?}");
                             }
                         }
-
-                        if exit_code != 0 {
-                            break;
-                        }
                     }
-
-                    Ok(exit_code)
                 }
-                _ => {
-                    match check_file(
-                        &arena,
-                        roc_file_path.to_owned(),
-                        opt_main_path.cloned(),
-                        emit_timings,
-                        RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
-                        threading,
-                    ) {
-                        Ok((problems, total_time)) => {
-                            problems.print_error_warning_count(total_time);
-                            println!(".\n");
-                            Ok(problems.exit_code())
-                        }
-
-                        Err(LoadingProblem::FormattedReport(report, _)) => {
-                            print!("{report}");
+            };
 
-                            Ok(1)
-                        }
-                        Err(other) => {
-                            panic!("build_file failed with error:\n{other:?}");
-                        }
-                    }
-                }
+            if matches.get_flag(FLAG_WATCH) {
+                // Clearing the terminal would mix escape codes into machine-readable output.
+                roc_cli::watch(
+                    &[roc_file_path.clone()],
+                    !render.is_machine_readable(),
+                    |_changed, source_paths| check(source_paths),
+                )
+            } else {
+                check(&mut Vec::new())
             }
         }
         Some((CMD_REPL, matches)) => {
@@ -348,6 +342,63 @@ fn main() -> io::Result<()> {
 
             Ok(0)
         }
+        Some((CMD_FIX, matches)) => {
+            let arena = Bump::new();
+            let roc_file_path = matches
+                .get_one::<PathBuf>(ROC_FILE)
+                .unwrap_or_else(|| internal_error!("No default for ROC_FILE"));
+
+            let fix_exit_code = match fix_files(&arena, roc_file_path.to_owned()) {
+                Ok(0) => {
+                    println!("Nothing to fix.");
+                    0
+                }
+                Ok(fixed) => {
+                    println!(
+                        "Fixed {fixed} {}.",
+                        if fixed == 1 { "problem" } else { "problems" }
+                    );
+                    0
+                }
+                Err(LoadingProblem::FormattedReport(report, ..)) => {
+                    eprintln!("{report}");
+                    1
+                }
+                Err(other) => {
+                    internal_error!("fix_files failed with error:\n{other:?}");
+                }
+            };
+
+            Ok(fix_exit_code)
+        }
+        Some((CMD_VENDOR, matches)) => {
+            let roc_file_path = matches
+                .get_one::<PathBuf>(ROC_FILE)
+                .unwrap_or_else(|| internal_error!("No default for ROC_FILE"));
+
+            let vendor_exit_code =
+                match vendor::vendor(roc_file_path, &cache::roc_cache_packages_dir()) {
+                    Ok(lock_file) => {
+                        let count = lock_file.packages.len();
+
+                        println!(
+                            "Vendored {count} {} into {}, and wrote {}.",
+                            if count == 1 { "package" } else { "packages" },
+                            vendor::vendor_dir(roc_file_path).display(),
+                            roc_file_path
+                                .with_file_name(vendor::LOCK_FILENAME)
+                                .display(),
+                        );
+                        0
+                    }
+                    Err(problem) => {
+                        eprintln!("{}", vendor_problem_message(problem, roc_file_path));
+                        1
+                    }
+                };
+
+            Ok(vendor_exit_code)
+        }
         Some((CMD_FORMAT, fmatches)) if Some(CMD_FORMAT_ANNOTATE) == fmatches.subcommand_name() => {
             let matches = fmatches
                 .subcommand_matches(CMD_FORMAT_ANNOTATE)
@@ -505,6 +556,87 @@ fn main() -> io::Result<()> {
     std::process::exit(exit_code);
 }
 
+fn check_markdown_file(
+    arena: &Bump,
+    roc_file_path: &Path,
+    opt_main_path: Option<&PathBuf>,
+    emit_timings: bool,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+    render: RenderTarget,
+    warnings: &WarningConfig,
+) -> io::Result<i32> {
+    if render.is_machine_readable() {
+        user_error!("Problems in markdown files can only be printed as text.");
+    }
+
+    // Extract the blocks of roc code
+    let file = fs::File::open(roc_file_path)?;
+    let markdown_file_reader = io::BufReader::new(file);
+    let mut roc_blocks: Vec<String> = Vec::new();
+    let mut in_roc_block: bool = false;
+    let mut current_block = String::new();
+
+    for line in markdown_file_reader.lines() {
+        let line = line.unwrap();
+        if line == "```roc" {
+            in_roc_block = true;
+        } else if (line == "```") & in_roc_block {
+            in_roc_block = false;
+            roc_blocks.push(current_block);
+            current_block = String::new();
+        } else if in_roc_block {
+            current_block.push_str(&line);
+            current_block.push('\n');
+        }
+    }
+
+    // now check each block, we exit early if any single block does not check
+    let mut exit_code = 0;
+
+    for block in roc_blocks.iter() {
+        let mut file = Builder::new().suffix(".roc").tempfile()?;
+        write!(file, "{}", block)?;
+
+        match check_file(
+            arena,
+            file.path().to_owned(),
+            opt_main_path.cloned(),
+            emit_timings,
+            roc_cache_dir,
+            threading,
+            render,
+            warnings,
+        ) {
+            Ok(CheckedFile {
+                problems,
+                total_time,
+                ..
+            }) => {
+                problems.print_error_warning_count(total_time);
+                println!(".\n");
+
+                exit_code = problems.exit_code();
+            }
+
+            Err(LoadingProblem::FormattedReport(report, _)) => {
+                print!("{report}");
+
+                exit_code = 1;
+            }
+            Err(other) => {
+                panic!("build_file failed with error:\n{other:?}");
+            }
+        }
+
+        if exit_code != 0 {
+            break;
+        }
+    }
+
+    Ok(exit_code)
+}
+
 fn read_all_roc_files(
     dir: &OsString,
     roc_file_paths: &mut Vec<OsString>,
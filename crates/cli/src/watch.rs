@@ -0,0 +1,380 @@
+//! `--watch` for `roc check`, `roc test` and `roc dev`: run again whenever one of the modules
+//! that the previous run loaded changes.
+//!
+//! Every run loads with the module cache, so modules whose source and imports didn't change
+//! aren't canonicalized and solved again, and `roc test` only runs the tests of the modules that
+//! the changes affect.
+#[cfg(not(windows))]
+use roc_collections::{MutMap, MutSet};
+#[cfg(not(windows))]
+use roc_module::symbol::ModuleId;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Editors often save a file in several steps, so wait this long for them to finish.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Calls `run` again every time one of the files it loaded changes, until the process is
+/// stopped. `run` adds the path of every module it loaded to the `Vec` it's given. If it
+/// couldn't load anything, e.g. because of a syntax error, the files from before are watched.
+///
+/// `run` is also given the files that changed since the last run that exited with 0, so it can
+/// skip work that only depends on files that didn't change. It's given None if no run has
+/// exited with 0 yet, which means everything has to be done.
+pub fn watch(
+    roots: &[PathBuf],
+    clear_terminal: bool,
+    mut run: impl FnMut(Option<&[PathBuf]>, &mut Vec<PathBuf>) -> io::Result<i32>,
+) -> io::Result<i32> {
+    let mut watched = WatchedFiles::new(roots.iter().cloned());
+    let mut changed: Option<Vec<PathBuf>> = None;
+
+    loop {
+        if clear_terminal {
+            // Clear the screen and the scrollback, and move the cursor to the top left.
+            print!("\x1B[2J\x1B[3J\x1B[H");
+            io::stdout().flush()?;
+        }
+
+        let mut source_paths = Vec::new();
+
+        let succeeded = match run(changed.as_deref(), &mut source_paths) {
+            Ok(exit_code) => exit_code == 0,
+            Err(err) => {
+                eprintln!("{err}");
+
+                false
+            }
+        };
+
+        if !source_paths.is_empty() {
+            source_paths.extend(roots.iter().cloned());
+            watched = watched.with_paths(source_paths);
+        }
+
+        println!(
+            "\n\x1B[36mWatching {} files for changes… (press Ctrl-C to stop)\x1B[39m",
+            watched.files.len()
+        );
+
+        let mut new_changes = watched.changes();
+        while new_changes.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+            new_changes = watched.changes();
+        }
+
+        thread::sleep(SETTLE_TIME);
+        new_changes.extend(watched.changes());
+
+        changed = match changed {
+            // Whatever the failed run didn't get to still has to be done.
+            Some(paths) if !succeeded => Some(paths.into_iter().chain(new_changes).collect()),
+            None if !succeeded => None,
+            _ => Some(new_changes),
+        };
+
+        if let Some(paths) = changed.as_mut() {
+            paths.sort();
+            paths.dedup();
+        }
+    }
+}
+
+/// The modules whose source is one of the `changed` files, and the modules that import one of
+/// those, directly or not.
+#[cfg(not(windows))]
+pub fn affected_modules(
+    changed: &[PathBuf],
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    imports: &MutMap<ModuleId, MutSet<ModuleId>>,
+) -> MutSet<ModuleId> {
+    let mut affected: MutSet<ModuleId> = sources
+        .iter()
+        .filter(|(_, (path, _))| changed.contains(path))
+        .map(|(module_id, _)| *module_id)
+        .collect();
+
+    loop {
+        let importers: Vec<ModuleId> = imports
+            .iter()
+            .filter(|(module_id, deps)| {
+                !affected.contains(*module_id) && deps.iter().any(|dep| affected.contains(dep))
+            })
+            .map(|(module_id, _)| *module_id)
+            .collect();
+
+        if importers.is_empty() {
+            return affected;
+        }
+
+        affected.extend(importers);
+    }
+}
+
+/// The files to watch, and what they looked like the last time we checked.
+///
+/// Files count as changed when their contents do, so saving a file without changing it doesn't
+/// start a new run. The directories that contain them are watched for `.roc` files being added
+/// or removed, which catches new modules that nothing could import before they existed.
+#[derive(Debug)]
+pub struct WatchedFiles {
+    files: Vec<WatchedFile>,
+    dirs: Vec<(PathBuf, Vec<PathBuf>)>,
+}
+
+#[derive(Debug)]
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<(SystemTime, u64)>,
+    hash: Option<u64>,
+}
+
+impl WatchedFile {
+    fn new(path: PathBuf) -> Self {
+        WatchedFile {
+            modified: modified(&path),
+            hash: content_hash(&path),
+            path,
+        }
+    }
+}
+
+impl WatchedFiles {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        WatchedFiles {
+            files: Vec::new(),
+            dirs: Vec::new(),
+        }
+        .with_paths(paths)
+    }
+
+    /// Watch `paths` instead. Files that were already watched keep their last known state, so
+    /// changes made in the meantime still show up.
+    pub fn with_paths(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut paths: Vec<PathBuf> = paths.into_iter().collect();
+        paths.sort();
+        paths.dedup();
+
+        // `roc test` can be given a directory
+        let (dirs, files): (Vec<PathBuf>, Vec<PathBuf>) =
+            paths.into_iter().partition(|path| path.is_dir());
+
+        let mut dir_paths: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|path| path.parent())
+            .map(|dir| match dir.as_os_str().is_empty() {
+                true => PathBuf::from("."),
+                false => dir.to_path_buf(),
+            })
+            .chain(dirs)
+            .collect();
+        dir_paths.sort();
+        dir_paths.dedup();
+
+        let files = files
+            .into_iter()
+            .map(
+                |path| match self.files.iter().position(|file| file.path == path) {
+                    Some(index) => self.files.swap_remove(index),
+                    None => WatchedFile::new(path),
+                },
+            )
+            .collect();
+
+        let dirs = dir_paths
+            .into_iter()
+            .map(
+                |dir| match self.dirs.iter().position(|(path, _)| *path == dir) {
+                    Some(index) => self.dirs.swap_remove(index),
+                    None => {
+                        let roc_files = roc_files_in(&dir);
+                        (dir, roc_files)
+                    }
+                },
+            )
+            .collect();
+
+        WatchedFiles { files, dirs }
+    }
+
+    /// The files that were changed, added or removed since the last time this was called.
+    pub fn changes(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+
+        for file in self.files.iter_mut() {
+            let modified = modified(&file.path);
+
+            // Only read the file again if it looks like it was written to.
+            if modified != file.modified {
+                file.modified = modified;
+
+                let hash = content_hash(&file.path);
+
+                if hash != file.hash {
+                    file.hash = hash;
+                    changed.push(file.path.clone());
+                }
+            }
+        }
+
+        for (dir, roc_files) in self.dirs.iter_mut() {
+            let current = roc_files_in(dir);
+
+            if current != *roc_files {
+                changed.extend(
+                    current
+                        .iter()
+                        .filter(|path| !roc_files.contains(path))
+                        .chain(roc_files.iter().filter(|path| !current.contains(path)))
+                        .cloned(),
+                );
+
+                *roc_files = current;
+            }
+        }
+
+        changed.sort();
+        changed.dedup();
+
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let bytes = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+
+    Some(hasher.finish())
+}
+
+fn roc_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut roc_files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "roc"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    roc_files.sort();
+
+    roc_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(windows))]
+    use roc_module::ident::ModuleName;
+    #[cfg(not(windows))]
+    use roc_module::symbol::ModuleIds;
+    use tempfile::tempdir;
+
+    #[test]
+    fn changed_contents() {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main.roc");
+        let other = dir.path().join("Other.roc");
+        fs::write(&main, "app [main] {}").unwrap();
+        fs::write(&other, "module []").unwrap();
+
+        let mut watched = WatchedFiles::new([main.clone(), other.clone()]);
+        assert!(watched.changes().is_empty());
+
+        fs::write(&other, "module [x]\n\nx = 1").unwrap();
+        assert_eq!(watched.changes(), [other.as_path()]);
+        assert!(watched.changes().is_empty());
+
+        // Writing the same contents again isn't a change, even though the file was written.
+        let contents = fs::read(&other).unwrap();
+        fs::write(&other, contents).unwrap();
+        assert!(watched.changes().is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_modules() {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main.roc");
+        let new_module = dir.path().join("New.roc");
+        fs::write(&main, "app [main] {}").unwrap();
+
+        let mut watched = WatchedFiles::new([main.clone()]);
+
+        fs::write(dir.path().join("notes.txt"), "not a module").unwrap();
+        assert!(watched.changes().is_empty());
+
+        fs::write(&new_module, "module []").unwrap();
+        assert_eq!(watched.changes(), [new_module.as_path()]);
+
+        fs::remove_file(&new_module).unwrap();
+        assert_eq!(watched.changes(), [new_module]);
+    }
+
+    #[test]
+    fn changes_between_runs_are_kept() {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main.roc");
+        let other = dir.path().join("Other.roc");
+        fs::write(&main, "app [main] {}").unwrap();
+        fs::write(&other, "module []").unwrap();
+
+        let watched = WatchedFiles::new([main.clone()]);
+
+        // The file changes while the next run is loading it
+        fs::write(&main, "app [main] { pf: platform \"p\" }").unwrap();
+
+        let mut watched = watched.with_paths([main.clone(), other]);
+        assert_eq!(watched.changes(), [main]);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn modules_affected_by_a_change() {
+        let mut module_ids = ModuleIds::default();
+        let mut sources: MutMap<ModuleId, (PathBuf, Box<str>)> = MutMap::default();
+        let mut module = |name: &str| {
+            let module_id = module_ids.get_or_insert(&ModuleName::from(name));
+            let path = PathBuf::from(format!("{name}.roc"));
+            sources.insert(module_id, (path, "".into()));
+
+            module_id
+        };
+
+        let main = module("Main");
+        let parser = module("Parser");
+        let lexer = module("Lexer");
+        let util = module("Util");
+
+        // Main imports Parser and Util, and Parser imports Lexer
+        let imports: MutMap<ModuleId, MutSet<ModuleId>> = [
+            (main, [parser, util].into_iter().collect()),
+            (parser, [lexer].into_iter().collect()),
+            (lexer, MutSet::default()),
+            (util, MutSet::default()),
+        ]
+        .into_iter()
+        .collect();
+
+        let affected = affected_modules(&[PathBuf::from("Lexer.roc")], &sources, &imports);
+        assert_eq!(affected, [lexer, parser, main].into_iter().collect());
+
+        let affected = affected_modules(&[PathBuf::from("Util.roc")], &sources, &imports);
+        assert_eq!(affected, [util, main].into_iter().collect());
+
+        let affected = affected_modules(&[PathBuf::from("New.roc")], &sources, &imports);
+        assert!(affected.is_empty());
+    }
+}
@@ -252,6 +252,85 @@ mod cli_tests {
         insta::assert_snapshot!(cli_test_out.normalize_stdout_and_stderr());
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn transitive_expects_filter() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root(
+                "crates/cli/tests/test-projects/expects_transitive",
+                "main.roc",
+            ),
+        )
+        .arg("--filter")
+        .arg("3, 4");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_clean_success();
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "0 failed and 1 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn transitive_expects_list() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root(
+                "crates/cli/tests/test-projects/expects_transitive",
+                "main.roc",
+            ),
+        )
+        .arg("--list");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_clean_success();
+        cli_test_out
+            .assert_stdout_and_stderr_ends_with("Transitive.roc:7: expect add(1, 2) == 3\n");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_list_reports_warnings() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root(
+                "crates/cli/tests/test-projects/known_bad",
+                "UnusedImport.roc",
+            ),
+        )
+        .arg("--list");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn isolated_expects() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root(
+                "crates/cli/tests/test-projects/expects_isolated",
+                "main.roc",
+            ),
+        )
+        .arg("--timeout")
+        .arg("1");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(output.contains("EXPECT TIMED OUT"), "{output}");
+        assert!(output.contains("EXPECT PANICKED"), "{output}");
+        assert!(output.contains("boom"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "2 failed and 1 passed in <ignored for test> ms.\n",
+        );
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn multiple_exposed() {
@@ -842,6 +921,32 @@ mod cli_tests {
 
             insta::assert_snapshot!(cli_test_out.normalize_stdout_and_stderr());
         }
+
+        #[test]
+        #[cfg_attr(
+            not(all(unix, target_arch = "x86_64")),
+            ignore = "only checked on x86_64 unix so far"
+        )]
+        fn expects_dbg_dev_backend() {
+            build_platform_host();
+
+            let cli_test = ExecCli::new(
+                CMD_TEST,
+                file_from_root("crates/cli/tests/test-projects/expects", "dbg_expects.roc"),
+            )
+            .arg("--dev");
+
+            let cli_test_out = cli_test.run();
+            cli_test_out.assert_clean_success();
+
+            // roc_dbg is called in the expect itself and in a function the expect calls
+            let output = cli_test_out.normalize_stdout_and_stderr();
+            assert!(output.contains("dbg_expects.roc:10] y = 21\n"), "{output}");
+            assert!(output.contains("dbg_expects.roc:4] x = 21\n"), "{output}");
+            cli_test_out.assert_stdout_and_stderr_ends_with(
+                "0 failed and 1 passed in <ignored for test> ms.\n",
+            );
+        }
     }
 
     mod test_platform_effects_zig {
@@ -0,0 +1,14 @@
+app [main] { pf: platform "../test-platform-simple-zig/main.roc" }
+
+double = \x ->
+    dbg x
+
+    x * 2
+
+expect
+    y = 21i64
+    dbg y
+
+    double(y) == 42
+
+main = "Program finished!\n"
@@ -0,0 +1,15 @@
+module [
+    count_up,
+    boom,
+]
+
+## Never returns, because it calls itself forever.
+count_up = \n -> count_up(n + 1)
+
+boom = \{} -> crash "boom"
+
+expect count_up(0) == 0
+
+expect boom({}) == 1
+
+expect 1 + 1 == 2
@@ -589,6 +589,10 @@ fn stmt_spec<'a>(
 
                         continuation = c;
                     }
+                    Located { remainder: c, .. } => {
+                        // every def is located, so these are just as common
+                        continuation = c;
+                    }
                     _ => break,
                 }
             }
@@ -625,6 +629,7 @@ fn stmt_spec<'a>(
         }
         Dbg { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
         Expect { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
+        Located { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
         Ret(symbol) => Ok(env.symbols[symbol]),
         Refcounting(modify_rc, continuation) => {
             apply_refcount_operation(builder, env, block, modify_rc)?;
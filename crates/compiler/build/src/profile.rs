@@ -0,0 +1,222 @@
+//! Profiles of the compiler for `--profile-compiler`, in the trace event format that
+//! Perfetto and chrome://tracing show as a timeline.
+use std::path::Path;
+use std::time::Instant;
+
+use roc_collections::MutMap;
+use roc_load::{current_thread_index, ModuleTiming};
+use roc_module::symbol::{Interns, ModuleId};
+use serde_json::{json, Value};
+
+/// Every event is in the same process
+const PID: u32 = 1;
+
+pub struct CompilerProfile {
+    /// Timestamps in the trace are relative to this
+    origin: Instant,
+    main_thread: u32,
+    events: Vec<Value>,
+}
+
+impl CompilerProfile {
+    /// Starts a profile on the thread that coordinates the build.
+    pub fn new(origin: Instant) -> Self {
+        CompilerProfile {
+            origin,
+            main_thread: current_thread_index(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Adds a span for each phase of each module, on the worker thread it ran on.
+    pub fn add_modules(&mut self, timings: &MutMap<ModuleId, ModuleTiming>, interns: &Interns) {
+        let mut modules: Vec<_> = timings.iter().collect();
+        modules.sort_by_key(|(_, timing)| timing.start_time);
+
+        for (module_id, timing) in modules {
+            let module_name = match interns.module_ids.get_name(*module_id) {
+                Some(name) if !name.is_empty() => name.as_str(),
+                // The app module has no name
+                _ => "app",
+            };
+
+            for span in timing.spans.iter() {
+                let event = self.complete_event(
+                    span.phase.name(),
+                    "module",
+                    span.thread,
+                    span.start,
+                    span.end,
+                    json!({ "module": module_name }),
+                );
+
+                self.events.push(event);
+            }
+        }
+    }
+
+    /// Adds a span for a phase of the whole build, like code gen, on the coordinating thread.
+    pub fn add(&mut self, name: &str, start: Instant, end: Instant) {
+        let event = self.complete_event(name, "build", self.main_thread, start, end, json!({}));
+
+        self.events.push(event);
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string(&self.trace())?)
+    }
+
+    /// The whole profile, with a name for each thread that has spans.
+    fn trace(&self) -> Value {
+        let mut threads: Vec<u32> = self
+            .events
+            .iter()
+            .filter_map(|event| event["tid"].as_u64())
+            .map(|tid| tid as u32)
+            .collect();
+        threads.sort_unstable();
+        threads.dedup();
+
+        // Name the threads, so the timeline doesn't just show numbers
+        let thread_names = threads.into_iter().map(|tid| {
+            let name = if tid == self.main_thread {
+                "main".to_string()
+            } else {
+                format!("worker {tid}")
+            };
+
+            json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": PID,
+                "tid": tid,
+                "args": { "name": name },
+            })
+        });
+
+        let trace_events: Vec<Value> = thread_names.chain(self.events.iter().cloned()).collect();
+
+        json!({
+            "traceEvents": trace_events,
+            "displayTimeUnit": "ms",
+        })
+    }
+
+    /// A "complete" event, which has a start and a duration. Times are in microseconds.
+    fn complete_event(
+        &self,
+        name: &str,
+        category: &str,
+        thread: u32,
+        start: Instant,
+        end: Instant,
+        args: Value,
+    ) -> Value {
+        let ts = start.saturating_duration_since(self.origin).as_secs_f64() * 1_000_000.0;
+        let dur = end.saturating_duration_since(start).as_secs_f64() * 1_000_000.0;
+
+        json!({
+            "name": name,
+            "cat": category,
+            "ph": "X",
+            "ts": ts,
+            "dur": dur,
+            "pid": PID,
+            "tid": thread,
+            "args": args,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_load::TimedPhase;
+    use roc_module::ident::ModuleName;
+    use std::time::Duration;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// The start and end of a complete event, in microseconds
+    fn bounds(event: &Value) -> (f64, f64) {
+        let ts = event["ts"].as_f64().unwrap();
+
+        (ts, ts + event["dur"].as_f64().unwrap())
+    }
+
+    #[test]
+    fn span_names_and_nesting() {
+        let origin = Instant::now();
+        let mut interns = Interns::default();
+        let module_id = interns.module_id(&ModuleName::from("Foo"));
+
+        let mut timing = ModuleTiming::new(origin);
+        timing.record(TimedPhase::ParseBody, origin + ms(1), origin + ms(2));
+        timing.record(TimedPhase::Solve, origin + ms(3), origin + ms(5));
+
+        let mut timings = MutMap::default();
+        timings.insert(module_id, timing);
+
+        let mut profile = CompilerProfile::new(origin);
+        profile.add("load", origin, origin + ms(6));
+        profile.add_modules(&timings, &interns);
+        profile.add("code gen", origin + ms(6), origin + ms(10));
+        profile.add("generate final IR", origin + ms(6), origin + ms(8));
+        profile.add("generate object", origin + ms(8), origin + ms(10));
+
+        let trace = profile.trace();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let spans: Vec<&Value> = events.iter().filter(|event| event["ph"] == "X").collect();
+        let span = |name: &str| *spans.iter().find(|event| event["name"] == name).unwrap();
+
+        let names: Vec<&str> = spans
+            .iter()
+            .map(|event| event["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "load",
+                "parse body",
+                "solve",
+                "code gen",
+                "generate final IR",
+                "generate object"
+            ]
+        );
+        assert_eq!(span("solve")["args"]["module"], "Foo");
+
+        // Rounding microseconds can move the ends of spans that end together apart a little
+        let epsilon = 0.001;
+
+        for (parent, children) in [
+            ("load", ["parse body", "solve"]),
+            ("code gen", ["generate final IR", "generate object"]),
+        ] {
+            let (start, end) = bounds(span(parent));
+
+            for child in children {
+                let (child_start, child_end) = bounds(span(child));
+
+                assert!(
+                    start <= child_start + epsilon && child_end <= end + epsilon,
+                    "{child} is not within {parent}"
+                );
+            }
+        }
+
+        assert!(bounds(span("load")).1 <= bounds(span("code gen")).0 + epsilon);
+
+        // Everything here ran on the thread that made the profile
+        let main_thread = events
+            .iter()
+            .find(|event| event["ph"] == "M" && event["tid"] == span("load")["tid"])
+            .unwrap();
+        assert_eq!(main_thread["args"]["name"], "main");
+        assert!(spans
+            .iter()
+            .all(|event| event["tid"] == span("load")["tid"]));
+    }
+}
@@ -1,20 +1,25 @@
 use crate::link::{link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy};
+use crate::profile::CompilerProfile;
 use bumpalo::collections::CollectIn;
 use bumpalo::Bump;
 use inkwell::memory_buffer::MemoryBuffer;
 use roc_error_macros::internal_error;
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
+use roc_gen_llvm::llvm::debug_info::DebugSources;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, LoadConfig, LoadMonomorphizedError,
     LoadedModule, LoadingProblem, MonomorphizedModule, Threading,
 };
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::{
     cli::{report_problems, Problems},
+    machine_readable::{render_diagnostics, Diagnostic},
     report::{RenderTarget, DEFAULT_PALETTE},
+    warnings::WarningConfig,
 };
 use roc_target::{Architecture, Target};
 use std::ops::Deref;
@@ -34,23 +39,37 @@ pub struct CodeGenTiming {
     pub generate_final_ir: Duration,
     pub code_gen_object: Duration,
     pub total: Duration,
+    /// When alias analysis started and how long it took, for the backends that do it
+    pub alias_analysis: Option<(Instant, Duration)>,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    render: RenderTarget,
+    warnings: &WarningConfig,
+) -> Problems {
     report_problems(
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
+        warnings,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(
+    loaded: &mut LoadedModule,
+    render: RenderTarget,
+    warnings: &WarningConfig,
+) -> Problems {
     report_problems(
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
+        warnings,
     )
 }
 
@@ -203,6 +222,7 @@ fn gen_from_mono_module_llvm<'a>(
 
     let builder = context.create_builder();
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let debug_sources = DebugSources::new(&dibuilder, &loaded.sources);
 
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
@@ -222,6 +242,7 @@ fn gen_from_mono_module_llvm<'a>(
             .keys()
             .copied()
             .collect(),
+        debug_sources,
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -247,7 +268,8 @@ fn gen_from_mono_module_llvm<'a>(
         EntryPoint::Test => roc_mono::ir::EntryPoint::Expects { symbols: &[] },
     };
 
-    roc_gen_llvm::llvm::build::build_procedures(
+    let alias_analysis_start = Instant::now();
+    let alias_analysis = roc_gen_llvm::llvm::build::build_procedures(
         &env,
         &loaded.layout_interner,
         opt_level,
@@ -402,6 +424,7 @@ fn gen_from_mono_module_llvm<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis: Some((alias_analysis_start, alias_analysis)),
         },
         ExpectMetadata {
             interns: env.interns,
@@ -495,6 +518,8 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        runs_expects: false,
+        inline_budget: Some(InlineBudget::DEFAULT),
     };
 
     let host_bytes = std::fs::read(built_host_path).unwrap_or_else(|_| {
@@ -532,6 +557,7 @@ fn gen_from_mono_module_dev_wasm32<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis: None,
         },
         ExpectMetadata {
             interns,
@@ -558,6 +584,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         mut interns,
         exposed_to_host,
         mut layout_interner,
+        sources,
         ..
     } = loaded;
 
@@ -567,6 +594,8 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        module_sources: sources,
+        inline_budget: Some(InlineBudget::DEFAULT),
     };
 
     let module_object =
@@ -588,6 +617,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis: None,
         },
         ExpectMetadata {
             interns,
@@ -614,6 +644,15 @@ pub struct BuiltFile<'a> {
     pub problems: Problems,
     pub total_time: Duration,
     pub expect_metadata: ExpectMetadata<'a>,
+    /// Every module that went into the binary, including the platform and packages
+    pub source_paths: Vec<PathBuf>,
+}
+
+pub struct CheckedFile {
+    pub problems: Problems,
+    pub total_time: Duration,
+    /// Every module that was checked, including the platform and packages
+    pub source_paths: Vec<PathBuf>,
 }
 
 pub enum BuildOrdering {
@@ -650,20 +689,35 @@ impl<'a> BuildFileError<'a> {
 pub fn handle_error_module(
     mut module: roc_load::LoadedModule,
     total_time: std::time::Duration,
+    render: RenderTarget,
+    warnings: &WarningConfig,
 ) -> std::io::Result<i32> {
     debug_assert!(module.total_problems() > 0);
 
-    let problems = report_problems_typechecked(&mut module);
+    let problems = report_problems_typechecked(&mut module, render, warnings);
 
-    problems.print_error_warning_count(total_time);
+    print_progress(render, &problems.error_warning_count(total_time));
 
     Ok(problems.exit_code())
 }
 
-pub fn handle_loading_problem(problem: LoadingProblem) -> std::io::Result<i32> {
+/// Prints progress like timings and problem counts. Machine-readable output has to be the only
+/// thing on stdout, so when there is some, progress goes to stderr instead.
+pub fn print_progress(render: RenderTarget, text: &str) {
+    if render.is_machine_readable() {
+        eprint!("{text}");
+    } else {
+        print!("{text}");
+    }
+}
+
+pub fn handle_loading_problem(
+    problem: LoadingProblem,
+    render: RenderTarget,
+) -> std::io::Result<i32> {
     match problem {
         LoadingProblem::FormattedReport(report, _) => {
-            print!("{report}");
+            print_loading_report(report, render);
             Ok(1)
         }
         _ => {
@@ -675,6 +729,16 @@ pub fn handle_loading_problem(problem: LoadingProblem) -> std::io::Result<i32> {
     }
 }
 
+/// Prints a report that was already rendered while loading, like a parse error.
+pub fn print_loading_report(report: String, render: RenderTarget) {
+    let diagnostics = [Diagnostic::from_loading_problem(report.clone())];
+
+    match render_diagnostics(render, &diagnostics) {
+        Some(document) => println!("{document}"),
+        None => print!("{report}"),
+    }
+}
+
 pub fn standard_load_config(
     target: Target,
     order: BuildOrdering,
@@ -692,6 +756,9 @@ pub fn standard_load_config(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: Some(roc_packaging::cache::roc_cache_modules_dir()),
     }
 }
 
@@ -708,11 +775,22 @@ pub fn build_file<'a>(
     suppress_build_host_warning: bool,
     wasm_dev_stack_bytes: Option<u32>,
     roc_cache_dir: RocCacheDir<'_>,
-    load_config: LoadConfig,
+    mut load_config: LoadConfig,
+    warnings: &WarningConfig,
     out_path: Option<&Path>,
+    profile_path: Option<&Path>,
     verbose: bool,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
+    let render = load_config.render;
+
+    // LLVM inlines and folds constants on its own, the other backends emit code for every proc
+    // and statement they are given
+    load_config.inline_budget = match code_gen_options.backend {
+        CodeGenBackend::Llvm(_) => None,
+        CodeGenBackend::Assembly(_) | CodeGenBackend::Wasm => Some(InlineBudget::DEFAULT),
+    };
+    load_config.simplify = !matches!(code_gen_options.backend, CodeGenBackend::Llvm(_));
 
     let loaded = roc_load::load_and_monomorphize(
         arena,
@@ -734,9 +812,12 @@ pub fn build_file<'a>(
         build_host,
         suppress_build_host_warning,
         wasm_dev_stack_bytes,
+        render,
+        warnings,
         loaded,
         compilation_start,
         out_path,
+        profile_path,
         verbose,
     )
 }
@@ -756,6 +837,7 @@ fn build_and_preprocess_host(
     code_gen_options: CodeGenOptions,
     dll_stub_symbols: Vec<String>,
     emit_timings: bool,
+    render: RenderTarget,
     linking_strategy: LinkingStrategy,
     platform_main_roc: &Path,
     preprocessed_host_path: &Path,
@@ -791,9 +873,9 @@ fn build_and_preprocess_host(
     };
     let (rebuild_duration, path) = rebuild_thread.join().expect("Failed to build host.");
     if emit_timings {
-        println!(
-            "Finished rebuilding the platform host in {} ms\n",
-            rebuild_duration
+        print_progress(
+            render,
+            &format!("Finished rebuilding the platform host in {rebuild_duration} ms\n\n"),
         );
     }
     path
@@ -811,11 +893,18 @@ fn build_loaded_file<'a>(
     build_host_requested: bool,
     suppress_build_host_warning: bool,
     wasm_dev_stack_bytes: Option<u32>,
+    render: RenderTarget,
+    warnings: &WarningConfig,
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
     out_path: Option<&Path>,
+    profile_path: Option<&Path>,
     verbose: bool,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
+    // Loading finished right before we were called; building the host and reporting problems
+    // below aren't part of it.
+    let load_end = Instant::now();
+
     // get the platform path from the app header
     let platform_main_roc_path = match &loaded.entry_point {
         EntryPoint::Executable { platform_path, .. } => platform_path.to_path_buf(),
@@ -846,6 +935,7 @@ fn build_loaded_file<'a>(
                         code_gen_options,
                         dll_stub_symbols,
                         emit_timings,
+                        render,
                         linking_strategy,
                         &platform_main_roc_path,
                         &output_exe_path,
@@ -890,9 +980,24 @@ fn build_loaded_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, render, warnings);
     let loaded = loaded;
 
+    let mut profile = profile_path.map(|_| {
+        let mut profile = CompilerProfile::new(compilation_start);
+        profile.add("load", compilation_start, load_end);
+        profile.add_modules(&loaded.timings, &loaded.interns);
+
+        profile
+    });
+
+    let source_paths = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let code_gen_start = Instant::now();
     let (roc_app_bytes, code_gen_timing, expect_metadata) = gen_from_mono_module(
         arena,
         loaded,
@@ -913,22 +1018,50 @@ fn build_loaded_file<'a>(
         "Generate final IR from Mono IR",
         code_gen_timing.generate_final_ir,
     );
+    if let Some((_, alias_analysis)) = code_gen_timing.alias_analysis {
+        report_timing(buf, "Alias analysis", alias_analysis);
+    }
     report_timing(buf, "Generate object", code_gen_timing.code_gen_object);
     buf.push('\n');
     report_timing(buf, "Total", code_gen_timing.total);
 
+    if let Some(profile) = profile.as_mut() {
+        let final_ir_end = code_gen_start + code_gen_timing.generate_final_ir;
+
+        profile.add(
+            "code gen",
+            code_gen_start,
+            code_gen_start + code_gen_timing.total,
+        );
+        profile.add("generate final IR", code_gen_start, final_ir_end);
+        if let Some((start, duration)) = code_gen_timing.alias_analysis {
+            profile.add("alias analysis", start, start + duration);
+        }
+        profile.add(
+            "generate object",
+            final_ir_end,
+            final_ir_end + code_gen_timing.code_gen_object,
+        );
+    }
+
     let compilation_end = compilation_start.elapsed();
     let size = roc_app_bytes.len();
 
     if emit_timings {
-        println!(
-            "\n\nCompilation finished!\n\nHere's how long each module took to compile:\n\n{buf}"
+        print_progress(
+            render,
+            &format!(
+                "\n\nCompilation finished!\n\nHere's how long each module took to compile:\n\n{buf}\n"
+            ),
         );
 
-        println!(
-            "Finished compilation and code gen in {} ms\n\nProduced a app.o file of size {:?}\n",
-            compilation_end.as_millis(),
-            size,
+        print_progress(
+            render,
+            &format!(
+                "Finished compilation and code gen in {} ms\n\nProduced a app.o file of size {:?}\n\n",
+                compilation_end.as_millis(),
+                size,
+            ),
         );
     }
 
@@ -1027,7 +1160,22 @@ fn build_loaded_file<'a>(
     let linking_time = link_start.elapsed();
 
     if emit_timings {
-        println!("Finished linking in {} ms\n", linking_time.as_millis());
+        print_progress(
+            render,
+            &format!("Finished linking in {} ms\n\n", linking_time.as_millis()),
+        );
+    }
+
+    if let (Some(mut profile), Some(profile_path)) = (profile, profile_path) {
+        profile.add("link", link_start, link_start + linking_time);
+
+        // The build itself succeeded, so this shouldn't fail it
+        if let Err(err) = profile.write(profile_path) {
+            eprintln!(
+                "Could not write the compiler profile to {}: {err}",
+                profile_path.display()
+            );
+        }
     }
 
     let total_time = compilation_start.elapsed();
@@ -1037,6 +1185,7 @@ fn build_loaded_file<'a>(
         problems,
         total_time,
         expect_metadata,
+        source_paths,
     })
 }
 
@@ -1335,7 +1484,9 @@ pub fn check_file<'a>(
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
-) -> Result<(Problems, Duration), LoadingProblem<'a>> {
+    render: RenderTarget,
+    warnings: &WarningConfig,
+) -> Result<CheckedFile, LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
     // only used for generating errors. We don't do code generation, so hardcoding should be fine
@@ -1347,11 +1498,13 @@ pub fn check_file<'a>(
     let load_config = LoadConfig {
         target,
         function_kind: FunctionKind::from_env(),
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render,
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: Some(roc_packaging::cache::roc_cache_modules_dir()),
     };
     let mut loaded = roc_load::load_and_typecheck(
         arena,
@@ -1396,14 +1549,33 @@ pub fn check_file<'a>(
     let compilation_end = compilation_start.elapsed();
 
     if emit_timings {
-        println!(
-            "\n\nCompilation finished!\n\nHere's how long each module took to compile:\n\n{buf}"
+        print_progress(
+            render,
+            &format!(
+                "\n\nCompilation finished!\n\nHere's how long each module took to compile:\n\n{buf}\n"
+            ),
         );
 
-        println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
+        print_progress(
+            render,
+            &format!(
+                "Finished checking in {} ms\n\n",
+                compilation_end.as_millis()
+            ),
+        );
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    let source_paths = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    Ok(CheckedFile {
+        problems: report_problems_typechecked(&mut loaded, render, warnings),
+        total_time: compilation_end,
+        source_paths,
+    })
 }
 
 pub fn build_str_test<'a>(
@@ -1432,6 +1604,7 @@ pub fn build_str_test<'a>(
     let threading = Threading::AtMost(2);
 
     let load_config = standard_load_config(target, build_ordering, threading);
+    let render = load_config.render;
 
     let compilation_start = std::time::Instant::now();
 
@@ -1461,9 +1634,12 @@ pub fn build_str_test<'a>(
         build_host_requested,
         suppress_build_host_warning,
         wasm_dev_stack_bytes,
+        render,
+        &WarningConfig::default(),
         loaded,
         compilation_start,
         None,
+        None,
         false,
     )
 }
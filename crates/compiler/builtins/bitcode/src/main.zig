@@ -95,6 +95,9 @@ const FLOATS = [_]type{ f32, f64 };
 const NUMBERS = INTEGERS ++ FLOATS;
 
 comptime {
+    exportNumFn(num.shiftLeftI128, "shift_left.i128");
+    exportNumFn(num.shiftLeftU128, "shift_left.u128");
+    exportNumFn(num.shiftRightI128, "shift_right.i128");
     exportNumFn(num.shiftRightZeroFillI128, "shift_right_zero_fill.i128");
     exportNumFn(num.shiftRightZeroFillU128, "shift_right_zero_fill.u128");
 
@@ -134,6 +137,8 @@ comptime {
 
         num.exportNumToFloatCast(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".num_to_float_cast_f32.");
         num.exportNumToFloatCast(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".num_to_float_cast_f64.");
+        num.exportToFloatChecked(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".to_float_checked_f32.");
+        num.exportToFloatChecked(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".to_float_checked_f64.");
 
         num.exportAddWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_with_overflow.");
         num.exportAddOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_or_panic.");
@@ -151,6 +156,8 @@ comptime {
         num.exportMulWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".mul_wrapped.");
 
         num.exportIsMultipleOf(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_multiple_of.");
+        num.exportNegate(T, ROC_BUILTINS ++ "." ++ NUM ++ ".negate.");
+        num.exportAbs(T, ROC_BUILTINS ++ "." ++ NUM ++ ".abs.");
 
         num.exportCountLeadingZeroBits(T, ROC_BUILTINS ++ "." ++ NUM ++ ".count_leading_zero_bits.");
         num.exportCountTrailingZeroBits(T, ROC_BUILTINS ++ "." ++ NUM ++ ".count_trailing_zero_bits.");
@@ -186,6 +193,9 @@ comptime {
         num.exportIsNan(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_nan.");
         num.exportIsInfinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_infinite.");
         num.exportIsFinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_finite.");
+
+        num.exportToFloatChecked(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".to_float_checked_f32.");
+        num.exportToFloatChecked(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".to_float_checked_f64.");
     }
 }
 
@@ -309,6 +309,27 @@ pub fn exportToIntCheckingMaxAndMin(comptime From: type, comptime To: type, comp
     @export(f, .{ .name = name ++ @typeName(From), .linkage = .strong });
 }
 
+pub fn exportToFloatChecked(comptime From: type, comptime To: type, comptime name: []const u8) void {
+    const f = struct {
+        fn func(input: From) callconv(.C) ToIntCheckedResult(To) {
+            const value: To = switch (@typeInfo(From)) {
+                .Int => @floatFromInt(input),
+                .Float => @floatCast(input),
+                else => @compileError("only integers and floats can be converted to floats"),
+            };
+            const input_is_finite = switch (@typeInfo(From)) {
+                .Int => true,
+                else => std.math.isFinite(input),
+            };
+            if (input_is_finite and !std.math.isFinite(value)) {
+                return .{ .out_of_bounds = true, .value = 0 };
+            }
+            return .{ .out_of_bounds = false, .value = value };
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(From), .linkage = .strong });
+}
+
 fn isMultipleOf(comptime T: type, lhs: T, rhs: T) bool {
     if (rhs == 0 or rhs == -1) {
         // lhs is a multiple of rhs iff
@@ -333,6 +354,40 @@ pub fn exportIsMultipleOf(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .strong });
 }
 
+pub fn exportNegate(comptime T: type, comptime name: []const u8) void {
+    const f = struct {
+        fn func(self: T) callconv(.C) T {
+            switch (@typeInfo(T).Int.signedness) {
+                .unsigned => return 0 -% self,
+                .signed => {
+                    if (self == std.math.minInt(T)) {
+                        roc_panic("Integer negation overflowed because its argument is the minimum value", 0);
+                    }
+                    return -self;
+                },
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .strong });
+}
+
+pub fn exportAbs(comptime T: type, comptime name: []const u8) void {
+    const f = struct {
+        fn func(self: T) callconv(.C) T {
+            switch (@typeInfo(T).Int.signedness) {
+                .unsigned => return self,
+                .signed => {
+                    if (self == std.math.minInt(T)) {
+                        roc_panic("Integer absolute overflowed because its argument is the minimum value", 0);
+                    }
+                    return if (self < 0) -self else self;
+                },
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .strong });
+}
+
 fn addWithOverflow(comptime T: type, self: T, other: T) WithOverflow(T) {
     switch (@typeInfo(T)) {
         .Int => {
@@ -568,14 +623,39 @@ pub fn exportMulWrappedInt(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .strong });
 }
 
-pub fn shiftRightZeroFillI128(self: i128, other: u8) callconv(.C) i128 {
+pub fn shiftLeftI128(self: i128, other: u8) callconv(.C) i128 {
+    if (other & 0b1000_0000 > 0) {
+        return 0;
+    } else {
+        return self << @as(u7, @intCast(other));
+    }
+}
+
+pub fn shiftLeftU128(self: u128, other: u8) callconv(.C) u128 {
     if (other & 0b1000_0000 > 0) {
         return 0;
+    } else {
+        return self << @as(u7, @intCast(other));
+    }
+}
+
+pub fn shiftRightI128(self: i128, other: u8) callconv(.C) i128 {
+    if (other & 0b1000_0000 > 0) {
+        return if (self < 0) -1 else 0;
     } else {
         return self >> @as(u7, @intCast(other));
     }
 }
 
+pub fn shiftRightZeroFillI128(self: i128, other: u8) callconv(.C) i128 {
+    if (other & 0b1000_0000 > 0) {
+        return 0;
+    } else {
+        const bits: u128 = @bitCast(self);
+        return @bitCast(bits >> @as(u7, @intCast(other)));
+    }
+}
+
 pub fn shiftRightZeroFillU128(self: u128, other: u8) callconv(.C) u128 {
     if (other & 0b1000_0000 > 0) {
         return 0;
@@ -584,7 +584,7 @@ test "increfC, static data" {
 }
 
 // This returns a compilation dependent pseudo random seed for dictionaries.
-// The seed is the address of this function.
+// The seed is the address of the host's roc_alloc, so a host can hash keys the same way (roc_std's RocDict does).
 // This avoids all roc Dicts using a known seed and being trivial to DOS.
 // Still not as secure as true random, but a lot better.
 // This value must not change between calls unless Dict is changed to store the seed on creation.
@@ -257,6 +257,18 @@ macro_rules! int_intrinsic {
     }};
 }
 
+#[macro_export]
+macro_rules! num_intrinsic {
+    ($name:literal) => {{
+        let mut output = int_intrinsic!($name);
+
+        output.options[1] = concat!($name, ".f32");
+        output.options[2] = concat!($name, ".f64");
+
+        output
+    }};
+}
+
 pub const NUM_SIN: IntrinsicName = float_intrinsic!("roc_builtins.num.sin");
 pub const NUM_COS: IntrinsicName = float_intrinsic!("roc_builtins.num.cos");
 pub const NUM_TAN: IntrinsicName = float_intrinsic!("roc_builtins.num.tan");
@@ -283,6 +295,10 @@ pub const INT_TO_FLOAT_CAST_F32: IntrinsicName =
     int_intrinsic!("roc_builtins.num.num_to_float_cast_f32");
 pub const INT_TO_FLOAT_CAST_F64: IntrinsicName =
     int_intrinsic!("roc_builtins.num.num_to_float_cast_f64");
+pub const NUM_TO_FLOAT_CHECKED_F32: IntrinsicName =
+    num_intrinsic!("roc_builtins.num.to_float_checked_f32");
+pub const NUM_TO_FLOAT_CHECKED_F64: IntrinsicName =
+    num_intrinsic!("roc_builtins.num.to_float_checked_f64");
 
 pub const NUM_ADD_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_or_panic");
 pub const NUM_ADD_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_saturated");
@@ -306,7 +322,11 @@ pub const NUM_MUL_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.mul_with_overflow");
 
 pub const NUM_IS_MULTIPLE_OF: IntrinsicName = int_intrinsic!("roc_builtins.num.is_multiple_of");
+pub const NUM_NEGATE: IntrinsicName = int_intrinsic!("roc_builtins.num.negate");
+pub const NUM_ABS: IntrinsicName = int_intrinsic!("roc_builtins.num.abs");
 
+pub const NUM_SHIFT_LEFT: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_left");
+pub const NUM_SHIFT_RIGHT: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_right");
 pub const NUM_SHIFT_RIGHT_ZERO_FILL: IntrinsicName =
     int_intrinsic!("roc_builtins.num.shift_right_zero_fill");
 
@@ -420,6 +440,7 @@ pub const DEC_SUB_OR_PANIC: &str = "roc_builtins.dec.sub_or_panic";
 pub const DEC_SUB_SATURATED: &str = "roc_builtins.dec.sub_saturated";
 pub const DEC_SUB_WITH_OVERFLOW: &str = "roc_builtins.dec.sub_with_overflow";
 pub const DEC_TAN: &str = "roc_builtins.dec.tan";
+pub const DEC_TO_F64: &str = "roc_builtins.dec.to_f64";
 pub const DEC_TO_I128: &str = "roc_builtins.dec.to_i128";
 pub const DEC_FROM_I128: &str = "roc_builtins.dec.from_i128";
 pub const DEC_TO_STR: &str = "roc_builtins.dec.to_str";
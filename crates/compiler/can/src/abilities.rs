@@ -1,3 +1,5 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
 
 use roc_collections::{all::MutMap, VecMap, VecSet};
@@ -17,7 +19,7 @@ use roc_types::{
 /// which is the job of this structure.
 pub type ResolvedImplementations = VecMap<ImplKey, ResolvedImpl>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MemberVariables {
     pub able_vars: Vec<Variable>,
     /// This includes - named rigid vars, lambda sets, wildcards. See
@@ -28,11 +30,11 @@ pub struct MemberVariables {
 
 /// The member and its signature is defined locally, in the module the store is created for.
 /// We need to instantiate and introduce this during solving.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResolvedMemberType(Variable);
 
 /// Member type information that needs to be resolved from imports.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PendingMemberType {
     /// The member and its signature is defined locally, in the module the store is created for.
     /// We need to instantiate and introduce this during solving.
@@ -46,17 +48,17 @@ pub enum PendingMemberType {
     Imported,
 }
 
-pub trait ResolvePhase: std::fmt::Debug + Clone + Copy {
-    type MemberType: std::fmt::Debug + Clone;
+pub trait ResolvePhase: std::fmt::Debug + Clone + Copy + Serialize + DeserializeOwned {
+    type MemberType: std::fmt::Debug + Clone + Serialize + DeserializeOwned;
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Pending;
 impl ResolvePhase for Pending {
     type MemberType = PendingMemberType;
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Resolved;
 impl ResolvePhase for Resolved {
     type MemberType = ResolvedMemberType;
@@ -65,7 +67,8 @@ impl ResolvePhase for Resolved {
 /// Stores information about an ability member definition, including the parent ability, the
 /// defining type, and what type variables need to be instantiated with instances of the ability.
 // TODO: SoA and put me in an arena
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct AbilityMemberData<Phase: ResolvePhase> {
     pub parent_ability: Symbol,
     pub region: Region,
@@ -90,7 +93,8 @@ impl AbilityMemberData<Resolved> {
 pub type SpecializationLambdaSets = VecMap<u8, Variable>;
 
 /// A particular specialization of an ability member.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct MemberSpecializationInfo<Phase: ResolvePhase> {
     _phase: std::marker::PhantomData<Phase>,
     pub symbol: Symbol,
@@ -107,14 +111,14 @@ impl MemberSpecializationInfo<Resolved> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
 pub struct SpecializationId(NonZeroU32);
 
 static_assertions::assert_eq_size!(SpecializationId, Option<SpecializationId>);
 
 /// A key into a particular implementation of an ability member for an opaque type.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct ImplKey {
     pub opaque: Symbol,
     pub ability_member: Symbol,
@@ -133,7 +137,8 @@ pub enum ResolvedImpl {
 // TODO(abilities): this should probably go on the Scope, I don't put it there for now because we
 // are only dealing with intra-module abilities for now.
 // TODO(abilities): many of these should be `VecMap`s. Do some benchmarking.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct IAbilitiesStore<Phase: ResolvePhase> {
     /// Maps an ability to the members defining it.
     members_of_ability: MutMap<Symbol, Vec<Symbol>>,
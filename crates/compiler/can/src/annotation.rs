@@ -14,6 +14,7 @@ use roc_types::types::{
     name_type_var, AbilitySet, Alias, AliasCommon, AliasKind, AliasVar, ExtImplicitOpenness,
     LambdaSet, OptAbleType, OptAbleVar, RecordField, Type, TypeExtension,
 };
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
 pub struct Annotation {
@@ -114,7 +115,7 @@ impl OwnedNamedOrAble {
 }
 
 /// A named type variable, not bound to an ability.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct NamedVariable {
     pub variable: Variable,
     pub name: Lowercase,
@@ -123,7 +124,7 @@ pub struct NamedVariable {
 }
 
 /// A type variable bound to an ability, like "a implements Hash".
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct AbleVariable {
     pub variable: Variable,
     pub name: Lowercase,
@@ -132,7 +133,7 @@ pub struct AbleVariable {
     pub first_seen: Region,
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct IntroducedVariables {
     pub wildcards: Vec<Loc<Variable>>,
     pub lambda_sets: Vec<Variable>,
@@ -29,6 +29,7 @@ use roc_types::subs::{ExhaustiveMark, IllegalCycleMark, RedundantMark, VarStore,
 use roc_types::types::{
     Alias, Category, EarlyReturnKind, IndexOrField, LambdaSet, OptAbleVar, Type,
 };
+use serde::{Deserialize, Serialize};
 use soa::Index;
 use std::fmt::{Debug, Display};
 use std::path::PathBuf;
@@ -72,7 +73,7 @@ impl Output {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize, Deserialize)]
 pub enum IntValue {
     I128([u8; 16]),
     U128([u8; 16]),
@@ -135,7 +136,7 @@ impl IntValue {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Expr {
     // Literals
 
@@ -155,6 +156,8 @@ pub enum Expr {
     },
 
     // An ingested files, its bytes, and the type variable.
+    // Not cached, because the module cache key doesn't cover the ingested file's contents.
+    #[serde(skip)]
     IngestedFile(Box<PathBuf>, Arc<Vec<u8>>, Variable),
 
     // Lookups
@@ -346,16 +349,17 @@ pub enum Expr {
     },
 
     /// Compiles, but will crash if reached
+    #[serde(skip)]
     RuntimeError(RuntimeError),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TryKind {
     KeywordPrefix,
     OperatorSuffix,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExpectLookup {
     pub symbol: Symbol,
     pub var: Variable,
@@ -526,7 +530,7 @@ impl Expr {
 
 /// Stores exhaustiveness-checking metadata for a closure argument that may
 /// have an annotated type.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AnnotatedMark {
     pub annotation_var: Variable,
     pub exhaustive: ExhaustiveMark,
@@ -550,7 +554,7 @@ impl AnnotatedMark {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ClosureData {
     pub function_type: Variable,
     pub closure_type: Variable,
@@ -570,7 +574,7 @@ pub struct ClosureData {
 ///
 /// We distinguish them from closures so we can have better error messages
 /// during constraint generation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StructAccessorData {
     pub name: Symbol,
     pub function_var: Variable,
@@ -647,7 +651,7 @@ impl StructAccessorData {
 /// An opaque wrapper like `@Foo`, which is equivalent to `\p -> @Foo p`
 /// These are desugared to closures, but we distinguish them so we can have
 /// better error messages during constraint generation.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OpaqueWrapFunctionData {
     pub opaque_name: Symbol,
     pub opaque_var: Variable,
@@ -719,7 +723,7 @@ impl OpaqueWrapFunctionData {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Field {
     pub var: Variable,
     // The region of the full `foo: f bar`, rather than just `f bar`
@@ -727,7 +731,7 @@ pub struct Field {
     pub loc_expr: Box<Loc<Expr>>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Recursive {
     NotRecursive = 0,
     Recursive = 1,
@@ -743,7 +747,7 @@ impl Recursive {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WhenBranchPattern {
     pub pattern: Loc<Pattern>,
     /// Degenerate branch patterns are those that don't fully bind symbols that the branch body
@@ -752,7 +756,7 @@ pub struct WhenBranchPattern {
     pub degenerate: bool,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WhenBranch {
     pub patterns: Vec<WhenBranchPattern>,
     pub value: Loc<Expr>,
@@ -2594,7 +2598,7 @@ fn desugar_str_segments(var_store: &mut VarStore, segments: Vec<StrSegment>) ->
     loc_expr.value
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Declarations {
     pub declarations: Vec<DeclarationTag>,
 
@@ -3043,7 +3047,7 @@ impl Declarations {
 
 roc_error_macros::assert_sizeof_default!(DeclarationTag, 8);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum DeclarationTag {
     Value,
     Expectation,
@@ -3071,7 +3075,7 @@ impl DeclarationTag {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FunctionDef {
     pub closure_type: Variable,
     pub return_type: Variable,
@@ -3081,7 +3085,7 @@ pub struct FunctionDef {
     pub arguments: Vec<(Variable, AnnotatedMark, Loc<Pattern>)>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DestructureDef {
     pub loc_pattern: Loc<Pattern>,
     pub pattern_vars: VecMap<Symbol, Variable>,
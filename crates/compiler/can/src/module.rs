@@ -21,6 +21,7 @@ use roc_problem::can::{Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{ExposedTypesStorageSubs, Subs, VarStore, Variable};
 use roc_types::types::{AbilitySet, Alias, Type};
+use serde::{Deserialize, Serialize};
 
 /// The types of all exposed values/functions of a collection of modules
 #[derive(Clone, Debug, Default)]
@@ -121,7 +122,7 @@ pub struct ExposedModuleTypes {
     pub resolved_implementations: ResolvedImplementations,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Module {
     pub module_id: ModuleId,
     pub exposed_imports: MutMap<Symbol, Region>,
@@ -136,7 +137,7 @@ pub struct Module {
     pub module_params: Option<ModuleParams>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleParams {
     pub region: Region,
     pub whole_symbol: Symbol,
@@ -162,7 +163,7 @@ impl ModuleParams {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RigidVariables {
     pub named: MutMap<Variable, Lowercase>,
     pub able: MutMap<Variable, (Lowercase, AbilitySet)>,
@@ -17,10 +17,11 @@ use roc_region::all::{Loc, Region};
 use roc_types::num::SingleQuoteBound;
 use roc_types::subs::{VarStore, Variable};
 use roc_types::types::{LambdaSet, OptAbleVar, PatternCategory, Type};
+use serde::{Deserialize, Serialize};
 
 /// A pattern, including possible problems (e.g. shadowing) so that
 /// codegen can generate a runtime error if this pattern is reached.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Pattern {
     Identifier(Symbol),
     As(Box<Loc<Pattern>>, Symbol),
@@ -93,6 +94,7 @@ pub enum Pattern {
     // Example: (5 = 1 + 2) is an unsupported pattern in an assignment; Int patterns aren't allowed in assignments!
     UnsupportedPattern(Region),
     // parse error patterns
+    #[serde(skip)]
     MalformedPattern(MalformedPatternProblem, Region),
 }
 
@@ -199,7 +201,7 @@ impl Pattern {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ListPatterns {
     pub patterns: Vec<Loc<Pattern>>,
     /// Where a rest pattern splits patterns before and after it, if it does at all.
@@ -230,7 +232,7 @@ impl ListPatterns {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RecordDestruct {
     pub var: Variable,
     pub label: Lowercase,
@@ -238,14 +240,14 @@ pub struct RecordDestruct {
     pub typ: DestructType,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TupleDestruct {
     pub var: Variable,
     pub destruct_index: usize,
     pub typ: (Variable, Loc<Pattern>),
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DestructType {
     Required,
     Optional(Variable, Loc<Expr>),
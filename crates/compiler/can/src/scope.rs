@@ -10,11 +10,12 @@ use roc_types::types::{Alias, AliasKind, AliasVar, EarlyReturnKind, Type};
 use crate::abilities::PendingAbilitiesStore;
 
 use bitvec::vec::BitVec;
+use serde::{Deserialize, Serialize};
 
 // ability -> member names
 pub(crate) type PendingAbilitiesInScope = VecMap<Symbol, VecSet<Symbol>>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Scope {
     /// The type aliases currently in scope
     pub aliases: VecMap<Symbol, Alias>,
@@ -574,7 +575,7 @@ enum ContainsIdent {
     NotPresent,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ScopedIdentIds {
     pub ident_ids: IdentIds,
     in_scope: BitVec,
@@ -684,7 +685,7 @@ impl ScopedIdentIds {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScopeModules {
     /// The ids of all modules in scope
     ids: Vec<ModuleId>,
@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::{borrow::Borrow, iter::FromIterator};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VecSet<T> {
     elements: Vec<T>,
 }
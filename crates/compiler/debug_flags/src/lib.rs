@@ -123,6 +123,14 @@ flags! {
     /// has been applied.
     ROC_PRINT_IR_AFTER_TRMC
 
+    /// Writes a pretty-printed mono IR to stderr after small procs have been inlined into their
+    /// callers. Inlining only happens for the dev and wasm backends.
+    ROC_PRINT_IR_AFTER_INLINING
+
+    /// Writes a pretty-printed mono IR to stderr after constants have been folded and dead
+    /// branches and lets removed. This only happens for the dev and wasm backends.
+    ROC_PRINT_IR_AFTER_SIMPLIFY
+
     /// Writes a pretty-printed mono IR to stderr after performing dropspecialization.
     /// Which inlines drop functions to remove pairs of alloc/dealloc instructions of its children.
     ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION
@@ -137,6 +145,10 @@ flags! {
     /// void layout.
     ROC_NO_UNBOUND_LAYOUT
 
+    /// Prints the test_gen programs that the `check-interp` feature couldn't check against the
+    /// mono IR interpreter, and why.
+    ROC_PRINT_SKIPPED_INTERP_CHECKS
+
     // ===LLVM Gen===
 
     /// Prints LLVM function verification output.
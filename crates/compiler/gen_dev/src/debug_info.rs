@@ -0,0 +1,435 @@
+//! DWARF debug info for the procedures in a dev backend object file.
+//!
+//! Every procedure gets a `DW_TAG_subprogram` named after its Roc symbol, and a line table
+//! sequence mapping its machine code back to the Roc source lines of the defs, branches and
+//! continuations it was generated from.
+//! This is enough for gdb, lldb and perf to show Roc files and lines.
+use std::path::PathBuf;
+
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, FileId, LineProgram, LineString, Range,
+    RangeList, RelocateWriter, Relocation, RelocationTarget, Sections, Writer,
+};
+use gimli::{Encoding, Format, LineEncoding, RunTimeEndian};
+use object::write::{Object, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationFlags, RelocationKind};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+
+/// What we need to know about a procedure after its machine code was added to the object.
+pub struct DebugProc {
+    pub symbol: Symbol,
+    /// The object file symbol at the start of the procedure's machine code
+    pub symbol_id: SymbolId,
+    pub linkage_name: String,
+    pub region: Region,
+    pub size: u64,
+    /// Offsets into the procedure's machine code, and the source regions they were generated from
+    pub line_table: std::vec::Vec<(u64, ModuleId, Region)>,
+}
+
+/// A debug section being written, along with the relocations it needs.
+#[derive(Clone)]
+struct DebugSection {
+    data: EndianVec<RunTimeEndian>,
+    relocations: std::vec::Vec<Relocation>,
+}
+
+impl DebugSection {
+    fn new(endian: RunTimeEndian) -> Self {
+        Self {
+            data: EndianVec::new(endian),
+            relocations: std::vec::Vec::new(),
+        }
+    }
+}
+
+impl RelocateWriter for DebugSection {
+    type Writer = EndianVec<RunTimeEndian>;
+
+    fn writer(&self) -> &Self::Writer {
+        &self.data
+    }
+
+    fn writer_mut(&mut self) -> &mut Self::Writer {
+        &mut self.data
+    }
+
+    fn relocate(&mut self, relocation: Relocation) {
+        self.relocations.push(relocation);
+    }
+}
+
+/// Adds `.debug_info`, `.debug_abbrev`, `.debug_line` and `.debug_ranges` sections for `procs`.
+/// Mach-O objects get them as `__debug_info` etc. in the `__DWARF` segment.
+///
+/// Only ELF and Mach-O objects get debug info for now. Nothing is emitted when we have no sources.
+pub fn add_debug_sections(
+    output: &mut Object,
+    interns: &Interns,
+    module_sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    procs: &[DebugProc],
+) {
+    let is_macho = match output.format() {
+        BinaryFormat::Elf => false,
+        BinaryFormat::MachO => true,
+        _ => return,
+    };
+
+    if module_sources.is_empty() || procs.is_empty() {
+        return;
+    }
+
+    // All the targets of the dev backend are little endian
+    let endian = RunTimeEndian::Little;
+
+    let encoding = Encoding {
+        format: Format::Dwarf32,
+        version: 4,
+        address_size: 8,
+    };
+
+    let mut dwarf = DwarfUnit::new(encoding);
+    dwarf.unit.line_program = LineProgram::new(
+        encoding,
+        LineEncoding::default(),
+        LineString::String(b".".to_vec()),
+        LineString::String(b"roc_app".to_vec()),
+        None,
+    );
+
+    let mut files: MutMap<ModuleId, (FileId, LineInfo)> = MutMap::default();
+    for (module_id, (path, src)) in module_sources {
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+            _ => ".".into(),
+        };
+
+        let line_program = &mut dwarf.unit.line_program;
+        let directory_id =
+            line_program.add_directory(LineString::String(directory.as_bytes().to_vec()));
+        let file_id = line_program.add_file(
+            LineString::String(filename.as_bytes().to_vec()),
+            directory_id,
+            None,
+        );
+
+        files.insert(*module_id, (file_id, LineInfo::new(src)));
+    }
+
+    // 1-based line and column, like DWARF wants them
+    let location = |module: ModuleId, region: Region| {
+        if region.is_empty() {
+            return None;
+        }
+
+        let (file_id, line_info) = files.get(&module)?;
+        let position = line_info.convert_pos(region.start());
+
+        Some((
+            *file_id,
+            position.line as u64 + 1,
+            position.column as u64 + 1,
+        ))
+    };
+
+    // Every procedure lives in its own section, so the unit covers them with a range list
+    let mut ranges = std::vec::Vec::with_capacity(procs.len());
+    let mut symbol_ids = std::vec::Vec::with_capacity(procs.len());
+    let root = dwarf.unit.root();
+
+    for proc in procs {
+        let address = Address::Symbol {
+            symbol: symbol_ids.len(),
+            addend: 0,
+        };
+        symbol_ids.push(proc.symbol_id);
+
+        ranges.push(Range::StartLength {
+            begin: address,
+            length: proc.size,
+        });
+
+        let name = format!(
+            "{}.{}",
+            proc.symbol.module_string(interns),
+            proc.symbol.as_str(interns)
+        );
+
+        let subprogram = dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let entry = dwarf.unit.get_mut(subprogram);
+        entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
+        entry.set(
+            gimli::DW_AT_linkage_name,
+            AttributeValue::String(proc.linkage_name.as_bytes().to_vec()),
+        );
+        entry.set(gimli::DW_AT_low_pc, AttributeValue::Address(address));
+        entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(proc.size));
+
+        let proc_location = location(proc.symbol.module_id(), proc.region);
+        if let Some((file_id, line, _)) = proc_location {
+            entry.set(
+                gimli::DW_AT_decl_file,
+                AttributeValue::FileIndex(Some(file_id)),
+            );
+            entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+        }
+
+        let rows = proc_location
+            .map(|location| (0, location))
+            .into_iter()
+            .chain(
+                proc.line_table
+                    .iter()
+                    .filter_map(|(offset, module, region)| {
+                        Some((*offset, location(*module, *region)?))
+                    }),
+            )
+            .filter(|(offset, _)| *offset < proc.size);
+
+        let line_program = &mut dwarf.unit.line_program;
+        let mut in_sequence = false;
+        for (offset, (file_id, line, column)) in rows {
+            if !in_sequence {
+                line_program.begin_sequence(Some(address));
+                in_sequence = true;
+            }
+
+            let row = line_program.row();
+            row.address_offset = offset;
+            row.file = file_id;
+            row.line = line;
+            row.column = column;
+            line_program.generate_row();
+        }
+
+        if in_sequence {
+            line_program.end_sequence(proc.size);
+        }
+    }
+
+    let range_list_id = dwarf.unit.ranges.add(RangeList(ranges));
+    let entry = dwarf.unit.get_mut(root);
+    entry.set(
+        gimli::DW_AT_producer,
+        AttributeValue::String(b"roc".to_vec()),
+    );
+    // DWARF has no language code for Roc, and claiming C would have debuggers parse
+    // expressions and print values as if they were C. This vendor code is read as plain
+    // assembly instead, which assumes nothing about the source language. (Same as gen_llvm.)
+    entry.set(
+        gimli::DW_AT_language,
+        AttributeValue::Language(gimli::DW_LANG_Mips_Assembler),
+    );
+    entry.set(
+        gimli::DW_AT_name,
+        AttributeValue::String(b"roc_app".to_vec()),
+    );
+    entry.set(gimli::DW_AT_comp_dir, AttributeValue::String(b".".to_vec()));
+    entry.set(
+        gimli::DW_AT_low_pc,
+        AttributeValue::Address(Address::Constant(0)),
+    );
+    entry.set(
+        gimli::DW_AT_ranges,
+        AttributeValue::RangeListRef(range_list_id),
+    );
+
+    let mut sections = Sections::new(DebugSection::new(endian));
+    if let Err(error) = dwarf.write(&mut sections) {
+        internal_error!("failed to write DWARF debug info: {:?}", error);
+    }
+
+    // Add all the sections first, since relocations can point from one to another
+    let mut section_ids = MutMap::default();
+    sections
+        .for_each_mut(|id, section| -> gimli::write::Result<()> {
+            if section.data.slice().is_empty() {
+                return Ok(());
+            }
+
+            let (segment, name) = if is_macho {
+                // Like LLVM, we write offsets into other debug sections directly, since the
+                // linker leaves the debug info of Mach-O objects alone
+                for relocation in section.relocations.iter() {
+                    if let RelocationTarget::Section(_) = relocation.target {
+                        section.data.write_udata_at(
+                            relocation.offset,
+                            relocation.addend as u64,
+                            relocation.size,
+                        )?;
+                    }
+                }
+
+                let name = format!("__{}", id.name().trim_start_matches('.'));
+                (b"__DWARF".to_vec(), name.into_bytes())
+            } else {
+                (std::vec::Vec::new(), id.name().as_bytes().to_vec())
+            };
+
+            let section_id = output.add_section(segment, name, object::SectionKind::Debug);
+            output.set_section_data(section_id, section.data.slice().to_vec(), 1);
+            section_ids.insert(id, section_id);
+
+            Ok(())
+        })
+        .unwrap();
+
+    sections
+        .for_each(|id, section| -> gimli::write::Result<()> {
+            let Some(section_id) = section_ids.get(&id) else {
+                return Ok(());
+            };
+
+            for relocation in section.relocations.iter() {
+                let symbol = match relocation.target {
+                    RelocationTarget::Symbol(index) => symbol_ids[index],
+                    RelocationTarget::Section(_) if is_macho => continue,
+                    RelocationTarget::Section(target) => {
+                        output.section_symbol(section_ids[&target])
+                    }
+                };
+
+                let result = output.add_relocation(
+                    *section_id,
+                    object::write::Relocation {
+                        offset: relocation.offset as u64,
+                        symbol,
+                        addend: relocation.addend,
+                        flags: RelocationFlags::Generic {
+                            kind: RelocationKind::Absolute,
+                            encoding: RelocationEncoding::Generic,
+                            size: relocation.size * 8,
+                        },
+                    },
+                );
+
+                if let Err(error) = result {
+                    internal_error!("failed to relocate DWARF debug info: {:?}", error);
+                }
+            }
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gimli::{EndianSlice, LittleEndian};
+    use object::write::{Symbol as ObjectSymbol, SymbolSection};
+    use object::{Architecture, Endianness, SectionKind, SymbolFlags, SymbolKind, SymbolScope};
+    use object::{Object as _, ObjectSection as _};
+    use roc_module::ident::ModuleName;
+    use roc_region::all::Position;
+
+    const SRC: &str = "main =\n    x = 1\n    x + 1\n";
+
+    fn region_of(snippet: &str) -> Region {
+        let start = SRC.find(snippet).unwrap() as u32;
+
+        Region::new(
+            Position::new(start),
+            Position::new(start + snippet.len() as u32),
+        )
+    }
+
+    /// Builds an object with one proc, and reads the line table back out of it
+    fn line_rows(format: BinaryFormat) -> (std::vec::Vec<(u64, u64)>, std::vec::Vec<String>) {
+        let mut interns = Interns::default();
+        let home = interns.module_id(&ModuleName::from("Test"));
+        let main = interns.all_ident_ids.get_or_insert(home).add_str("main");
+
+        let mut module_sources = MutMap::default();
+        module_sources.insert(home, (PathBuf::from("src/Test.roc"), SRC.into()));
+
+        let mut output = Object::new(format, Architecture::X86_64, Endianness::Little);
+        let text = output.add_section(
+            output
+                .segment_name(object::write::StandardSegment::Text)
+                .to_vec(),
+            b".text.main".to_vec(),
+            SectionKind::Text,
+        );
+        output.set_section_data(text, vec![0x90; 16], 16);
+        let symbol_id = output.add_symbol(ObjectSymbol {
+            name: b"roc_main".to_vec(),
+            value: 0,
+            size: 16,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+
+        let proc = DebugProc {
+            symbol: Symbol::new(home, main),
+            symbol_id,
+            linkage_name: "roc_main".to_string(),
+            region: region_of(SRC),
+            size: 16,
+            line_table: vec![
+                (4, home, region_of("x = 1")),
+                (10, home, region_of("x + 1")),
+            ],
+        };
+        add_debug_sections(&mut output, &interns, &module_sources, &[proc]);
+
+        let bytes = output.write().unwrap();
+        let file = object::File::parse(&*bytes).unwrap();
+        let load_section = |id: gimli::SectionId| -> Result<_, gimli::Error> {
+            // object finds the Mach-O `__debug_*` sections by their ELF names too
+            let data = file
+                .section_by_name(id.name())
+                .and_then(|section| section.data().ok())
+                .unwrap_or(&[]);
+
+            Ok(EndianSlice::new(data, LittleEndian))
+        };
+        let dwarf = gimli::Dwarf::load(load_section).unwrap();
+
+        let header = dwarf.units().next().unwrap().unwrap();
+        let unit = dwarf.unit(header).unwrap();
+
+        let mut names = vec![];
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs().unwrap() {
+            if entry.tag() == gimli::DW_TAG_subprogram {
+                let name = entry.attr_value(gimli::DW_AT_name).unwrap().unwrap();
+                let name = dwarf.attr_string(&unit, name).unwrap();
+                names.push(name.to_string_lossy().into_owned());
+            }
+        }
+
+        let mut rows = unit.line_program.clone().unwrap().rows();
+        let mut lines = vec![];
+        while let Some((_, row)) = rows.next_row().unwrap() {
+            if !row.end_sequence() {
+                lines.push((row.address(), row.line().unwrap().get()));
+            }
+        }
+
+        (lines, names)
+    }
+
+    #[test]
+    fn elf_line_table() {
+        let (lines, names) = line_rows(BinaryFormat::Elf);
+
+        assert_eq!(lines, vec![(0, 1), (4, 2), (10, 3)]);
+        assert_eq!(names, vec!["Test.main"]);
+    }
+
+    #[test]
+    fn macho_line_table() {
+        let (lines, names) = line_rows(BinaryFormat::MachO);
+
+        assert_eq!(lines, vec![(0, 1), (4, 2), (10, 3)]);
+        assert_eq!(names, vec!["Test.main"]);
+    }
+}
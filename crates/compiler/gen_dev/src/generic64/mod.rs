@@ -17,6 +17,7 @@ use roc_mono::layout::{
     TagIdIntType, UnionLayout,
 };
 use roc_mono::low_level::HigherOrder;
+use roc_region::all::Region;
 use roc_target::Target;
 use std::marker::PhantomData;
 
@@ -781,6 +782,7 @@ pub struct Backend64Bit<
     relocs: Vec<'a, Relocation>,
     proc_name: Option<String>,
     is_self_recursive: Option<SelfRecursive>,
+    line_table: Vec<'a, (u64, ModuleId, Region)>,
 
     last_seen_map: MutMap<Symbol, *const Stmt<'a>>,
     layout_map: MutMap<Symbol, InLayout<'a>>,
@@ -812,11 +814,12 @@ pub fn new_backend_64bit<
         env,
         interns,
         layout_interner,
-        helper_proc_gen: CodeGenHelp::new(env.arena, target, env.module_id),
+        helper_proc_gen: CodeGenHelp::new(env.arena, target, env.module_id, env.inline_budget),
         helper_proc_symbols: bumpalo::vec![in env.arena],
         caller_procs: bumpalo::vec![in env.arena],
         proc_name: None,
         is_self_recursive: None,
+        line_table: bumpalo::vec![in env.arena],
         buf: bumpalo::vec![in env.arena],
         relocs: bumpalo::vec![in env.arena],
         last_seen_map: MutMap::default(),
@@ -901,6 +904,7 @@ impl<
     fn reset(&mut self, name: String, is_self_recursive: SelfRecursive) {
         self.proc_name = Some(name);
         self.is_self_recursive = Some(is_self_recursive);
+        self.line_table.clear();
         self.last_seen_map.clear();
         self.layout_map.clear();
         self.join_map.clear();
@@ -909,6 +913,15 @@ impl<
         self.storage_manager.reset();
     }
 
+    fn mark_line(&mut self, module: ModuleId, region: Region) {
+        self.line_table
+            .push((self.buf.len() as u64, module, region));
+    }
+
+    fn line_table(&self) -> &[(u64, ModuleId, Region)] {
+        &self.line_table
+    }
+
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const InLayout<'a>)> {
         &mut self.literal_map
     }
@@ -929,7 +942,7 @@ impl<
         &mut self.free_map
     }
 
-    fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>) {
+    fn finalize(&mut self) -> (Vec<'a, u8>, Vec<'a, Relocation>) {
         let mut out = bumpalo::vec![in self.env.arena];
 
         // Setup stack.
@@ -947,6 +960,11 @@ impl<
         );
         let setup_offset = out.len();
 
+        // The function body will come after the stack setup.
+        for (offset, _, _) in self.line_table.iter_mut() {
+            *offset += setup_offset as u64;
+        }
+
         // Deal with jumps to the return address.
         let old_relocs = std::mem::replace(&mut self.relocs, bumpalo::vec![in self.env.arena]);
 
@@ -1342,6 +1360,20 @@ impl<
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::abs_freg32_freg32(&mut self.buf, &mut self.relocs, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                dst,
+                bitcode::NUM_ABS[int_width].to_string(),
+                &[*src],
+                &[*layout],
+                layout,
+            ),
+            LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                dst,
+                bitcode::DEC_ABS.to_string(),
+                &[*src],
+                &[*layout],
+                layout,
+            ),
             x => todo!("NumAbs: layout, {:?}", x),
         }
     }
@@ -1687,6 +1719,20 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::div_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128)) => self.build_fn_call(
+                dst,
+                "__divti3".to_string(),
+                &[*src1, *src2],
+                &[*layout, *layout],
+                layout,
+            ),
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U128)) => self.build_fn_call(
+                dst,
+                "__udivti3".to_string(),
+                &[*src1, *src2],
+                &[*layout, *layout],
+                layout,
+            ),
             LayoutRepr::Builtin(Builtin::Decimal) => {
                 self.build_fn_call(
                     dst,
@@ -1759,7 +1805,21 @@ impl<
                     src2_reg,
                 );
             }
-            x => todo!("NumDiv: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128)) => self.build_fn_call(
+                dst,
+                "__modti3".to_string(),
+                &[*src1, *src2],
+                &[*layout, *layout],
+                layout,
+            ),
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U128)) => self.build_fn_call(
+                dst,
+                "__umodti3".to_string(),
+                &[*src1, *src2],
+                &[*layout, *layout],
+                layout,
+            ),
+            x => todo!("NumRem: layout, {:?}", x),
         }
     }
 
@@ -1770,6 +1830,15 @@ impl<
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::neg_reg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(int_width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_NEGATE[int_width].to_string(),
+                    &[*src],
+                    &[*layout],
+                    layout,
+                )
+            }
             LayoutRepr::F32 => {
                 let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
@@ -4461,7 +4530,15 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128bit(
+                    dst,
+                    src1,
+                    src2,
+                    int_width,
+                    ASM::and_reg64_reg64_reg64,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4481,7 +4558,15 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128bit(
+                    dst,
+                    src1,
+                    src2,
+                    int_width,
+                    ASM::or_reg64_reg64_reg64,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4501,7 +4586,15 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128bit(
+                    dst,
+                    src1,
+                    src2,
+                    int_width,
+                    ASM::xor_reg64_reg64_reg64,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4521,7 +4614,17 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = Layout::from_int_width(int_width);
+
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_LEFT[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4548,7 +4651,18 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = Layout::from_int_width(int_width);
+
+                // like the narrower widths, U128 is shifted as if it were signed
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_RIGHT[IntWidth::I128].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4597,17 +4711,13 @@ impl<
 
         match int_width {
             IntWidth::U128 | IntWidth::I128 => {
-                let layout = match int_width {
-                    IntWidth::I128 => Layout::I128,
-                    IntWidth::U128 => Layout::U128,
-                    _ => unreachable!(),
-                };
+                let layout = Layout::from_int_width(int_width);
 
                 self.build_fn_call(
                     dst,
                     bitcode::NUM_SHIFT_RIGHT_ZERO_FILL[int_width].to_string(),
                     &[*src1, *src2],
-                    &[layout, layout],
+                    &[layout, Layout::U8],
                     &layout,
                 );
             }
@@ -4651,38 +4761,76 @@ impl<
         let buf = &mut self.buf;
 
         match (source, target) {
-            (U128, U64) => {
+            (U128 | I128, U64 | I64 | U32 | I32 | U16 | I16 | U8 | I8) => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
 
+                // the lower 8 bytes hold the bits that survive the truncation
                 let (offset, _size) = self.storage_manager.stack_offset_and_size(src);
 
-                ASM::mov_reg64_base32(buf, dst_reg, offset + 8);
+                ASM::mov_reg64_base32(buf, dst_reg, offset);
+
+                match target.stack_size() {
+                    8 => {}
+                    4 => ASM::mov_reg_reg(buf, RegisterWidth::W32, dst_reg, dst_reg),
+                    2 => ASM::mov_reg_reg(buf, RegisterWidth::W16, dst_reg, dst_reg),
+                    _ => ASM::mov_reg_reg(buf, RegisterWidth::W8, dst_reg, dst_reg),
+                }
 
                 return;
             }
-            (U64, U128) => {
+            (U64 | I64 | U32 | I32 | U16 | I16 | U8 | I8, U128 | I128) => {
                 let src_reg = self.storage_manager.load_to_general_reg(buf, src);
 
                 let base_offset = self.storage_manager.claim_stack_area_layout(
                     self.layout_interner,
                     *dst,
-                    Layout::U128,
+                    Layout::from_int_width(target),
                 );
 
-                let tmp = Symbol::DEV_TMP;
-                let tmp_reg = self.storage_manager.claim_general_reg(buf, &tmp);
+                self.storage_manager
+                    .with_tmp_general_reg(buf, |storage_manager, buf, tmp_reg| {
+                        // widen the source to 64 bits for the lower 8 bytes
+                        let register_width = match source.stack_size() {
+                            8 => RegisterWidth::W64,
+                            4 => RegisterWidth::W32,
+                            2 => RegisterWidth::W16,
+                            _ => RegisterWidth::W8,
+                        };
 
-                // move a zero into the lower 8 bytes
-                ASM::mov_reg64_imm64(buf, tmp_reg, 0x0);
-                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                        if source.is_signed() {
+                            ASM::movsx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                        } else {
+                            ASM::xor_reg64_reg64_reg64(buf, tmp_reg, tmp_reg, tmp_reg);
+                            ASM::mov_reg_reg(buf, register_width, tmp_reg, src_reg);
+                        }
 
-                ASM::mov_base32_reg64(buf, base_offset, src_reg);
+                        ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
 
-                self.free_symbol(&tmp);
+                        // the upper 8 bytes are all sign bits, or all zeroes
+                        if source.is_signed() {
+                            storage_manager.with_tmp_general_reg(
+                                buf,
+                                |storage_manager, buf, shift_reg| {
+                                    ASM::mov_reg64_imm64(buf, shift_reg, 63);
+                                    ASM::sar_reg64_reg64_reg64(
+                                        buf,
+                                        storage_manager,
+                                        tmp_reg,
+                                        tmp_reg,
+                                        shift_reg,
+                                    );
+                                },
+                            );
+                        } else {
+                            ASM::mov_reg64_imm64(buf, tmp_reg, 0x0);
+                        }
+
+                        ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                    });
 
                 return;
             }
-            (U128, I128) | (I128, U128) => {
+            (U128 | I128, U128 | I128) => {
                 let to_offset = self.storage_manager.claim_stack_area_layout(
                     self.layout_interner,
                     *dst,
@@ -4839,6 +4987,18 @@ impl<
                 let int_width = arg_layout.to_int_width();
                 self.build_int_to_float_cast(dst, src, int_width, FloatWidth::F32);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                let as_f64 = self.debug_symbol("dec_as_f64");
+                self.num_to_f64(&as_f64, src, arg_layout);
+
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src_reg = self
+                    .storage_manager
+                    .load_to_float_reg(&mut self.buf, &as_f64);
+                ASM::to_float_freg32_freg64(&mut self.buf, dst_reg, src_reg);
+
+                self.free_symbol(&as_f64);
+            }
             arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F32),
         }
     }
@@ -4864,6 +5024,15 @@ impl<
                 let int_width = arg_layout.to_int_width();
                 self.build_int_to_float_cast(dst, src, int_width, FloatWidth::F64);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_TO_F64.to_string(),
+                    &[*src],
+                    &[Layout::DEC],
+                    &Layout::F64,
+                );
+            }
             arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F64),
         }
     }
@@ -4879,11 +5048,68 @@ impl<
                     &Layout::DEC,
                 );
             }
+            LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_FROM_FLOAT[float_width].to_string(),
+                    &[*src],
+                    &[*arg_layout],
+                    &Layout::DEC,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                let base_offset = self.storage_manager.claim_stack_area_layout(
+                    self.layout_interner,
+                    *dst,
+                    Layout::DEC,
+                );
+
+                self.storage_manager.copy_symbol_to_stack_offset(
+                    self.layout_interner,
+                    &mut self.buf,
+                    base_offset,
+                    src,
+                    arg_layout,
+                );
+            }
 
             arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::DEC),
         }
     }
 
+    fn build_int_bitwise_128bit(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+        op: fn(&mut Vec<'a, u8>, GeneralReg, GeneralReg, GeneralReg),
+    ) {
+        let buf = &mut self.buf;
+
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let dst_offset = self.storage_manager.claim_stack_area_layout(
+            self.layout_interner,
+            *dst,
+            Layout::from_int_width(int_width),
+        );
+
+        // bitwise operations do not carry, so each 8-byte half is independent
+        self.storage_manager
+            .with_tmp_general_reg(buf, |storage_manager, buf, tmp1_reg| {
+                storage_manager.with_tmp_general_reg(buf, |_, buf, tmp2_reg| {
+                    for half in [0, 8] {
+                        ASM::mov_reg64_base32(buf, tmp1_reg, src1_offset + half);
+                        ASM::mov_reg64_base32(buf, tmp2_reg, src2_offset + half);
+                        op(buf, tmp1_reg, tmp1_reg, tmp2_reg);
+                        ASM::mov_base32_reg64(buf, dst_offset + half, tmp1_reg);
+                    }
+                });
+            });
+    }
+
     fn compare_128bit(
         &mut self,
         op: CompareOperation,
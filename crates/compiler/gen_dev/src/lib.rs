@@ -6,6 +6,7 @@
 #![allow(clippy::large_enum_variant, clippy::upper_case_acronyms)]
 
 use std::collections::hash_map::Entry;
+use std::path::PathBuf;
 
 use bumpalo::{collections::Vec, Bump};
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
@@ -15,6 +16,7 @@ use roc_module::ident::ModuleName;
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::{CallerProc, CodeGenHelp};
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
     Literal, ModifyRc, Param, Proc, ProcLayout, SelfRecursive, Stmt,
@@ -24,10 +26,12 @@ use roc_mono::layout::{
     TagIdIntType, UnionLayout,
 };
 use roc_mono::list_element_layout;
+use roc_region::all::Region;
 
+mod debug_info;
 mod generic64;
 mod object_builder;
-pub use object_builder::build_module;
+pub use object_builder::{build_module, expect_symbol_name};
 use roc_target::Target;
 mod run_roc;
 
@@ -39,6 +43,9 @@ pub enum AssemblyBackendMode {
     Test,
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     Repl,
+    /// Like `Test`, but failed `expect`s write their values to the shared buffer that
+    /// `roc test` reads
+    Expect,
 }
 
 impl AssemblyBackendMode {
@@ -47,6 +54,7 @@ impl AssemblyBackendMode {
             AssemblyBackendMode::Binary => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::Expect => true,
         }
     }
 
@@ -55,6 +63,7 @@ impl AssemblyBackendMode {
             AssemblyBackendMode::Binary => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::Expect => true,
         }
     }
 
@@ -63,6 +72,16 @@ impl AssemblyBackendMode {
             AssemblyBackendMode::Binary => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::Expect => true,
+        }
+    }
+
+    fn runs_expects(self) -> bool {
+        match self {
+            AssemblyBackendMode::Binary => false,
+            AssemblyBackendMode::Test => false,
+            AssemblyBackendMode::Repl => false,
+            AssemblyBackendMode::Expect => true,
         }
     }
 }
@@ -73,6 +92,11 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// Paths and sources of the modules being compiled, used to emit DWARF debug info.
+    /// When this is empty, no debug info is emitted.
+    pub module_sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The budget for inlining the refcount and equality helpers, or `None` to always call them.
+    pub inline_budget: Option<InlineBudget>,
 }
 
 // These relocations likely will need a length.
@@ -275,8 +299,27 @@ impl<'a> LastSeenMap<'a> {
                 }
             }
 
-            Stmt::Dbg { .. } => todo!("dbg not implemented in the dev backend"),
-            Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.set_last_seen(*symbol, stmt);
+                self.scan_ast_help(remainder);
+            }
+            Stmt::Located { remainder, .. } => {
+                self.scan_ast_help(remainder);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                self.set_last_seen(*condition, stmt);
+                for sym in *lookups {
+                    self.set_last_seen(*sym, stmt);
+                }
+                self.scan_ast_help(remainder);
+            }
 
             Stmt::Crash(msg, _crash_tag) => {
                 self.set_last_seen(*msg, stmt);
@@ -513,11 +556,18 @@ trait Backend<'a> {
     /// It also passes basic procedure information to the builder for setup of the next function.
     fn reset(&mut self, name: String, is_self_recursive: SelfRecursive);
 
+    /// mark_line records that the code emitted next comes from `region` in the source of `module`.
+    fn mark_line(&mut self, module: ModuleId, region: Region);
+
+    /// line_table gets the code offsets and source regions recorded for the last built procedure.
+    /// The offsets are only final once finalize has run.
+    fn line_table(&self) -> &[(u64, ModuleId, Region)];
+
     /// finalize does any setup and cleanup that should happen around the procedure.
     /// finalize does setup because things like stack size and jump locations are not know until the function is written.
     /// For example, this can store the frame pointer and setup stack space.
     /// finalize is run at the end of build_proc when all internal code is finalized.
-    fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>);
+    fn finalize(&mut self) -> (Vec<'a, u8>, Vec<'a, Relocation>);
 
     // load_args is used to let the backend know what the args are.
     // The backend should track these args so it can use them as needed.
@@ -537,7 +587,7 @@ trait Backend<'a> {
         &mut self,
         proc: Proc<'a>,
         layout_ids: &mut LayoutIds<'a>,
-    ) -> (Vec<u8>, Vec<Relocation>, Vec<'a, (Symbol, String)>) {
+    ) -> (Vec<'a, u8>, Vec<'a, Relocation>, Vec<'a, (Symbol, String)>) {
         let proc_name = self.lambda_name_to_string(
             proc.name,
             proc.args.iter().map(|t| t.0),
@@ -704,8 +754,86 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable: _,
+                remainder,
+            } => {
+                self.roc_dbg(source_location, source, *symbol);
+                self.free_symbols(stmt);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => {
+                self.mark_line(*module, *region);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
+            Stmt::Expect { remainder, .. } if !self.env().mode.runs_expects() => {
+                self.free_symbols(stmt);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
+            Stmt::Expect { lookups, .. } => {
+                let expect = &*self.env().arena.alloc(stmt.clone());
+                let layout_map = self.layout_map();
+                let lookup_layouts: std::vec::Vec<_> =
+                    lookups.iter().map(|sym| layout_map[sym]).collect();
+
+                // Expand the Expect statement into IR that writes the failure to the shared buffer
+                let (expect_stmt, new_specializations) = {
+                    let (module_id, layout_interner, interns, helper_proc_gen, _) =
+                        self.module_interns_helpers_mut();
+                    let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+
+                    helper_proc_gen.expand_expect_stmt(
+                        ident_ids,
+                        layout_interner,
+                        expect,
+                        &lookup_layouts,
+                        *ret_layout,
+                    )
+                };
+
+                for spec in new_specializations.into_iter() {
+                    self.helper_proc_symbols_mut().push(spec);
+                }
+
+                self.build_stmt(layout_ids, expect_stmt, ret_layout)
+            }
             Stmt::Crash(msg, crash_tag) => self.roc_panic(*msg, *crash_tag),
-            x => todo!("the statement, {:?}", x),
+        }
+    }
+
+    fn roc_dbg(&mut self, source_location: &'a str, source: &'a str, msg: Symbol) {
+        let location = self.debug_symbol("dbg_location");
+        let source_str = self.debug_symbol("dbg_source");
+        self.load_literal(&location, &Layout::STR, &Literal::Str(source_location));
+        self.load_literal(&source_str, &Layout::STR, &Literal::Str(source));
+        self.load_literal_symbols(&[msg]);
+
+        // roc_dbg takes its arguments as `*RocStr`, so put them on the stack
+        let location_ptr = self.debug_symbol("location_ptr");
+        let msg_ptr = self.debug_symbol("msg_ptr");
+        let source_ptr = self.debug_symbol("source_ptr");
+        self.build_alloca(location_ptr, Some(location), Layout::STR);
+        self.build_alloca(msg_ptr, Some(msg), Layout::STR);
+        self.build_alloca(source_ptr, Some(source_str), Layout::STR);
+
+        self.build_fn_call(
+            &Symbol::DEV_TMP2,
+            String::from("roc_dbg"),
+            &[location_ptr, msg_ptr, source_ptr],
+            &[Layout::U64, Layout::U64, Layout::U64],
+            &Layout::UNIT,
+        );
+
+        self.free_symbol(&Symbol::DEV_TMP2);
+        for sym in [location, source_str, location_ptr, msg_ptr, source_ptr] {
+            self.free_symbol(&sym);
         }
     }
 
@@ -1906,6 +2034,70 @@ trait Backend<'a> {
 
                 self.build_num_int_cast(sym, &args[0], source_width, target_width)
             }
+            LowLevel::NumToIntChecked => {
+                let arg_width = arg_layouts[0].try_int_width().unwrap();
+
+                let ret_width = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Struct(&[value_layout, ..]) => value_layout.try_int_width(),
+                    _ => None,
+                };
+
+                let Some(ret_width) = ret_width else {
+                    internal_error!(
+                        "NumToIntChecked is not defined for signature {:?} -> {:?}",
+                        arg_layouts[0],
+                        ret_layout
+                    );
+                };
+
+                let intrinsic = if arg_width.is_signed() {
+                    bitcode::NUM_INT_TO_INT_CHECKING_MAX_AND_MIN[ret_width][arg_width].to_string()
+                } else {
+                    bitcode::NUM_INT_TO_INT_CHECKING_MAX[ret_width][arg_width].to_string()
+                };
+
+                self.build_fn_call(sym, intrinsic, args, arg_layouts, ret_layout)
+            }
+            LowLevel::NumToFloatChecked => {
+                let intrinsics = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Struct(&[Layout::F32, ..]) => &bitcode::NUM_TO_FLOAT_CHECKED_F32,
+                    LayoutRepr::Struct(&[Layout::F64, ..]) => &bitcode::NUM_TO_FLOAT_CHECKED_F64,
+                    _ => internal_error!(
+                        "NumToFloatChecked is not defined for signature {:?} -> {:?}",
+                        arg_layouts[0],
+                        ret_layout
+                    ),
+                };
+
+                match self.interner().get_repr(arg_layouts[0]) {
+                    LayoutRepr::Builtin(Builtin::Int(int_width)) => {
+                        let intrinsic = intrinsics[int_width].to_string();
+                        self.build_fn_call(sym, intrinsic, args, arg_layouts, ret_layout)
+                    }
+                    LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                        let intrinsic = intrinsics[float_width].to_string();
+                        self.build_fn_call(sym, intrinsic, args, arg_layouts, ret_layout)
+                    }
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        // every Dec fits in an F64, so convert first and check the F64
+                        let as_f64 = self.debug_symbol("dec_as_f64");
+
+                        self.build_fn_call(
+                            &as_f64,
+                            bitcode::DEC_TO_F64.to_string(),
+                            args,
+                            arg_layouts,
+                            &Layout::F64,
+                        );
+
+                        let intrinsic = intrinsics[FloatWidth::F64].to_string();
+                        self.build_fn_call(sym, intrinsic, &[as_f64], &[Layout::F64], ret_layout);
+
+                        self.free_symbol(&as_f64);
+                    }
+                    other => internal_error!("NumToFloatChecked on non-number layout {:?}", other),
+                }
+            }
             LowLevel::NumIsMultipleOf => {
                 let int_width = arg_layouts[0].try_int_width().unwrap();
                 let intrinsic = bitcode::NUM_IS_MULTIPLE_OF[int_width].to_string();
@@ -1,3 +1,4 @@
+use crate::debug_info::{add_debug_sections, DebugProc};
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{AssemblyBackendMode, Backend, Env, Relocation};
 use bumpalo::collections::Vec;
@@ -7,6 +8,7 @@ use object::{
     Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags, RelocationKind,
     SectionKind, SymbolFlags, SymbolKind, SymbolScope,
 };
+use roc_builtins::bitcode;
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::symbol;
@@ -319,23 +321,6 @@ fn generate_roc_panic<'a, B: Backend<'a>>(backend: &mut B, output: &mut Object)
     }
 }
 
-fn generate_roc_dbg<'a, B: Backend<'a>>(_backend: &mut B, output: &mut Object) {
-    let text_section = output.section_id(StandardSection::Text);
-    let proc_symbol = Symbol {
-        name: "roc_dbg".as_bytes().to_vec(),
-        value: 0,
-        size: 0,
-        kind: SymbolKind::Text,
-        scope: SymbolScope::Dynamic,
-        weak: false,
-        section: SymbolSection::Section(text_section),
-        flags: SymbolFlags::None,
-    };
-    let _proc_id = output.add_symbol(proc_symbol);
-    // TODO: Actually generate an impl instead of just an empty symbol.
-    // At a minimum, it should just be a ret.
-}
-
 fn generate_wrapper<'a, B: Backend<'a>>(
     backend: &mut B,
     output: &mut Object,
@@ -455,7 +440,12 @@ fn build_object<'a, B: Backend<'a>>(
     }
 
     if backend.env().mode.generate_roc_dbg() {
-        generate_roc_dbg(&mut backend, &mut output);
+        generate_wrapper(
+            &mut backend,
+            &mut output,
+            "roc_dbg".into(),
+            bitcode::UTILS_DBG_IMPL.into(),
+        );
     }
 
     if backend.env().mode.generate_allocators() {
@@ -587,6 +577,26 @@ fn build_object<'a, B: Backend<'a>>(
                         Exposed::TestMain,
                     );
                 }
+                AssemblyBackendMode::Expect => {
+                    // `roc test` calls each expect like the repl calls its main, so that a
+                    // crash is reported rather than taking the process down
+                    let expect_helper = roc_mono::code_gen_help::repl_helper(
+                        code_gen_help,
+                        ident_ids,
+                        layout_interner,
+                        &proc,
+                    );
+
+                    build_proc_symbol(
+                        &mut output,
+                        &mut layout_ids,
+                        &mut procs,
+                        &mut backend,
+                        layout,
+                        expect_helper,
+                        Exposed::Expect(sym),
+                    );
+                }
                 AssemblyBackendMode::Binary => { /* do nothing */ }
             }
 
@@ -624,11 +634,13 @@ fn build_object<'a, B: Backend<'a>>(
 
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
+    let mut debug_procs = std::vec::Vec::new();
     for (fn_name, section_id, proc_id, proc) in procs {
         build_proc(
             &mut output,
             &mut backend,
             &mut relocations,
+            &mut debug_procs,
             &mut layout_ids,
             data_section,
             fn_name,
@@ -651,7 +663,7 @@ fn build_object<'a, B: Backend<'a>>(
         std::mem::swap(caller_procs, &mut owned_caller_procs);
 
         let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
-        let mut helper_procs = helper_proc_gen.take_procs();
+        let mut helper_procs = helper_proc_gen.take_procs(ident_ids);
 
         for caller_proc in owned_caller_procs {
             helper_symbols_and_layouts.push((caller_proc.proc_symbol, caller_proc.proc_layout));
@@ -720,6 +732,7 @@ fn build_object<'a, B: Backend<'a>>(
             &mut output,
             &mut backend,
             &mut relocations,
+            &mut debug_procs,
             &mut layout_ids,
             data_section,
             fn_name,
@@ -736,6 +749,14 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    add_debug_sections(
+        &mut output,
+        backend.interns(),
+        &backend.env().module_sources,
+        &debug_procs,
+    );
+
     output
 }
 
@@ -778,6 +799,7 @@ fn build_exposed_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'a>) -> P
         ret_layout: proc.ret_layout,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         is_erased: proc.is_erased,
+        region: proc.region,
     }
 }
 
@@ -859,6 +881,7 @@ fn build_exposed_generic_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'
         ret_layout: roc_mono::layout::Layout::UNIT,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         is_erased: proc.is_erased,
+        region: proc.region,
     }
 }
 
@@ -868,6 +891,18 @@ enum Exposed {
     Exposed,
     NotExposed,
     TestMain,
+    Expect(symbol::Symbol),
+}
+
+/// The name of the function that runs a top-level expect, in a module built with
+/// `AssemblyBackendMode::Expect`
+pub fn expect_symbol_name(symbol: symbol::Symbol, interns: &Interns) -> String {
+    format!(
+        "Expect_{}_{}_{}",
+        symbol.module_string(interns),
+        symbol.as_str(interns),
+        symbol.ident_id().index()
+    )
 }
 
 fn build_proc_symbol<'a, B: Backend<'a>>(
@@ -901,6 +936,7 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
             layout.result,
         ),
         Exposed::TestMain => String::from("test_main"),
+        Exposed::Expect(expect) => expect_symbol_name(expect, backend.interns()),
     };
 
     let proc_symbol = Symbol {
@@ -911,7 +947,9 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
         // TODO: Depending on whether we are building a static or dynamic lib, this should change.
         // We should use Dynamic -> anyone, Linkage -> static link, Compilation -> this module only.
         scope: match exposed {
-            Exposed::ExposedGeneric | Exposed::Exposed | Exposed::TestMain => SymbolScope::Dynamic,
+            Exposed::ExposedGeneric | Exposed::Exposed | Exposed::TestMain | Exposed::Expect(_) => {
+                SymbolScope::Dynamic
+            }
             Exposed::NotExposed => SymbolScope::Linkage,
         },
         weak: false,
@@ -927,6 +965,7 @@ fn build_proc<'a, B: Backend<'a>>(
     output: &mut Object,
     backend: &mut B,
     relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
+    debug_procs: &mut std::vec::Vec<DebugProc>,
     layout_ids: &mut LayoutIds<'a>,
     data_section: SectionId,
     fn_name: String,
@@ -936,8 +975,19 @@ fn build_proc<'a, B: Backend<'a>>(
 ) {
     let mut local_data_index = 0;
     let target = backend.target();
+    let symbol = proc.name.name();
+    let region = proc.region;
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
+
+    debug_procs.push(DebugProc {
+        symbol,
+        symbol_id: proc_id,
+        linkage_name: fn_name.clone(),
+        region,
+        size: proc_data.len() as u64,
+        line_table: backend.line_table().to_vec(),
+    });
     for reloc in relocs.iter() {
         let elfreloc = match reloc {
             Relocation::LocalData { offset, data } => {
@@ -1100,7 +1150,7 @@ fn add_undefined_rc_proc(
     }
 }
 
-fn format_symbol_name(sym: roc_module::symbol::Symbol) -> std::vec::Vec<u8> {
+fn format_symbol_name(sym: symbol::Symbol) -> std::vec::Vec<u8> {
     let name = format!(".text.{:x}", sym.as_u64());
     let length = Ord::min(name.len(), 16);
 
@@ -19,7 +19,7 @@ use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -53,12 +53,15 @@ use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::Region;
 use roc_std::RocDec;
 use roc_target::{PtrWidth, Target};
 use std::convert::TryInto;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use super::convert::{struct_type_from_union_layout, RocUnion};
+use super::debug_info::{set_debug_location, DebugSources, PendingDebugValue};
 use super::intrinsics::{
     add_intrinsics, LLVM_FRAME_ADDRESS, LLVM_MEMSET_I32, LLVM_MEMSET_I64, LLVM_SETJMP,
     LLVM_STACK_SAVE,
@@ -660,20 +663,23 @@ fn print_fn_verification_output() -> bool {
 #[macro_export]
 macro_rules! debug_info_init {
     ($env:expr, $function_value:expr) => {{
+        $crate::debug_info_init!($env, $function_value, $env.compile_unit.get_file(), 0, 0)
+    }};
+    ($env:expr, $function_value:expr, $file:expr, $line:expr, $column:expr) => {{
         use inkwell::debug_info::AsDIScope;
 
         let func_scope = $function_value.get_subprogram().expect("subprogram");
         let lexical_block = $env.dibuilder.create_lexical_block(
             /* scope */ func_scope.as_debug_info_scope(),
-            /* file */ $env.compile_unit.get_file(),
-            /* line_no */ 0,
-            /* column_no */ 0,
+            /* file */ $file,
+            /* line_no */ $line,
+            /* column_no */ $column,
         );
 
         let loc = $env.dibuilder.create_debug_location(
             $env.context,
-            /* line */ 0,
-            /* column */ 0,
+            /* line */ $line,
+            /* column */ $column,
             /* current_scope */ lexical_block.as_debug_info_scope(),
             /* inlined_at */ None,
         );
@@ -741,6 +747,7 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target: Target,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    pub debug_sources: DebugSources<'ctx>,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -954,12 +961,15 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             inkwell::module::FlagBehavior::Warning,
             debug_metadata_version,
         );
+        // DWARF has no language code for Roc, and claiming C would have debuggers parse
+        // expressions and print values as if they were C. This vendor code is read as plain
+        // assembly instead, which assumes nothing about the source language. (Same as gen_dev.)
         module.create_debug_info_builder(
             true,
-            /* language */ inkwell::debug_info::DWARFSourceLanguage::C,
+            /* language */ inkwell::debug_info::DWARFSourceLanguage::MipsAssembler,
             /* filename */ "roc_app",
             /* directory */ ".",
-            /* producer */ "my llvm compiler frontend",
+            /* producer */ "roc",
             /* is_optimized */ false,
             /* compiler command line flags */ "",
             /* runtime_ver */ 0,
@@ -974,8 +984,29 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
+        self.new_subprogram_at(function_name, self.compile_unit.get_file(), 0)
+    }
+
+    /// A subprogram for a Roc proc, at the place in the source where it was defined
+    pub fn new_proc_subprogram(
+        &self,
+        function_name: &str,
+        symbol: Symbol,
+        region: Region,
+    ) -> DISubprogram<'ctx> {
+        match self.debug_sources.location(symbol.module_id(), region) {
+            Some(location) => self.new_subprogram_at(function_name, location.file, location.line),
+            None => self.new_subprogram(function_name),
+        }
+    }
+
+    fn new_subprogram_at(
+        &self,
+        function_name: &str,
+        file: DIFile<'ctx>,
+        line: u32,
+    ) -> DISubprogram<'ctx> {
         let dibuilder = self.dibuilder;
-        let compile_unit = self.compile_unit;
 
         let ditype = dibuilder
             .create_basic_type(
@@ -987,22 +1018,22 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             .unwrap();
 
         let subroutine_type = dibuilder.create_subroutine_type(
-            compile_unit.get_file(),
+            file,
             /* return type */ Some(ditype.as_type()),
             /* parameter types */ &[],
             inkwell::debug_info::DIFlags::PUBLIC,
         );
 
         dibuilder.create_function(
-            /* scope */ compile_unit.get_file().as_debug_info_scope(),
+            /* scope */ file.as_debug_info_scope(),
             /* func name */ function_name,
             /* linkage_name */ None,
-            /* file */ compile_unit.get_file(),
-            /* line_no */ 0,
+            /* file */ file,
+            /* line_no */ line,
             /* DIType */ subroutine_type,
             /* is_local_to_unit */ true,
             /* is_definition */ true,
-            /* scope_line */ 0,
+            /* scope_line */ line,
             /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
             /* is_optimized */ false,
         )
@@ -3061,22 +3092,46 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
         Let(first_symbol, first_expr, first_layout, mut cont) => {
             let mut queue = Vec::new_in(env.arena);
 
-            queue.push((first_symbol, first_expr, first_layout));
+            queue.push((first_symbol, first_expr, first_layout, None));
 
-            while let Let(symbol, expr, layout, new_cont) = cont {
-                queue.push((symbol, expr, layout));
+            // Later defs start with their location
+            let mut location = None;
+            loop {
+                match cont {
+                    Let(symbol, expr, layout, new_cont) => {
+                        queue.push((symbol, expr, layout, location.take()));
 
-                cont = new_cont;
+                        cont = new_cont;
+                    }
+                    Located {
+                        module,
+                        region,
+                        remainder: new_cont @ Let(..),
+                    } => {
+                        location = Some((*module, *region));
+
+                        cont = new_cont;
+                    }
+                    _ => break,
+                }
             }
 
+            let outer_location = scope.location();
+
             let mut stack = Vec::with_capacity_in(queue.len(), env.arena);
+            let mut debug_values = Vec::new_in(env.arena);
 
-            for (symbol, expr, layout) in queue {
+            for (symbol, expr, layout, location) in queue {
                 debug_assert!(!matches!(
                     layout_interner.get_repr(*layout),
                     LayoutRepr::RecursivePointer(_)
                 ));
 
+                if let Some((module, region)) = location {
+                    scope.set_location(location);
+                    set_debug_location(env, module, region);
+                }
+
                 let val = build_exp_expr(
                     env,
                     layout_interner,
@@ -3097,6 +3152,23 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
 
                 scope.insert(*symbol, *layout, val);
                 stack.push(*symbol);
+
+                // Variables that only hold part of an expression have no name in the source
+                match scope.location() {
+                    Some((module, region)) if !symbol.is_generated(&env.interns) => {
+                        debug_values.extend(PendingDebugValue::new(
+                            env,
+                            layout_interner,
+                            *symbol,
+                            *layout,
+                            val,
+                            module,
+                            region,
+                            None,
+                        ));
+                    }
+                    _ => {}
+                }
             }
 
             let result = build_exp_stmt(
@@ -3109,10 +3181,16 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                 cont,
             );
 
+            scope.set_location(outer_location);
+
             for symbol in stack {
                 scope.remove(&symbol);
             }
 
+            for debug_value in debug_values {
+                debug_value.emit(env.dibuilder);
+            }
+
             result
         }
         Ret(symbol) => {
@@ -3487,6 +3565,29 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             )
         }
 
+        Located {
+            module,
+            region,
+            remainder,
+        } => {
+            let outer_location = scope.set_location(Some((*module, *region)));
+            set_debug_location(env, *module, *region);
+
+            let result = build_exp_stmt(
+                env,
+                layout_interner,
+                layout_ids,
+                func_spec_solutions,
+                scope,
+                parent,
+                remainder,
+            );
+
+            scope.set_location(outer_location);
+
+            result
+        }
+
         Expect {
             condition: cond_symbol,
             region,
@@ -5365,8 +5466,8 @@ pub fn build_procedures<'a>(
     entry_point: EntryPoint<'a>,
     debug_output_file: Option<&Path>,
     glue_layouts: &GlueLayouts<'a>,
-) {
-    let mod_solutions = build_procedures_help(
+) -> Duration {
+    let (mod_solutions, alias_analysis) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5411,6 +5512,8 @@ pub fn build_procedures<'a>(
             getter_name,
         );
     }
+
+    alias_analysis
 }
 
 pub fn build_wasm_test_wrapper<'a, 'ctx>(
@@ -5420,7 +5523,7 @@ pub fn build_wasm_test_wrapper<'a, 'ctx>(
     procedures: MutMap<(Symbol, ProcLayout<'a>), roc_mono::ir::Proc<'a>>,
     entry_point: SingleEntryPoint<'a>,
 ) -> (&'static str, FunctionValue<'ctx>) {
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5447,7 +5550,7 @@ pub fn build_procedures_return_main<'a, 'ctx>(
     host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     entry_point: SingleEntryPoint<'a>,
 ) -> (&'static str, FunctionValue<'ctx>) {
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5481,7 +5584,7 @@ pub fn build_procedures_expose_expects<'a>(
         symbols: &flattened_symbols,
     };
 
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5564,13 +5667,14 @@ fn build_procedures_help<'a>(
     host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     entry_point: EntryPoint<'a>,
     debug_output_file: Option<&Path>,
-) -> &'a ModSolutions {
+) -> (&'a ModSolutions, Duration) {
     let mut layout_ids = roc_mono::layout::LayoutIds::default();
     let mut scope = Scope::default();
 
     let it1 = procedures.iter().map(|x| x.1);
     let it2 = host_exposed_lambda_sets.iter().map(|(_, _, hels)| hels);
 
+    let alias_analysis_start = Instant::now();
     let solutions = match roc_alias_analysis::spec_program(
         env.arena,
         layout_interner,
@@ -5582,6 +5686,7 @@ fn build_procedures_help<'a>(
         Err(e) => panic!("Error in alias analysis: {e}"),
         Ok(solutions) => solutions,
     };
+    let alias_analysis = alias_analysis_start.elapsed();
 
     let solutions = env.arena.alloc(solutions);
 
@@ -5675,7 +5780,7 @@ fn build_procedures_help<'a>(
         }
     }
 
-    mod_solutions
+    (mod_solutions, alias_analysis)
 }
 
 pub enum FuncBorrowSpec {
@@ -5751,7 +5856,7 @@ fn build_proc_header<'a, 'ctx>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(&fn_name, symbol, proc.region);
     fn_val.set_subprogram(subprogram);
 
     debug_info_init!(env, fn_val);
@@ -6046,12 +6151,32 @@ fn build_proc<'a, 'ctx>(
 
     builder.position_at_end(entry);
 
-    debug_info_init!(env, fn_val);
+    match env
+        .debug_sources
+        .location(proc.name.name().module_id(), proc.region)
+    {
+        Some(location) => {
+            debug_info_init!(env, fn_val, location.file, location.line, location.column)
+        }
+        None => debug_info_init!(env, fn_val),
+    }
 
     // Add args to scope
-    for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
+    let mut debug_values = Vec::with_capacity_in(args.len(), env.arena);
+    for (index, (arg_val, (layout, arg_symbol))) in fn_val.get_param_iter().zip(args).enumerate() {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
         scope.insert(*arg_symbol, *layout, arg_val);
+
+        debug_values.extend(PendingDebugValue::new(
+            env,
+            layout_interner,
+            *arg_symbol,
+            *layout,
+            arg_val,
+            proc.name.name().module_id(),
+            proc.region,
+            Some(index as u32 + 1),
+        ));
     }
 
     let body = build_exp_stmt(
@@ -6070,6 +6195,10 @@ fn build_proc<'a, 'ctx>(
             builder.new_build_return(Some(&body));
         }
     }
+
+    for debug_value in debug_values {
+        debug_value.emit(env.dibuilder);
+    }
 }
 
 pub fn verify_fn(fn_val: FunctionValue<'_>) {
@@ -0,0 +1,357 @@
+use std::path::PathBuf;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::debug_info::{
+    DIFile, DIFlags, DIFlagsConstants, DILocalVariable, DILocation, DIType, DebugInfoBuilder,
+};
+use inkwell::values::{BasicValueEnum, InstructionValue};
+use inkwell::AddressSpace;
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_mono::layout::{
+    Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
+};
+use roc_region::all::{LineInfo, Region};
+
+use super::build::Env;
+
+// DWARF base type encodings, see section 7.8 of the DWARF 5 spec
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+
+/// The Roc source files that procs were lowered from, so debug info can point at real files and
+/// lines. Empty when no sources are available (e.g. in the REPL), in which case all debug info
+/// falls back to the placeholder compile unit at line 0.
+#[derive(Default)]
+pub struct DebugSources<'ctx> {
+    files: MutMap<ModuleId, (DIFile<'ctx>, LineInfo)>,
+}
+
+/// A 1-based line and column in a Roc source file
+#[derive(Clone, Copy)]
+pub struct SourceLocation<'ctx> {
+    pub file: DIFile<'ctx>,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl<'ctx> DebugSources<'ctx> {
+    pub fn new(
+        dibuilder: &DebugInfoBuilder<'ctx>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Self {
+        let files = sources
+            .iter()
+            .map(|(module_id, (path, src))| {
+                let filename = path.file_name().unwrap_or_default().to_string_lossy();
+                let directory = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+                    _ => ".".into(),
+                };
+                let file = dibuilder.create_file(&filename, &directory);
+
+                (*module_id, (file, LineInfo::new(src)))
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    /// Where `region` is in the source of `module`.
+    ///
+    /// Returns [None] for generated code, which has no region, and for modules we have no source for.
+    pub fn location(&self, module: ModuleId, region: Region) -> Option<SourceLocation<'ctx>> {
+        if region.is_empty() {
+            return None;
+        }
+
+        let (file, line_info) = self.files.get(&module)?;
+        let position = line_info.convert_pos(region.start());
+
+        Some(SourceLocation {
+            file: *file,
+            line: position.line + 1,
+            column: position.column + 1,
+        })
+    }
+}
+
+/// Points the builder's debug location at `region`, staying in the current debug scope.
+/// Does nothing if there is no current debug location or no source for `region`.
+pub(crate) fn set_debug_location(env: &Env, module: ModuleId, region: Region) {
+    let Some(current) = env.builder.get_current_debug_location() else {
+        return;
+    };
+    let Some(location) = env.debug_sources.location(module, region) else {
+        return;
+    };
+
+    let debug_location = env.dibuilder.create_debug_location(
+        env.context,
+        location.line,
+        location.column,
+        current.get_scope(),
+        /* inlined_at */ None,
+    );
+    env.builder.set_current_debug_location(debug_location);
+}
+
+/// A DWARF type for values of this layout, so debuggers can display them.
+///
+/// Scalars become base types, `Str`, `List` and records become structs, and heap-allocated values
+/// become pointers. Decimals, tag unions stored by value and functions have no debug type.
+fn debug_type_from_layout<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'_>,
+    layout: InLayout<'_>,
+) -> Option<DIType<'ctx>> {
+    let ptr_bytes = env.target.ptr_size() as u32;
+    let length_name = match ptr_bytes {
+        4 => "U32",
+        _ => "U64",
+    };
+
+    match layout_interner.get_repr(layout) {
+        LayoutRepr::Builtin(Builtin::Int(int_width)) => {
+            let encoding = if int_width.is_signed() {
+                DW_ATE_SIGNED
+            } else {
+                DW_ATE_UNSIGNED
+            };
+
+            basic_type(env, int_width.type_name(), int_width.stack_size(), encoding)
+        }
+        LayoutRepr::Builtin(Builtin::Float(float_width)) => basic_type(
+            env,
+            float_width.type_name(),
+            float_width.stack_size(),
+            DW_ATE_FLOAT,
+        ),
+        LayoutRepr::Builtin(Builtin::Bool) => basic_type(env, "Bool", 1, DW_ATE_BOOLEAN),
+        LayoutRepr::Builtin(Builtin::Str) => {
+            let bytes = pointer_type(env, basic_type(env, "U8", 1, DW_ATE_UNSIGNED)?);
+            let length = basic_type(env, length_name, ptr_bytes, DW_ATE_UNSIGNED)?;
+
+            struct_type(
+                env,
+                "Str",
+                &[
+                    ("bytes", bytes, ptr_bytes, ptr_bytes),
+                    ("length", length, ptr_bytes, ptr_bytes),
+                    ("capacity", length, ptr_bytes, ptr_bytes),
+                ],
+            )
+        }
+        LayoutRepr::Builtin(Builtin::List(element_layout)) => {
+            // the elements still show up as bytes when their own layout has no debug type
+            let element = debug_type_from_layout(env, layout_interner, element_layout)
+                .or_else(|| basic_type(env, "U8", 1, DW_ATE_UNSIGNED))?;
+            let elements = pointer_type(env, element);
+            let length = basic_type(env, length_name, ptr_bytes, DW_ATE_UNSIGNED)?;
+
+            struct_type(
+                env,
+                "List",
+                &[
+                    ("elements", elements, ptr_bytes, ptr_bytes),
+                    ("length", length, ptr_bytes, ptr_bytes),
+                    ("capacity", length, ptr_bytes, ptr_bytes),
+                ],
+            )
+        }
+        LayoutRepr::Struct(field_layouts) => {
+            let names: Vec<String> = (0..field_layouts.len()).map(|i| i.to_string()).collect();
+            let mut fields = Vec::with_capacity(field_layouts.len());
+            for (name, field_layout) in names.iter().zip(field_layouts.iter()) {
+                fields.push((
+                    name.as_str(),
+                    debug_type_from_layout(env, layout_interner, *field_layout)?,
+                    layout_interner.stack_size(*field_layout),
+                    layout_interner.alignment_bytes(*field_layout),
+                ));
+            }
+
+            struct_type(env, "Record", &fields)
+        }
+        LayoutRepr::Ptr(inner) => {
+            let inner = debug_type_from_layout(env, layout_interner, inner)?;
+
+            Some(pointer_type(env, inner))
+        }
+        LayoutRepr::Union(UnionLayout::NonRecursive(_)) => None,
+        // the contents of a heap-allocated union depend on its tag, so only the address is shown
+        LayoutRepr::Union(_) | LayoutRepr::RecursivePointer(_) => {
+            let byte = basic_type(env, "U8", 1, DW_ATE_UNSIGNED)?;
+
+            Some(pointer_type(env, byte))
+        }
+        LayoutRepr::Builtin(Builtin::Decimal)
+        | LayoutRepr::LambdaSet(_)
+        | LayoutRepr::FunctionPointer(_)
+        | LayoutRepr::Erased(_) => None,
+    }
+}
+
+fn basic_type<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    name: &str,
+    size_in_bytes: u32,
+    encoding: u32,
+) -> Option<DIType<'ctx>> {
+    env.dibuilder
+        .create_basic_type(name, size_in_bytes as u64 * 8, encoding, DIFlags::PUBLIC)
+        .ok()
+        .map(|basic_type| basic_type.as_type())
+}
+
+fn pointer_type<'ctx>(env: &Env<'_, 'ctx, '_>, pointee: DIType<'ctx>) -> DIType<'ctx> {
+    let ptr_bits = env.target.ptr_size() as u32 * 8;
+
+    env.dibuilder
+        .create_pointer_type(
+            "",
+            pointee,
+            ptr_bits as u64,
+            ptr_bits,
+            AddressSpace::default(),
+        )
+        .as_type()
+}
+
+/// A struct with C layout, given the name, type, size and alignment in bytes of each field
+fn struct_type<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    name: &str,
+    fields: &[(&str, DIType<'ctx>, u32, u32)],
+) -> Option<DIType<'ctx>> {
+    let scope = env.compile_unit.as_debug_info_scope();
+    let file = env.compile_unit.get_file();
+
+    let mut offset = 0;
+    let mut struct_alignment = 1;
+    let mut members = Vec::with_capacity(fields.len());
+    for (field_name, ty, size, alignment) in fields {
+        let alignment = (*alignment).max(1);
+        offset = offset.next_multiple_of(alignment);
+        struct_alignment = struct_alignment.max(alignment);
+
+        let member = env.dibuilder.create_member_type(
+            scope,
+            field_name,
+            file,
+            /* line_no */ 0,
+            *size as u64 * 8,
+            alignment * 8,
+            offset as u64 * 8,
+            DIFlags::PUBLIC,
+            *ty,
+        );
+        members.push(member.as_type());
+
+        offset += size;
+    }
+    let size = offset.next_multiple_of(struct_alignment);
+
+    let struct_type = env.dibuilder.create_struct_type(
+        scope,
+        name,
+        file,
+        /* line_number */ 0,
+        size as u64 * 8,
+        struct_alignment * 8,
+        DIFlags::PUBLIC,
+        /* derived_from */ None,
+        &members,
+        /* runtime_language */ 0,
+        /* vtable_holder */ None,
+        /* unique_id */ "",
+    );
+
+    Some(struct_type.as_type())
+}
+
+/// A local variable whose `llvm.dbg.value` can only be placed once the instruction following its
+/// definition has been built.
+pub(crate) struct PendingDebugValue<'ctx> {
+    variable: DILocalVariable<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    location: DILocation<'ctx>,
+    block: BasicBlock<'ctx>,
+    /// The last instruction in `block` when the value was defined
+    after: Option<InstructionValue<'ctx>>,
+}
+
+impl<'ctx> PendingDebugValue<'ctx> {
+    /// Describes `symbol` as a local variable of the current debug scope, declared at `region` of
+    /// `module` and holding `value` from the current position of the builder onwards. `arg_no` is
+    /// the 1-based index of a proc argument.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        env: &Env<'_, 'ctx, '_>,
+        layout_interner: &STLayoutInterner<'_>,
+        symbol: Symbol,
+        layout: InLayout<'_>,
+        value: BasicValueEnum<'ctx>,
+        module: ModuleId,
+        region: Region,
+        arg_no: Option<u32>,
+    ) -> Option<Self> {
+        let location = env.builder.get_current_debug_location()?;
+        let source = env.debug_sources.location(module, region)?;
+        let ty = debug_type_from_layout(env, layout_interner, layout)?;
+        let block = env.builder.get_insert_block()?;
+
+        let scope = location.get_scope();
+        let name = symbol.as_str(&env.interns);
+        let variable = match arg_no {
+            Some(arg_no) => env.dibuilder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                source.file,
+                source.line,
+                ty,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+            ),
+            None => env.dibuilder.create_auto_variable(
+                scope,
+                name,
+                source.file,
+                source.line,
+                ty,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+                /* align_in_bits */ 0,
+            ),
+        };
+
+        Some(Self {
+            variable,
+            value,
+            location,
+            block,
+            after: block.get_last_instruction(),
+        })
+    }
+
+    /// Emits the `llvm.dbg.value`, if an instruction was built after the value's definition.
+    pub(crate) fn emit(self, dibuilder: &DebugInfoBuilder<'ctx>) {
+        let before = match self.after {
+            Some(after) => after.get_next_instruction(),
+            None => self.block.get_first_instruction(),
+        };
+
+        if let Some(before) = before {
+            dibuilder.insert_dbg_value_before(
+                self.value,
+                self.variable,
+                /* expr */ None,
+                self.location,
+                before,
+            );
+        }
+    }
+}
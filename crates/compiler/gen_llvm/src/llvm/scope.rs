@@ -8,12 +8,15 @@ use roc_mono::{
     ir::{JoinPointId, Param, ProcLayout},
     layout::InLayout,
 };
+use roc_region::all::Region;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct Scope<'a, 'ctx> {
     symbols: ImMap<Symbol, (InLayout<'a>, BasicValueEnum<'ctx>)>,
     top_level_thunks: ImMap<Symbol, (ProcLayout<'a>, FunctionValue<'ctx>)>,
     join_points: ImMap<JoinPointId, (BasicBlock<'ctx>, Vec<JoinPointArg<'ctx>>)>,
+    /// Where the code being built came from, see [roc_mono::ir::Stmt::Located]
+    location: Option<(ModuleId, Region)>,
 }
 
 #[derive(Debug)]
@@ -45,6 +48,18 @@ impl<'a, 'ctx> Scope<'a, 'ctx> {
         }
     }
 
+    pub fn location(&self) -> Option<(ModuleId, Region)> {
+        self.location
+    }
+
+    /// Returns the location of the code built before
+    pub fn set_location(
+        &mut self,
+        location: Option<(ModuleId, Region)>,
+    ) -> Option<(ModuleId, Region)> {
+        std::mem::replace(&mut self.location, location)
+    }
+
     pub fn insert_top_level_thunk(
         &mut self,
         symbol: Symbol,
@@ -257,7 +257,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     }
 
     pub fn get_helpers(&mut self) -> Vec<'a, Proc<'a>> {
-        self.helper_proc_gen.take_procs()
+        let ident_ids = self
+            .interns
+            .all_ident_ids
+            .get_mut(&self.env.module_id)
+            .unwrap();
+
+        self.helper_proc_gen.take_procs(ident_ids)
     }
 
     pub fn register_helper_proc(
@@ -579,7 +585,8 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
     fn stmt(&mut self, stmt: &Stmt<'a>) {
         match stmt {
-            Stmt::Let(_, _, _, _) => self.stmt_let(stmt),
+            // There is no debug info for Wasm, so where the code came from doesn't matter
+            Stmt::Let(_, _, _, _) | Stmt::Located { .. } => self.stmt_let(stmt),
 
             Stmt::Ret(sym) => self.stmt_ret(*sym),
 
@@ -605,8 +612,27 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 _ => self.stmt_refcounting(modify, following),
             },
 
-            Stmt::Dbg { .. } => todo!("dbg is not implemented in the wasm backend"),
-            Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable: _,
+                remainder,
+            } => self.stmt_dbg(source_location, source, *symbol, remainder),
+
+            Stmt::Expect { remainder, .. } if !self.env.runs_expects => self.stmt(remainder),
+
+            Stmt::Expect {
+                condition,
+                region,
+                lookups: _,
+                variables: _,
+                remainder,
+            } => {
+                let module_id: u32 = unsafe { std::mem::transmute(condition.module_id()) };
+                let region = [region.start().offset, region.end().offset, module_id];
+                self.stmt_expect(*condition, region, remainder)
+            }
 
             Stmt::Crash(sym, tag) => self.stmt_crash(*sym, *tag),
         }
@@ -633,19 +659,25 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
     fn stmt_let(&mut self, stmt: &Stmt<'a>) {
         let mut current_stmt = stmt;
-        while let Stmt::Let(sym, expr, layout, following) = current_stmt {
-            if DEBUG_SETTINGS.let_stmt_ir {
-                print!("\nlet {:?} = {}", sym, expr.to_pretty(200, true));
-            }
+        loop {
+            match current_stmt {
+                Stmt::Let(sym, expr, layout, following) => {
+                    if DEBUG_SETTINGS.let_stmt_ir {
+                        print!("\nlet {:?} = {}", sym, expr.to_pretty(200, true));
+                    }
 
-            let kind = match following {
-                Stmt::Ret(ret_sym) if *sym == *ret_sym => StoredVarKind::ReturnValue,
-                _ => StoredVarKind::Variable,
-            };
+                    let kind = match following {
+                        Stmt::Ret(ret_sym) if *sym == *ret_sym => StoredVarKind::ReturnValue,
+                        _ => StoredVarKind::Variable,
+                    };
 
-            self.stmt_let_store_expr(*sym, *layout, expr, kind);
+                    self.stmt_let_store_expr(*sym, *layout, expr, kind);
 
-            current_stmt = *following;
+                    current_stmt = *following;
+                }
+                Stmt::Located { remainder, .. } => current_stmt = *remainder,
+                _ => break,
+            }
         }
 
         self.stmt(current_stmt);
@@ -898,25 +930,69 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.stmt(following);
     }
 
+    /// Pass the source location, the rendered source and the inspected value to the host's `roc_dbg`
+    fn stmt_dbg(
+        &mut self,
+        source_location: &str,
+        source: &str,
+        msg: Symbol,
+        following: &'a Stmt<'a>,
+    ) {
+        let location_sym = self.string_literal_var("dbg_location", source_location);
+        let source_sym = self.string_literal_var("dbg_source", source);
+
+        // All three strings are on the stack, so we pass pointers to them
+        self.storage
+            .load_symbols(&mut self.code_builder, &[location_sym, msg, source_sym]);
+        self.call_host_fn_after_loading_args("roc_dbg");
+
+        self.stmt(following);
+    }
+
+    /// Report a failed inline `expect` to the host's `roc_expect_failed`, then carry on.
+    /// The host gets the region of the expect and the module it's in, but not the looked-up values.
+    fn stmt_expect(&mut self, condition: Symbol, region: [u32; 3], following: &'a Stmt<'a>) {
+        self.start_block();
+
+        // skip the report if the condition holds
+        self.storage
+            .load_symbols(&mut self.code_builder, &[condition]);
+        self.code_builder.br_if(0);
+
+        for value in region {
+            self.code_builder.i32_const(value as i32);
+        }
+        self.call_host_fn_after_loading_args("roc_expect_failed");
+
+        self.end_block();
+
+        self.stmt(following);
+    }
+
     pub fn stmt_internal_error(&mut self, msg: &'a str) {
-        let msg_sym = self.create_symbol("panic_str");
-        let msg_storage = self.storage.allocate_var(
+        let msg_sym = self.string_literal_var("panic_str", msg);
+        self.stmt_crash(msg_sym, CrashTag::Roc);
+    }
+
+    /// Store a string literal as a RocStr on the stack
+    fn string_literal_var(&mut self, debug_name: &str, string: &str) -> Symbol {
+        let sym = self.create_symbol(debug_name);
+        let storage = self.storage.allocate_var(
             self.layout_interner,
             Layout::STR,
-            msg_sym,
+            sym,
             StoredVarKind::Variable,
         );
 
-        // Store the message as a RocStr on the stack
-        let (local_id, offset) = match msg_storage {
+        let (local_id, offset) = match storage {
             StoredValue::StackMemory { location, .. } => {
                 location.local_and_offset(self.storage.stack_frame_pointer)
             }
             _ => internal_error!("String must always have stack memory"),
         };
-        self.expr_string_literal(msg, local_id, offset);
+        self.expr_string_literal(string, local_id, offset);
 
-        self.stmt_crash(msg_sym, CrashTag::Roc);
+        sym
     }
 
     pub fn stmt_crash(&mut self, msg: Symbol, tag: CrashTag) {
@@ -1072,8 +1148,8 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                         write128(lower_bits, upper_bits);
                     }
                     Literal::Float(_) => {
-                        // Also not implemented in LLVM backend (nor in Rust!)
-                        todo!("f128 type");
+                        // Roc has no 128-bit float, and Dec literals are `Literal::Decimal`
+                        internal_error!("Float literal {:?} stored in stack memory", lit);
                     }
                     Literal::Str(string) => {
                         let (local_id, offset) =
@@ -16,6 +16,7 @@ use bumpalo::{self, Bump};
 use roc_collections::all::{MutMap, MutSet};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::{Proc, ProcLayout};
 use roc_mono::layout::{LayoutIds, STLayoutInterner};
 use roc_target::Target;
@@ -44,6 +45,11 @@ pub struct Env<'a> {
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    /// Whether to report failed inline `expect`s to the host's `roc_expect_failed`.
+    /// Only test hosts define it, so other builds skip the expects instead.
+    pub runs_expects: bool,
+    /// The budget for inlining the refcount and equality helpers, or `None` to always call them.
+    pub inline_budget: Option<InlineBudget>,
 }
 
 impl Env<'_> {
@@ -133,7 +139,7 @@ pub fn build_app_module<'a, 'r>(
         host_to_app_map,
         host_module,
         fn_index_offset,
-        CodeGenHelp::new(env.arena, Target::Wasm32, env.module_id),
+        CodeGenHelp::new(env.arena, Target::Wasm32, env.module_id, env.inline_budget),
     );
 
     if DEBUG_SETTINGS.user_procs_ir {
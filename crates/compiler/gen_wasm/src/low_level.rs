@@ -10,6 +10,7 @@ use roc_mono::layout::{Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, Un
 use roc_mono::low_level::HigherOrder;
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
+use crate::code_builder::CodeBuilder;
 use crate::layout::{StackMemoryFormat, WasmLayout};
 use crate::storage::{AddressValue, StackMemoryLocation, StoredValue};
 use crate::PTR_TYPE;
@@ -1218,12 +1219,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_GREATER_THAN[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_GREATER_THAN[IntWidth::I128],
                         );
                     }
@@ -1255,12 +1255,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_GREATER_THAN_OR_EQUAL[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_GREATER_THAN_OR_EQUAL[IntWidth::I128],
                         );
                     }
@@ -1292,12 +1291,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_LESS_THAN[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_LESS_THAN[IntWidth::I128],
                         );
                     }
@@ -1330,12 +1328,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_LESS_THAN_OR_EQUAL[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_LESS_THAN_OR_EQUAL[IntWidth::I128],
                         );
                     }
@@ -1388,8 +1385,17 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_lt();
                         backend.code_builder.i32_add();
                     }
-                    I128 | Decimal => {
-                        self.load_args(backend);
+                    I128 => {
+                        let intrinsic = if is_signed {
+                            &bitcode::NUM_COMPARE[IntWidth::I128]
+                        } else {
+                            &bitcode::NUM_COMPARE[IntWidth::U128]
+                        };
+
+                        self.load_args_and_call_zig(backend, intrinsic);
+                    }
+                    Decimal => {
+                        // same as i128
                         self.load_args_and_call_zig(backend, &bitcode::NUM_COMPARE[IntWidth::I128]);
                     }
                 }
@@ -1399,7 +1405,7 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
                     F32 => backend.code_builder.f32_div(),
                     F64 => backend.code_builder.f64_div(),
-                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_DIV),
+                    Decimal => backend.call_host_fn_after_loading_args(bitcode::DEC_DIV),
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1421,6 +1427,14 @@ impl<'a> LowLevelCall<'a> {
                             backend.code_builder.i64_div_u()
                         }
                     }
+                    I128 => {
+                        // from compiler_rt
+                        if is_signed {
+                            backend.call_host_fn_after_loading_args("__divti3")
+                        } else {
+                            backend.call_host_fn_after_loading_args("__udivti3")
+                        }
+                    }
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1433,9 +1447,30 @@ impl<'a> LowLevelCall<'a> {
 
             NumRemUnchecked => {
                 self.load_args(backend);
+                let is_signed = symbol_is_signed_int(backend, self.arguments[0]);
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
-                    I32 => backend.code_builder.i32_rem_s(),
-                    I64 => backend.code_builder.i64_rem_s(),
+                    I32 => {
+                        if is_signed {
+                            backend.code_builder.i32_rem_s()
+                        } else {
+                            backend.code_builder.i32_rem_u()
+                        }
+                    }
+                    I64 => {
+                        if is_signed {
+                            backend.code_builder.i64_rem_s()
+                        } else {
+                            backend.code_builder.i64_rem_u()
+                        }
+                    }
+                    I128 => {
+                        // from compiler_rt
+                        if is_signed {
+                            backend.call_host_fn_after_loading_args("__modti3")
+                        } else {
+                            backend.call_host_fn_after_loading_args("__umodti3")
+                        }
+                    }
                     _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
                 }
             }
@@ -1535,6 +1570,16 @@ impl<'a> LowLevelCall<'a> {
                         code_builder.get_local(tmp);
                     }
 
+                    I128 => {
+                        let width = if is_signed {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_IS_MULTIPLE_OF[width]);
+                    }
+
                     _ => panic_ret_type(),
                 }
             }
@@ -1599,7 +1644,16 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_abs(),
                     F64 => backend.code_builder.f64_abs(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    I128 => {
+                        let width = if layout_is_signed_int(self.ret_layout) {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+
+                        backend.call_host_fn_after_loading_args(&bitcode::NUM_ABS[width]);
+                    }
+                    Decimal => backend.call_host_fn_after_loading_args(bitcode::DEC_ABS),
                 }
             }
             NumNeg => {
@@ -1632,8 +1686,16 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_neg(),
                     F64 => backend.code_builder.f64_neg(),
-                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_NEGATE),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    I128 => {
+                        let width = if layout_is_signed_int(self.ret_layout) {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+
+                        backend.call_host_fn_after_loading_args(&bitcode::NUM_NEGATE[width]);
+                    }
+                    Decimal => backend.call_host_fn_after_loading_args(bitcode::DEC_NEGATE),
                 }
             }
             NumSin => match self.ret_layout_raw {
@@ -1687,41 +1749,81 @@ impl<'a> LowLevelCall<'a> {
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
                 let arg_is_signed = symbol_is_signed_int(backend, self.arguments[0]);
                 match (ret_type, arg_type) {
-                    (F32, I32) => backend.code_builder.f32_convert_s_i32(),
-                    (F32, I64) => backend.code_builder.f32_convert_s_i64(),
+                    (F32, I32) => {
+                        if arg_is_signed {
+                            backend.code_builder.f32_convert_s_i32()
+                        } else {
+                            backend.code_builder.f32_convert_u_i32()
+                        }
+                    }
+                    (F32, I64) => {
+                        if arg_is_signed {
+                            backend.code_builder.f32_convert_s_i64()
+                        } else {
+                            backend.code_builder.f32_convert_u_i64()
+                        }
+                    }
+                    (F32, I128) => {
+                        let int_width = match arg_is_signed {
+                            true => IntWidth::I128,
+                            false => IntWidth::U128,
+                        };
+
+                        backend.call_host_fn_after_loading_args(
+                            &bitcode::INT_TO_FLOAT_CAST_F32[int_width],
+                        );
+                    }
                     (F32, F32) => {}
                     (F32, F64) => backend.code_builder.f32_demote_f64(),
+                    (F32, Decimal) => {
+                        backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64);
+                        backend.code_builder.f32_demote_f64();
+                    }
 
-                    (F64, I32) => backend.code_builder.f64_convert_s_i32(),
-                    (F64, I64) => backend.code_builder.f64_convert_s_i64(),
-                    (F64, F32) => backend.code_builder.f64_promote_f32(),
-                    (F64, F64) => {}
-
-                    (Decimal, I32) => {
+                    (F64, I32) => {
+                        if arg_is_signed {
+                            backend.code_builder.f64_convert_s_i32()
+                        } else {
+                            backend.code_builder.f64_convert_u_i32()
+                        }
+                    }
+                    (F64, I64) => {
+                        if arg_is_signed {
+                            backend.code_builder.f64_convert_s_i64()
+                        } else {
+                            backend.code_builder.f64_convert_u_i64()
+                        }
+                    }
+                    (F64, I128) => {
                         let int_width = match arg_is_signed {
-                            true => IntWidth::I32,
-                            false => IntWidth::U32,
+                            true => IntWidth::I128,
+                            false => IntWidth::U128,
                         };
 
-                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]);
+                        backend.call_host_fn_after_loading_args(
+                            &bitcode::INT_TO_FLOAT_CAST_F64[int_width],
+                        );
                     }
-                    (Decimal, I64) => {
-                        let int_width = match arg_is_signed {
-                            true => IntWidth::I64,
-                            false => IntWidth::U64,
+                    (F64, F32) => backend.code_builder.f64_promote_f32(),
+                    (F64, F64) => {}
+                    (F64, Decimal) => backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64),
+
+                    (Decimal, I32 | I64 | I128) => {
+                        let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+                        let int_width = match backend.layout_interner.get_repr(arg_layout) {
+                            LayoutRepr::Builtin(Builtin::Int(int_width)) => int_width,
+                            x => internal_error!("NumToFrac is not defined for {:?}", x),
                         };
 
-                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]);
+                        backend.call_host_fn_after_loading_args(&bitcode::DEC_FROM_INT[int_width]);
                     }
                     (Decimal, F32) => {
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::DEC_FROM_FLOAT[FloatWidth::F32],
                         );
                     }
                     (Decimal, F64) => {
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::DEC_FROM_FLOAT[FloatWidth::F64],
                         );
                     }
@@ -1775,15 +1877,11 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumRound => {
-                self.load_args(backend);
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
-                let ret_type = CodeGenNumType::from(self.ret_layout);
 
-                let width = match ret_type {
-                    CodeGenNumType::I32 => IntWidth::I32,
-                    CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => internal_error!("Invalid return type for round: {:?}", ret_type),
+                let width = match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => width,
+                    _ => internal_error!("Invalid return type for round: {:?}", self.ret_layout),
                 };
 
                 match arg_type {
@@ -1798,13 +1896,26 @@ impl<'a> LowLevelCall<'a> {
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
                 let ret_type = CodeGenNumType::from(self.ret_layout);
 
-                let width = match ret_type {
-                    CodeGenNumType::I32 => IntWidth::I32,
-                    CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => internal_error!("Invalid return type for round: {:?}", ret_type),
+                let width = match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => width,
+                    _ => internal_error!("Invalid return type for round: {:?}", self.ret_layout),
                 };
 
+                if ret_type == I128 {
+                    // Wasm has no instruction to truncate a float to 128 bits
+                    let intrinsic = match (arg_type, self.lowlevel) {
+                        (F32, NumCeiling) => &bitcode::NUM_CEILING_F32[width],
+                        (F64, NumCeiling) => &bitcode::NUM_CEILING_F64[width],
+                        (Decimal, NumCeiling) => &bitcode::DEC_CEILING[width],
+                        (F32, NumFloor) => &bitcode::NUM_FLOOR_F32[width],
+                        (F64, NumFloor) => &bitcode::NUM_FLOOR_F64[width],
+                        (Decimal, NumFloor) => &bitcode::DEC_FLOOR[width],
+                        _ => internal_error!("Invalid argument type for ceiling: {:?}", arg_type),
+                    };
+
+                    return backend.call_host_fn_after_loading_args(intrinsic);
+                }
+
                 match (arg_type, self.lowlevel) {
                     (F32, NumCeiling) => {
                         backend.code_builder.f32_ceil();
@@ -1813,7 +1924,8 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_ceil();
                     }
                     (Decimal, NumCeiling) => {
-                        return self.load_args_and_call_zig(backend, &bitcode::DEC_CEILING[width]);
+                        return backend
+                            .call_host_fn_after_loading_args(&bitcode::DEC_CEILING[width]);
                     }
                     (F32, NumFloor) => {
                         backend.code_builder.f32_floor();
@@ -1822,7 +1934,7 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_floor();
                     }
                     (Decimal, NumFloor) => {
-                        return self.load_args_and_call_zig(backend, &bitcode::DEC_FLOOR[width]);
+                        return backend.call_host_fn_after_loading_args(&bitcode::DEC_FLOOR[width]);
                     }
                     _ => internal_error!("Invalid argument type for ceiling: {:?}", arg_type),
                 }
@@ -1833,7 +1945,6 @@ impl<'a> LowLevelCall<'a> {
                     (I32, F64) => backend.code_builder.i32_trunc_s_f64(),
                     (I64, F32) => backend.code_builder.i64_trunc_s_f32(),
                     (I64, F64) => backend.code_builder.i64_trunc_s_f64(),
-                    (I128, _) => todo!("{:?} for I128", self.lowlevel),
                     _ => panic_ret_type(),
                 }
             }
@@ -1876,45 +1987,82 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumBitwiseAnd => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
+                let ret_type = CodeGenNumType::from(self.ret_layout);
+                if ret_type != I128 {
+                    self.load_args(backend);
+                }
+                match ret_type {
                     I32 => backend.code_builder.i32_and(),
                     I64 => backend.code_builder.i64_and(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => num_bitwise_i128(
+                        backend,
+                        &self.ret_storage,
+                        self.arguments,
+                        CodeBuilder::i64_and,
+                    ),
                     _ => panic_ret_type(),
                 }
             }
             NumBitwiseXor => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
+                let ret_type = CodeGenNumType::from(self.ret_layout);
+                if ret_type != I128 {
+                    self.load_args(backend);
+                }
+                match ret_type {
                     I32 => backend.code_builder.i32_xor(),
                     I64 => backend.code_builder.i64_xor(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => num_bitwise_i128(
+                        backend,
+                        &self.ret_storage,
+                        self.arguments,
+                        CodeBuilder::i64_xor,
+                    ),
                     _ => panic_ret_type(),
                 }
             }
             NumBitwiseOr => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
+                let ret_type = CodeGenNumType::from(self.ret_layout);
+                if ret_type != I128 {
+                    self.load_args(backend);
+                }
+                match ret_type {
                     I32 => backend.code_builder.i32_or(),
                     I64 => backend.code_builder.i64_or(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => num_bitwise_i128(
+                        backend,
+                        &self.ret_storage,
+                        self.arguments,
+                        CodeBuilder::i64_or,
+                    ),
                     _ => panic_ret_type(),
                 }
             }
             NumShiftLeftBy => {
                 let num = self.arguments[0];
                 let bits = self.arguments[1];
-                backend
-                    .storage
-                    .load_symbols(&mut backend.code_builder, &[num, bits]);
                 match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_shl(),
+                    I32 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
+                        backend.code_builder.i32_shl();
+                    }
                     I64 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shl();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = if symbol_is_signed_int(backend, num) {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_SHIFT_LEFT[width]);
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1961,7 +2109,13 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shr_s();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        // Like the narrower widths, U128 is shifted as if it were signed
+                        self.load_args_and_call_zig(
+                            backend,
+                            &bitcode::NUM_SHIFT_RIGHT[IntWidth::I128],
+                        );
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -2032,8 +2186,12 @@ impl<'a> LowLevelCall<'a> {
                         self.wrap_small_int(backend, ret_width);
                     }
                     (I32, I128) => {
-                        self.load_args(backend);
-                        backend.code_builder.i32_load(Align::Bytes4, 0);
+                        let (arg_ptr, arg_offset) =
+                            stack_memory_local_and_offset(backend, self.arguments[0]);
+                        backend.code_builder.get_local(arg_ptr);
+                        backend.code_builder.i64_load(Align::Bytes8, arg_offset);
+                        backend.code_builder.i32_wrap_i64();
+                        self.wrap_small_int(backend, ret_width);
                     }
                     (I64, I32) => {
                         self.load_args(backend);
@@ -2047,21 +2205,24 @@ impl<'a> LowLevelCall<'a> {
                         self.load_args(backend);
                     }
                     (I64, I128) => {
-                        let (frame_ptr, offset) = match backend.storage.get(&self.arguments[0]) {
-                            StoredValue::StackMemory { location, .. } => {
-                                location.local_and_offset(backend.storage.stack_frame_pointer)
-                            }
-                            _ => internal_error!("I128 should be in stack memory"),
-                        };
+                        let (frame_ptr, offset) =
+                            stack_memory_local_and_offset(backend, self.arguments[0]);
                         backend.code_builder.get_local(frame_ptr);
                         backend.code_builder.i64_load(Align::Bytes8, offset);
                     }
-                    (I128, I64) => {
-                        // Symbols are loaded as if for a call, so the i128 "return address" and i64 value are on the value stack
+                    (I128, I32 | I64) => {
+                        // Symbols are loaded as if for a call, so the i128 "return address" and the value are on the value stack
                         self.load_args(backend);
+                        if arg_type == I32 {
+                            if arg_width.is_signed() {
+                                backend.code_builder.i64_extend_s_i32()
+                            } else {
+                                backend.code_builder.i64_extend_u_i32()
+                            }
+                        }
                         backend.code_builder.i64_store(Align::Bytes8, 0);
 
-                        // Zero the most significant 64 bits
+                        // Fill the most significant 64 bits with the sign bit, or with zeroes
                         let (frame_ptr, offset) = match &self.ret_storage {
                             StoredValue::StackMemory { location, .. } => {
                                 location.local_and_offset(backend.storage.stack_frame_pointer)
@@ -2069,9 +2230,41 @@ impl<'a> LowLevelCall<'a> {
                             _ => internal_error!("I128 should be in stack memory"),
                         };
                         backend.code_builder.get_local(frame_ptr);
-                        backend.code_builder.i64_const(0);
+                        if arg_width.is_signed() {
+                            backend
+                                .storage
+                                .load_symbols(&mut backend.code_builder, self.arguments);
+                            if arg_type == I32 {
+                                backend.code_builder.i64_extend_s_i32();
+                            }
+                            backend.code_builder.i64_const(63);
+                            backend.code_builder.i64_shr_s();
+                        } else {
+                            backend.code_builder.i64_const(0);
+                        }
                         backend.code_builder.i64_store(Align::Bytes8, offset + 8);
                     }
+                    (I128, I128) => {
+                        // Only the interpretation of the bits changes
+                        let (ret_ptr, ret_offset) = match &self.ret_storage {
+                            StoredValue::StackMemory { location, .. } => {
+                                location.local_and_offset(backend.storage.stack_frame_pointer)
+                            }
+                            _ => internal_error!("I128 should be in stack memory"),
+                        };
+                        let (arg_ptr, arg_offset) =
+                            stack_memory_local_and_offset(backend, self.arguments[0]);
+                        for half in [0, 8] {
+                            backend.code_builder.get_local(ret_ptr);
+                            backend.code_builder.get_local(arg_ptr);
+                            backend
+                                .code_builder
+                                .i64_load(Align::Bytes8, arg_offset + half);
+                            backend
+                                .code_builder
+                                .i64_store(Align::Bytes8, ret_offset + half);
+                        }
+                    }
 
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
@@ -2091,6 +2284,10 @@ impl<'a> LowLevelCall<'a> {
                 match (ret_type, arg_type) {
                     (F32, F32) => {}
                     (F32, F64) => backend.code_builder.f32_demote_f64(),
+                    (F32, Decimal) => {
+                        backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64);
+                        backend.code_builder.f32_demote_f64();
+                    }
                     (F32, I32) => {
                         if arg_signed {
                             backend.code_builder.f32_convert_s_i32()
@@ -2106,6 +2303,8 @@ impl<'a> LowLevelCall<'a> {
                         }
                     }
                     (F64, F64) => {}
+                    (F64, F32) => backend.code_builder.f64_promote_f32(),
+                    (F64, Decimal) => backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64),
                     (F64, I32) => {
                         if arg_signed {
                             backend.code_builder.f64_convert_s_i32()
@@ -2120,6 +2319,20 @@ impl<'a> LowLevelCall<'a> {
                             backend.code_builder.f64_convert_u_i64()
                         }
                     }
+                    (F32 | F64, I128) => {
+                        let int_width = if arg_signed {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+                        let intrinsic = if ret_type == F32 {
+                            &bitcode::INT_TO_FLOAT_CAST_F32[int_width]
+                        } else {
+                            &bitcode::INT_TO_FLOAT_CAST_F64[int_width]
+                        };
+
+                        backend.call_host_fn_after_loading_args(intrinsic);
+                    }
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
@@ -2165,7 +2378,35 @@ impl<'a> LowLevelCall<'a> {
                 }
             }
             NumToFloatChecked => {
-                todo!("implement toF32Checked and toF64Checked");
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+
+                let intrinsics = match self.ret_layout_raw {
+                    LayoutRepr::Struct(&[Layout::F32, ..]) => &bitcode::NUM_TO_FLOAT_CHECKED_F32,
+                    LayoutRepr::Struct(&[Layout::F64, ..]) => &bitcode::NUM_TO_FLOAT_CHECKED_F64,
+                    _ => {
+                        internal_error!(
+                            "NumToFloatChecked is not defined for signature {:?} -> {:?}",
+                            arg_layout,
+                            self.ret_layout
+                        );
+                    }
+                };
+
+                self.load_args(backend);
+                match backend.layout_interner.get_repr(arg_layout) {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => {
+                        backend.call_host_fn_after_loading_args(&intrinsics[width]);
+                    }
+                    LayoutRepr::Builtin(Builtin::Float(width)) => {
+                        backend.call_host_fn_after_loading_args(&intrinsics[width]);
+                    }
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        // every Dec fits in an F64, so convert first and check the F64
+                        backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64);
+                        backend.call_host_fn_after_loading_args(&intrinsics[FloatWidth::F64]);
+                    }
+                    x => internal_error!("NumToFloatChecked is not defined for {:?}", x),
+                }
             }
             NumWithoutDecimalPoint => self.load_args_and_call_zig(backend, bitcode::DEC_TO_I128),
             NumWithDecimalPoint => self.load_args_and_call_zig(backend, bitcode::DEC_FROM_I128),
@@ -2462,6 +2703,50 @@ impl<'a> LowLevelCall<'a> {
     }
 }
 
+/// The local holding the base address of an I128 in stack memory, and its offset from there
+fn stack_memory_local_and_offset(backend: &WasmBackend<'_, '_>, symbol: Symbol) -> (LocalId, u32) {
+    match backend.storage.get(&symbol) {
+        StoredValue::StackMemory { location, .. } => {
+            location.local_and_offset(backend.storage.stack_frame_pointer)
+        }
+        _ => internal_error!("I128 should be in stack memory"),
+    }
+}
+
+/// Apply a bitwise operation to each 64-bit half of two 128-bit integers.
+/// Bitwise operations never carry between bits, so the halves are independent.
+fn num_bitwise_i128<'a>(
+    backend: &mut WasmBackend<'a, '_>,
+    ret_storage: &StoredValue,
+    arguments: &[Symbol],
+    op: fn(&mut CodeBuilder<'a>),
+) {
+    let (ret_ptr, ret_offset) = match ret_storage {
+        StoredValue::StackMemory { location, .. } => {
+            location.local_and_offset(backend.storage.stack_frame_pointer)
+        }
+        _ => internal_error!("I128 should be in stack memory"),
+    };
+    let (lhs_ptr, lhs_offset) = stack_memory_local_and_offset(backend, arguments[0]);
+    let (rhs_ptr, rhs_offset) = stack_memory_local_and_offset(backend, arguments[1]);
+
+    for half in [0, 8] {
+        backend.code_builder.get_local(ret_ptr);
+        backend.code_builder.get_local(lhs_ptr);
+        backend
+            .code_builder
+            .i64_load(Align::Bytes8, lhs_offset + half);
+        backend.code_builder.get_local(rhs_ptr);
+        backend
+            .code_builder
+            .i64_load(Align::Bytes8, rhs_offset + half);
+        op(&mut backend.code_builder);
+        backend
+            .code_builder
+            .i64_store(Align::Bytes8, ret_offset + half);
+    }
+}
+
 /// Helper for NumIsNan op
 fn num_is_nan(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
     use StoredValue::*;
@@ -309,6 +309,20 @@ impl Clone for IdentStr {
     }
 }
 
+impl serde::Serialize for IdentStr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for IdentStr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        Ok(Self::from_str(&string))
+    }
+}
+
 impl Drop for IdentStr {
     fn drop(&mut self) {
         if !self.is_empty() && !self.is_small_str() {
@@ -22,7 +22,8 @@ pub use roc_load_internal::file::{
     Threading,
 };
 pub use roc_load_internal::module::{
-    CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule, MonomorphizedModule,
+    current_thread_index, CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule,
+    ModuleTiming, MonomorphizedModule, PhaseSpan, TimedPhase,
 };
 pub use roc_solve::FunctionKind;
 
@@ -56,6 +57,7 @@ pub fn load_single_threaded<'a>(
     render: RenderTarget,
     palette: Palette,
     roc_cache_dir: RocCacheDir<'_>,
+    module_cache_dir: Option<PathBuf>,
     exec_mode: ExecutionMode,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let cached_subs = read_cached_types();
@@ -67,6 +69,9 @@ pub fn load_single_threaded<'a>(
         exposed_types,
         target,
         function_kind,
+        None,
+        false,
+        module_cache_dir,
         cached_subs,
         render,
         palette,
@@ -183,6 +188,7 @@ pub fn load_and_typecheck_str<'a>(
     function_kind: FunctionKind,
     render: RenderTarget,
     roc_cache_dir: RocCacheDir<'_>,
+    module_cache_dir: Option<PathBuf>,
     palette: Palette,
 ) -> Result<LoadedModule, LoadingProblem<'a>> {
     use LoadResult::*;
@@ -207,6 +213,7 @@ pub fn load_and_typecheck_str<'a>(
         render,
         palette,
         roc_cache_dir,
+        module_cache_dir,
         ExecutionMode::Check,
     )? {
         Monomorphized(_) => unreachable!(""),
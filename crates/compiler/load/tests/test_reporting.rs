@@ -130,6 +130,9 @@ mod test_reporting {
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
                 function_kind: FunctionKind::LambdaSet,
+                inline_budget: None,
+                simplify: false,
+                module_cache_dir: None,
             };
             let result = roc_load::load_and_typecheck(
                 arena,
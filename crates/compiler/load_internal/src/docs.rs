@@ -7,10 +7,11 @@ use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::{self, ExtractSpaces, TypeHeader, TypeVar};
 use roc_parse::ast::{AssignedField, FunctionArrow};
 use roc_parse::ast::{CommentOrNewline, TypeDef, ValueDef};
+use serde::{Deserialize, Serialize};
 
 // Documentation generation requirements
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ModuleDocumentation {
     pub name: String,
     pub entries: Vec<DocEntry>,
@@ -29,14 +30,14 @@ impl ModuleDocumentation {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DocEntry {
     DocDef(DocDef),
     DetachedDoc(String),
     ModuleDoc(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocDef {
     pub name: String,
     pub symbol: Symbol,
@@ -45,7 +46,7 @@ pub struct DocDef {
     pub docs: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeAnnotation {
     TagUnion {
         tags: Vec<Tag>,
@@ -53,6 +54,7 @@ pub enum TypeAnnotation {
     },
     Function {
         args: Vec<TypeAnnotation>,
+        #[serde(with = "FunctionArrowDef")]
         arrow: FunctionArrow,
         output: Box<TypeAnnotation>,
     },
@@ -87,13 +89,21 @@ pub enum TypeAnnotation {
     },
 }
 
-#[derive(Debug, Clone)]
+/// Lets [TypeAnnotation] be cached without the parser depending on serde.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "FunctionArrow")]
+enum FunctionArrowDef {
+    Pure,
+    Effectful,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImplementsClause {
     pub name: String,
     pub abilities: Vec<TypeAnnotation>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RecordField {
     RecordField {
         name: String,
@@ -108,7 +118,7 @@ pub enum RecordField {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AbilityMember {
     pub name: String,
     pub type_annotation: TypeAnnotation,
@@ -116,7 +126,7 @@ pub struct AbilityMember {
     pub docs: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub name: String,
     pub values: Vec<TypeAnnotation>,
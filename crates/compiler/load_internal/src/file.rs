@@ -4,9 +4,11 @@ use crate::docs::ModuleDocumentation;
 use crate::module::{
     CheckedModule, ConstrainedModule, EntryPoint, Expectations, ExposedToHost,
     FoundSpecializationsModule, LateSpecializationsModule, LoadedModule, ModuleHeader,
-    ModuleTiming, MonomorphizedModule, ParsedModule, ToplevelExpects, TypeCheckedModule,
+    ModuleTiming, MonomorphizedModule, ParsedModule, TimedPhase, ToplevelExpects,
+    TypeCheckedModule,
 };
 use crate::module_cache::ModuleCache;
+use crate::types_cache::{CachedCanModule, TypesCache, TypesCacheEntry};
 use bumpalo::{collections::CollectIn, Bump};
 use crossbeam::channel::{bounded, Sender};
 use crossbeam::deque::{Injector, Worker};
@@ -29,9 +31,9 @@ use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
-    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_IR_AFTER_TRMC,
-    ROC_PRINT_LOAD_LOG,
+    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_INLINING,
+    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SIMPLIFY,
+    ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_IR_AFTER_TRMC, ROC_PRINT_LOAD_LOG,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::internal_error;
@@ -41,6 +43,7 @@ use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PQModuleName, PackageModuleIds,
     PackageQualified, Symbol,
 };
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::{
     CapturedSymbols, ExternalSpecializations, GlueLayouts, HostExposedLambdaSets, PartialProc,
     Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
@@ -116,6 +119,13 @@ pub struct LoadConfig {
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
     pub function_kind: FunctionKind,
+    /// Inline small procs into their callers, for backends that don't do that themselves
+    pub inline_budget: Option<InlineBudget>,
+    /// Fold constants and remove dead branches and lets, for backends that don't do that themselves
+    pub simplify: bool,
+    /// Where to cache the canonical output and solved types of user modules between runs, if
+    /// anywhere. Tests and build scripts should leave this as None.
+    pub module_cache_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -315,11 +325,34 @@ fn start_phase<'a>(
                     }
                 }
 
-                let skip_constraint_gen = {
+                let mut skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
                     state.cached_types.lock().contains_key(&module_id)
                 };
+                let mut cached_can = None;
+                let mut types_cache_entry = None;
+
+                if !skip_constraint_gen {
+                    if let Some(entry) = state.types_cache_entry(module_id) {
+                        match entry.load() {
+                            Some(type_state) => {
+                                log!("loaded cached types for {:?}", module_id);
+                                state.cached_types.lock().insert(module_id, type_state);
+                                skip_constraint_gen = true;
+
+                                cached_can = entry.load_can();
+                                if cached_can.is_none() {
+                                    types_cache_entry = Some(entry);
+                                }
+                            }
+                            None => {
+                                types_cache_entry = Some(entry.clone());
+                                state.types_cache_entries.insert(module_id, entry);
+                            }
+                        }
+                    }
+                }
 
                 BuildTask::CanonicalizeAndConstrain {
                     parsed,
@@ -329,6 +362,8 @@ fn start_phase<'a>(
                     aliases,
                     abilities_store,
                     skip_constraint_gen,
+                    cached_can: cached_can.map(Box::new),
+                    types_cache_entry,
                     exposed_module_ids: state.exposed_modules,
                     exec_mode: state.exec_mode,
                     imported_module_params,
@@ -364,6 +399,7 @@ fn start_phase<'a>(
                 };
 
                 let is_host_exposed = state.root_id == module.module_id;
+                let opt_types_cache_entry = state.types_cache_entries.remove(&module_id);
 
                 BuildTask::solve_module(
                     module,
@@ -380,6 +416,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_types.clone(),
+                    opt_types_cache_entry,
                     derived_module,
                     state.exec_mode,
                     is_host_exposed,
@@ -731,6 +768,8 @@ struct State<'a> {
     pub platform_path: PlatformPath<'a>,
     pub target: Target,
     pub(self) function_kind: FunctionKind,
+    pub(self) inline_budget: Option<InlineBudget>,
+    pub(self) simplify: bool,
 
     /// Note: only packages and platforms actually expose any modules;
     /// for all others, this will be empty.
@@ -776,9 +815,14 @@ struct State<'a> {
 
     make_specializations_pass: MakeSpecializationsPass,
 
-    // cached types (used for builtin modules, could include packages in the future too)
+    // cached types (builtin modules, and user modules loaded from the types cache)
     cached_types: CachedTypeState,
 
+    /// Where the solved types of user modules are persisted between runs, if anywhere
+    types_cache: Option<TypesCache>,
+    /// Entries for modules that missed the types cache, to be written once they are solved
+    types_cache_entries: MutMap<ModuleId, TypesCacheEntry>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
@@ -789,6 +833,24 @@ impl<'a> State<'a> {
         self.exec_mode.goal_phase()
     }
 
+    /// The types cache entry for a user module, or None if we aren't caching its types
+    fn types_cache_entry(&mut self, module_id: ModuleId) -> Option<TypesCacheEntry> {
+        if module_id.is_builtin() {
+            return None;
+        }
+
+        let types_cache = self.types_cache.as_ref()?;
+        let import_graph_hash = self.module_cache.import_graph_hash(module_id)?;
+
+        Some(types_cache.entry(
+            &import_graph_hash,
+            self.exec_mode,
+            self.function_kind,
+            self.root_id == module_id,
+            self.exposed_modules,
+        ))
+    }
+
     fn new(
         root_id: ModuleId,
         root_path: PathBuf,
@@ -796,6 +858,9 @@ impl<'a> State<'a> {
         opt_platform_shorthand: Option<&'a str>,
         target: Target,
         function_kind: FunctionKind,
+        inline_budget: Option<InlineBudget>,
+        simplify: bool,
+        module_cache_dir: Option<PathBuf>,
         exposed_types: ExposedByModule,
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
@@ -807,6 +872,7 @@ impl<'a> State<'a> {
         exec_mode: ExecutionMode,
     ) -> Self {
         let cache_dir = roc_packaging::cache::roc_cache_packages_dir();
+        let types_cache = module_cache_dir.and_then(TypesCache::new);
         let dependencies = Dependencies::new(exec_mode.goal_phase());
 
         Self {
@@ -818,6 +884,8 @@ impl<'a> State<'a> {
             cache_dir,
             target,
             function_kind,
+            inline_budget,
+            simplify,
             platform_data: None,
             platform_path: PlatformPath::NotSpecified,
             module_cache: ModuleCache::default(),
@@ -838,6 +906,8 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            types_cache,
+            types_cache_entries: MutMap::default(),
             render,
             palette,
             exec_mode,
@@ -921,6 +991,10 @@ enum BuildTask<'a> {
         abilities_store: PendingAbilitiesStore,
         exposed_module_ids: &'a [ModuleId],
         skip_constraint_gen: bool,
+        /// The canonical output of this module, if it was found in the types cache
+        cached_can: Option<Box<CachedCanModule>>,
+        /// Where to persist the canonical output of this module, if anywhere
+        types_cache_entry: Option<TypesCacheEntry>,
         exec_mode: ExecutionMode,
         imported_module_params: VecMap<ModuleId, ModuleParams>,
         solo_can_output: SoloCanOutput<'a>,
@@ -939,6 +1013,7 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedTypeState,
+        types_cache_entry: Option<TypesCacheEntry>,
         derived_module: SharedDerivedModule,
         exec_mode: ExecutionMode,
         is_host_exposed: bool,
@@ -1144,6 +1219,9 @@ pub fn load_and_typecheck_str<'a>(
         threading,
         exec_mode: ExecutionMode::Check,
         function_kind,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: None,
     };
 
     match load(
@@ -1358,6 +1436,7 @@ fn handle_root_type<'a>(
                         Arc::clone(&ident_ids_by_module),
                         Arc::clone(&arc_shorthands),
                         cache_dir,
+                        roc_cache_dir,
                     )?;
 
                     header_output.msg = Msg::Many(messages);
@@ -1404,6 +1483,7 @@ fn load_packages_from_main<'a>(
     ident_ids_by_module: SharedIdentIdsByModule,
     arc_shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
     cache_dir: &Path,
+    roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<(), LoadingProblem<'a>> {
     let src_bytes = fs::read(&filename).map_err(|err| LoadingProblem::FileProblem {
         filename: filename.clone(),
@@ -1433,7 +1513,7 @@ fn load_packages_from_main<'a>(
     load_packages(
         packages,
         messages,
-        RocCacheDir::Persistent(cache_dir),
+        roc_cache_dir,
         src_dir.clone(),
         arena,
         None,
@@ -1559,6 +1639,9 @@ pub fn load<'a>(
             exposed_types,
             load_config.target,
             load_config.function_kind,
+            load_config.inline_budget,
+            load_config.simplify,
+            load_config.module_cache_dir,
             cached_types,
             load_config.render,
             load_config.palette,
@@ -1571,6 +1654,9 @@ pub fn load<'a>(
             exposed_types,
             load_config.target,
             load_config.function_kind,
+            load_config.inline_budget,
+            load_config.simplify,
+            load_config.module_cache_dir,
             cached_types,
             load_config.render,
             load_config.palette,
@@ -1588,6 +1674,9 @@ pub fn load_single_threaded<'a>(
     exposed_types: ExposedByModule,
     target: Target,
     function_kind: FunctionKind,
+    inline_budget: Option<InlineBudget>,
+    simplify: bool,
+    module_cache_dir: Option<PathBuf>,
     cached_types: MutMap<ModuleId, TypeState>,
     render: RenderTarget,
     palette: Palette,
@@ -1621,6 +1710,9 @@ pub fn load_single_threaded<'a>(
         opt_platform_shorthand,
         target,
         function_kind,
+        inline_budget,
+        simplify,
+        module_cache_dir,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
@@ -1968,6 +2060,9 @@ fn load_multi_threaded<'a>(
     exposed_types: ExposedByModule,
     target: Target,
     function_kind: FunctionKind,
+    inline_budget: Option<InlineBudget>,
+    simplify: bool,
+    module_cache_dir: Option<PathBuf>,
     cached_types: MutMap<ModuleId, TypeState>,
     render: RenderTarget,
     palette: Palette,
@@ -2017,6 +2112,9 @@ fn load_multi_threaded<'a>(
         opt_platform_shorthand,
         target,
         function_kind,
+        inline_budget,
+        simplify,
+        module_cache_dir,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
@@ -2912,6 +3010,30 @@ fn update<'a>(
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_TRMC);
 
+                    if let Some(inline_budget) = state.inline_budget {
+                        roc_mono::inline::inline_procs(
+                            arena,
+                            module_id,
+                            ident_ids,
+                            inline_budget,
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_INLINING);
+                        debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
+                    }
+
+                    if state.simplify {
+                        roc_mono::simplify::simplify_procs(
+                            arena,
+                            &layout_interner,
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_SIMPLIFY);
+                        debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
+                    }
+
                     inc_dec::insert_inc_dec_operations(
                         arena,
                         &layout_interner,
@@ -2920,6 +3042,8 @@ fn update<'a>(
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_REFCOUNT);
 
+                    let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
+
                     drop_specialization::specialize_drops(
                         arena,
                         &mut layout_interner,
@@ -3316,6 +3440,7 @@ fn finish_specialization<'a>(
         type_problems,
         can_problems,
         sources,
+        imports,
         ..
     } = module_cache;
 
@@ -3345,6 +3470,7 @@ fn finish_specialization<'a>(
         host_exposed_lambda_sets,
         entry_point,
         sources,
+        imports,
         timings: state.timings,
         toplevel_expects,
         glue_layouts: GlueLayouts { getters: vec![] },
@@ -3465,13 +3591,18 @@ fn load_package_from_disk<'a>(
             let bytes = arena.alloc(bytes_vec);
             let parse_state = roc_parse::state::State::new(bytes);
             let parsed = roc_parse::header::parse_header(arena, parse_state.clone());
-            let parse_header_duration = parse_start.elapsed();
+            let parse_end = Instant::now();
 
             // Insert the first entries for this module's timings
             let mut pkg_module_timing = ModuleTiming::new(module_start_time);
 
-            pkg_module_timing.read_roc_file = file_io_duration;
-            pkg_module_timing.parse_header = parse_header_duration;
+            pkg_module_timing.read_roc_file = pkg_module_timing.record(
+                TimedPhase::ReadFile,
+                file_io_start,
+                file_io_start + file_io_duration,
+            );
+            pkg_module_timing.parse_header =
+                pkg_module_timing.record(TimedPhase::ParseHeader, parse_start, parse_end);
 
             match parsed {
                 Ok((
@@ -3900,7 +4031,7 @@ fn parse_header<'a>(
     let parse_start = Instant::now();
     let parse_state = roc_parse::state::State::new(src_bytes);
     let parsed = roc_parse::header::parse_header(arena, parse_state.clone());
-    let parse_header_duration = parse_start.elapsed();
+    let parse_end = Instant::now();
 
     if let Err(problem) = ensure_roc_file(&filename, src_bytes) {
         let problem = match problem {
@@ -3923,8 +4054,13 @@ fn parse_header<'a>(
     // Insert the first entries for this module's timings
     let mut module_timing = ModuleTiming::new(start_time);
 
-    module_timing.read_roc_file = read_file_duration;
-    module_timing.parse_header = parse_header_duration;
+    module_timing.read_roc_file = module_timing.record(
+        TimedPhase::ReadFile,
+        start_time,
+        start_time + read_file_duration,
+    );
+    module_timing.parse_header =
+        module_timing.record(TimedPhase::ParseHeader, parse_start, parse_end);
 
     match parsed {
         Ok((
@@ -4423,6 +4559,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedTypeState,
+        types_cache_entry: Option<TypesCacheEntry>,
         derived_module: SharedDerivedModule,
         exec_mode: ExecutionMode,
         is_host_exposed: bool,
@@ -4448,6 +4585,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            types_cache_entry,
             derived_module,
             exec_mode,
             is_host_exposed,
@@ -4746,6 +4884,50 @@ struct SolveResult {
     checkmate: Option<roc_checkmate::Collector>,
 }
 
+/// Writes the types of a freshly solved module to the types cache, before anything else gets a
+/// chance to modify its subs, so that loading them later picks up exactly where solving left off.
+fn persist_solve_result(entry: &TypesCacheEntry, solve_result: SolveResult) -> SolveResult {
+    let SolveResult {
+        solved: Solved(subs),
+        solved_implementations,
+        exposed_vars_by_symbol,
+        problems,
+        abilities_store,
+        imported_modules_with_params,
+
+        #[cfg(debug_assertions)]
+        checkmate,
+    } = solve_result;
+
+    let type_state = TypeState {
+        subs,
+        exposed_vars_by_symbol,
+        abilities: abilities_store,
+        solved_implementations,
+    };
+
+    entry.store(&type_state);
+
+    let TypeState {
+        subs,
+        exposed_vars_by_symbol,
+        abilities,
+        solved_implementations,
+    } = type_state;
+
+    SolveResult {
+        solved: Solved(subs),
+        solved_implementations,
+        exposed_vars_by_symbol,
+        problems,
+        abilities_store: abilities,
+        imported_modules_with_params,
+
+        #[cfg(debug_assertions)]
+        checkmate,
+    }
+}
+
 #[allow(clippy::complexity)]
 fn run_solve_solve(
     exposed_for_module: ExposedForModule,
@@ -4902,6 +5084,7 @@ fn run_solve<'a>(
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_types: CachedTypeState,
+    types_cache_entry: Option<TypesCacheEntry>,
     derived_module: SharedDerivedModule,
     exec_mode: ExecutionMode,
     is_host_exposed: bool,
@@ -4923,43 +5106,15 @@ fn run_solve<'a>(
     let has_dbgs = module.has_dbgs;
     let module = module;
 
-    let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    function_kind,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                    is_host_exposed,
-                    //
-                    #[cfg(debug_assertions)]
-                    checkmate,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
-                    imported_modules_with_params: vec![],
+    let opt_cached_types = {
+        // Give this its own scope to make sure that the Guard from the lock() is dropped
+        // before we go on to solve
+        cached_types.lock().remove(&module_id)
+    };
 
-                    #[cfg(debug_assertions)]
-                    checkmate: None,
-                },
-            }
-        } else {
-            run_solve_solve(
+    let solve_result = match opt_cached_types {
+        None => {
+            let solve_result = run_solve_solve(
                 exposed_for_module,
                 types,
                 constraints,
@@ -4973,8 +5128,32 @@ fn run_solve<'a>(
                 //
                 #[cfg(debug_assertions)]
                 checkmate,
-            )
+            );
+
+            match types_cache_entry {
+                // Cached types come back without problems, so only cache modules that have none
+                Some(entry) if solve_result.problems.is_empty() => {
+                    persist_solve_result(&entry, solve_result)
+                }
+                _ => solve_result,
+            }
         }
+        Some(TypeState {
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        }) => SolveResult {
+            solved: Solved(subs),
+            solved_implementations,
+            exposed_vars_by_symbol,
+            problems: vec![],
+            abilities_store: abilities,
+            imported_modules_with_params: vec![],
+
+            #[cfg(debug_assertions)]
+            checkmate: None,
+        },
     };
 
     let SolveResult {
@@ -5022,7 +5201,7 @@ fn run_solve<'a>(
 
     // Record the final timings
     let solve_end = Instant::now();
-    module_timing.solve = solve_end.duration_since(solve_start);
+    module_timing.solve = module_timing.record(TimedPhase::Solve, solve_start, solve_end);
 
     // Send the subs to the main thread for processing,
     Msg::SolvedTypes {
@@ -5186,6 +5365,7 @@ fn canonicalize_and_constrain<'a>(
     imported_abilities_state: PendingAbilitiesStore,
     parsed: ParsedModule<'a>,
     skip_constraint_gen: bool,
+    types_cache_entry: Option<TypesCacheEntry>,
     exposed_module_ids: &[ModuleId],
     exec_mode: ExecutionMode,
     imported_module_params: VecMap<ModuleId, ModuleParams>,
@@ -5277,7 +5457,11 @@ fn canonicalize_and_constrain<'a>(
 
     let canonicalize_end = Instant::now();
 
-    module_timing.canonicalize = canonicalize_end.duration_since(canonicalize_start);
+    module_timing.canonicalize = module_timing.record(
+        TimedPhase::Canonicalize,
+        canonicalize_start,
+        canonicalize_end,
+    );
 
     // Generate documentation information
     // TODO: store timing information?
@@ -5322,6 +5506,7 @@ fn canonicalize_and_constrain<'a>(
         }
     }
 
+    let constrain_start = Instant::now();
     let mut constraints = Constraints::new();
 
     let constraint = if skip_constraint_gen {
@@ -5338,6 +5523,10 @@ fn canonicalize_and_constrain<'a>(
         )
     };
 
+    let constrain_end = Instant::now();
+    module_timing.constrain =
+        module_timing.record(TimedPhase::Constrain, constrain_start, constrain_end);
+
     // _after has an underscore because it's unused in --release builds
     let _after = roc_types::types::get_type_clone_count();
 
@@ -5394,14 +5583,36 @@ fn canonicalize_and_constrain<'a>(
         module_params: module_output.module_params,
     };
 
-    let constrained_module = ConstrainedModule {
+    let can_module = CachedCanModule {
         module,
         declarations: module_output.declarations,
+        ident_ids: module_output.scope.locals.ident_ids,
+        var_store,
+        module_docs,
+    };
+
+    if let Some(entry) = types_cache_entry {
+        if module_output.problems.is_empty() {
+            entry.store_can(&can_module);
+        }
+    }
+
+    let CachedCanModule {
+        module,
+        declarations,
+        ident_ids,
+        var_store,
+        module_docs,
+    } = can_module;
+
+    let constrained_module = ConstrainedModule {
+        module,
+        declarations,
         available_modules,
         var_store,
         constraints,
         constraint,
-        ident_ids: module_output.scope.locals.ident_ids,
+        ident_ids,
         dep_idents,
         module_timing,
         types,
@@ -5415,6 +5626,41 @@ fn canonicalize_and_constrain<'a>(
     }
 }
 
+/// The types of this module are cached too, so there is nothing left to constrain.
+fn can_and_con_from_cache(
+    parsed: ParsedModule,
+    dep_idents: IdentIdsByModule,
+    cached_can: CachedCanModule,
+) -> CanAndCon {
+    let CachedCanModule {
+        module,
+        declarations,
+        ident_ids,
+        var_store,
+        module_docs,
+    } = cached_can;
+
+    let constrained_module = ConstrainedModule {
+        module,
+        declarations,
+        available_modules: parsed.available_modules,
+        var_store,
+        constraints: Constraints::new(),
+        constraint: roc_can::constraint::Constraint::True,
+        ident_ids,
+        dep_idents,
+        module_timing: parsed.module_timing,
+        types: Types::new(),
+        pending_derives: Default::default(),
+    };
+
+    CanAndCon {
+        constrained_module,
+        canonicalization_problems: Vec::new(),
+        module_docs,
+    }
+}
+
 fn parse<'a>(
     arena: &'a Bump,
     header: ModuleHeader<'a>,
@@ -5449,7 +5695,7 @@ fn parse<'a>(
     // immediately afterward (for the beginning of canonicalization).
     let parse_end = Instant::now();
 
-    module_timing.parse_body = parse_end.duration_since(parse_start);
+    module_timing.parse_body = module_timing.record(TimedPhase::ParseBody, parse_start, parse_end);
 
     let mut available_modules: MutMap<ModuleId, Region> = MutMap::default();
     let exposed_values = header.header_type.exposed_or_provided_values();
@@ -5786,9 +6032,14 @@ fn make_specializations<'a>(
     mono_env.home.register_debug_idents(mono_env.ident_ids);
 
     let make_specializations_end = Instant::now();
+    let make_specializations = module_timing.record(
+        TimedPhase::MakeSpecializations,
+        make_specializations_start,
+        make_specializations_end,
+    );
     module_timing
         .make_specializations
-        .push(make_specializations_end.duration_since(make_specializations_start));
+        .push(make_specializations);
 
     Msg::MadeSpecializations {
         module_id: home,
@@ -5945,6 +6196,7 @@ fn build_pending_specializations<'a>(
                             body_var: expr_var,
                             // This is a 0-arity thunk, so it cannot be recursive
                             is_self_recursive: false,
+                            region: body.region,
                         };
 
                         procs_base.partial_procs.insert(symbol, proc);
@@ -6081,6 +6333,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -6149,6 +6402,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -6168,8 +6422,11 @@ fn build_pending_specializations<'a>(
     procs_base.module_thunks = module_thunks.into_bump_slice();
 
     let find_specializations_end = Instant::now();
-    module_timing.find_specializations =
-        find_specializations_end.duration_since(find_specializations_start);
+    module_timing.find_specializations = module_timing.record(
+        TimedPhase::FindSpecializations,
+        find_specializations_start,
+        find_specializations_end,
+    );
 
     Msg::FoundSpecializations {
         module_id: home,
@@ -6300,6 +6557,7 @@ fn load_derived_partial_procs<'a>(
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: Region::zero(),
                 }
             }
         };
@@ -6316,8 +6574,11 @@ fn load_derived_partial_procs<'a>(
 
     let load_derived_procs_end = Instant::now();
 
-    module_timing.find_specializations =
-        load_derived_procs_end.duration_since(load_derived_procs_start);
+    module_timing.find_specializations = module_timing.record(
+        TimedPhase::FindSpecializations,
+        load_derived_procs_start,
+        load_derived_procs_end,
+    );
 }
 
 fn run_task<'a>(
@@ -6374,25 +6635,31 @@ fn run_task<'a>(
             aliases,
             abilities_store,
             skip_constraint_gen,
+            cached_can,
+            types_cache_entry,
             exposed_module_ids,
             exec_mode,
             imported_module_params,
             solo_can_output,
         } => {
-            let can_and_con = canonicalize_and_constrain(
-                arena,
-                &qualified_module_ids,
-                dep_idents,
-                exposed_symbols,
-                aliases,
-                abilities_store,
-                parsed,
-                skip_constraint_gen,
-                exposed_module_ids,
-                exec_mode,
-                imported_module_params,
-                solo_can_output,
-            );
+            let can_and_con = match cached_can {
+                Some(cached_can) => can_and_con_from_cache(parsed, dep_idents, *cached_can),
+                None => canonicalize_and_constrain(
+                    arena,
+                    &qualified_module_ids,
+                    dep_idents,
+                    exposed_symbols,
+                    aliases,
+                    abilities_store,
+                    parsed,
+                    skip_constraint_gen,
+                    types_cache_entry,
+                    exposed_module_ids,
+                    exec_mode,
+                    imported_module_params,
+                    solo_can_output,
+                ),
+            };
 
             Ok(Msg::CanonicalizedAndConstrained(can_and_con))
         }
@@ -6410,6 +6677,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            types_cache_entry,
             derived_module,
             exec_mode,
             is_host_exposed,
@@ -6430,6 +6698,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            types_cache_entry,
             derived_module,
             exec_mode,
             is_host_exposed,
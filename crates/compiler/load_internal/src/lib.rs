@@ -8,6 +8,7 @@ pub mod docs;
 pub mod file;
 pub mod module;
 mod module_cache;
+mod types_cache;
 
 #[cfg(target_family = "wasm")]
 mod wasm_instant;
@@ -183,6 +183,8 @@ pub struct MonomorphizedModule<'a> {
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules that each module imports directly
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub needs_prebuilt_host: bool,
@@ -250,6 +252,8 @@ pub struct ModuleTiming {
     // indexed by make specializations pass
     pub make_specializations: Vec<Duration>,
     // TODO pub monomorphize: Duration,
+    /// When and on which thread each phase ran, for `--profile-compiler`
+    pub spans: Vec<PhaseSpan>,
     /// Total duration will always be more than the sum of the other fields, due
     /// to things like state lookups in between phases, waiting on other threads, etc.
     pub start_time: Instant,
@@ -268,6 +272,7 @@ impl ModuleTiming {
             solve: Duration::default(),
             find_specializations: Duration::default(),
             make_specializations: Vec::with_capacity(2),
+            spans: Vec::new(),
             start_time,
             end_time: start_time, // just for now; we'll overwrite this at the end
         }
@@ -277,6 +282,19 @@ impl ModuleTiming {
         self.end_time.duration_since(self.start_time)
     }
 
+    /// Records that `phase` ran on the current thread from `start` to `end`,
+    /// and returns how long that took.
+    pub fn record(&mut self, phase: TimedPhase, start: Instant, end: Instant) -> Duration {
+        self.spans.push(PhaseSpan {
+            phase,
+            start,
+            end,
+            thread: current_thread_index(),
+        });
+
+        end.duration_since(start)
+    }
+
     /// Subtract all the other fields from total_start_to_finish
     pub fn other(&self) -> Duration {
         let Self {
@@ -289,6 +307,7 @@ impl ModuleTiming {
             solve,
             find_specializations,
             make_specializations,
+            spans: _,
             start_time,
             end_time,
         } = self;
@@ -310,3 +329,53 @@ impl ModuleTiming {
         calculate(Some(end_time.duration_since(*start_time))).unwrap_or_default()
     }
 }
+
+/// A phase of compiling a single module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedPhase {
+    ReadFile,
+    ParseHeader,
+    ParseBody,
+    Canonicalize,
+    Constrain,
+    Solve,
+    FindSpecializations,
+    MakeSpecializations,
+}
+
+impl TimedPhase {
+    pub fn name(self) -> &'static str {
+        match self {
+            TimedPhase::ReadFile => "read file",
+            TimedPhase::ParseHeader => "parse header",
+            TimedPhase::ParseBody => "parse body",
+            TimedPhase::Canonicalize => "canonicalize",
+            TimedPhase::Constrain => "constrain",
+            TimedPhase::Solve => "solve",
+            TimedPhase::FindSpecializations => "find specializations",
+            TimedPhase::MakeSpecializations => "make specializations",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseSpan {
+    pub phase: TimedPhase,
+    pub start: Instant,
+    pub end: Instant,
+    /// See [current_thread_index]
+    pub thread: u32,
+}
+
+/// A small number that identifies the current thread, in the order threads first asked for one.
+pub fn current_thread_index() -> u32 {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static NEXT_THREAD_INDEX: AtomicU32 = AtomicU32::new(0);
+
+    thread_local! {
+        static THREAD_INDEX: u32 = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+    }
+
+    THREAD_INDEX.with(|index| *index)
+}
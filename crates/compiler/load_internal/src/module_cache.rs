@@ -7,6 +7,7 @@ use roc_can::abilities::PendingAbilitiesStore;
 use roc_can::module::ModuleParams;
 use roc_can_solo::module::SoloCanOutput;
 use roc_collections::{MutMap, MutSet, VecMap};
+use roc_error_macros::internal_error;
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{ModuleId, PQModuleName, Symbol};
 use roc_mono::ir::ExternalSpecializations;
@@ -15,6 +16,7 @@ use roc_region::all::Region;
 use roc_solve_problem::TypeError;
 use roc_types::subs::Variable;
 use roc_types::types::Alias;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
 /// Struct storing various intermediate stages by their ModuleId
@@ -47,8 +49,8 @@ pub(crate) struct ModuleCache<'a> {
     pub(crate) type_problems: MutMap<ModuleId, Vec<TypeError>>,
 
     pub(crate) sources: MutMap<ModuleId, (PathBuf, &'a str)>,
-    #[allow(dead_code)]
     pub(crate) content_hashes: MutMap<ModuleId, String>,
+    pub(crate) import_graph_hashes: MutMap<ModuleId, String>,
 }
 
 impl<'a> ModuleCache<'a> {
@@ -70,7 +72,6 @@ impl<'a> ModuleCache<'a> {
         self.has_can_errors() || self.has_type_errors()
     }
 
-    #[allow(dead_code)]
     pub fn add_module_content_hash(&mut self, module_id: ModuleId, contents: &str) -> String {
         let hash = Self::hash_contents(contents);
         self.content_hashes.insert(module_id, hash.clone());
@@ -78,10 +79,75 @@ impl<'a> ModuleCache<'a> {
         hash
     }
 
-    #[allow(dead_code)]
     pub fn hash_contents(contents: &str) -> String {
         base64_url::encode(blake3::hash(contents.as_bytes()).as_bytes())
     }
+
+    /// Hashes a module's source together with the import graph hashes of everything it imports,
+    /// so that a change anywhere below a module in the import graph also changes its hash.
+    ///
+    /// The module's [ModuleId] is part of the hash, because ids are handed out in load order and
+    /// end up baked into the symbols of anything derived from the module (e.g. its solved types).
+    ///
+    /// Returns None if the source of the module or of one of its imports has not been loaded.
+    pub fn import_graph_hash(&mut self, module_id: ModuleId) -> Option<String> {
+        if let Some(hash) = self.import_graph_hashes.get(&module_id) {
+            return Some(hash.clone());
+        }
+
+        let content_hash = match self.content_hashes.get(&module_id) {
+            Some(hash) => hash.clone(),
+            None => {
+                let (_, src) = self.sources.get(&module_id)?;
+                self.add_module_content_hash(module_id, src)
+            }
+        };
+
+        let imports: Vec<ModuleId> = self
+            .imports
+            .get(&module_id)
+            .map(|imports| imports.iter().copied().collect())
+            .unwrap_or_default();
+
+        let mut import_hashes = Vec::with_capacity(imports.len());
+        for import in imports {
+            import_hashes.push(self.import_graph_hash(import)?);
+        }
+        // The set of imports has no stable iteration order
+        import_hashes.sort();
+
+        let mut hasher = blake3::Hasher::new();
+
+        module_id.hash(&mut Blake3Hasher(&mut hasher));
+        hasher.update(content_hash.as_bytes());
+
+        if let Some(name) = self.module_names.get(&module_id) {
+            hasher.update(name.package_shorthand().unwrap_or_default().as_bytes());
+            hasher.update(name.as_inner().as_str().as_bytes());
+        }
+
+        for import_hash in import_hashes {
+            hasher.update(import_hash.as_bytes());
+        }
+
+        let hash = base64_url::encode(hasher.finalize().as_bytes());
+        self.import_graph_hashes.insert(module_id, hash.clone());
+
+        Some(hash)
+    }
+}
+
+/// Lets types that only implement [Hash] feed a [blake3::Hasher].
+pub(crate) struct Blake3Hasher<'a>(pub(crate) &'a mut blake3::Hasher);
+
+impl Hasher for Blake3Hasher<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        internal_error!("Blake3Hasher is only used to feed bytes into a blake3 hash")
+    }
 }
 
 impl Default for ModuleCache<'_> {
@@ -135,6 +201,7 @@ impl Default for ModuleCache<'_> {
             type_problems: Default::default(),
             sources: Default::default(),
             content_hashes: Default::default(),
+            import_graph_hashes: Default::default(),
         }
     }
 }
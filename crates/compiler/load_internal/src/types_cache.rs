@@ -0,0 +1,239 @@
+//! An on-disk cache of the canonicalized modules and solved types of user modules.
+//!
+//! The builtins ship with their solved types baked into the compiler (see the build script of
+//! `roc_load`). This gives user modules the same treatment at runtime: once a module
+//! canonicalizes and type-checks without problems, its canonical output and its [TypeState] are
+//! written to the module cache directory, keyed by the module's import graph hash. On later runs,
+//! a module whose entries are found skips canonicalization, constraint generation and solving.
+//! It is still parsed, because its header and imports determine the import graph hash.
+use crate::docs::ModuleDocumentation;
+use crate::file::ExecutionMode;
+use crate::module_cache::Blake3Hasher;
+use roc_can::expr::Declarations;
+use roc_can::module::{Module, TypeState};
+use roc_module::symbol::{IdentIds, ModuleId};
+use roc_solve::FunctionKind;
+use roc_types::subs::VarStore;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const MAGIC: [u8; 8] = *b"roctypes";
+
+/// Bump this whenever the layout of a cache entry changes.
+const FORMAT_VERSION: u32 = 2;
+
+/// magic, format version, payload length, blake3 checksum of the payload
+const HEADER_LEN: usize = 8 + 4 + 8 + 32;
+
+/// Set this environment variable to 1 to neither read nor write cached modules.
+const SKIP_MODULE_CACHE: &str = "ROC_SKIP_MODULE_CACHE";
+
+#[derive(Debug, Clone)]
+pub(crate) struct TypesCache {
+    dir: PathBuf,
+    compiler_fingerprint: &'static str,
+}
+
+impl TypesCache {
+    /// Returns None if caching is turned off, or if we cannot tell which compiler build is
+    /// running; entries written by a different build of the compiler must never be read back.
+    pub fn new(dir: PathBuf) -> Option<Self> {
+        if std::env::var_os(SKIP_MODULE_CACHE).is_some_and(|value| value == "1") {
+            return None;
+        }
+
+        let compiler_fingerprint = compiler_fingerprint()?;
+
+        Some(Self {
+            dir,
+            compiler_fingerprint,
+        })
+    }
+
+    /// The cache entry for a module, given its import graph hash and everything else that
+    /// influences how it is solved.
+    pub fn entry(
+        &self,
+        import_graph_hash: &str,
+        exec_mode: ExecutionMode,
+        function_kind: FunctionKind,
+        is_host_exposed: bool,
+        exposed_module_ids: &[ModuleId],
+    ) -> TypesCacheEntry {
+        // Module params are only lowered outside of check mode, and lowering them changes the
+        // declarations (and so the variables) of the module.
+        let lowers_params = !matches!(exec_mode, ExecutionMode::Check);
+        let function_kind = match function_kind {
+            FunctionKind::LambdaSet => 0u8,
+            FunctionKind::Erased => 1u8,
+        };
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.compiler_fingerprint.as_bytes());
+        hasher.update(import_graph_hash.as_bytes());
+        hasher.update(&[lowers_params as u8, function_kind, is_host_exposed as u8]);
+        // The docs of a module leave out types from modules its package doesn't expose
+        exposed_module_ids.hash(&mut Blake3Hasher(&mut hasher));
+
+        let path = self
+            .dir
+            .join(base64_url::encode(hasher.finalize().as_bytes()));
+
+        TypesCacheEntry {
+            path: path.with_extension("dat"),
+            can_path: path.with_extension("can"),
+        }
+    }
+}
+
+/// Everything later phases need from canonicalizing a module. Params are already lowered,
+/// if the execution mode lowers them.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedCanModule {
+    pub module: Module,
+    pub declarations: Declarations,
+    pub ident_ids: IdentIds,
+    pub var_store: VarStore,
+    pub module_docs: Option<ModuleDocumentation>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TypesCacheEntry {
+    path: PathBuf,
+    can_path: PathBuf,
+}
+
+impl TypesCacheEntry {
+    /// Any entry that is missing, truncated or otherwise unreadable counts as a cache miss.
+    pub fn load(&self) -> Option<TypeState> {
+        let bytes = std::fs::read(&self.path).ok()?;
+        let payload = verified_payload(&bytes)?;
+
+        // Subs deserialization reinterprets the bytes in place, so it needs them to be aligned
+        // the same way the builtin types are (see `include_bytes_align_as!` in roc_load).
+        let mut aligned = vec![0u128; payload.len().div_ceil(std::mem::size_of::<u128>())];
+        // Safety: the u128 buffer is at least payload.len() bytes long.
+        let aligned_bytes = unsafe {
+            let ptr = aligned.as_mut_ptr() as *mut u8;
+            std::ptr::copy_nonoverlapping(payload.as_ptr(), ptr, payload.len());
+            std::slice::from_raw_parts(ptr as *const u8, payload.len())
+        };
+
+        let (type_state, offset) = TypeState::deserialize(aligned_bytes);
+
+        (offset == payload.len()).then_some(type_state)
+    }
+
+    /// Failing to write an entry is never fatal; the module just gets checked again next time.
+    pub fn store(&self, type_state: &TypeState) {
+        if mentions_derived_symbols(type_state) {
+            return;
+        }
+
+        let mut payload = Vec::new();
+        if type_state.serialize(&mut payload).is_err() {
+            return;
+        }
+
+        let _ = write_atomically(&self.path, &payload);
+    }
+
+    /// Like [TypesCacheEntry::load], for the canonical output of the module.
+    pub fn load_can(&self) -> Option<CachedCanModule> {
+        let bytes = std::fs::read(&self.can_path).ok()?;
+
+        bincode::deserialize(verified_payload(&bytes)?).ok()
+    }
+
+    /// Modules are only ever loaded from the cache when canonicalizing them reported no
+    /// problems, so only call this for those.
+    pub fn store_can(&self, can_module: &CachedCanModule) {
+        // Serialization fails for declarations that contain runtime errors
+        if let Ok(payload) = bincode::serialize(can_module) {
+            let _ = write_atomically(&self.can_path, &payload);
+        }
+    }
+}
+
+/// Derived implementations get their symbols in whatever order modules happen to request them,
+/// so a later run may hand out the same symbol for something else entirely.
+fn mentions_derived_symbols(type_state: &TypeState) -> bool {
+    type_state
+        .subs
+        .symbol_names
+        .iter()
+        .chain(
+            type_state
+                .exposed_vars_by_symbol
+                .iter()
+                .map(|(symbol, _)| symbol),
+        )
+        .any(|symbol| symbol.module_id() == ModuleId::DERIVED_GEN)
+}
+
+fn verified_payload(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.len() < HEADER_LEN {
+        return None;
+    }
+
+    let (header, payload) = bytes.split_at(HEADER_LEN);
+    let (magic, header) = header.split_at(MAGIC.len());
+    let (version, header) = header.split_at(4);
+    let (len, checksum) = header.split_at(8);
+
+    let version = u32::from_le_bytes(version.try_into().ok()?);
+    let len = u64::from_le_bytes(len.try_into().ok()?);
+
+    let is_valid = magic == MAGIC
+        && version == FORMAT_VERSION
+        && len == payload.len() as u64
+        && checksum == blake3::hash(payload).as_bytes();
+
+    is_valid.then_some(payload)
+}
+
+/// Several `roc` processes may share a cache directory, so an entry must never be observed
+/// half-written.
+fn write_atomically(path: &Path, payload: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(&MAGIC)?;
+    file.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    file.write_all(&(payload.len() as u64).to_le_bytes())?;
+    file.write_all(blake3::hash(payload).as_bytes())?;
+    file.write_all(payload)?;
+    file.persist(path)?;
+
+    Ok(())
+}
+
+/// Identifies the running compiler binary by its path, size and modification time. Hashing the
+/// whole binary would be more precise, but it is large enough that doing so on every run
+/// would eat into the time the cache saves.
+fn compiler_fingerprint() -> Option<&'static str> {
+    static FINGERPRINT: OnceLock<Option<String>> = OnceLock::new();
+
+    FINGERPRINT
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            let metadata = std::fs::metadata(&exe).ok()?;
+            let modified = metadata
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?;
+
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(exe.as_os_str().as_encoded_bytes());
+            hasher.update(&metadata.len().to_le_bytes());
+            hasher.update(&modified.as_nanos().to_le_bytes());
+
+            Some(base64_url::encode(hasher.finalize().as_bytes()))
+        })
+        .as_deref()
+}
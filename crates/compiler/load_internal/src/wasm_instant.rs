@@ -22,6 +22,14 @@ impl Instant {
     }
 }
 
+impl std::ops::Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, _: Duration) -> Instant {
+        Instant
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Duration;
 
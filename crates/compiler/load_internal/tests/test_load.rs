@@ -21,7 +21,7 @@ use roc_load_internal::docs::DocDef;
 use roc_load_internal::file::{
     ExecutionMode, LoadConfig, LoadResult, LoadStart, LoadingProblem, Threading,
 };
-use roc_load_internal::module::LoadedModule;
+use roc_load_internal::module::{LoadedModule, TimedPhase};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::RocCacheDir;
@@ -46,6 +46,17 @@ fn load_and_typecheck(
     target: Target,
     function_kind: FunctionKind,
 ) -> Result<LoadedModule, LoadingProblem> {
+    load_and_typecheck_help(arena, filename, exposed_types, target, function_kind, None)
+}
+
+fn load_and_typecheck_help<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    exposed_types: ExposedByModule,
+    target: Target,
+    function_kind: FunctionKind,
+    module_cache_dir: Option<PathBuf>,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
     use LoadResult::*;
 
     let load_start = LoadStart::from_path(
@@ -63,6 +74,9 @@ fn load_and_typecheck(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir,
     };
 
     match roc_load_internal::file::load(
@@ -672,6 +686,85 @@ fn iface_dep_types() {
     );
 }
 
+#[test]
+fn iface_dep_types_from_types_cache() {
+    let modules_dir = TmpDir::new("tmp/iface_dep_types_from_types_cache");
+    let filename = fixtures_dir().join("module_with_deps").join("Primary.roc");
+
+    let load = || {
+        let arena = Bump::new();
+
+        load_and_typecheck_help(
+            &arena,
+            filename.clone(),
+            Default::default(),
+            TARGET,
+            FunctionKind::LambdaSet,
+            Some(modules_dir.path().to_path_buf()),
+        )
+        .expect("Test module failed to load")
+    };
+
+    let expected_types = hashmap! {
+        "blah2" => "Frac *",
+        "blah3" => "Str",
+        "str" => "Str",
+        "always_three" => "* -> Frac *",
+        "identity" => "a -> a",
+        "z" => "Frac *",
+        "w" => "Dep1.Identity {}",
+        "succeed" => "a -> Dep1.Identity a",
+        "yay" => "Res.Res {} err",
+        "with_default" => "Res.Res a err, a -> a",
+    };
+
+    // The first load canonicalizes and solves every module and fills the cache...
+    expect_types(load(), expected_types.clone());
+
+    let cached_entries = std::fs::read_dir(modules_dir.path())
+        .expect("The types cache was not written")
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    let count_entries = |extension: &str| {
+        cached_entries
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .count()
+    };
+    let cached_can_entries = count_entries("can");
+    let cached_types_entries = count_entries("dat");
+    assert!(cached_can_entries > 0);
+    assert_eq!(
+        cached_can_entries + cached_types_entries,
+        cached_entries.len()
+    );
+
+    // ...and the second one gets the same declarations and types back out of it, without adding
+    // new entries. Only modules with warnings (Dep2 has an unused import) are canonicalized again,
+    // so that their warnings are still reported.
+    let loaded_module = load();
+
+    let canonicalized = loaded_module
+        .timings
+        .iter()
+        .filter(|(module_id, timing)| {
+            !module_id.is_builtin()
+                && timing
+                    .spans
+                    .iter()
+                    .any(|span| span.phase == TimedPhase::Canonicalize)
+        })
+        .count();
+    assert_eq!(canonicalized, cached_types_entries - cached_can_entries);
+
+    expect_types(loaded_module, expected_types);
+
+    assert_eq!(
+        std::fs::read_dir(modules_dir.path()).unwrap().count(),
+        cached_entries.len()
+    );
+}
+
 #[test]
 fn app_dep_types() {
     let subs_by_module = Default::default();
@@ -1,5 +1,6 @@
 use self::Associativity::*;
 use self::BinOp::*;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
 
@@ -66,7 +67,7 @@ const DISPLAY_STRINGS: [(BinOp, &str); 18] = [
     (Or, "or"),
 ];
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CalledVia {
     /// Calling with space, e.g. (foo bar)
     Space,
@@ -112,7 +113,7 @@ pub enum CalledVia {
     Try,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnaryOp {
     /// (-), e.g. (-x)
     Negate,
@@ -129,7 +130,7 @@ impl std::fmt::Display for UnaryOp {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Suffix {
     /// (!), e.g. (Stdin.line!)
     Bang,
@@ -146,7 +147,7 @@ impl std::fmt::Display for Suffix {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinOp {
     // highest precedence
     Caret,
@@ -1,4 +1,5 @@
 pub use roc_ident::IdentStr;
+use serde::{Deserialize, Serialize};
 use std::{
     fmt::{self, Debug},
     path::{Path, PathBuf},
@@ -7,7 +8,7 @@ use std::{
 use crate::symbol::PQModuleName;
 
 /// This could be uppercase or lowercase, qualified or unqualified.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct Ident(pub IdentStr);
 
 impl Ident {
@@ -45,7 +46,7 @@ impl<'a> QualifiedModuleName<'a> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ModuleName(IdentStr);
 
 impl ModuleName {
@@ -70,15 +71,15 @@ impl std::ops::Deref for ModuleName {
 }
 
 /// An uncapitalized identifier, such as a field name or local variable
-#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Lowercase(IdentStr);
 
 /// A capitalized identifier, such as a tag name or module name
-#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Uppercase(IdentStr);
 
 /// A string representing a foreign (linked-in) symbol
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct ForeignSymbol(IdentStr);
 
 pub type TagIdIntType = u16;
@@ -90,7 +91,7 @@ pub type TagIdIntType = u16;
 /// If tags had a Symbol representation, then each module would have to
 /// deal with contention on a global mutex around translating tag strings
 /// into integers. (Record field labels work the same way, for the same reason.)
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TagName(pub Uppercase);
 
 roc_error_macros::assert_sizeof_non_wasm!(TagName, 16);
@@ -366,7 +367,7 @@ impl fmt::Display for Uppercase {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum IdentSuffix {
     None,
     Bang,
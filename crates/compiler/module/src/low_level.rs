@@ -1,9 +1,10 @@
 use crate::symbol::Symbol;
+use serde::{Deserialize, Serialize};
 
 /// Low-level operations that get translated directly into e.g. LLVM instructions.
 /// These are always wrapped when exposed to end users, and can only make it
 /// into an Expr when added directly by can::builtins
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LowLevel {
     StrConcat,
     StrJoinWith,
@@ -4,6 +4,7 @@ use roc_collections::{SmallStringInterner, VecMap};
 use roc_error_macros::internal_error;
 use roc_ident::IdentStr;
 use roc_region::all::Region;
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
 use std::{fmt, u32};
 
@@ -26,7 +27,7 @@ use std::{fmt, u32};
 //
 // #[repr(packed)] gives you #[repr(packed(1))], and then all your reads are unaligned
 // so we set the alignment to (the natural) 4
-#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(packed(4))]
 pub struct Symbol {
     ident_id: u32,
@@ -364,7 +365,7 @@ static DEBUG_IDENT_IDS_BY_MODULE_ID: std::sync::Mutex<roc_collections::VecMap<u3
     std::sync::Mutex::new(roc_collections::VecMap::new());
 
 /// A globally unique ID that gets assigned to each module as it is loaded.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ModuleId(NonZeroU32);
 
 impl ModuleId {
@@ -641,6 +642,7 @@ impl ModuleIds {
 
 mod ident_id {
     use crate::ident::IdentSuffix;
+    use serde::{Deserialize, Serialize};
 
     /// An ID that is assigned to interned string identifiers within a module.
     /// By turning these strings into numbers, post-canonicalization processes
@@ -648,7 +650,7 @@ mod ident_id {
     ///
     /// This ID is unique within a given module, not globally - so to turn this back into
     /// a string, you would need a ModuleId, an IdentId, and a Map<ModuleId, Map<IdentId, String>>.
-    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct IdentId(u32);
 
     const BANG_FLAG: u32 = 1u32 << 31;
@@ -693,7 +695,7 @@ mod ident_id {
 pub use ident_id::IdentId;
 
 /// Stores a mapping between Ident and IdentId.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IdentIds {
     pub interner: SmallStringInterner,
 }
@@ -0,0 +1,1110 @@
+use bumpalo::collections::vec::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode;
+use roc_module::ident::ForeignSymbol;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, Symbol};
+
+use crate::ir::{
+    BranchInfo, Call, CallType, Expr, JoinPointId, Literal, Param, Stmt, UpdateModeId,
+};
+use crate::layout::{
+    Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
+};
+
+use super::{CodeGenHelp, Context, HelperOp, ARG_1, ARG_2, ARG_3, ARG_4};
+
+// Shape of an expect frame, the same as the LLVM backend writes:
+//
+//     region_start  (u32)
+//     region_end    (u32)
+//     module_id     (u32)
+// /-- ptr_lookup_1  (ptr_size)
+// |   var_lookup_1  (u32)
+// |   ..
+// |   ptr_lookup_n  (ptr_size)
+// |   var_lookup_n  (u32)
+// \-> lookup_val_1  (varsize)
+//     ..
+//     lookup_val_n  (varsize)
+//
+// The shared buffer starts with the number of frames and the offset of the next free byte.
+// Values are cloned into the buffer with a `Clone` helper proc for their layout, which takes
+// (buffer, offset, extra_offset, value). The value itself goes at `offset`, and anything it
+// points to goes at `extra_offset`. The helper returns the new `extra_offset`.
+
+#[derive(Clone, Copy)]
+struct Cursors {
+    buffer: Symbol,
+    offset: Symbol,
+    extra_offset: Symbol,
+}
+
+/// A straight line of `Let`s, nested into a `Stmt` once we know what comes after them
+struct Block<'a> {
+    lets: Vec<'a, (Symbol, Expr<'a>, InLayout<'a>)>,
+}
+
+impl<'a> Block<'a> {
+    fn new(arena: &'a Bump) -> Self {
+        Block {
+            lets: Vec::new_in(arena),
+        }
+    }
+
+    fn push(&mut self, symbol: Symbol, expr: Expr<'a>, layout: InLayout<'a>) -> Symbol {
+        self.lets.push((symbol, expr, layout));
+        symbol
+    }
+
+    fn finish(self, arena: &'a Bump, tail: Stmt<'a>) -> Stmt<'a> {
+        self.lets
+            .into_iter()
+            .rev()
+            .fold(tail, |next, (symbol, expr, layout)| {
+                Stmt::Let(symbol, expr, layout, arena.alloc(next))
+            })
+    }
+}
+
+/// Replace an `Expect` statement with code that writes a frame to the shared buffer when the
+/// condition is false, and then carries on with the remainder either way.
+pub fn expect_stmt<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    expect: &'a Stmt<'a>,
+    lookup_layouts: &[InLayout<'a>],
+    ret_layout: InLayout<'a>,
+) -> &'a Stmt<'a> {
+    let Stmt::Expect {
+        condition,
+        region,
+        lookups,
+        variables,
+        remainder,
+    } = expect
+    else {
+        unreachable!("not an expect: {:?}", expect)
+    };
+
+    let arena = root.arena;
+    let layout_isize = root.layout_isize;
+    let ptr_size = root.target.ptr_size() as u32;
+
+    let mut block = Block::new(arena);
+
+    let buffer = root.create_symbol(ident_ids, "buffer");
+    let start_buffer = Expr::Call(Call {
+        call_type: CallType::Foreign {
+            foreign_symbol: ForeignSymbol::from(bitcode::UTILS_EXPECT_FAILED_START_SHARED_BUFFER),
+            ret_layout: layout_isize,
+        },
+        arguments: &[],
+    });
+    block.push(buffer, start_buffer, layout_isize);
+
+    // Read the state
+    let zero = int(root, ident_ids, &mut block, 0, layout_isize);
+    let count_offset = zero;
+    let next_offset = add(root, ident_ids, &mut block, zero, ptr_size);
+    let count = read(
+        root,
+        ident_ids,
+        layout_interner,
+        &mut block,
+        buffer,
+        count_offset,
+        layout_isize,
+    );
+    let frame_start = read(
+        root,
+        ident_ids,
+        layout_interner,
+        &mut block,
+        buffer,
+        next_offset,
+        layout_isize,
+    );
+
+    // Write the header
+    let module_id: u32 = unsafe { std::mem::transmute(condition.module_id()) };
+    let header = [region.start().offset, region.end().offset, module_id];
+
+    let mut offset = frame_start;
+    for value in header {
+        let value = int(root, ident_ids, &mut block, value as u64, Layout::U32);
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            buffer,
+            offset,
+            value,
+            Layout::U32,
+        );
+        offset = add(root, ident_ids, &mut block, offset, 4);
+    }
+    let after_header = offset;
+
+    // Clone the values, after the table of where they are
+    let table_size = lookups.len() as u32 * (ptr_size + 4);
+    let mut cursor = add(root, ident_ids, &mut block, after_header, table_size);
+    let mut lookup_starts = Vec::with_capacity_in(lookups.len(), arena);
+
+    for (lookup, layout) in lookups.iter().zip(lookup_layouts) {
+        lookup_starts.push(cursor);
+
+        let stack_size = layout_interner.stack_size(*layout);
+        let cursors = Cursors {
+            buffer,
+            offset: cursor,
+            extra_offset: add(root, ident_ids, &mut block, cursor, stack_size),
+        };
+
+        cursor = clone_value(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            &mut block,
+            cursors,
+            *lookup,
+            *layout,
+        );
+    }
+
+    // Fill in the table
+    let mut offset = after_header;
+    for (lookup_start, variable) in lookup_starts.iter().zip(variables.iter()) {
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            buffer,
+            offset,
+            *lookup_start,
+            layout_isize,
+        );
+        offset = add(root, ident_ids, &mut block, offset, ptr_size);
+
+        let variable = int(
+            root,
+            ident_ids,
+            &mut block,
+            variable.index() as u64,
+            Layout::U32,
+        );
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            buffer,
+            offset,
+            variable,
+            Layout::U32,
+        );
+        offset = add(root, ident_ids, &mut block, offset, 4);
+    }
+
+    // Update the state
+    let one = int(root, ident_ids, &mut block, 1, layout_isize);
+    let new_count = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        LowLevel::NumAdd,
+        &[count, one],
+        layout_isize,
+    );
+    write(
+        root,
+        ident_ids,
+        layout_interner,
+        &mut block,
+        buffer,
+        count_offset,
+        new_count,
+        layout_isize,
+    );
+    write(
+        root,
+        ident_ids,
+        layout_interner,
+        &mut block,
+        buffer,
+        next_offset,
+        cursor,
+        layout_isize,
+    );
+
+    let expect_done = JoinPointId(root.create_symbol(ident_ids, "expect_done"));
+    let jump_done = Stmt::Jump(expect_done, &[]);
+
+    let failed = block.finish(arena, jump_done.clone());
+    let if_failed = Stmt::if_then_else(
+        arena,
+        *condition,
+        ret_layout,
+        jump_done,
+        arena.alloc(failed),
+    );
+
+    arena.alloc(Stmt::Join {
+        id: expect_done,
+        parameters: &[],
+        body: remainder,
+        remainder: arena.alloc(if_failed),
+    })
+}
+
+/// The body of the `Clone` helper proc for a layout
+pub fn clone_generic<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout: InLayout<'a>,
+) -> Stmt<'a> {
+    let arena = root.arena;
+    let cursors = Cursors {
+        buffer: ARG_1,
+        offset: ARG_2,
+        extra_offset: ARG_3,
+    };
+
+    match layout_interner.get_repr(layout) {
+        LayoutRepr::Builtin(Builtin::Str) => {
+            let extra_offset = root.create_symbol(ident_ids, "extra_offset");
+            let clone_str = Expr::Call(Call {
+                call_type: CallType::Foreign {
+                    foreign_symbol: ForeignSymbol::from(bitcode::STR_CLONE_TO),
+                    ret_layout: root.layout_isize,
+                },
+                arguments: arena.alloc([ARG_4, ARG_1, ARG_2, ARG_3]),
+            });
+
+            Stmt::Let(
+                extra_offset,
+                clone_str,
+                root.layout_isize,
+                arena.alloc(Stmt::Ret(extra_offset)),
+            )
+        }
+
+        LayoutRepr::Builtin(Builtin::List(elem_layout)) => {
+            clone_list(root, ident_ids, ctx, layout_interner, elem_layout)
+        }
+
+        LayoutRepr::Builtin(_) => {
+            let mut block = Block::new(arena);
+            write(
+                root,
+                ident_ids,
+                layout_interner,
+                &mut block,
+                ARG_1,
+                ARG_2,
+                ARG_4,
+                layout,
+            );
+            block.finish(arena, Stmt::Ret(ARG_3))
+        }
+
+        LayoutRepr::Struct(field_layouts) => {
+            let mut block = Block::new(arena);
+            let extra_offset = clone_fields(
+                root,
+                ident_ids,
+                ctx,
+                layout_interner,
+                &mut block,
+                cursors,
+                field_layouts,
+                |index| Expr::StructAtIndex {
+                    index,
+                    field_layouts,
+                    structure: ARG_4,
+                },
+            );
+            block.finish(arena, Stmt::Ret(extra_offset))
+        }
+
+        LayoutRepr::Union(union_layout) => {
+            clone_union(root, ident_ids, ctx, layout_interner, union_layout)
+        }
+
+        // Since we will never actually display functions (and hence lambda sets)
+        // we just write nothing to the buffer
+        LayoutRepr::LambdaSet(_) | LayoutRepr::FunctionPointer(_) | LayoutRepr::Erased(_) => {
+            Stmt::Ret(ARG_3)
+        }
+
+        LayoutRepr::RecursivePointer(_) => {
+            unreachable!("recursive pointers are replaced by their union before we get here")
+        }
+
+        LayoutRepr::Ptr(_) => unreachable!("for internal use only"),
+    }
+}
+
+fn clone_list<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    elem_layout: InLayout<'a>,
+) -> Stmt<'a> {
+    use LowLevel::*;
+    let arena = root.arena;
+    let layout_isize = root.layout_isize;
+    let ptr_size = root.target.ptr_size() as u32;
+
+    let ptr_layout = layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(elem_layout));
+
+    let mut block = Block::new(arena);
+
+    // We only copy the elements we actually have, so the capacity is the length
+    let len = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        ListLenUsize,
+        &[ARG_4],
+        layout_isize,
+    );
+    let mut offset = ARG_2;
+    for value in [ARG_3, len, len] {
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            ARG_1,
+            offset,
+            value,
+            layout_isize,
+        );
+        offset = add(root, ident_ids, &mut block, offset, ptr_size);
+    }
+
+    // The elements go at the extra offset, and anything they point to goes after them
+    let elem_size = layout_interner.stack_size(elem_layout);
+    let size = int(root, ident_ids, &mut block, elem_size as u64, layout_isize);
+    let elements_width = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        NumMul,
+        &[len, size],
+        layout_isize,
+    );
+    let rest_start = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        NumAdd,
+        &[ARG_3, elements_width],
+        layout_isize,
+    );
+
+    let elements = root.create_symbol(ident_ids, "elements");
+    let elements_expr = Expr::StructAtIndex {
+        index: 0,
+        field_layouts: arena.alloc([ptr_layout, layout_isize]),
+        structure: ARG_4,
+    };
+    block.push(elements, elements_expr, ptr_layout);
+    let start = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        PtrCast,
+        &[elements],
+        layout_isize,
+    );
+    let end = lowlevel(
+        root,
+        ident_ids,
+        &mut block,
+        NumAdd,
+        &[start, elements_width],
+        layout_isize,
+    );
+
+    //
+    // Loop over the elements
+    //
+
+    let elems_loop = JoinPointId(root.create_symbol(ident_ids, "elems_loop"));
+    let addr = root.create_symbol(ident_ids, "addr");
+    let elem_offset = root.create_symbol(ident_ids, "elem_offset");
+    let extra_offset = root.create_symbol(ident_ids, "extra_offset");
+
+    let mut loop_body = Block::new(arena);
+    let ptr = lowlevel(
+        root,
+        ident_ids,
+        &mut loop_body,
+        PtrCast,
+        &[addr],
+        ptr_layout,
+    );
+    let elem = root.create_symbol(ident_ids, "elem");
+    loop_body.push(elem, Expr::ptr_load(arena.alloc(ptr)), elem_layout);
+
+    let cursors = Cursors {
+        buffer: ARG_1,
+        offset: elem_offset,
+        extra_offset,
+    };
+    let new_extra_offset = clone_value(
+        root,
+        ident_ids,
+        ctx,
+        layout_interner,
+        &mut loop_body,
+        cursors,
+        elem,
+        elem_layout,
+    );
+
+    let next_addr = add(root, ident_ids, &mut loop_body, addr, elem_size);
+    let next_offset = add(root, ident_ids, &mut loop_body, elem_offset, elem_size);
+    let jump_back = Stmt::Jump(
+        elems_loop,
+        arena.alloc([next_addr, next_offset, new_extra_offset]),
+    );
+
+    let mut check_end = Block::new(arena);
+    let is_end = lowlevel(
+        root,
+        ident_ids,
+        &mut check_end,
+        NumGte,
+        &[addr, end],
+        Layout::BOOL,
+    );
+    let if_end_of_list = Stmt::if_then_else(
+        arena,
+        is_end,
+        layout_isize,
+        Stmt::Ret(extra_offset),
+        arena.alloc(loop_body.finish(arena, jump_back)),
+    );
+
+    let param = |symbol| Param {
+        symbol,
+        layout: layout_isize,
+    };
+
+    let joinpoint_loop = Stmt::Join {
+        id: elems_loop,
+        parameters: arena.alloc([param(addr), param(elem_offset), param(extra_offset)]),
+        body: arena.alloc(check_end.finish(arena, if_end_of_list)),
+        remainder: arena.alloc(Stmt::Jump(
+            elems_loop,
+            arena.alloc([start, ARG_3, rest_start]),
+        )),
+    };
+
+    block.finish(arena, joinpoint_loop)
+}
+
+fn clone_union<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+) -> Stmt<'a> {
+    use UnionLayout::*;
+
+    let parent_rec_ptr_layout = ctx.recursive_union;
+    if !matches!(union_layout, NonRecursive(_)) {
+        ctx.recursive_union = Some(union_layout);
+    }
+
+    let body = match union_layout {
+        NonRecursive(&[]) => {
+            // An empty tag union has no values, so this is unreachable
+            Stmt::Ret(ARG_3)
+        }
+
+        NonRecursive(tags) => {
+            let tag_id_offset = union_layout.tag_id_offset(layout_interner).unwrap();
+
+            clone_tag_switch(
+                root,
+                ident_ids,
+                ctx,
+                layout_interner,
+                union_layout,
+                tags.len(),
+                |root, ident_ids, ctx, layout_interner, tag_id| {
+                    let mut block = Block::new(root.arena);
+                    let cursors = Cursors {
+                        buffer: ARG_1,
+                        offset: ARG_2,
+                        extra_offset: ARG_3,
+                    };
+
+                    let extra_offset = clone_tag_fields(
+                        root,
+                        ident_ids,
+                        ctx,
+                        layout_interner,
+                        &mut block,
+                        cursors,
+                        union_layout,
+                        tag_id,
+                        tags[tag_id as usize],
+                    );
+
+                    // The tag id goes after the payload, like it does in memory
+                    let tag_id_layout = union_layout.tag_id_layout();
+                    let id_offset = add(root, ident_ids, &mut block, ARG_2, tag_id_offset);
+                    let id = int(root, ident_ids, &mut block, tag_id as u64, tag_id_layout);
+                    write(
+                        root,
+                        ident_ids,
+                        layout_interner,
+                        &mut block,
+                        ARG_1,
+                        id_offset,
+                        id,
+                        tag_id_layout,
+                    );
+
+                    block.finish(root.arena, Stmt::Ret(extra_offset))
+                },
+            )
+        }
+
+        Recursive(tags) => clone_tag_switch(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            union_layout,
+            tags.len(),
+            |root, ident_ids, ctx, layout_interner, tag_id| {
+                clone_heap_tag(
+                    root,
+                    ident_ids,
+                    ctx,
+                    layout_interner,
+                    union_layout,
+                    tag_id,
+                    tags[tag_id as usize],
+                )
+            },
+        ),
+
+        NonNullableUnwrapped(field_layouts) => clone_heap_tag(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            union_layout,
+            0,
+            field_layouts,
+        ),
+
+        NullableWrapped {
+            nullable_id,
+            other_tags,
+        } => clone_tag_switch(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            union_layout,
+            other_tags.len() + 1,
+            |root, ident_ids, ctx, layout_interner, tag_id| {
+                use std::cmp::Ordering::*;
+                let field_layouts = match tag_id.cmp(&nullable_id) {
+                    Less => other_tags[tag_id as usize],
+                    Equal => return clone_null(root, ident_ids, layout_interner),
+                    Greater => other_tags[tag_id as usize - 1],
+                };
+
+                clone_heap_tag(
+                    root,
+                    ident_ids,
+                    ctx,
+                    layout_interner,
+                    union_layout,
+                    tag_id,
+                    field_layouts,
+                )
+            },
+        ),
+
+        NullableUnwrapped {
+            nullable_id,
+            other_fields,
+        } => clone_tag_switch(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            union_layout,
+            2,
+            |root, ident_ids, ctx, layout_interner, tag_id| {
+                if tag_id == nullable_id as u16 {
+                    clone_null(root, ident_ids, layout_interner)
+                } else {
+                    clone_heap_tag(
+                        root,
+                        ident_ids,
+                        ctx,
+                        layout_interner,
+                        union_layout,
+                        tag_id,
+                        other_fields,
+                    )
+                }
+            },
+        ),
+    };
+
+    ctx.recursive_union = parent_rec_ptr_layout;
+
+    body
+}
+
+/// Switch on the tag id of `ARG_4`, with one branch per tag
+fn clone_tag_switch<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+    number_of_tags: usize,
+    mut clone_tag: impl FnMut(
+        &mut CodeGenHelp<'a>,
+        &mut IdentIds,
+        &mut Context<'a>,
+        &mut STLayoutInterner<'a>,
+        u16,
+    ) -> Stmt<'a>,
+) -> Stmt<'a> {
+    let arena = root.arena;
+    let tag_id_layout = union_layout.tag_id_layout();
+
+    let tag_id = root.create_symbol(ident_ids, "tag_id");
+    let tag_id_expr = Expr::GetTagId {
+        structure: ARG_4,
+        union_layout,
+    };
+
+    let mut branches = Vec::with_capacity_in(number_of_tags, arena);
+    for id in 0..number_of_tags as u16 {
+        let branch = clone_tag(root, ident_ids, ctx, layout_interner, id);
+        branches.push((id as u64, BranchInfo::None, branch));
+    }
+
+    let (_, _, default_branch) = branches.pop().unwrap();
+
+    let switch = Stmt::Switch {
+        cond_symbol: tag_id,
+        cond_layout: tag_id_layout,
+        branches: branches.into_bump_slice(),
+        default_branch: (BranchInfo::None, arena.alloc(default_branch)),
+        ret_layout: root.layout_isize,
+    };
+
+    Stmt::Let(tag_id, tag_id_expr, tag_id_layout, arena.alloc(switch))
+}
+
+/// A tag of a recursive union: write a "pointer" to the extra offset, then clone the fields
+/// there. If the tag id isn't stored in the pointer, it goes after the fields.
+fn clone_heap_tag<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+    tag_id: u16,
+    field_layouts: &'a [InLayout<'a>],
+) -> Stmt<'a> {
+    let arena = root.arena;
+    let layout_isize = root.layout_isize;
+    let mut block = Block::new(arena);
+
+    let stores_tag_id_in_pointer = union_layout.stores_tag_id_in_pointer(root.target);
+
+    if stores_tag_id_in_pointer {
+        let id = int(root, ident_ids, &mut block, tag_id as u64, Layout::U32);
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            ARG_1,
+            ARG_2,
+            id,
+            Layout::U32,
+        );
+
+        let extra_u32 = lowlevel(
+            root,
+            ident_ids,
+            &mut block,
+            LowLevel::NumIntCast,
+            &[ARG_3],
+            Layout::U32,
+        );
+        let offset = add(root, ident_ids, &mut block, ARG_2, 4);
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            ARG_1,
+            offset,
+            extra_u32,
+            Layout::U32,
+        );
+    } else {
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            ARG_1,
+            ARG_2,
+            ARG_3,
+            layout_isize,
+        );
+    }
+
+    let width = match union_layout {
+        UnionLayout::NullableUnwrapped { .. } => {
+            LayoutRepr::struct_(field_layouts).stack_size(layout_interner)
+        }
+        _ => union_layout.data_size_and_alignment(layout_interner).0,
+    };
+
+    let cursors = Cursors {
+        buffer: ARG_1,
+        offset: ARG_3,
+        extra_offset: add(root, ident_ids, &mut block, ARG_3, width),
+    };
+
+    let extra_offset = clone_tag_fields(
+        root,
+        ident_ids,
+        ctx,
+        layout_interner,
+        &mut block,
+        cursors,
+        union_layout,
+        tag_id,
+        field_layouts,
+    );
+
+    let has_tag_id_field = !stores_tag_id_in_pointer
+        && matches!(
+            union_layout,
+            UnionLayout::Recursive(_) | UnionLayout::NullableWrapped { .. }
+        );
+
+    if has_tag_id_field {
+        let fields_size = field_layouts
+            .iter()
+            .map(|layout| layout_interner.stack_size(*layout))
+            .sum();
+        let tag_id_layout = union_layout.tag_id_layout();
+        let id_offset = add(root, ident_ids, &mut block, ARG_3, fields_size);
+        let id = int(root, ident_ids, &mut block, tag_id as u64, tag_id_layout);
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            &mut block,
+            ARG_1,
+            id_offset,
+            id,
+            tag_id_layout,
+        );
+    }
+
+    block.finish(arena, Stmt::Ret(extra_offset))
+}
+
+/// The null tag of a nullable union is written as a null "pointer"
+fn clone_null<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    layout_interner: &mut STLayoutInterner<'a>,
+) -> Stmt<'a> {
+    let layout_isize = root.layout_isize;
+    let mut block = Block::new(root.arena);
+
+    let zero = int(root, ident_ids, &mut block, 0, layout_isize);
+    write(
+        root,
+        ident_ids,
+        layout_interner,
+        &mut block,
+        ARG_1,
+        ARG_2,
+        zero,
+        layout_isize,
+    );
+
+    block.finish(root.arena, Stmt::Ret(ARG_3))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn clone_tag_fields<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    cursors: Cursors,
+    union_layout: UnionLayout<'a>,
+    tag_id: u16,
+    field_layouts: &'a [InLayout<'a>],
+) -> Symbol {
+    clone_fields(
+        root,
+        ident_ids,
+        ctx,
+        layout_interner,
+        block,
+        cursors,
+        field_layouts,
+        |index| Expr::UnionAtIndex {
+            structure: ARG_4,
+            tag_id,
+            union_layout,
+            index,
+        },
+    )
+}
+
+/// Clone fields one after the other, starting at the offset. Returns the new extra offset.
+#[allow(clippy::too_many_arguments)]
+fn clone_fields<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    mut cursors: Cursors,
+    field_layouts: &'a [InLayout<'a>],
+    field_expr: impl Fn(u64) -> Expr<'a>,
+) -> Symbol {
+    for (i, field_layout) in field_layouts.iter().enumerate() {
+        let field = root.create_symbol(ident_ids, &format!("field_{i}"));
+        block.push(field, field_expr(i as u64), *field_layout);
+
+        cursors.extra_offset = clone_value(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            block,
+            cursors,
+            field,
+            *field_layout,
+        );
+
+        if i + 1 < field_layouts.len() {
+            let width = layout_interner.stack_size(*field_layout);
+            cursors.offset = add(root, ident_ids, block, cursors.offset, width);
+        }
+    }
+
+    cursors.extra_offset
+}
+
+/// Clone a value, either by writing it directly or by calling the `Clone` helper proc for its
+/// layout. Returns the new extra offset.
+#[allow(clippy::too_many_arguments)]
+fn clone_value<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    cursors: Cursors,
+    value: Symbol,
+    layout: InLayout<'a>,
+) -> Symbol {
+    debug_assert_eq!(ctx.op, HelperOp::Clone);
+
+    let repr = layout_interner.get_repr(layout);
+
+    if layout_interner.stack_size(layout) == 0 {
+        cursors.extra_offset
+    } else if repr.safe_to_memcpy(layout_interner) {
+        write(
+            root,
+            ident_ids,
+            layout_interner,
+            block,
+            cursors.buffer,
+            cursors.offset,
+            value,
+            layout,
+        );
+        cursors.extra_offset
+    } else {
+        let arguments =
+            root.arena
+                .alloc([cursors.buffer, cursors.offset, cursors.extra_offset, value]);
+        let call = root
+            .call_specialized_op(ident_ids, ctx, layout_interner, layout, arguments)
+            .unwrap();
+
+        let extra_offset = root.create_symbol(ident_ids, "extra_offset");
+        block.push(extra_offset, call, root.layout_isize)
+    }
+}
+
+fn int<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    block: &mut Block<'a>,
+    value: u64,
+    layout: InLayout<'a>,
+) -> Symbol {
+    let symbol = root.create_symbol(ident_ids, "int");
+    let literal = Expr::Literal(Literal::Int((value as i128).to_ne_bytes()));
+    block.push(symbol, literal, layout)
+}
+
+fn lowlevel<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    block: &mut Block<'a>,
+    op: LowLevel,
+    arguments: &[Symbol],
+    layout: InLayout<'a>,
+) -> Symbol {
+    let symbol = root.create_symbol(ident_ids, "tmp");
+    let call = Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op,
+            update_mode: UpdateModeId::BACKEND_DUMMY,
+        },
+        arguments: root.arena.alloc_slice_copy(arguments),
+    });
+    block.push(symbol, call, layout)
+}
+
+/// `offset + amount`
+fn add<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    block: &mut Block<'a>,
+    offset: Symbol,
+    amount: u32,
+) -> Symbol {
+    if amount == 0 {
+        return offset;
+    }
+
+    let layout_isize = root.layout_isize;
+    let amount = int(root, ident_ids, block, amount as u64, layout_isize);
+    lowlevel(
+        root,
+        ident_ids,
+        block,
+        LowLevel::NumAdd,
+        &[offset, amount],
+        layout_isize,
+    )
+}
+
+fn buffer_ptr<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    buffer: Symbol,
+    offset: Symbol,
+    layout: InLayout<'a>,
+) -> Symbol {
+    let address = lowlevel(
+        root,
+        ident_ids,
+        block,
+        LowLevel::NumAdd,
+        &[buffer, offset],
+        root.layout_isize,
+    );
+
+    let ptr_layout = layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(layout));
+    lowlevel(
+        root,
+        ident_ids,
+        block,
+        LowLevel::PtrCast,
+        &[address],
+        ptr_layout,
+    )
+}
+
+/// Read a value of the given layout from the buffer at `offset`
+fn read<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    buffer: Symbol,
+    offset: Symbol,
+    layout: InLayout<'a>,
+) -> Symbol {
+    let ptr = buffer_ptr(
+        root,
+        ident_ids,
+        layout_interner,
+        block,
+        buffer,
+        offset,
+        layout,
+    );
+
+    let value = root.create_symbol(ident_ids, "value");
+    block.push(value, Expr::ptr_load(root.arena.alloc(ptr)), layout)
+}
+
+/// Write `value` to the buffer at `offset`
+#[allow(clippy::too_many_arguments)]
+fn write<'a>(
+    root: &CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    layout_interner: &mut STLayoutInterner<'a>,
+    block: &mut Block<'a>,
+    buffer: Symbol,
+    offset: Symbol,
+    value: Symbol,
+    layout: InLayout<'a>,
+) {
+    let ptr = buffer_ptr(
+        root,
+        ident_ids,
+        layout_interner,
+        block,
+        buffer,
+        offset,
+        layout,
+    );
+
+    let unit = root.create_symbol(ident_ids, "unit");
+    block.push(
+        unit,
+        Expr::ptr_store(root.arena.alloc([ptr, value])),
+        Layout::UNIT,
+    );
+}
@@ -1,10 +1,13 @@
 use bumpalo::collections::vec::Vec;
 use bumpalo::collections::CollectIn;
 use bumpalo::Bump;
+use roc_collections::MutMap;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_region::all::Region;
 use roc_target::Target;
 
+use crate::inline::{inline_procs, InlinableProcs, InlineBudget};
 use crate::ir::{
     BranchInfo, Call, CallSpecId, CallType, Expr, JoinPointId, Literal, ModifyRc, PassedFunction,
     Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
@@ -16,6 +19,7 @@ use crate::layout::{
 
 mod copy;
 mod equality;
+mod expect;
 mod refcount;
 
 const LAYOUT_BOOL: InLayout = Layout::BOOL;
@@ -45,6 +49,7 @@ pub enum HelperOp {
     ResetRef,
     Eq,
     IndirectCopy,
+    Clone,
 }
 
 impl HelperOp {
@@ -69,6 +74,7 @@ struct Specialization<'a> {
     op: HelperOp,
     layout: InLayout<'a>,
     symbol: Symbol,
+    proc_layout: ProcLayout<'a>,
     proc: Option<Proc<'a>>,
 }
 
@@ -97,6 +103,9 @@ pub struct Context<'a> {
 /// 2) After the backend has generated code for all user procs, it takes the IR for all of the
 ///    specialized helpers procs, and generates target code for them too.
 ///
+/// With an inline budget, small helpers are copied into the refcount statements that call them in
+/// step 1, and into the other helpers in step 2.
+///
 pub struct CodeGenHelp<'a> {
     arena: &'a Bump,
     home: ModuleId,
@@ -104,10 +113,18 @@ pub struct CodeGenHelp<'a> {
     layout_isize: InLayout<'a>,
     specializations: Vec<'a, Specialization<'a>>,
     debug_recursion_depth: usize,
+    inline_budget: Option<InlineBudget>,
+    /// The helpers that can be inlined, and how many specializations there were when they were picked
+    inlinable: Option<(usize, InlinableProcs<'a>)>,
 }
 
 impl<'a> CodeGenHelp<'a> {
-    pub fn new(arena: &'a Bump, target: Target, home: ModuleId) -> Self {
+    pub fn new(
+        arena: &'a Bump,
+        target: Target,
+        home: ModuleId,
+        inline_budget: Option<InlineBudget>,
+    ) -> Self {
         let layout_isize = Layout::isize(target);
 
         CodeGenHelp {
@@ -117,15 +134,35 @@ impl<'a> CodeGenHelp<'a> {
             layout_isize,
             specializations: Vec::with_capacity_in(16, arena),
             debug_recursion_depth: 0,
+            inline_budget,
+            inlinable: None,
         }
     }
 
-    pub fn take_procs(&mut self) -> Vec<'a, Proc<'a>> {
-        let procs_iter = self
+    pub fn take_procs(&mut self, ident_ids: &mut IdentIds) -> Vec<'a, Proc<'a>> {
+        let keys = Vec::from_iter_in(
+            self.specializations
+                .iter()
+                .map(|spec| (spec.symbol, spec.proc_layout)),
+            self.arena,
+        );
+
+        let mut procs: MutMap<_, _> = self
             .specializations
             .drain(0..)
-            .map(|spec| spec.proc.unwrap());
-        Vec::from_iter_in(procs_iter, self.arena)
+            .map(|spec| ((spec.symbol, spec.proc_layout), spec.proc.unwrap()))
+            .collect();
+
+        self.inlinable = None;
+
+        if let Some(budget) = self.inline_budget {
+            inline_procs(self.arena, self.home, ident_ids, budget, &mut procs);
+        }
+
+        Vec::from_iter_in(
+            keys.iter().map(|key| procs.remove(key).unwrap()),
+            self.arena,
+        )
     }
 
     // ============================================================================
@@ -277,6 +314,35 @@ impl<'a> CodeGenHelp<'a> {
         (expr, ctx.new_linker_data)
     }
 
+    /// Expand an `Expect` node to code that writes a frame to the shared expect buffer when
+    /// the condition is false. `lookup_layouts` are the layouts of the expect's lookups.
+    pub fn expand_expect_stmt(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        layout_interner: &mut STLayoutInterner<'a>,
+        expect: &'a Stmt<'a>,
+        lookup_layouts: &[InLayout<'a>],
+        ret_layout: InLayout<'a>,
+    ) -> (&'a Stmt<'a>, Vec<'a, (Symbol, ProcLayout<'a>)>) {
+        let mut ctx = Context {
+            new_linker_data: Vec::new_in(self.arena),
+            recursive_union: None,
+            op: HelperOp::Clone,
+        };
+
+        let stmt = expect::expect_stmt(
+            self,
+            ident_ids,
+            &mut ctx,
+            layout_interner,
+            expect,
+            lookup_layouts,
+            ret_layout,
+        );
+
+        (stmt, ctx.new_linker_data)
+    }
+
     /// Generate a copy procedure, *without* a Call expression.
     /// *This method should be rarely used* - only when the proc is to be called from Zig.
     pub fn gen_copy_proc(
@@ -296,6 +362,44 @@ impl<'a> CodeGenHelp<'a> {
         (proc_name, ctx.new_linker_data)
     }
 
+    /// `let binding = call in following`, where `call` is a call to a helper. The helper's body is
+    /// copied in instead if it is small enough. `following` is not copied, since the backend that
+    /// asked for this statement has already looked at it.
+    fn let_helper_call(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        binding: Symbol,
+        call: Expr<'a>,
+        layout: InLayout<'a>,
+        following: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
+        let (Some(budget), Expr::Call(call)) = (self.inline_budget, &call) else {
+            return self
+                .arena
+                .alloc(Stmt::Let(binding, call, layout, following));
+        };
+
+        let (arena, home) = (self.arena, self.home);
+        let helper_count = self.specializations.len();
+
+        let (_, inlinable) = match &mut self.inlinable {
+            Some(inlinable) if inlinable.0 == helper_count => inlinable,
+            inlinable => {
+                let mut procs: MutMap<_, _> = self
+                    .specializations
+                    .iter()
+                    .filter_map(|spec| Some(((spec.symbol, spec.proc_layout), spec.proc.clone()?)))
+                    .collect();
+
+                let procs = InlinableProcs::new(arena, home, ident_ids, budget, &mut procs);
+
+                inlinable.insert((helper_count, procs))
+            }
+        };
+
+        inlinable.inline_call(arena, home, ident_ids, binding, call, layout, following)
+    }
+
     // ============================================================================
     //
     //              CALL SPECIALIZED OP
@@ -343,6 +447,10 @@ impl<'a> CodeGenHelp<'a> {
                     IndirectInc => (LAYOUT_UNIT, arena.alloc([ptr_arg])),
                     Eq => (LAYOUT_BOOL, self.arena.alloc([arg, arg])),
                     IndirectCopy => (LAYOUT_UNIT, self.arena.alloc([ptr_arg, ptr_arg])),
+                    Clone => {
+                        let isize = self.layout_isize;
+                        (isize, self.arena.alloc([isize, isize, isize, arg]))
+                    }
                 }
             };
 
@@ -399,6 +507,7 @@ impl<'a> CodeGenHelp<'a> {
             op: ctx.op,
             layout,
             symbol: proc_symbol,
+            proc_layout,
             proc: None,
         });
 
@@ -456,6 +565,10 @@ impl<'a> CodeGenHelp<'a> {
                 LAYOUT_UNIT,
                 copy::copy_indirect(self, ident_ids, ctx, layout_interner, layout),
             ),
+            Clone => (
+                self.layout_isize,
+                expect::clone_generic(self, ident_ids, ctx, layout_interner, layout),
+            ),
         };
 
         let args: &'a [(InLayout<'a>, Symbol)] = {
@@ -483,6 +596,15 @@ impl<'a> CodeGenHelp<'a> {
                         layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(layout));
                     self.arena.alloc([(ptr_layout, ARG_1), (ptr_layout, ARG_2)])
                 }
+                Clone => {
+                    let isize = self.layout_isize;
+                    self.arena.alloc([
+                        (isize, ARG_1),
+                        (isize, ARG_2),
+                        (isize, ARG_3),
+                        (layout, ARG_4),
+                    ])
+                }
             }
         };
 
@@ -494,6 +616,7 @@ impl<'a> CodeGenHelp<'a> {
             ret_layout,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         });
 
         proc_symbol
@@ -564,6 +687,15 @@ impl<'a> CodeGenHelp<'a> {
                     niche: Niche::NONE,
                 }
             }
+            HelperOp::Clone => {
+                let isize = self.layout_isize;
+
+                ProcLayout {
+                    arguments: self.arena.alloc([isize, isize, isize, layout]),
+                    result: isize,
+                    niche: Niche::NONE,
+                }
+            }
         };
 
         (proc_symbol, proc_layout)
@@ -876,6 +1008,7 @@ impl<'a> CallerProc<'a> {
             ret_layout: Layout::UNIT,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         if false {
@@ -1018,6 +1151,7 @@ impl<'a> CallerProc<'a> {
             ret_layout: Layout::BOOL,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         if false {
@@ -1060,6 +1194,11 @@ fn layout_needs_helper_proc<'a>(
     layout: InLayout<'a>,
     op: HelperOp,
 ) -> bool {
+    if op == HelperOp::Clone {
+        // Values that can be written directly never get this far
+        return true;
+    }
+
     match layout_interner.get_repr(layout) {
         LayoutRepr::Builtin(
             Builtin::Int(_) | Builtin::Float(_) | Builtin::Bool | Builtin::Decimal,
@@ -1125,6 +1264,7 @@ pub fn test_helper<'a>(
         ret_layout: output_layout,
         is_self_recursive: main_proc.is_self_recursive,
         is_erased: false,
+        region: Region::zero(),
     }
 }
 
@@ -1325,6 +1465,7 @@ pub fn repl_helper<'a>(
         ret_layout: Layout::UNIT,
         is_self_recursive: main_proc.is_self_recursive,
         is_erased: false,
+        region: Region::zero(),
     }
 }
 
@@ -53,8 +53,14 @@ pub fn refcount_stmt<'a>(
                 )
                 .unwrap();
 
-            let call_stmt = Stmt::Let(call_result_empty, call_expr, LAYOUT_UNIT, following);
-            arena.alloc(amount_stmt(arena.alloc(call_stmt)))
+            let call_stmt = root.let_helper_call(
+                ident_ids,
+                call_result_empty,
+                call_expr,
+                LAYOUT_UNIT,
+                following,
+            );
+            arena.alloc(amount_stmt(call_stmt))
         }
 
         ModifyRc::Dec(structure) => {
@@ -69,8 +75,13 @@ pub fn refcount_stmt<'a>(
                     arena.alloc([*structure]),
                 )
                 .unwrap();
-            let call_stmt = Stmt::Let(call_result_empty, call_expr, LAYOUT_UNIT, following);
-            arena.alloc(call_stmt)
+            root.let_helper_call(
+                ident_ids,
+                call_result_empty,
+                call_expr,
+                LAYOUT_UNIT,
+                following,
+            )
         }
 
         ModifyRc::DecRef(structure) => {
@@ -345,6 +345,11 @@ impl<'a, 'r> Ctx<'a, 'r> {
             &Stmt::Dbg { remainder, .. } => {
                 self.check_stmt(remainder);
             }
+            &Stmt::Located { remainder, .. } => {
+                // not printed, so it takes up no line
+                self.line -= 1;
+                self.check_stmt(remainder);
+            }
             &Stmt::Expect {
                 condition,
                 region: _,
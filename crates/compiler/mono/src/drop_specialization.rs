@@ -629,6 +629,21 @@ fn specialize_drops_stmt<'a, 'i>(
                 remainder,
             ),
         }),
+        Stmt::Located {
+            module,
+            region,
+            remainder,
+        } => arena.alloc(Stmt::Located {
+            module: *module,
+            region: *region,
+            remainder: specialize_drops_stmt(
+                arena,
+                layout_interner,
+                ident_ids,
+                environment,
+                remainder,
+            ),
+        }),
         Stmt::Join {
             id,
             parameters,
@@ -1282,7 +1297,12 @@ where
 {
     match continutation {
         // The continuation is a single stmt. So we can insert it inline and skip creating a joinpoint.
-        Stmt::Ret(_) | Stmt::Jump(_, _) => {
+        Stmt::Ret(_)
+        | Stmt::Jump(_, _)
+        | Stmt::Located {
+            remainder: Stmt::Ret(_) | Stmt::Jump(_, _),
+            ..
+        } => {
             let u = unique(layout_interner, ident_ids, continutation);
             let n = not_unique(layout_interner, ident_ids, continutation);
 
@@ -184,7 +184,9 @@ impl<'a, 'i> SymbolRcTypesEnv<'a, 'i> {
             Stmt::Refcounting(_, _) => unreachable!(
                 "Refcounting operations should not be present in the AST at this point."
             ),
-            Stmt::Expect { remainder, .. } | Stmt::Dbg { remainder, .. } => {
+            Stmt::Expect { remainder, .. }
+            | Stmt::Dbg { remainder, .. }
+            | Stmt::Located { remainder, .. } => {
                 self.insert_symbols_rc_type_stmt(remainder);
             }
             Stmt::Join {
@@ -225,17 +227,17 @@ impl<'a, 'i> SymbolRcTypesEnv<'a, 'i> {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) enum Ownership {
+pub enum Ownership {
     Owned,
     Borrowed,
 }
 
 impl Ownership {
-    pub(crate) fn is_owned(&self) -> bool {
+    pub fn is_owned(&self) -> bool {
         matches!(self, Ownership::Owned)
     }
 
-    pub(crate) fn is_borrowed(&self) -> bool {
+    pub fn is_borrowed(&self) -> bool {
         matches!(self, Ownership::Borrowed)
     }
 }
@@ -487,15 +489,30 @@ fn insert_refcount_operations_stmt<'v, 'a>(
         Stmt::Let(_, _, _, _) => {
             // Collect all the subsequent let bindings (including the current one).
             // To prevent the stack from overflowing when there are many let bindings.
-            let mut triples = vec![];
+            // The source locations of later defs are collected along with their first binding.
+            let mut lets = vec![];
             let mut current_stmt = stmt;
-            while let Stmt::Let(binding, expr, layout, next_stmt) = current_stmt {
-                triples.push((binding, expr, layout));
-                current_stmt = next_stmt
+            let mut location = None;
+            loop {
+                match current_stmt {
+                    Stmt::Let(binding, expr, layout, next_stmt) => {
+                        lets.push((binding, expr, layout, location.take()));
+                        current_stmt = next_stmt
+                    }
+                    Stmt::Located {
+                        module,
+                        region,
+                        remainder: next_stmt @ Stmt::Let(..),
+                    } => {
+                        location = Some((*module, *region));
+                        current_stmt = next_stmt
+                    }
+                    _ => break,
+                }
             }
 
             debug_assert!(
-                !triples.is_empty(),
+                !lets.is_empty(),
                 "Expected at least one let binding in the vector"
             );
             debug_assert!(
@@ -503,17 +520,16 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                 "All let bindings should be in the vector"
             );
 
-            for (binding, _, _) in triples.iter() {
+            for (binding, _, _, _) in lets.iter() {
                 environment.add_symbol(**binding); // Add the bound symbol to the environment. As it can be used in the continuation.
             }
 
-            triples
-                .into_iter()
+            lets.into_iter()
                 .rev()
                 // First evaluate the continuation and let it consume it's free symbols.
                 .fold(
                     insert_refcount_operations_stmt(arena, environment, current_stmt),
-                    |new_stmt, (binding, expr, layout)| {
+                    |new_stmt, (binding, expr, layout, location)| {
                         // If the binding is still owned in the environment, it is not used in the continuation and we can drop it right away.
                         let new_stmt_without_unused = match environment
                             .get_symbol_ownership(binding)
@@ -525,14 +541,23 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                         // And as the symbol should not be in scope before this let binding, remove it from the environment.
                         environment.remove_symbol(*binding);
 
-                        insert_refcount_operations_binding(
+                        let new_stmt = insert_refcount_operations_binding(
                             arena,
                             environment,
                             binding,
                             expr,
                             layout,
                             new_stmt_without_unused,
-                        )
+                        );
+
+                        match location {
+                            Some((module, region)) => arena.alloc(Stmt::Located {
+                                module,
+                                region,
+                                remainder: new_stmt,
+                            }),
+                            None => new_stmt,
+                        }
                     },
                 )
         }
@@ -717,6 +742,15 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                 remainder: newer_remainder,
             })
         }
+        Stmt::Located {
+            module,
+            region,
+            remainder,
+        } => arena.alloc(Stmt::Located {
+            module: *module,
+            region: *region,
+            remainder: insert_refcount_operations_stmt(arena, environment, remainder),
+        }),
         Stmt::Join {
             id: joinpoint_id,
             parameters,
@@ -1243,7 +1277,7 @@ fn insert_dec_stmt<'a>(
 /**
  * Retrieve the borrow signature of a low-level operation.
  */
-pub(crate) fn lowlevel_borrow_signature(op: LowLevel) -> &'static [Ownership] {
+pub fn lowlevel_borrow_signature(op: LowLevel) -> &'static [Ownership] {
     use LowLevel::*;
 
     const IRRELEVANT: Ownership = Ownership::Owned;
@@ -0,0 +1,904 @@
+//! Inlining of small procs into their callers.
+//!
+//! LLVM does its own inlining, but the dev and wasm backends emit a call for every proc they are
+//! given. This pass copies the bodies of small, non-recursive procs into their call sites. It runs
+//! before refcount insertion, so the copied bodies don't contain any refcounting yet and the
+//! refcount passes see the inlined program as if it had been written that way.
+//!
+//! A call `let x = f a b` in tail position becomes the body of `f`. A call to a proc whose body is
+//! a straight line of `let`s is spliced in directly. Any other call becomes a join point:
+//!
+//! ```text
+//! joinpoint #inl x =
+//!     <rest of the caller>
+//! in
+//!     <body of f, where every `ret y` became `jump #inl y`>
+//! ```
+//!
+//! Every symbol and join point bound in a copied body is renamed, so the same proc can be inlined
+//! several times into one caller. Procs that are (mutually) recursive are never inlined, which keeps
+//! the loops made by the tail recursion pass intact.
+//!
+//! The refcount and equality helpers from `code_gen_help` only exist once a backend asks for them,
+//! long after this pass ran. `code_gen_help` inlines those with [`InlinableProcs`]: into the
+//! refcount statements it expands, and into each other once the backend takes them.
+
+use std::collections::hash_map::Entry;
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_collections::{MutMap, MutSet, ReferenceMatrix, TopologicalSort};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{
+    BranchInfo, Call, CallSpecId, CallType, Expr, HigherOrderLowLevel, JoinPointId,
+    ListLiteralElement, ModifyRc, Param, Proc, ProcLayout, SelfRecursive, Stmt,
+};
+use crate::layout::InLayout;
+use crate::low_level::HigherOrder;
+
+/// The size of the largest proc body, counted in statements, that will be copied into its callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InlineBudget(pub usize);
+
+impl InlineBudget {
+    pub const DEFAULT: Self = InlineBudget(12);
+}
+
+type ProcKey<'a> = (Symbol, ProcLayout<'a>);
+
+/// Copies small, non-recursive procs into their callers.
+pub fn inline_procs<'a>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    budget: InlineBudget,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let keys = sorted_keys(procs);
+    let mut inlinable = InlinableProcs::empty(procs);
+
+    let mut inliner = Inliner {
+        arena,
+        home,
+        ident_ids,
+        inlinable: &mut inlinable,
+    };
+
+    let done = inliner.inline_candidates(budget, &keys, procs);
+
+    for key in keys {
+        if !done.contains(&key) {
+            let proc = procs.get_mut(&key).unwrap();
+
+            proc.body = inliner.inline_calls(&proc.body).clone();
+        }
+    }
+}
+
+/// Procs that are ready to be copied into their callers, each with its own calls already inlined.
+pub struct InlinableProcs<'a> {
+    procs: MutMap<ProcKey<'a>, Proc<'a>>,
+    /// Every call in a copied body gets a fresh id, so that ids stay unique within a proc
+    next_call_spec_id: CallSpecId,
+}
+
+impl<'a> InlinableProcs<'a> {
+    /// Copies the small, non-recursive procs in `procs` into each other, and keeps the ones that
+    /// are still within the budget afterwards.
+    pub fn new(
+        arena: &'a Bump,
+        home: ModuleId,
+        ident_ids: &mut IdentIds,
+        budget: InlineBudget,
+        procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    ) -> Self {
+        let keys = sorted_keys(procs);
+        let mut inlinable = Self::empty(procs);
+
+        Inliner {
+            arena,
+            home,
+            ident_ids,
+            inlinable: &mut inlinable,
+        }
+        .inline_candidates(budget, &keys, procs);
+
+        inlinable
+    }
+
+    fn empty(procs: &MutMap<ProcKey<'a>, Proc<'a>>) -> Self {
+        InlinableProcs {
+            procs: MutMap::default(),
+            next_call_spec_id: procs
+                .values()
+                .map(|proc| max_call_spec_id(&proc.body))
+                .max()
+                .unwrap_or(CallSpecId::BACKEND_DUMMY)
+                .next(),
+        }
+    }
+
+    /// `let binding = call in cont`, with a copy of the body of the called proc if it is one of
+    /// these. `cont` is used as it is, without copying it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn inline_call(
+        &mut self,
+        arena: &'a Bump,
+        home: ModuleId,
+        ident_ids: &mut IdentIds,
+        binding: Symbol,
+        call: &Call<'a>,
+        layout: InLayout<'a>,
+        cont: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
+        Inliner {
+            arena,
+            home,
+            ident_ids,
+            inlinable: self,
+        }
+        .inline_or_call(binding, call, layout, cont)
+    }
+}
+
+// sort for deterministic symbol names, see the #7531 workaround in tail_recursion
+fn sorted_keys<'a>(procs: &MutMap<ProcKey<'a>, Proc<'a>>) -> std::vec::Vec<ProcKey<'a>> {
+    let mut keys: std::vec::Vec<ProcKey<'a>> = procs.keys().copied().collect();
+    keys.sort_by_key(|(symbol, _)| *symbol);
+
+    keys
+}
+
+fn is_inline_candidate(proc: &Proc, budget: InlineBudget) -> bool {
+    matches!(proc.is_self_recursive, SelfRecursive::NotSelfRecursive)
+        && !proc.is_erased
+        && !has_debug_stmts(&proc.body)
+        && stmt_size(&proc.body) <= budget.0
+}
+
+/// The number of statements in a proc body, which is what the budget is measured in.
+fn stmt_size(stmt: &Stmt) -> usize {
+    match stmt {
+        Stmt::Let(_, _, _, cont) => 1 + stmt_size(cont),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            let branches: usize = branches
+                .iter()
+                .map(|(_, _, branch)| stmt_size(branch))
+                .sum();
+
+            1 + branches + stmt_size(default_branch.1)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => 1 + stmt_size(body) + stmt_size(remainder),
+        Stmt::Refcounting(_, cont) => 1 + stmt_size(cont),
+        Stmt::Located { remainder, .. } => stmt_size(remainder),
+        Stmt::Expect { remainder, .. } | Stmt::Dbg { remainder, .. } => 1 + stmt_size(remainder),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => 1,
+    }
+}
+
+/// `expect` and `dbg` refer to type variables of the module they were written in, so procs that
+/// use them stay where they are.
+fn has_debug_stmts(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Let(_, _, _, cont) | Stmt::Refcounting(_, cont) => has_debug_stmts(cont),
+        Stmt::Located { remainder, .. } => has_debug_stmts(remainder),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            branches
+                .iter()
+                .any(|(_, _, branch)| has_debug_stmts(branch))
+                || has_debug_stmts(default_branch.1)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => has_debug_stmts(body) || has_debug_stmts(remainder),
+        Stmt::Expect { .. } | Stmt::Dbg { .. } => true,
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => false,
+    }
+}
+
+fn call_by_name_key<'a>(call_type: &CallType<'a>) -> Option<ProcKey<'a>> {
+    match call_type {
+        CallType::ByName {
+            name,
+            ret_layout,
+            arg_layouts,
+            ..
+        } => Some((
+            name.name(),
+            ProcLayout {
+                arguments: arg_layouts,
+                result: *ret_layout,
+                niche: name.niche(),
+            },
+        )),
+        _ => None,
+    }
+}
+
+fn collect_callees<'a>(stmt: &Stmt<'a>, callees: &mut std::vec::Vec<ProcKey<'a>>) {
+    match stmt {
+        Stmt::Let(_, expr, _, cont) => {
+            if let Expr::Call(call) = expr {
+                callees.extend(call_by_name_key(&call.call_type));
+            }
+
+            collect_callees(cont, callees)
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            for (_, _, branch) in branches.iter() {
+                collect_callees(branch, callees);
+            }
+
+            collect_callees(default_branch.1, callees)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => {
+            collect_callees(body, callees);
+            collect_callees(remainder, callees)
+        }
+        Stmt::Refcounting(_, cont) => collect_callees(cont, callees),
+        Stmt::Expect { remainder, .. }
+        | Stmt::Dbg { remainder, .. }
+        | Stmt::Located { remainder, .. } => collect_callees(remainder, callees),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+    }
+}
+
+fn max_call_spec_id(stmt: &Stmt) -> CallSpecId {
+    match stmt {
+        Stmt::Let(_, expr, _, cont) => {
+            let here = match expr {
+                Expr::Call(Call {
+                    call_type:
+                        CallType::ByName {
+                            specialization_id, ..
+                        },
+                    ..
+                }) => *specialization_id,
+                Expr::Call(Call {
+                    call_type: CallType::HigherOrder(higher_order),
+                    ..
+                }) => higher_order.passed_function.specialization_id,
+                _ => CallSpecId::BACKEND_DUMMY,
+            };
+
+            here.max(max_call_spec_id(cont))
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => branches
+            .iter()
+            .map(|(_, _, branch)| max_call_spec_id(branch))
+            .fold(max_call_spec_id(default_branch.1), Ord::max),
+        Stmt::Join {
+            body, remainder, ..
+        } => max_call_spec_id(body).max(max_call_spec_id(remainder)),
+        Stmt::Refcounting(_, cont) => max_call_spec_id(cont),
+        Stmt::Expect { remainder, .. }
+        | Stmt::Dbg { remainder, .. }
+        | Stmt::Located { remainder, .. } => max_call_spec_id(remainder),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => CallSpecId::BACKEND_DUMMY,
+    }
+}
+
+/// If the body is a sequence of `let`s ending in a `ret`, the returned symbol.
+fn straight_line_result(mut stmt: &Stmt) -> Option<Symbol> {
+    loop {
+        match stmt {
+            Stmt::Let(_, _, _, cont) => stmt = cont,
+            Stmt::Located { remainder, .. } => stmt = remainder,
+            Stmt::Ret(symbol) => return Some(*symbol),
+            _ => return None,
+        }
+    }
+}
+
+struct Inliner<'a, 'i> {
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    inlinable: &'i mut InlinableProcs<'a>,
+}
+
+/// What a `ret` in a copied body turns into.
+#[derive(Clone, Copy)]
+enum Return<'a> {
+    /// The call was in tail position, so the copied body returns from the caller.
+    Ret,
+    /// Jump to the join point that holds the rest of the caller.
+    Jump(JoinPointId),
+    /// The body is a straight line, so the rest of the caller comes right after it.
+    Continue(&'a Stmt<'a>),
+}
+
+struct CopyEnv<'a> {
+    symbols: MutMap<Symbol, Symbol>,
+    join_points: MutMap<JoinPointId, JoinPointId>,
+    ret: Return<'a>,
+}
+
+impl<'a, 'i> Inliner<'a, 'i> {
+    fn unique_symbol(&mut self) -> Symbol {
+        let ident_id = self.ident_ids.gen_unique();
+
+        Symbol::new(self.home, ident_id)
+    }
+
+    fn fresh_call_spec_id(&mut self) -> CallSpecId {
+        let id = self.inlinable.next_call_spec_id;
+        self.inlinable.next_call_spec_id = id.next();
+
+        id
+    }
+
+    /// Expands the candidates among `keys` in place, and makes the ones that are still within the
+    /// budget afterwards inlinable. Returns the candidates, which have no calls left to inline.
+    fn inline_candidates(
+        &mut self,
+        budget: InlineBudget,
+        keys: &[ProcKey<'a>],
+        procs: &mut MutMap<ProcKey<'a>, Proc<'a>>,
+    ) -> MutSet<ProcKey<'a>> {
+        let candidates: std::vec::Vec<ProcKey<'a>> = keys
+            .iter()
+            .filter(|key| is_inline_candidate(&procs[*key], budget))
+            .copied()
+            .collect();
+
+        let candidate_indices: MutMap<ProcKey<'a>, usize> = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+
+        let mut matrix = ReferenceMatrix::new(candidates.len());
+        let mut callees = std::vec::Vec::new();
+
+        for (row, key) in candidates.iter().enumerate() {
+            callees.clear();
+            collect_callees(&procs[key].body, &mut callees);
+
+            for callee in callees.iter() {
+                if let Some(col) = candidate_indices.get(callee) {
+                    matrix.set_row_col(row, *col, true);
+                }
+            }
+        }
+
+        // Callers come before their callees. Candidates that are part of a cycle, or that are only
+        // reachable through one, are left out of the groups and are never inlined.
+        let groups = match matrix.topological_sort_into_groups() {
+            TopologicalSort::Groups { groups } => groups,
+            TopologicalSort::HasCycles { groups, .. } => groups,
+        };
+
+        let mut done = MutSet::default();
+
+        // Expand the callees first, so that a candidate is copied with its own calls already inlined.
+        for group in groups.iter().rev() {
+            for index in group {
+                let key = candidates[*index as usize];
+                let proc = procs.get_mut(&key).unwrap();
+
+                proc.body = self.inline_calls(&proc.body).clone();
+
+                // inlining into a candidate can push it over the budget
+                if stmt_size(&proc.body) <= budget.0 {
+                    self.inlinable.procs.insert(key, proc.clone());
+                }
+
+                done.insert(key);
+            }
+        }
+
+        done
+    }
+
+    fn inline_calls(&mut self, stmt: &Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.arena;
+
+        match stmt {
+            Stmt::Let(binding, Expr::Call(call), layout, cont) => {
+                let cont = self.inline_calls(cont);
+
+                self.inline_or_call(*binding, call, *layout, cont)
+            }
+            Stmt::Let(binding, expr, layout, cont) => {
+                let cont = self.inline_calls(cont);
+
+                arena.alloc(Stmt::Let(*binding, expr.clone(), *layout, cont))
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches.iter().map(|(label, info, branch)| {
+                        (*label, info.clone(), self.inline_calls(branch).clone())
+                    }),
+                    arena,
+                );
+
+                let default_branch = (
+                    default_branch.0.clone(),
+                    self.inline_calls(default_branch.1),
+                );
+
+                arena.alloc(Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch,
+                    ret_layout: *ret_layout,
+                })
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let body = self.inline_calls(body);
+                let remainder = self.inline_calls(remainder);
+
+                arena.alloc(Stmt::Join {
+                    id: *id,
+                    parameters,
+                    body,
+                    remainder,
+                })
+            }
+            Stmt::Refcounting(modify, cont) => {
+                let cont = self.inline_calls(cont);
+
+                arena.alloc(Stmt::Refcounting(*modify, cont))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => {
+                let remainder = self.inline_calls(remainder);
+
+                arena.alloc(Stmt::Expect {
+                    condition: *condition,
+                    region: *region,
+                    lookups,
+                    variables,
+                    remainder,
+                })
+            }
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => {
+                let remainder = self.inline_calls(remainder);
+
+                arena.alloc(Stmt::Dbg {
+                    source_location,
+                    source,
+                    symbol: *symbol,
+                    variable: *variable,
+                    remainder,
+                })
+            }
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => {
+                let remainder = self.inline_calls(remainder);
+
+                arena.alloc(Stmt::Located {
+                    module: *module,
+                    region: *region,
+                    remainder,
+                })
+            }
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => arena.alloc(stmt.clone()),
+        }
+    }
+
+    fn inline_or_call(
+        &mut self,
+        binding: Symbol,
+        call: &Call<'a>,
+        layout: InLayout<'a>,
+        cont: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
+        match call_by_name_key(&call.call_type) {
+            Some(key) if self.inlinable.procs.contains_key(&key) => {
+                self.inline_call(binding, layout, call.arguments, key, cont)
+            }
+            _ => self
+                .arena
+                .alloc(Stmt::Let(binding, Expr::Call(call.clone()), layout, cont)),
+        }
+    }
+
+    /// Replace `let binding = callee arguments in cont` with a copy of the callee's body.
+    fn inline_call(
+        &mut self,
+        binding: Symbol,
+        layout: InLayout<'a>,
+        arguments: &[Symbol],
+        callee: ProcKey<'a>,
+        cont: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
+        let arena = self.arena;
+        let callee = self.inlinable.procs[&callee].clone();
+
+        let mut env = CopyEnv {
+            symbols: callee
+                .args
+                .iter()
+                .map(|(_, param)| *param)
+                .zip(arguments.iter().copied())
+                .collect(),
+            join_points: MutMap::default(),
+            ret: Return::Ret,
+        };
+
+        if let Stmt::Ret(returned) = cont {
+            if *returned == binding {
+                return self.copy_stmt(&mut env, &callee.body);
+            }
+        }
+
+        if let Some(result) = straight_line_result(&callee.body) {
+            // a proc that returns one of its arguments still needs the join point
+            if let Entry::Vacant(entry) = env.symbols.entry(result) {
+                entry.insert(binding);
+                env.ret = Return::Continue(cont);
+
+                return self.copy_stmt(&mut env, &callee.body);
+            }
+        }
+
+        let id = JoinPointId(self.unique_symbol());
+        env.ret = Return::Jump(id);
+
+        let remainder = self.copy_stmt(&mut env, &callee.body);
+
+        arena.alloc(Stmt::Join {
+            id,
+            parameters: arena.alloc([Param {
+                symbol: binding,
+                layout,
+            }]),
+            body: cont,
+            remainder,
+        })
+    }
+
+    fn bind(&mut self, env: &mut CopyEnv<'a>, symbol: Symbol) -> Symbol {
+        // the only symbols that are mapped before they are bound are the result of a straight line
+        match env.symbols.get(&symbol) {
+            Some(renamed) => *renamed,
+            None => {
+                let renamed = self.unique_symbol();
+                env.symbols.insert(symbol, renamed);
+
+                renamed
+            }
+        }
+    }
+
+    fn copy_stmt(&mut self, env: &mut CopyEnv<'a>, stmt: &Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.arena;
+
+        match stmt {
+            Stmt::Let(binding, expr, layout, cont) => {
+                let expr = self.copy_expr(env, expr);
+                let binding = self.bind(env, *binding);
+                let cont = self.copy_stmt(env, cont);
+
+                arena.alloc(Stmt::Let(binding, expr, *layout, cont))
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches.iter().map(|(label, info, branch)| {
+                        let info = copy_branch_info(env, info);
+
+                        (*label, info, self.copy_stmt(env, branch).clone())
+                    }),
+                    arena,
+                );
+
+                let default_branch = (
+                    copy_branch_info(env, &default_branch.0),
+                    self.copy_stmt(env, default_branch.1),
+                );
+
+                arena.alloc(Stmt::Switch {
+                    cond_symbol: env.symbol(*cond_symbol),
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch,
+                    ret_layout: *ret_layout,
+                })
+            }
+            Stmt::Ret(symbol) => match env.ret {
+                Return::Ret => arena.alloc(Stmt::Ret(env.symbol(*symbol))),
+                Return::Jump(id) => arena.alloc(Stmt::Jump(id, arena.alloc([env.symbol(*symbol)]))),
+                Return::Continue(cont) => cont,
+            },
+            Stmt::Refcounting(modify, cont) => {
+                let modify = match *modify {
+                    ModifyRc::Inc(symbol, amount) => ModifyRc::Inc(env.symbol(symbol), amount),
+                    ModifyRc::Dec(symbol) => ModifyRc::Dec(env.symbol(symbol)),
+                    ModifyRc::DecRef(symbol) => ModifyRc::DecRef(env.symbol(symbol)),
+                    ModifyRc::Free(symbol) => ModifyRc::Free(env.symbol(symbol)),
+                };
+                let cont = self.copy_stmt(env, cont);
+
+                arena.alloc(Stmt::Refcounting(modify, cont))
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let new_id = JoinPointId(self.unique_symbol());
+                env.join_points.insert(*id, new_id);
+
+                let parameters = Vec::from_iter_in(
+                    parameters.iter().map(|param| Param {
+                        symbol: self.bind(env, param.symbol),
+                        layout: param.layout,
+                    }),
+                    arena,
+                );
+
+                let body = self.copy_stmt(env, body);
+                let remainder = self.copy_stmt(env, remainder);
+
+                arena.alloc(Stmt::Join {
+                    id: new_id,
+                    parameters: parameters.into_bump_slice(),
+                    body,
+                    remainder,
+                })
+            }
+            Stmt::Jump(id, arguments) => {
+                let arguments =
+                    Vec::from_iter_in(arguments.iter().map(|symbol| env.symbol(*symbol)), arena);
+
+                arena.alloc(Stmt::Jump(env.join_points[id], arguments.into_bump_slice()))
+            }
+            Stmt::Crash(message, tag) => arena.alloc(Stmt::Crash(env.symbol(*message), *tag)),
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => {
+                let remainder = self.copy_stmt(env, remainder);
+
+                arena.alloc(Stmt::Located {
+                    module: *module,
+                    region: *region,
+                    remainder,
+                })
+            }
+            Stmt::Expect { .. } | Stmt::Dbg { .. } => {
+                unreachable!("procs with expect or dbg are not inlined")
+            }
+        }
+    }
+
+    fn copy_expr(&mut self, env: &CopyEnv<'a>, expr: &Expr<'a>) -> Expr<'a> {
+        let arena = self.arena;
+        let symbols = |symbols: &[Symbol]| -> &'a [Symbol] {
+            arena.alloc_slice_fill_iter(symbols.iter().map(|symbol| env.symbol(*symbol)))
+        };
+
+        match expr {
+            Expr::Call(Call {
+                call_type,
+                arguments,
+            }) => {
+                let call_type = match call_type {
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        specialization_id: _,
+                    } => CallType::ByName {
+                        name: *name,
+                        ret_layout: *ret_layout,
+                        arg_layouts,
+                        specialization_id: self.fresh_call_spec_id(),
+                    },
+                    CallType::ByPointer {
+                        pointer,
+                        ret_layout,
+                        arg_layouts,
+                    } => CallType::ByPointer {
+                        pointer: env.symbol(*pointer),
+                        ret_layout: *ret_layout,
+                        arg_layouts,
+                    },
+                    CallType::HigherOrder(higher_order) => {
+                        let mut passed_function = higher_order.passed_function;
+                        passed_function.captured_environment =
+                            env.symbol(passed_function.captured_environment);
+                        passed_function.specialization_id = self.fresh_call_spec_id();
+
+                        let op = match higher_order.op {
+                            HigherOrder::ListSortWith { xs } => {
+                                HigherOrder::ListSortWith { xs: env.symbol(xs) }
+                            }
+                        };
+
+                        CallType::HigherOrder(arena.alloc(HigherOrderLowLevel {
+                            op,
+                            passed_function,
+                            ..(*higher_order).clone()
+                        }))
+                    }
+                    CallType::Foreign { .. } | CallType::LowLevel { .. } => call_type.clone(),
+                };
+
+                Expr::Call(Call {
+                    call_type,
+                    arguments: symbols(arguments),
+                })
+            }
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse,
+            } => Expr::Tag {
+                tag_layout: *tag_layout,
+                tag_id: *tag_id,
+                arguments: symbols(arguments),
+                reuse: reuse.map(|mut token| {
+                    token.symbol = env.symbol(token.symbol);
+                    token
+                }),
+            },
+            Expr::Struct(fields) => Expr::Struct(symbols(fields)),
+            Expr::StructAtIndex {
+                index,
+                field_layouts,
+                structure,
+            } => Expr::StructAtIndex {
+                index: *index,
+                field_layouts,
+                structure: env.symbol(*structure),
+            },
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => Expr::GetTagId {
+                structure: env.symbol(*structure),
+                union_layout: *union_layout,
+            },
+            Expr::UnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+            } => Expr::UnionAtIndex {
+                structure: env.symbol(*structure),
+                tag_id: *tag_id,
+                union_layout: *union_layout,
+                index: *index,
+            },
+            Expr::GetElementPointer {
+                structure,
+                union_layout,
+                indices,
+            } => Expr::GetElementPointer {
+                structure: env.symbol(*structure),
+                union_layout: *union_layout,
+                indices,
+            },
+            Expr::Array { elem_layout, elems } => Expr::Array {
+                elem_layout: *elem_layout,
+                elems: arena.alloc_slice_fill_iter(elems.iter().map(|elem| match elem {
+                    ListLiteralElement::Symbol(symbol) => {
+                        ListLiteralElement::Symbol(env.symbol(*symbol))
+                    }
+                    ListLiteralElement::Literal(_) => *elem,
+                })),
+            },
+            Expr::ErasedMake { value, callee } => Expr::ErasedMake {
+                value: value.map(|value| env.symbol(value)),
+                callee: env.symbol(*callee),
+            },
+            Expr::ErasedLoad { symbol, field } => Expr::ErasedLoad {
+                symbol: env.symbol(*symbol),
+                field: *field,
+            },
+            Expr::Alloca {
+                element_layout,
+                initializer,
+            } => Expr::Alloca {
+                element_layout: *element_layout,
+                initializer: initializer.map(|initializer| env.symbol(initializer)),
+            },
+            Expr::Reset {
+                symbol,
+                update_mode,
+            } => Expr::Reset {
+                symbol: env.symbol(*symbol),
+                update_mode: *update_mode,
+            },
+            Expr::ResetRef {
+                symbol,
+                update_mode,
+            } => Expr::ResetRef {
+                symbol: env.symbol(*symbol),
+                update_mode: *update_mode,
+            },
+            Expr::Literal(_)
+            | Expr::NullPointer
+            | Expr::EmptyArray
+            | Expr::FunctionPointer { .. } => expr.clone(),
+        }
+    }
+}
+
+impl<'a> CopyEnv<'a> {
+    fn symbol(&self, symbol: Symbol) -> Symbol {
+        self.symbols.get(&symbol).copied().unwrap_or(symbol)
+    }
+}
+
+fn copy_branch_info<'a>(env: &CopyEnv<'a>, info: &BranchInfo<'a>) -> BranchInfo<'a> {
+    match info {
+        BranchInfo::None => BranchInfo::None,
+        BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        } => BranchInfo::Constructor {
+            scrutinee: env.symbol(*scrutinee),
+            layout: *layout,
+            tag_id: *tag_id,
+        },
+        BranchInfo::List { scrutinee, len } => BranchInfo::List {
+            scrutinee: env.symbol(*scrutinee),
+            len: *len,
+        },
+        BranchInfo::Unique { scrutinee, unique } => BranchInfo::Unique {
+            scrutinee: env.symbol(*scrutinee),
+            unique: *unique,
+        },
+    }
+}
@@ -18,8 +18,9 @@ use roc_collections::VecMap;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
-    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_RUNTIME_ERROR_GEN,
+    ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_INLINING,
+    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SIMPLIFY,
+    ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_RUNTIME_ERROR_GEN,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::{internal_error, todo_abilities, todo_lambda_erasure};
@@ -54,6 +55,12 @@ pub fn pretty_print_ir_symbols() -> bool {
     dbg_do!(ROC_PRINT_IR_AFTER_SPECIALIZATION, {
         return true;
     });
+    dbg_do!(ROC_PRINT_IR_AFTER_INLINING, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_SIMPLIFY, {
+        return true;
+    });
     dbg_do!(ROC_PRINT_IR_AFTER_RESET_REUSE, {
         return true;
     });
@@ -211,6 +218,8 @@ pub struct PartialProc<'a> {
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
     pub is_self_recursive: bool,
+    /// Where the body of this function was defined, for debug info
+    pub region: Region,
 }
 
 impl<'a> PartialProc<'a> {
@@ -225,6 +234,7 @@ impl<'a> PartialProc<'a> {
         ret_var: Variable,
     ) -> PartialProc<'a> {
         let number_of_arguments = loc_args.len();
+        let region = loc_body.region;
 
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
@@ -240,6 +250,7 @@ impl<'a> PartialProc<'a> {
                     body: body.value,
                     body_var: ret_var,
                     is_self_recursive,
+                    region,
                 }
             }
 
@@ -257,6 +268,7 @@ impl<'a> PartialProc<'a> {
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
                     is_self_recursive: false,
+                    region,
                 }
             }
         }
@@ -309,6 +321,8 @@ pub struct Proc<'a> {
     pub ret_layout: InLayout<'a>,
     pub is_self_recursive: SelfRecursive,
     pub is_erased: bool,
+    /// Where this proc was defined in its module's source; [Region::zero] for generated procs
+    pub region: Region,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -1058,6 +1072,8 @@ impl<'a> Procs<'a> {
             _ => false,
         };
 
+        let region = loc_body.region;
+
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
                 // an anonymous closure. These will always be specialized already
@@ -1110,6 +1126,7 @@ impl<'a> Procs<'a> {
                                         body: body.value,
                                         body_var: ret_var,
                                         is_self_recursive,
+                                        region,
                                     };
 
                                     self.partial_procs.insert(name.name(), partial_proc);
@@ -1138,6 +1155,7 @@ impl<'a> Procs<'a> {
                                     body: body.value,
                                     body_var: ret_var,
                                     is_self_recursive,
+                                    region,
                                 };
 
                                 self.partial_procs.insert(name.name(), partial_proc)
@@ -1543,6 +1561,13 @@ pub enum Stmt<'a> {
         /// What happens after the dbg
         remainder: &'a Stmt<'a>,
     },
+    /// The code for `remainder` comes from `region` in the source of `module`, up to the next
+    /// `Located` in it. Has no effect at runtime; backends use it for debug info.
+    Located {
+        module: ModuleId,
+        region: Region,
+        remainder: &'a Stmt<'a>,
+    },
     /// a join point `join f <params> = <continuation> in remainder`
     Join {
         id: JoinPointId,
@@ -1728,7 +1753,7 @@ impl<'a> Call<'a> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CallSpecId {
     id: u32,
 }
@@ -1738,6 +1763,10 @@ impl CallSpecId {
         self.id.to_ne_bytes()
     }
 
+    pub(crate) fn next(self) -> Self {
+        Self { id: self.id + 1 }
+    }
+
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* specialization so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
@@ -2267,6 +2296,9 @@ impl<'a> Stmt<'a> {
                 .append(alloc.hardline())
                 .append(remainder.to_doc(alloc, interner, pretty)),
 
+            // Where code came from only matters for debug info
+            Located { remainder, .. } => remainder.to_doc(alloc, interner, pretty),
+
             Ret(symbol) => alloc
                 .text("ret ")
                 .append(symbol_to_doc(alloc, *symbol, pretty))
@@ -2444,6 +2476,13 @@ fn from_can_let<'a>(
         };
     }
 
+    let region = def.loc_pattern.region;
+    // Nested defs mark their own code
+    let cont_region = match cont.value {
+        LetNonRec(..) | LetRec(..) => Region::zero(),
+        _ => cont.region,
+    };
+
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
         return match def.loc_expr.value {
             Closure(closure_data) => {
@@ -2685,7 +2724,8 @@ fn from_can_let<'a>(
                 };
                 procs.symbol_specializations.mark_eligible(*symbol);
 
-                let mut stmt = lower_rest!(variable, cont.value);
+                let rest = lower_rest!(variable, cont.value);
+                let mut stmt = located(env, cont_region, rest);
 
                 let needed_specializations = procs.symbol_specializations.remove(*symbol).unwrap();
                 let zero_specialization = if needed_specializations.is_empty() {
@@ -2712,12 +2752,13 @@ fn from_can_let<'a>(
                     );
                 }
 
-                stmt
+                located(env, region, stmt)
             }
             _ => {
                 let rest = lower_rest!(variable, cont.value);
+                let rest = located(env, cont_region, rest);
 
-                with_hole(
+                let stmt = with_hole(
                     env,
                     def.loc_expr.value,
                     def.expr_var,
@@ -2725,7 +2766,9 @@ fn from_can_let<'a>(
                     layout_cache,
                     *symbol,
                     env.arena.alloc(rest),
-                )
+                );
+
+                located(env, region, stmt)
             }
         };
     }
@@ -2749,7 +2792,8 @@ fn from_can_let<'a>(
         };
 
     // convert the continuation
-    let mut stmt = lower_rest!(variable, cont.value);
+    let rest = lower_rest!(variable, cont.value);
+    let mut stmt = located(env, cont_region, rest);
 
     // layer on any default record fields
     for (symbol, variable, expr) in assignments {
@@ -2757,7 +2801,7 @@ fn from_can_let<'a>(
         stmt = with_hole(env, expr, variable, procs, layout_cache, symbol, hole);
     }
 
-    match def.loc_expr.value {
+    let stmt = match def.loc_expr.value {
         roc_can::expr::Expr::Var(outer_symbol, _) if !procs.is_module_thunk(outer_symbol) => {
             store_pattern(env, procs, layout_cache, &mono_pattern, outer_symbol, stmt)
         }
@@ -2776,6 +2820,23 @@ fn from_can_let<'a>(
                 env.arena.alloc(stmt),
             )
         }
+    };
+
+    located(env, region, stmt)
+}
+
+/// Marks the code of `stmt` as coming from `region` of the module being specialized, for debug
+/// info. Generated code has no region, and is left as it is. So is a bare `ret` or `jump`, which
+/// emits no code of its own and would otherwise hide a tail call from the later passes.
+fn located<'a>(env: &Env<'a, '_>, region: Region, stmt: Stmt<'a>) -> Stmt<'a> {
+    if region.is_empty() || matches!(stmt, Stmt::Ret(_) | Stmt::Jump(..)) {
+        return stmt;
+    }
+
+    Stmt::Located {
+        module: env.home,
+        region,
+        remainder: env.arena.alloc(stmt),
     }
 }
 
@@ -3333,6 +3394,7 @@ fn generate_runtime_error_function<'a>(
         ret_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         is_erased,
+        region: Region::zero(),
     }
 }
 
@@ -3428,6 +3490,7 @@ fn generate_host_exposed_function<'a>(
                 ret_layout: result,
                 is_self_recursive: SelfRecursive::NotSelfRecursive,
                 is_erased: false,
+                region: Region::zero(),
             };
 
             let top_level = ProcLayout::from_raw_named(env.arena, lambda_name, layout);
@@ -3492,6 +3555,7 @@ fn generate_host_exposed_lambda_set<'a>(
         ret_layout: return_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         is_erased: false,
+        region: Region::zero(),
     };
 
     let top_level = ProcLayout::new(
@@ -3553,6 +3617,7 @@ fn specialize_proc_help<'a>(
 
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
+    let region = partial_proc.region;
 
     let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
 
@@ -3586,6 +3651,7 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 is_erased,
+                region,
             }
         }
         SpecializedLayout::FunctionBody {
@@ -3801,6 +3867,7 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 is_erased,
+                region,
             }
         }
     };
@@ -4671,7 +4738,7 @@ pub fn with_hole<'a>(
                     if is_terminated {
                         let terminator = hole;
 
-                        let mut stmt = with_hole(
+                        let else_stmt = with_hole(
                             env,
                             final_else.value,
                             branch_var,
@@ -4680,6 +4747,7 @@ pub fn with_hole<'a>(
                             assigned,
                             terminator,
                         );
+                        let mut stmt = located(env, final_else.region, else_stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = env.unique_symbol();
@@ -4693,6 +4761,7 @@ pub fn with_hole<'a>(
                                 assigned,
                                 terminator,
                             );
+                            let then = located(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -4716,7 +4785,7 @@ pub fn with_hole<'a>(
                             .arena
                             .alloc(Stmt::Jump(id, env.arena.alloc([assigned_in_jump])));
 
-                        let mut stmt = with_hole(
+                        let else_stmt = with_hole(
                             env,
                             final_else.value,
                             branch_var,
@@ -4725,6 +4794,7 @@ pub fn with_hole<'a>(
                             assigned_in_jump,
                             terminator,
                         );
+                        let mut stmt = located(env, final_else.region, else_stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -4744,6 +4814,7 @@ pub fn with_hole<'a>(
                                 assigned_in_jump,
                                 terminator,
                             );
+                            let then = located(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -7076,7 +7147,8 @@ pub fn from_can<'a>(
                 "invalid condition type in if expression"
             );
 
-            let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            let else_stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            let mut stmt = located(env, final_else.region, else_stmt);
 
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -7087,6 +7159,7 @@ pub fn from_can<'a>(
                     cond_var,
                 );
                 let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
+                let then = located(env, loc_then.region, then);
 
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -7255,7 +7328,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -7301,14 +7374,14 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at_zero(roc_can::expr::Expr::RuntimeError(runtime_error)),
                     ));
                 }
             }
@@ -7321,7 +7394,9 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
@@ -7362,7 +7437,7 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .filter_map(|(pattern, opt_guard, can_expr)| {
+        .filter_map(|(pattern, opt_guard, loc_expr)| {
             // If the pattern has a void layout we can drop it; however, we must still perform the
             // work of building the body, because that may contain specializations we must
             // discover for use elsewhere. See
@@ -7380,20 +7455,29 @@ fn from_can_when<'a>(
             };
 
             let branch_stmt = match join_point {
-                None => from_can(env, expr_var, can_expr, procs, layout_cache),
+                None => from_can(env, expr_var, loc_expr.value, procs, layout_cache),
                 Some(id) => {
                     let symbol = env.unique_symbol();
                     let arguments = bumpalo::vec![in env.arena; symbol].into_bump_slice();
                     let jump = env.arena.alloc(Stmt::Jump(id, arguments));
 
-                    with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
+                    with_hole(
+                        env,
+                        loc_expr.value,
+                        expr_var,
+                        procs,
+                        layout_cache,
+                        symbol,
+                        jump,
+                    )
                 }
             };
+            let branch_stmt = located(env, loc_expr.region, branch_stmt);
 
             use decision_tree::Guard;
-            let result = if let Some(loc_expr) = opt_guard {
+            let result = if let Some(loc_guard) = opt_guard {
                 let guard_spec = GuardStmtSpec {
-                    guard_expr: loc_expr.value,
+                    guard_expr: loc_guard.value,
                     identity: env.next_call_specialization_id(),
                 };
 
@@ -7662,6 +7746,19 @@ fn substitute_in_stmt_help<'a>(
             }
         }
 
+        Located {
+            module,
+            region,
+            remainder,
+        } => match substitute_in_stmt_help(arena, remainder, subs) {
+            Some(remainder) => Some(arena.alloc(Located {
+                module: *module,
+                region: *region,
+                remainder,
+            })),
+            None => None,
+        },
+
         Dbg {
             source_location,
             source,
@@ -10375,6 +10472,7 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         answer.push(GlueProc {
@@ -10470,6 +10568,7 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         answer.push(GlueProc {
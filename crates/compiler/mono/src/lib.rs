@@ -13,10 +13,12 @@ pub mod borrow;
 pub mod code_gen_help;
 pub mod drop_specialization;
 pub mod inc_dec;
+pub mod inline;
 pub mod ir;
 pub mod layout;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod simplify;
 pub mod tail_recursion;
 
 pub mod debug;
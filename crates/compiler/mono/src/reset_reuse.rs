@@ -109,15 +109,30 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
         Stmt::Let(_, _, _, _) => {
             // Collect all the subsequent let bindings (including the current one).
             // To prevent the stack from overflowing when there are many let bindings.
-            let mut triples = vec![];
+            // The source locations of later defs are collected along with their first binding.
+            let mut lets = vec![];
             let mut current_stmt = stmt;
-            while let Stmt::Let(binding, expr, layout, next_stmt) = current_stmt {
-                triples.push((binding, expr, layout));
-                current_stmt = next_stmt
+            let mut location = None;
+            loop {
+                match current_stmt {
+                    Stmt::Let(binding, expr, layout, next_stmt) => {
+                        lets.push((binding, expr, layout, location.take()));
+                        current_stmt = next_stmt
+                    }
+                    Stmt::Located {
+                        module,
+                        region,
+                        remainder: next_stmt @ Stmt::Let(..),
+                    } => {
+                        location = Some((*module, *region));
+                        current_stmt = next_stmt
+                    }
+                    _ => break,
+                }
             }
 
             debug_assert!(
-                !triples.is_empty(),
+                !lets.is_empty(),
                 "Expected at least one let binding in the vector"
             );
             debug_assert!(
@@ -127,7 +142,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
 
             // Update the triplets with reuse operations. Making sure to update the environment before the next let binding.
             let mut new_triplets = vec![];
-            for (binding, expr, layout) in triples {
+            for (binding, expr, layout, location) in lets {
                 let new_expr = match expr {
                     Expr::Tag {
                         tag_layout,
@@ -207,7 +222,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 };
 
                 environment.add_symbol_layout(*binding, layout);
-                new_triplets.push((binding, new_expr, layout))
+                new_triplets.push((binding, new_expr, layout, location))
             }
 
             let new_continuation = insert_reset_reuse_operations_stmt(
@@ -222,14 +237,23 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
 
             new_triplets.into_iter().rev().fold(
                 new_continuation,
-                |new_continuation, (binding, (opt_ptr_cast, new_expr), layout)| {
+                |new_continuation, (binding, (opt_ptr_cast, new_expr), layout, location)| {
                     let new_let =
                         arena.alloc(Stmt::Let(*binding, new_expr, *layout, new_continuation));
 
                     // if the layout for the reuse does not match that of the reset, use PtrCast to convert the layout.
-                    match opt_ptr_cast {
+                    let new_stmt = match opt_ptr_cast {
                         Some(ptr_cast) => ptr_cast(new_let),
                         None => new_let,
+                    };
+
+                    match location {
+                        Some((module, region)) => arena.alloc(Stmt::Located {
+                            module,
+                            region,
+                            remainder: new_stmt,
+                        }),
+                        None => new_stmt,
                     }
                 },
             )
@@ -650,6 +674,27 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 remainder: new_remainder,
             })
         }
+        Stmt::Located {
+            module,
+            region,
+            remainder,
+        } => {
+            let new_remainder = insert_reset_reuse_operations_stmt(
+                arena,
+                layout_interner,
+                home,
+                ident_ids,
+                update_mode_ids,
+                environment,
+                remainder,
+            );
+
+            arena.alloc(Stmt::Located {
+                module: *module,
+                region: *region,
+                remainder: new_remainder,
+            })
+        }
         Stmt::Join {
             id: joinpoint_id,
             parameters,
@@ -0,0 +1,785 @@
+//! Constant folding and dead code removal on the mono IR.
+//!
+//! Specialization leaves behind plenty of work that can be done at compile time: arithmetic on
+//! literals, `Switch`es on a condition that is a literal or the tag id of a tag we just built, and
+//! `Let`s whose value nothing uses. LLVM cleans all of this up by itself, but the dev and wasm
+//! backends emit code for every statement they are given, so this pass does it for them.
+//!
+//! The pass runs before refcount insertion and works on one proc at a time:
+//!
+//! 1. Lowlevel operations on literals become literals, and a `Switch` on a known value becomes
+//!    the branch it would take. Integer arithmetic that would crash at runtime (overflow, division
+//!    by zero) is left alone, so the program still crashes where it would have.
+//! 2. Join points that nothing jumps to any more are removed, and so are `Let`s of pure
+//!    expressions that are not used.
+
+use std::collections::hash_map::Entry;
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::MutMap;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+use roc_std::RocDec;
+
+use crate::ir::{
+    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, Proc, ProcLayout, Stmt,
+};
+use crate::layout::{
+    Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
+};
+
+pub fn simplify_procs<'a>(
+    arena: &'a Bump,
+    interner: &STLayoutInterner<'a>,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    for proc in procs.values_mut() {
+        let mut folder = Folder {
+            arena,
+            interner,
+            literals: MutMap::default(),
+            tag_ids: MutMap::default(),
+            jump_arguments: MutMap::default(),
+        };
+        let body = folder.fold_stmt(&proc.body);
+
+        let mut uses = Uses::default();
+        uses.add_stmt(&body);
+        let body = uses.remove_dead_join_points(arena, &body);
+
+        let mut uses = Uses::default();
+        uses.add_stmt(&body);
+        proc.body = uses.remove_dead_lets(arena, &body);
+    }
+}
+
+struct Folder<'a, 'i> {
+    arena: &'a Bump,
+    interner: &'i STLayoutInterner<'a>,
+    /// Symbols bound to a literal. Symbols are unique within a proc, so this needs no scoping.
+    literals: MutMap<Symbol, (Literal<'a>, InLayout<'a>)>,
+    /// Symbols bound to a tag whose id we know.
+    tag_ids: MutMap<Symbol, TagIdIntType>,
+    /// The literals passed to each join point parameter by the jumps seen so far, `None` where
+    /// they don't all pass the same literal.
+    jump_arguments: MutMap<JoinPointId, std::vec::Vec<Option<(Literal<'a>, InLayout<'a>)>>>,
+}
+
+impl<'a, 'i> Folder<'a, 'i> {
+    fn fold_stmt(&mut self, stmt: &Stmt<'a>) -> Stmt<'a> {
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                let expr = self.fold_expr(expr, *layout);
+
+                match &expr {
+                    Expr::Literal(literal) => {
+                        self.literals.insert(*symbol, (*literal, *layout));
+                    }
+                    Expr::Tag { tag_id, .. } => {
+                        self.tag_ids.insert(*symbol, *tag_id);
+                    }
+                    _ => {}
+                }
+
+                let cont = self.fold_stmt(cont);
+                Stmt::Let(*symbol, expr, *layout, self.arena.alloc(cont))
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                if let Some(value) = self.switch_value(*cond_symbol) {
+                    let taken = branches
+                        .iter()
+                        .find(|(label, _, _)| *label == value)
+                        .map(|(_, _, branch)| branch)
+                        .unwrap_or(default_branch.1);
+
+                    return self.fold_stmt(taken);
+                }
+
+                let mut new_branches = Vec::with_capacity_in(branches.len(), self.arena);
+                for (label, info, branch) in branches.iter() {
+                    new_branches.push((*label, info.clone(), self.fold_stmt(branch)));
+                }
+                let default = self.fold_stmt(default_branch.1);
+
+                Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: new_branches.into_bump_slice(),
+                    default_branch: (default_branch.0.clone(), self.arena.alloc(default)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Refcounting(modify, cont) => {
+                let cont = self.fold_stmt(cont);
+                Stmt::Refcounting(*modify, self.arena.alloc(cont))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: self.arena.alloc(self.fold_stmt(remainder)),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: *symbol,
+                variable: *variable,
+                remainder: self.arena.alloc(self.fold_stmt(remainder)),
+            },
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => Stmt::Located {
+                module: *module,
+                region: *region,
+                remainder: self.arena.alloc(self.fold_stmt(remainder)),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                // the remainder goes first, so we know what its jumps pass to the parameters. A join
+                // point that jumps to itself is a loop, so its parameters change between iterations
+                let remainder = self.fold_stmt(remainder);
+
+                if !jumps_to(body, *id) {
+                    if let Some(arguments) = self.jump_arguments.get(id) {
+                        for (param, argument) in parameters.iter().zip(arguments) {
+                            if let Some(literal) = argument {
+                                self.literals.insert(param.symbol, *literal);
+                            }
+                        }
+                    }
+                }
+
+                let body = self.fold_stmt(body);
+
+                Stmt::Join {
+                    id: *id,
+                    parameters,
+                    body: self.arena.alloc(body),
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Stmt::Jump(id, arguments) => {
+                let passed = arguments
+                    .iter()
+                    .map(|argument| self.literals.get(argument).copied());
+
+                match self.jump_arguments.entry(*id) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(passed.collect());
+                    }
+                    Entry::Occupied(mut entry) => {
+                        for (seen, new) in entry.get_mut().iter_mut().zip(passed) {
+                            if *seen != new {
+                                *seen = None;
+                            }
+                        }
+                    }
+                }
+
+                stmt.clone()
+            }
+            Stmt::Ret(_) | Stmt::Crash(_, _) => stmt.clone(),
+        }
+    }
+
+    fn fold_expr(&self, expr: &Expr<'a>, layout: InLayout<'a>) -> Expr<'a> {
+        match expr {
+            Expr::Call(call) => {
+                if let CallType::LowLevel { op, .. } =
+                    call.call_type.clone().replace_lowlevel_wrapper()
+                {
+                    if let Some(literal) = self.fold_lowlevel(op, call.arguments) {
+                        return Expr::Literal(literal);
+                    }
+                }
+            }
+            Expr::GetTagId { structure, .. } => {
+                if let (Some(tag_id), LayoutRepr::Builtin(Builtin::Int(_))) =
+                    (self.tag_ids.get(structure), self.interner.get_repr(layout))
+                {
+                    return Expr::Literal(Literal::Int((*tag_id as i128).to_ne_bytes()));
+                }
+            }
+            _ => {}
+        }
+
+        expr.clone()
+    }
+
+    fn fold_lowlevel(&self, op: LowLevel, arguments: &[Symbol]) -> Option<Literal<'a>> {
+        let literals = arguments
+            .iter()
+            .map(|argument| self.literals.get(argument).copied())
+            .collect::<Option<std::vec::Vec<_>>>()?;
+        let (_, layout) = literals.first()?;
+
+        match self.interner.get_repr(*layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
+                let bits = literals
+                    .iter()
+                    .map(|(literal, _)| int_bits(literal))
+                    .collect::<Option<std::vec::Vec<_>>>()?;
+
+                fold_int(op, width, &bits)
+            }
+            LayoutRepr::Builtin(Builtin::Bool) => {
+                let bools = literals
+                    .iter()
+                    .map(|(literal, _)| match literal {
+                        Literal::Bool(b) => Some(*b),
+                        _ => None,
+                    })
+                    .collect::<Option<std::vec::Vec<_>>>()?;
+
+                match (op, bools.as_slice()) {
+                    (LowLevel::Not, [a]) => Some(Literal::Bool(!a)),
+                    (LowLevel::Eq, [a, b]) => Some(Literal::Bool(a == b)),
+                    (LowLevel::NotEq, [a, b]) => Some(Literal::Bool(a != b)),
+                    _ => None,
+                }
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                let decs = literals
+                    .iter()
+                    .map(|(literal, _)| match literal {
+                        Literal::Decimal(bytes) => Some(RocDec::from_ne_bytes(*bytes)),
+                        _ => None,
+                    })
+                    .collect::<Option<std::vec::Vec<_>>>()?;
+
+                fold_dec(op, &decs)
+            }
+            _ => None,
+        }
+    }
+
+    /// The value a `Switch` on this symbol would compare its branches against, if it is known.
+    fn switch_value(&self, symbol: Symbol) -> Option<u64> {
+        match self.literals.get(&symbol)?.0 {
+            Literal::Bool(b) => Some(b as u64),
+            Literal::Byte(b) => Some(b as u64),
+            // negative integers are left alone, they only match the label they were encoded as
+            Literal::Int(bytes) => u64::try_from(i128::from_ne_bytes(bytes)).ok(),
+            Literal::U128(bytes) => u64::try_from(u128::from_ne_bytes(bytes)).ok(),
+            Literal::Float(_) | Literal::Decimal(_) | Literal::Str(_) => None,
+        }
+    }
+}
+
+/// The bits of an integer literal. Narrower integers are truncated from these by the caller.
+fn int_bits(literal: &Literal) -> Option<u128> {
+    match literal {
+        Literal::Int(bytes) | Literal::U128(bytes) => Some(u128::from_ne_bytes(*bytes)),
+        Literal::Byte(b) => Some(*b as u128),
+        Literal::Bool(b) => Some(*b as u128),
+        Literal::Float(_) | Literal::Decimal(_) | Literal::Str(_) => None,
+    }
+}
+
+trait FoldInt: Copy + Ord {
+    fn from_bits(bits: u128) -> Self;
+    fn into_literal<'a>(self) -> Literal<'a>;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+    fn bitand(self, other: Self) -> Self;
+    fn bitor(self, other: Self) -> Self;
+    fn bitxor(self, other: Self) -> Self;
+}
+
+macro_rules! impl_fold_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FoldInt for $ty {
+                fn from_bits(bits: u128) -> Self {
+                    bits as $ty
+                }
+
+                fn into_literal<'a>(self) -> Literal<'a> {
+                    // mono stores every integer that fits as an `Int`, only larger u128s as `U128`
+                    match i128::try_from(self) {
+                        Ok(int) => Literal::Int(int.to_ne_bytes()),
+                        Err(_) => Literal::U128((self as u128).to_ne_bytes()),
+                    }
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> { <$ty>::checked_add(self, other) }
+                fn checked_sub(self, other: Self) -> Option<Self> { <$ty>::checked_sub(self, other) }
+                fn checked_mul(self, other: Self) -> Option<Self> { <$ty>::checked_mul(self, other) }
+                fn checked_div(self, other: Self) -> Option<Self> { <$ty>::checked_div(self, other) }
+                fn checked_rem(self, other: Self) -> Option<Self> { <$ty>::checked_rem(self, other) }
+                fn checked_neg(self) -> Option<Self> { <$ty>::checked_neg(self) }
+                fn wrapping_add(self, other: Self) -> Self { <$ty>::wrapping_add(self, other) }
+                fn wrapping_sub(self, other: Self) -> Self { <$ty>::wrapping_sub(self, other) }
+                fn wrapping_mul(self, other: Self) -> Self { <$ty>::wrapping_mul(self, other) }
+                fn saturating_add(self, other: Self) -> Self { <$ty>::saturating_add(self, other) }
+                fn saturating_sub(self, other: Self) -> Self { <$ty>::saturating_sub(self, other) }
+                fn saturating_mul(self, other: Self) -> Self { <$ty>::saturating_mul(self, other) }
+                fn bitand(self, other: Self) -> Self { self & other }
+                fn bitor(self, other: Self) -> Self { self | other }
+                fn bitxor(self, other: Self) -> Self { self ^ other }
+            }
+        )*
+    };
+}
+
+impl_fold_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+fn fold_int<'a>(op: LowLevel, width: IntWidth, bits: &[u128]) -> Option<Literal<'a>> {
+    match width {
+        IntWidth::U8 => fold_int_help::<u8>(op, bits),
+        IntWidth::U16 => fold_int_help::<u16>(op, bits),
+        IntWidth::U32 => fold_int_help::<u32>(op, bits),
+        IntWidth::U64 => fold_int_help::<u64>(op, bits),
+        IntWidth::U128 => fold_int_help::<u128>(op, bits),
+        IntWidth::I8 => fold_int_help::<i8>(op, bits),
+        IntWidth::I16 => fold_int_help::<i16>(op, bits),
+        IntWidth::I32 => fold_int_help::<i32>(op, bits),
+        IntWidth::I64 => fold_int_help::<i64>(op, bits),
+        IntWidth::I128 => fold_int_help::<i128>(op, bits),
+    }
+}
+
+fn fold_int_help<'a, T: FoldInt>(op: LowLevel, bits: &[u128]) -> Option<Literal<'a>> {
+    use LowLevel::*;
+
+    let (a, b) = match bits {
+        [a] => (T::from_bits(*a), None),
+        [a, b] => (T::from_bits(*a), Some(T::from_bits(*b))),
+        _ => return None,
+    };
+
+    let value = match (op, b) {
+        // these crash on overflow at runtime, so only fold them when they don't overflow
+        (NumAdd, Some(b)) => a.checked_add(b)?,
+        (NumSub, Some(b)) => a.checked_sub(b)?,
+        (NumMul, Some(b)) => a.checked_mul(b)?,
+        (NumDivTruncUnchecked, Some(b)) => a.checked_div(b)?,
+        (NumRemUnchecked, Some(b)) => a.checked_rem(b)?,
+        (NumNeg, None) => a.checked_neg()?,
+
+        (NumAddWrap, Some(b)) => a.wrapping_add(b),
+        (NumSubWrap, Some(b)) => a.wrapping_sub(b),
+        (NumMulWrap, Some(b)) => a.wrapping_mul(b),
+        (NumAddSaturated, Some(b)) => a.saturating_add(b),
+        (NumSubSaturated, Some(b)) => a.saturating_sub(b),
+        (NumMulSaturated, Some(b)) => a.saturating_mul(b),
+        (NumBitwiseAnd, Some(b)) => a.bitand(b),
+        (NumBitwiseOr, Some(b)) => a.bitor(b),
+        (NumBitwiseXor, Some(b)) => a.bitxor(b),
+
+        (NumGt, Some(b)) => return Some(Literal::Bool(a > b)),
+        (NumGte, Some(b)) => return Some(Literal::Bool(a >= b)),
+        (NumLt, Some(b)) => return Some(Literal::Bool(a < b)),
+        (NumLte, Some(b)) => return Some(Literal::Bool(a <= b)),
+        (Eq, Some(b)) => return Some(Literal::Bool(a == b)),
+        (NotEq, Some(b)) => return Some(Literal::Bool(a != b)),
+
+        _ => return None,
+    };
+
+    Some(value.into_literal())
+}
+
+fn fold_dec<'a>(op: LowLevel, decs: &[RocDec]) -> Option<Literal<'a>> {
+    use LowLevel::*;
+
+    let value = match (op, decs) {
+        (NumAdd, [a, b]) => a.checked_add(*b)?,
+        (NumSub, [a, b]) => a.checked_sub(*b)?,
+        (NumMul, [a, b]) => a.checked_mul(*b)?,
+        (NumNeg, [a]) => a.checked_neg()?,
+
+        (NumGt, [a, b]) => return Some(Literal::Bool(a > b)),
+        (NumGte, [a, b]) => return Some(Literal::Bool(a >= b)),
+        (NumLt, [a, b]) => return Some(Literal::Bool(a < b)),
+        (NumLte, [a, b]) => return Some(Literal::Bool(a <= b)),
+        (Eq, [a, b]) => return Some(Literal::Bool(a == b)),
+        (NotEq, [a, b]) => return Some(Literal::Bool(a != b)),
+
+        _ => return None,
+    };
+
+    Some(Literal::Decimal(value.to_ne_bytes()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Use {
+    Symbol(Symbol),
+    JoinPoint(JoinPointId),
+}
+
+/// How often each symbol and join point is used in a proc body.
+#[derive(Default)]
+struct Uses(MutMap<Use, usize>);
+
+impl Uses {
+    fn count(&self, key: Use) -> usize {
+        self.0.get(&key).copied().unwrap_or(0)
+    }
+
+    fn add_stmt(&mut self, stmt: &Stmt) {
+        stmt_uses(stmt, &mut |key| *self.0.entry(key).or_insert(0) += 1);
+    }
+
+    fn forget_stmt(&mut self, stmt: &Stmt) {
+        stmt_uses(stmt, &mut |key| self.forget(key));
+    }
+
+    fn forget_expr(&mut self, expr: &Expr) {
+        expr_uses(expr, &mut |symbol| self.forget(Use::Symbol(symbol)));
+    }
+
+    fn forget(&mut self, key: Use) {
+        if let Some(count) = self.0.get_mut(&key) {
+            *count -= 1;
+        }
+    }
+
+    fn remove_dead_join_points<'a>(&mut self, arena: &'a Bump, stmt: &Stmt<'a>) -> Stmt<'a> {
+        self.rebuild(arena, stmt, &mut |uses, arena, stmt| match stmt {
+            Stmt::Join {
+                id,
+                body,
+                remainder,
+                ..
+            } if uses.count(Use::JoinPoint(*id)) == 0 => {
+                uses.forget_stmt(body);
+                Some(uses.remove_dead_join_points(arena, remainder))
+            }
+            _ => None,
+        })
+    }
+
+    fn remove_dead_lets<'a>(&mut self, arena: &'a Bump, stmt: &Stmt<'a>) -> Stmt<'a> {
+        self.rebuild(arena, stmt, &mut |uses, arena, stmt| match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                // the continuation goes first, so that lets only it used are gone by now
+                let cont = uses.remove_dead_lets(arena, cont);
+
+                if uses.count(Use::Symbol(*symbol)) == 0 && is_pure(expr) {
+                    uses.forget_expr(expr);
+                    Some(cont)
+                } else {
+                    Some(Stmt::Let(*symbol, expr.clone(), *layout, arena.alloc(cont)))
+                }
+            }
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => match uses.remove_dead_lets(arena, remainder) {
+                // all of the code from this region was dead
+                remainder @ Stmt::Located { .. } => Some(remainder),
+                remainder => Some(Stmt::Located {
+                    module: *module,
+                    region: *region,
+                    remainder: arena.alloc(remainder),
+                }),
+            },
+            _ => None,
+        })
+    }
+
+    /// Rebuild a statement, giving `rewrite` the first chance at every statement in it.
+    fn rebuild<'a, F>(&mut self, arena: &'a Bump, stmt: &Stmt<'a>, rewrite: &mut F) -> Stmt<'a>
+    where
+        F: FnMut(&mut Self, &'a Bump, &Stmt<'a>) -> Option<Stmt<'a>>,
+    {
+        if let Some(new) = rewrite(self, arena, stmt) {
+            return new;
+        }
+
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                let cont = self.rebuild(arena, cont, rewrite);
+                Stmt::Let(*symbol, expr.clone(), *layout, arena.alloc(cont))
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+                for (label, info, branch) in branches.iter() {
+                    new_branches.push((*label, info.clone(), self.rebuild(arena, branch, rewrite)));
+                }
+                let default = self.rebuild(arena, default_branch.1, rewrite);
+
+                Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: new_branches.into_bump_slice(),
+                    default_branch: (default_branch.0.clone(), arena.alloc(default)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Refcounting(modify, cont) => {
+                let cont = self.rebuild(arena, cont, rewrite);
+                Stmt::Refcounting(*modify, arena.alloc(cont))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: arena.alloc(self.rebuild(arena, remainder, rewrite)),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: *symbol,
+                variable: *variable,
+                remainder: arena.alloc(self.rebuild(arena, remainder, rewrite)),
+            },
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => Stmt::Located {
+                module: *module,
+                region: *region,
+                remainder: arena.alloc(self.rebuild(arena, remainder, rewrite)),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let body = self.rebuild(arena, body, rewrite);
+                let remainder = self.rebuild(arena, remainder, rewrite);
+
+                Stmt::Join {
+                    id: *id,
+                    parameters,
+                    body: arena.alloc(body),
+                    remainder: arena.alloc(remainder),
+                }
+            }
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => stmt.clone(),
+        }
+    }
+}
+
+fn jumps_to(stmt: &Stmt, id: JoinPointId) -> bool {
+    let mut found = false;
+    stmt_uses(stmt, &mut |key| found |= key == Use::JoinPoint(id));
+    found
+}
+
+fn stmt_uses(stmt: &Stmt, f: &mut impl FnMut(Use)) {
+    match stmt {
+        Stmt::Let(_, expr, _, cont) => {
+            expr_uses(expr, &mut |symbol| f(Use::Symbol(symbol)));
+            stmt_uses(cont, f);
+        }
+        Stmt::Switch {
+            cond_symbol,
+            branches,
+            default_branch,
+            ..
+        } => {
+            f(Use::Symbol(*cond_symbol));
+
+            let branches = branches.iter().map(|(_, info, branch)| (info, branch));
+            for (info, branch) in branches.chain([(&default_branch.0, default_branch.1)]) {
+                match info {
+                    BranchInfo::None => {}
+                    BranchInfo::Constructor { scrutinee, .. }
+                    | BranchInfo::List { scrutinee, .. }
+                    | BranchInfo::Unique { scrutinee, .. } => f(Use::Symbol(*scrutinee)),
+                }
+                stmt_uses(branch, f);
+            }
+        }
+        Stmt::Ret(symbol) => f(Use::Symbol(*symbol)),
+        Stmt::Refcounting(modify, cont) => {
+            f(Use::Symbol(modify.get_symbol()));
+            stmt_uses(cont, f);
+        }
+        Stmt::Expect {
+            condition,
+            lookups,
+            remainder,
+            ..
+        } => {
+            f(Use::Symbol(*condition));
+            lookups.iter().for_each(|symbol| f(Use::Symbol(*symbol)));
+            stmt_uses(remainder, f);
+        }
+        Stmt::Dbg {
+            symbol, remainder, ..
+        } => {
+            f(Use::Symbol(*symbol));
+            stmt_uses(remainder, f);
+        }
+        Stmt::Located { remainder, .. } => stmt_uses(remainder, f),
+        Stmt::Join {
+            body, remainder, ..
+        } => {
+            stmt_uses(body, f);
+            stmt_uses(remainder, f);
+        }
+        Stmt::Jump(id, arguments) => {
+            f(Use::JoinPoint(*id));
+            arguments.iter().for_each(|symbol| f(Use::Symbol(*symbol)));
+        }
+        Stmt::Crash(symbol, _) => f(Use::Symbol(*symbol)),
+    }
+}
+
+fn expr_uses(expr: &Expr, f: &mut impl FnMut(Symbol)) {
+    match expr {
+        Expr::Literal(_) | Expr::NullPointer | Expr::EmptyArray | Expr::FunctionPointer { .. } => {}
+        Expr::Call(call) => {
+            match &call.call_type {
+                CallType::ByPointer { pointer, .. } => f(*pointer),
+                CallType::HigherOrder(higher_order) => {
+                    f(higher_order.passed_function.captured_environment)
+                }
+                CallType::ByName { .. } | CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+            }
+            call.arguments.iter().for_each(|symbol| f(*symbol));
+        }
+        Expr::Tag {
+            arguments, reuse, ..
+        } => {
+            arguments.iter().for_each(|symbol| f(*symbol));
+            if let Some(reuse) = reuse {
+                f(reuse.symbol);
+            }
+        }
+        Expr::Struct(fields) => fields.iter().for_each(|symbol| f(*symbol)),
+        Expr::StructAtIndex { structure, .. }
+        | Expr::GetTagId { structure, .. }
+        | Expr::UnionAtIndex { structure, .. }
+        | Expr::GetElementPointer { structure, .. } => f(*structure),
+        Expr::Array { elems, .. } => {
+            for elem in elems.iter() {
+                if let ListLiteralElement::Symbol(symbol) = elem {
+                    f(*symbol);
+                }
+            }
+        }
+        Expr::ErasedMake { value, callee } => {
+            if let Some(value) = value {
+                f(*value);
+            }
+            f(*callee);
+        }
+        Expr::ErasedLoad { symbol, .. }
+        | Expr::Reset { symbol, .. }
+        | Expr::ResetRef { symbol, .. } => f(*symbol),
+        Expr::Alloca { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                f(*initializer);
+            }
+        }
+    }
+}
+
+/// Whether an expression can be dropped when its value is not used.
+fn is_pure(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_)
+        | Expr::Struct(_)
+        | Expr::StructAtIndex { .. }
+        | Expr::GetTagId { .. }
+        | Expr::UnionAtIndex { .. }
+        | Expr::Array { .. }
+        | Expr::EmptyArray
+        | Expr::NullPointer
+        | Expr::FunctionPointer { .. }
+        | Expr::ErasedMake { .. }
+        | Expr::ErasedLoad { .. } => true,
+        Expr::Tag { reuse, .. } => reuse.is_none(),
+        Expr::Call(call) => match call.call_type.clone().replace_lowlevel_wrapper() {
+            CallType::LowLevel { op, .. } => is_pure_lowlevel(op),
+            _ => false,
+        },
+        Expr::GetElementPointer { .. }
+        | Expr::Alloca { .. }
+        | Expr::Reset { .. }
+        | Expr::ResetRef { .. } => false,
+    }
+}
+
+/// Lowlevels that can neither crash nor have side effects.
+fn is_pure_lowlevel(op: LowLevel) -> bool {
+    use LowLevel::*;
+
+    matches!(
+        op,
+        NumAddWrap
+            | NumSubWrap
+            | NumMulWrap
+            | NumAddSaturated
+            | NumSubSaturated
+            | NumMulSaturated
+            | NumGt
+            | NumGte
+            | NumLt
+            | NumLte
+            | NumCompare
+            | NumBitwiseAnd
+            | NumBitwiseOr
+            | NumBitwiseXor
+            | NumIntCast
+            | ListLenU64
+            | Eq
+            | NotEq
+            | Not
+    )
+}
@@ -385,6 +385,26 @@ fn insert_jumps<'a>(
             None => None,
         },
 
+        Located {
+            module,
+            region,
+            remainder,
+        } => match insert_jumps(
+            arena,
+            remainder,
+            goal_id,
+            needle,
+            needle_arguments,
+            needle_result,
+        ) {
+            Some(cont) => Some(arena.alloc(Located {
+                module: *module,
+                region: *region,
+                remainder: cont,
+            })),
+            None => None,
+        },
+
         Ret(_) => None,
         Jump(_, _) => None,
         Crash(..) => None,
@@ -535,7 +555,9 @@ fn trmc_candidates_help(
             }
         }
         Stmt::Refcounting(_, next) => trmc_candidates_help(function_name, next, candidates),
-        Stmt::Expect { remainder, .. } | Stmt::Dbg { remainder, .. } => {
+        Stmt::Expect { remainder, .. }
+        | Stmt::Dbg { remainder, .. }
+        | Stmt::Located { remainder, .. } => {
             trmc_candidates_help(function_name, remainder, candidates)
         }
         Stmt::Join {
@@ -791,6 +813,7 @@ impl<'a> TrmcEnv<'a> {
             ret_layout: proc.ret_layout,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: proc.is_erased,
+            region: proc.region,
         }
     }
 
@@ -1007,6 +1030,15 @@ impl<'a> TrmcEnv<'a> {
                 variable: *variable,
                 remainder: arena.alloc(self.walk_stmt(env, remainder)),
             },
+            Stmt::Located {
+                module,
+                region,
+                remainder,
+            } => Stmt::Located {
+                module: *module,
+                region: *region,
+                remainder: arena.alloc(self.walk_stmt(env, remainder)),
+            },
             Stmt::Join {
                 id,
                 parameters,
@@ -1100,7 +1132,7 @@ fn stmt_contains_symbol_nonrec(stmt: &Stmt, needle: Symbol) -> bool {
             condition, lookups, ..
         } => needle == *condition || lookups.contains(&needle),
         Stmt::Dbg { symbol, .. } => needle == *symbol,
-        Stmt::Join { .. } => false,
+        Stmt::Join { .. } | Stmt::Located { .. } => false,
         Stmt::Jump(_, arguments) => arguments.contains(&needle),
         Stmt::Crash(symbol, _) => needle == *symbol,
     }
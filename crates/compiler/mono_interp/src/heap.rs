@@ -0,0 +1,285 @@
+use roc_mono::layout::TagIdIntType;
+
+use crate::{Error, Value};
+
+/// The address of an allocation on the interpreter's [Heap].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Addr(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefCount {
+    /// The number of references to a live allocation
+    Live(usize),
+    /// Static data like string literals, and the memory of an `Alloca`, which refcounting ignores
+    Constant,
+    Freed,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeapData<'a> {
+    Str(Vec<u8>),
+    List {
+        elements: Vec<Value<'a>>,
+        capacity: usize,
+    },
+    /// A box, or a tag of a recursive tag union
+    Tag {
+        tag_id: TagIdIntType,
+        fields: Vec<Value<'a>>,
+    },
+    /// The memory of an `Alloca`
+    Cell(Value<'a>),
+    /// An allocation whose contents are gone, because it was freed or reset for reuse
+    Empty,
+}
+
+impl<'a> HeapData<'a> {
+    /// The values this allocation holds a reference to
+    fn children(&self) -> &[Value<'a>] {
+        match self {
+            HeapData::List { elements, .. } => elements,
+            HeapData::Tag { fields, .. } => fields,
+            HeapData::Str(_) | HeapData::Cell(_) | HeapData::Empty => &[],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Allocation<'a> {
+    refcount: RefCount,
+    data: HeapData<'a>,
+}
+
+/// The interpreter's heap, which checks every access against the refcounts that the IR maintains.
+#[derive(Debug, Default)]
+pub struct Heap<'a> {
+    allocations: Vec<Allocation<'a>>,
+}
+
+impl<'a> Heap<'a> {
+    /// Allocates `data` with a refcount of 1.
+    pub fn alloc(&mut self, data: HeapData<'a>) -> Addr {
+        self.push(RefCount::Live(1), data)
+    }
+
+    pub fn alloc_constant(&mut self, data: HeapData<'a>) -> Addr {
+        self.push(RefCount::Constant, data)
+    }
+
+    fn push(&mut self, refcount: RefCount, data: HeapData<'a>) -> Addr {
+        self.allocations.push(Allocation { refcount, data });
+
+        Addr(self.allocations.len() - 1)
+    }
+
+    pub fn refcount(&self, addr: Addr) -> RefCount {
+        self.allocations[addr.0].refcount
+    }
+
+    pub fn get(&self, addr: Addr) -> Result<&HeapData<'a>, Error> {
+        match &self.allocations[addr.0] {
+            Allocation {
+                refcount: RefCount::Freed,
+                ..
+            }
+            | Allocation {
+                data: HeapData::Empty,
+                ..
+            } => Err(Error::UseAfterFree(addr)),
+            Allocation { data, .. } => Ok(data),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, addr: Addr) -> Result<&mut HeapData<'a>, Error> {
+        match &mut self.allocations[addr.0] {
+            Allocation {
+                refcount: RefCount::Freed,
+                ..
+            }
+            | Allocation {
+                data: HeapData::Empty,
+                ..
+            } => Err(Error::UseAfterFree(addr)),
+            Allocation { data, .. } => Ok(data),
+        }
+    }
+
+    pub fn str(&self, addr: Addr) -> Result<&[u8], Error> {
+        match self.get(addr)? {
+            HeapData::Str(bytes) => Ok(bytes),
+            other => Err(Error::Malformed(format!(
+                "expected a string, got {other:?}"
+            ))),
+        }
+    }
+
+    pub fn list(&self, addr: Addr) -> Result<&[Value<'a>], Error> {
+        match self.get(addr)? {
+            HeapData::List { elements, .. } => Ok(elements),
+            other => Err(Error::Malformed(format!("expected a list, got {other:?}"))),
+        }
+    }
+
+    pub fn tag(&self, addr: Addr) -> Result<(TagIdIntType, &[Value<'a>]), Error> {
+        match self.get(addr)? {
+            HeapData::Tag { tag_id, fields } => Ok((*tag_id, fields)),
+            other => Err(Error::Malformed(format!("expected a tag, got {other:?}"))),
+        }
+    }
+
+    pub fn is_unique(&self, addr: Addr) -> Result<bool, Error> {
+        match self.refcount(addr) {
+            RefCount::Live(count) => Ok(count == 1),
+            RefCount::Constant => Ok(false),
+            RefCount::Freed => Err(Error::UseAfterFree(addr)),
+        }
+    }
+
+    /// Adds `amount` references to each allocation `value` points to.
+    pub fn inc(&mut self, value: &Value<'a>, amount: usize) -> Result<(), Error> {
+        let mut addrs = Vec::new();
+        value.for_each_addr(&mut |addr| addrs.push(addr));
+
+        for addr in addrs {
+            let allocation = &mut self.allocations[addr.0];
+
+            match allocation.refcount {
+                RefCount::Live(count) => allocation.refcount = RefCount::Live(count + amount),
+                RefCount::Constant => {}
+                RefCount::Freed => return Err(Error::UseAfterFree(addr)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drops a reference to each allocation `value` points to, and recursively drops the
+    /// children of the ones that this frees.
+    pub fn dec(&mut self, value: &Value<'a>) -> Result<(), Error> {
+        let mut pending = Vec::new();
+        value.for_each_addr(&mut |addr| pending.push(addr));
+
+        while let Some(addr) = pending.pop() {
+            if let Some(data) = self.decrement(addr)? {
+                for child in data.children() {
+                    child.for_each_addr(&mut |addr| pending.push(addr));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like [Heap::dec], but leaves the children of freed allocations alone. A list holds a
+    /// single reference to its elements no matter how many references there are to the list,
+    /// so lists drop their elements either way.
+    pub fn dec_ref(&mut self, value: &Value<'a>) -> Result<(), Error> {
+        let mut addrs = Vec::new();
+        value.for_each_addr(&mut |addr| addrs.push(addr));
+
+        for addr in addrs {
+            if let Some(HeapData::List { elements, .. }) = self.decrement(addr)? {
+                for element in elements {
+                    self.dec(&element)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Frees the allocations `value` points to whatever their refcount, without dropping
+    /// their children.
+    pub fn free(&mut self, value: &Value<'a>) -> Result<(), Error> {
+        let mut addrs = Vec::new();
+        value.for_each_addr(&mut |addr| addrs.push(addr));
+
+        for addr in addrs {
+            let allocation = &mut self.allocations[addr.0];
+
+            match allocation.refcount {
+                RefCount::Live(_) => {
+                    allocation.refcount = RefCount::Freed;
+                    allocation.data = HeapData::Empty;
+                }
+                RefCount::Constant => {}
+                RefCount::Freed => return Err(Error::UseAfterFree(addr)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Implements `Reset` and `ResetRef`. If `addr` is the only reference to its allocation,
+    /// its children are dropped (only if `dec_children` is set) and it is emptied, ready to be
+    /// reused, and this returns true. Otherwise, the reference is dropped.
+    pub(crate) fn reset(&mut self, addr: Addr, dec_children: bool) -> Result<bool, Error> {
+        if !self.is_unique(addr)? {
+            self.decrement(addr)?;
+
+            return Ok(false);
+        }
+
+        let data = std::mem::replace(self.get_mut(addr)?, HeapData::Empty);
+
+        if dec_children {
+            for child in data.children() {
+                self.dec(child)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Overwrites a unique allocation with a new value. The allocation was either emptied by
+    /// [Heap::reset], or drop specialization checked that it's unique and moved its children
+    /// out, so its old contents hold no references.
+    pub(crate) fn reuse(&mut self, addr: Addr, data: HeapData<'a>) -> Result<(), Error> {
+        let allocation = &mut self.allocations[addr.0];
+
+        match allocation.refcount {
+            RefCount::Live(1) => {
+                allocation.data = data;
+
+                Ok(())
+            }
+            RefCount::Freed => Err(Error::UseAfterFree(addr)),
+            refcount => Err(Error::Malformed(format!(
+                "reused an allocation that isn't unique: {refcount:?}"
+            ))),
+        }
+    }
+
+    /// Drops a reference to `addr`, returning its contents if that freed it
+    fn decrement(&mut self, addr: Addr) -> Result<Option<HeapData<'a>>, Error> {
+        let allocation = &mut self.allocations[addr.0];
+
+        match allocation.refcount {
+            RefCount::Live(1) => {
+                allocation.refcount = RefCount::Freed;
+
+                Ok(Some(std::mem::replace(
+                    &mut allocation.data,
+                    HeapData::Empty,
+                )))
+            }
+            RefCount::Live(count) => {
+                allocation.refcount = RefCount::Live(count - 1);
+
+                Ok(None)
+            }
+            RefCount::Constant => Ok(None),
+            RefCount::Freed => Err(Error::UseAfterFree(addr)),
+        }
+    }
+
+    /// The allocations that haven't been freed
+    pub fn live_allocations(&self) -> Vec<Addr> {
+        self.allocations
+            .iter()
+            .enumerate()
+            .filter(|(_, allocation)| matches!(allocation.refcount, RefCount::Live(_)))
+            .map(|(index, _)| Addr(index))
+            .collect()
+    }
+}
@@ -0,0 +1,565 @@
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::all::MutMap;
+use roc_module::symbol::Symbol;
+use roc_mono::ir::{
+    Call, CallType, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal, ModifyRc,
+    Param, Proc, ProcLayout, Stmt,
+};
+use roc_mono::layout::{
+    Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType, UnionLayout,
+};
+use roc_mono::low_level::HigherOrder;
+use roc_std::RocDec;
+
+use crate::{Error, Heap, HeapData, IntValue, Value};
+
+/// Runs mono IR procs against a [Heap] that checks their refcounting.
+pub struct Interpreter<'a, 'r> {
+    pub(crate) interner: &'r STLayoutInterner<'a>,
+    procs: &'r MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub(crate) heap: Heap<'a>,
+}
+
+/// The symbols and join points in scope in a proc
+#[derive(Default)]
+struct Frame<'a> {
+    symbols: MutMap<Symbol, (Value<'a>, InLayout<'a>)>,
+    join_points: MutMap<JoinPointId, (&'a [Param<'a>], &'a Stmt<'a>)>,
+}
+
+impl<'a> Frame<'a> {
+    fn get(&self, symbol: Symbol) -> Result<&(Value<'a>, InLayout<'a>), Error> {
+        self.symbols
+            .get(&symbol)
+            .ok_or_else(|| Error::Malformed(format!("{symbol:?} is not defined")))
+    }
+
+    fn value(&self, symbol: Symbol) -> Result<&Value<'a>, Error> {
+        self.get(symbol).map(|(value, _)| value)
+    }
+
+    fn values(&self, symbols: &[Symbol]) -> Result<Vec<Value<'a>>, Error> {
+        symbols
+            .iter()
+            .map(|symbol| self.value(*symbol).cloned())
+            .collect()
+    }
+
+    fn bind(&mut self, symbol: Symbol, value: Value<'a>, layout: InLayout<'a>) {
+        self.symbols.insert(symbol, (value, layout));
+    }
+}
+
+impl<'a, 'r> Interpreter<'a, 'r> {
+    pub fn new(
+        interner: &'r STLayoutInterner<'a>,
+        procs: &'r MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    ) -> Self {
+        Self {
+            interner,
+            procs,
+            heap: Heap::default(),
+        }
+    }
+
+    pub fn heap(&self) -> &Heap<'a> {
+        &self.heap
+    }
+
+    pub fn heap_mut(&mut self) -> &mut Heap<'a> {
+        &mut self.heap
+    }
+
+    /// Calls the proc `name` with the given layout. Like in compiled code, the proc owns its
+    /// arguments, and the caller owns the result.
+    pub fn call(
+        &mut self,
+        name: Symbol,
+        layout: ProcLayout<'a>,
+        arguments: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let procs = self.procs;
+        let proc = procs.get(&(name, layout)).ok_or(Error::UnknownProc(name))?;
+
+        if proc.args.len() != arguments.len() {
+            return Err(Error::Malformed(format!(
+                "{name:?} takes {} arguments, but got {}",
+                proc.args.len(),
+                arguments.len()
+            )));
+        }
+
+        let mut frame = Frame::default();
+
+        for ((layout, symbol), value) in proc.args.iter().zip(arguments) {
+            frame.bind(*symbol, value, *layout);
+        }
+
+        self.run(&mut frame, &proc.body)
+    }
+
+    /// Drops `value`, which the caller owns, and then reports everything still on the heap as
+    /// a leak.
+    pub fn drop_and_check_leaks(&mut self, value: &Value<'a>) -> Result<(), Error> {
+        self.heap.dec(value)?;
+
+        let leaks = self.heap.live_allocations();
+
+        if leaks.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Leak(leaks))
+        }
+    }
+
+    fn run(&mut self, frame: &mut Frame<'a>, body: &'r Stmt<'a>) -> Result<Value<'a>, Error> {
+        let mut stmt = body;
+
+        loop {
+            match stmt {
+                Stmt::Let(symbol, expr, layout, continuation) => {
+                    let value = self.eval(frame, expr, *layout)?;
+                    frame.bind(*symbol, value, *layout);
+
+                    stmt = *continuation;
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let label = switch_label(frame.value(*cond_symbol)?)?;
+
+                    stmt = branches
+                        .iter()
+                        .find(|(branch_label, _, _)| *branch_label == label)
+                        .map(|(_, _, branch)| branch)
+                        .unwrap_or(default_branch.1);
+                }
+                Stmt::Ret(symbol) => return frame.value(*symbol).cloned(),
+                Stmt::Refcounting(modify_rc, continuation) => {
+                    let value = frame.value(modify_rc.get_symbol())?;
+
+                    match modify_rc {
+                        ModifyRc::Inc(_, amount) => self.heap.inc(value, *amount as usize)?,
+                        ModifyRc::Dec(_) => self.heap.dec(value)?,
+                        ModifyRc::DecRef(_) => self.heap.dec_ref(value)?,
+                        ModifyRc::Free(_) => self.heap.free(value)?,
+                    }
+
+                    stmt = *continuation;
+                }
+                Stmt::Expect { remainder, .. }
+                | Stmt::Dbg { remainder, .. }
+                | Stmt::Located { remainder, .. } => {
+                    stmt = *remainder;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    frame.join_points.insert(*id, (*parameters, *body));
+
+                    stmt = *remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    let (parameters, body) = *frame.join_points.get(id).ok_or_else(|| {
+                        Error::Malformed(format!("jump to undefined join point {id:?}"))
+                    })?;
+                    let arguments = frame.values(arguments)?;
+
+                    for (param, value) in parameters.iter().zip(arguments) {
+                        frame.bind(param.symbol, value, param.layout);
+                    }
+
+                    stmt = body;
+                }
+                Stmt::Crash(symbol, tag) => {
+                    let message = match frame.value(*symbol)? {
+                        Value::Str(addr) => String::from_utf8_lossy(self.heap.str(*addr)?).into(),
+                        other => return Err(expected("a crash message", other)),
+                    };
+
+                    return Err(Error::Crash { message, tag: *tag });
+                }
+            }
+        }
+    }
+
+    fn eval(
+        &mut self,
+        frame: &Frame<'a>,
+        expr: &'r Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match expr {
+            Expr::Literal(literal) => self.literal(*literal, layout),
+            Expr::Call(call) => self.eval_call(frame, call, layout),
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse,
+            } => {
+                let fields = frame.values(arguments)?;
+                let reuse = match reuse {
+                    Some(token) => Some(frame.value(token.symbol)?.clone()),
+                    None => None,
+                };
+
+                self.tag(*tag_layout, *tag_id, fields, reuse)
+            }
+            Expr::Struct(fields) => Ok(Value::Struct(frame.values(fields)?)),
+            Expr::NullPointer => Ok(Value::Null),
+            Expr::StructAtIndex {
+                index, structure, ..
+            } => match frame.value(*structure)? {
+                Value::Struct(fields) => field(fields, *index),
+                other => Err(expected("a struct", other)),
+            },
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => {
+                let tag_id = match frame.value(*structure)? {
+                    Value::Union(tag_id, _) => *tag_id,
+                    Value::Pointer(addr) => self.heap.tag(*addr)?.0,
+                    Value::Null => nullable_id(*union_layout)?,
+                    other => return Err(expected("a tag union", other)),
+                };
+
+                Ok(Value::Int(IntValue::from_bits(
+                    self.int_width(layout)?,
+                    tag_id as u128,
+                )))
+            }
+            Expr::UnionAtIndex {
+                structure, index, ..
+            } => match frame.value(*structure)? {
+                Value::Union(_, fields) => field(fields, *index),
+                Value::Pointer(addr) => field(self.heap.tag(*addr)?.1, *index),
+                other => Err(expected("a tag union", other)),
+            },
+            Expr::GetElementPointer {
+                structure, indices, ..
+            } => match (frame.value(*structure)?, indices.last()) {
+                (Value::Pointer(addr), Some(index)) => Ok(Value::Ref(*addr, Some(*index as usize))),
+                (other, _) => Err(expected("a pointer to a tag", other)),
+            },
+            Expr::Array { elem_layout, elems } => {
+                let elements = elems
+                    .iter()
+                    .map(|elem| match elem {
+                        ListLiteralElement::Literal(literal) => {
+                            self.literal(*literal, *elem_layout)
+                        }
+                        ListLiteralElement::Symbol(symbol) => frame.value(*symbol).cloned(),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(self.new_list(elements))
+            }
+            Expr::EmptyArray => Ok(Value::List(self.heap.alloc_constant(HeapData::List {
+                elements: Vec::new(),
+                capacity: 0,
+            }))),
+            Expr::ErasedMake { .. } | Expr::ErasedLoad { .. } => {
+                Err(Error::Unsupported("type-erased values".into()))
+            }
+            Expr::FunctionPointer { lambda_name } => Ok(Value::FunctionPointer(*lambda_name)),
+            Expr::Alloca { initializer, .. } => {
+                let value = match initializer {
+                    Some(symbol) => frame.value(*symbol)?.clone(),
+                    None => Value::Null,
+                };
+
+                Ok(Value::Ref(
+                    self.heap.alloc_constant(HeapData::Cell(value)),
+                    None,
+                ))
+            }
+            Expr::Reset { symbol, .. } => self.reset(frame.value(*symbol)?, true),
+            Expr::ResetRef { symbol, .. } => self.reset(frame.value(*symbol)?, false),
+        }
+    }
+
+    fn literal(&mut self, literal: Literal<'a>, layout: InLayout<'a>) -> Result<Value<'a>, Error> {
+        let repr = self.interner.runtime_representation(layout);
+
+        match (literal, repr) {
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Int(width))) => Ok(Value::Int(
+                IntValue::from_i128(width, i128::from_ne_bytes(bytes)),
+            )),
+            (Literal::U128(bytes), LayoutRepr::Builtin(Builtin::Int(width))) => Ok(Value::Int(
+                IntValue::from_bits(width, u128::from_ne_bytes(bytes)),
+            )),
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Float(width))) => Ok(
+                crate::lowlevel::float(width, i128::from_ne_bytes(bytes) as f64),
+            ),
+            (Literal::Float(value), LayoutRepr::Builtin(Builtin::Float(width))) => {
+                Ok(crate::lowlevel::float(width, value))
+            }
+            (Literal::Decimal(bytes), _) => Ok(Value::Dec(RocDec::from_ne_bytes(bytes))),
+            (Literal::Bool(value), _) => Ok(Value::Bool(value)),
+            (Literal::Byte(value), _) => {
+                Ok(Value::Int(IntValue::from_bits(IntWidth::U8, value as u128)))
+            }
+            (Literal::Str(string), _) => Ok(Value::Str(
+                self.heap
+                    .alloc_constant(HeapData::Str(string.as_bytes().to_vec())),
+            )),
+            (literal, repr) => Err(Error::Malformed(format!(
+                "literal {literal:?} with layout {repr:?}"
+            ))),
+        }
+    }
+
+    fn eval_call(
+        &mut self,
+        frame: &Frame<'a>,
+        call: &'r Call<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, Error> {
+        let call_type = call.call_type.clone().replace_lowlevel_wrapper();
+
+        // the arguments of a higher-order call include the passed function, which isn't a value
+        if let CallType::HigherOrder(higher_order) = call_type {
+            return self.higher_order(frame, higher_order);
+        }
+
+        let arguments = frame.values(call.arguments)?;
+
+        match call_type {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } => {
+                let proc_layout = ProcLayout {
+                    arguments: arg_layouts,
+                    result: ret_layout,
+                    niche: name.niche(),
+                };
+
+                self.call(name.name(), proc_layout, arguments)
+            }
+            CallType::ByPointer {
+                pointer,
+                ret_layout,
+                arg_layouts,
+            } => match frame.value(pointer)? {
+                Value::FunctionPointer(name) => {
+                    let proc_layout = ProcLayout {
+                        arguments: arg_layouts,
+                        result: ret_layout,
+                        niche: name.niche(),
+                    };
+
+                    self.call(name.name(), proc_layout, arguments)
+                }
+                other => Err(expected("a function pointer", other)),
+            },
+            CallType::Foreign { foreign_symbol, .. } => Err(Error::Unsupported(format!(
+                "foreign call to {foreign_symbol:?}"
+            ))),
+            CallType::LowLevel { op, .. } => {
+                let argument_layouts = call
+                    .arguments
+                    .iter()
+                    .map(|symbol| frame.get(*symbol).map(|(_, layout)| *layout))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.lowlevel(op, arguments, &argument_layouts, layout)
+            }
+            CallType::HigherOrder(_) => unreachable!(),
+        }
+    }
+
+    fn higher_order(
+        &mut self,
+        frame: &Frame<'a>,
+        higher_order: &HigherOrderLowLevel<'a>,
+    ) -> Result<Value<'a>, Error> {
+        let passed_function = &higher_order.passed_function;
+        let proc_layout = ProcLayout {
+            arguments: passed_function.argument_layouts,
+            result: passed_function.return_layout,
+            niche: passed_function.name.niche(),
+        };
+        // the captured environment is only passed along if the function has one
+        let captured_environment = match passed_function.argument_layouts.len() {
+            3 => Some(frame.value(passed_function.captured_environment)?.clone()),
+            _ => None,
+        };
+
+        match higher_order.op {
+            HigherOrder::ListSortWith { xs } => {
+                let list = frame.value(xs)?.clone();
+                let elements = self.copy_elements(&list)?;
+                self.heap.dec(&list)?;
+
+                // like the zig builtin, increment the arguments of every comparison, since procs
+                // own their arguments
+                let mut compare = |interp: &mut Self, a: &Value<'a>, b: &Value<'a>| {
+                    let mut arguments = vec![a.clone(), b.clone()];
+                    arguments.extend(captured_environment.clone());
+
+                    for argument in arguments.iter() {
+                        interp.heap.inc(argument, 1)?;
+                    }
+
+                    let order = interp.call(passed_function.name.name(), proc_layout, arguments)?;
+
+                    crate::lowlevel::order_from_value(&order)
+                };
+
+                let sorted = self.sort_with(elements, &mut compare)?;
+
+                Ok(self.new_list(sorted))
+            }
+        }
+    }
+
+    /// A stable merge sort with a comparison that can fail
+    fn sort_with(
+        &mut self,
+        mut elements: Vec<Value<'a>>,
+        compare: &mut impl FnMut(&mut Self, &Value<'a>, &Value<'a>) -> Result<std::cmp::Ordering, Error>,
+    ) -> Result<Vec<Value<'a>>, Error> {
+        if elements.len() < 2 {
+            return Ok(elements);
+        }
+
+        let right = elements.split_off(elements.len() / 2);
+        let left = self.sort_with(elements, compare)?;
+        let right = self.sort_with(right, compare)?;
+
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            if compare(self, b, a)? == std::cmp::Ordering::Less {
+                merged.extend(right.next());
+            } else {
+                merged.extend(left.next());
+            }
+        }
+
+        merged.extend(left);
+        merged.extend(right);
+
+        Ok(merged)
+    }
+
+    fn tag(
+        &mut self,
+        union_layout: UnionLayout<'a>,
+        tag_id: TagIdIntType,
+        fields: Vec<Value<'a>>,
+        reuse: Option<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        match union_layout {
+            UnionLayout::NonRecursive(_) => return Ok(Value::Union(tag_id, fields)),
+            UnionLayout::NullableWrapped { nullable_id, .. } if nullable_id == tag_id => {
+                return Ok(Value::Null)
+            }
+            UnionLayout::NullableUnwrapped { nullable_id, .. }
+                if nullable_id as TagIdIntType == tag_id =>
+            {
+                return Ok(Value::Null)
+            }
+            _ => {}
+        }
+
+        let data = HeapData::Tag { tag_id, fields };
+
+        match reuse {
+            Some(Value::Pointer(addr)) => {
+                self.heap.reuse(addr, data)?;
+
+                Ok(Value::Pointer(addr))
+            }
+            Some(Value::Null) | None => Ok(Value::Pointer(self.heap.alloc(data))),
+            Some(other) => Err(expected("a reuse token", &other)),
+        }
+    }
+
+    fn reset(&mut self, value: &Value<'a>, dec_children: bool) -> Result<Value<'a>, Error> {
+        match value {
+            Value::Null => Ok(Value::Null),
+            Value::Pointer(addr) => {
+                if self.heap.reset(*addr, dec_children)? {
+                    Ok(Value::Pointer(*addr))
+                } else {
+                    Ok(Value::Null)
+                }
+            }
+            other => Err(expected("a pointer to a tag", other)),
+        }
+    }
+
+    pub(crate) fn new_list(&mut self, elements: Vec<Value<'a>>) -> Value<'a> {
+        let capacity = elements.len();
+
+        Value::List(self.heap.alloc(HeapData::List { elements, capacity }))
+    }
+
+    /// The elements of `list`, each with an extra reference for the caller
+    pub(crate) fn copy_elements(&mut self, list: &Value<'a>) -> Result<Vec<Value<'a>>, Error> {
+        let elements = match list {
+            Value::List(addr) => self.heap.list(*addr)?.to_vec(),
+            other => return Err(expected("a list", other)),
+        };
+
+        for element in elements.iter() {
+            self.heap.inc(element, 1)?;
+        }
+
+        Ok(elements)
+    }
+
+    pub(crate) fn int_width(&self, layout: InLayout<'a>) -> Result<IntWidth, Error> {
+        match self.interner.runtime_representation(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Ok(width),
+            repr => Err(Error::Malformed(format!(
+                "expected an integer layout, got {repr:?}"
+            ))),
+        }
+    }
+}
+
+pub(crate) fn expected(what: &str, value: &Value) -> Error {
+    Error::Malformed(format!("expected {what}, got {value:?}"))
+}
+
+fn field<'a>(fields: &[Value<'a>], index: u64) -> Result<Value<'a>, Error> {
+    fields
+        .get(index as usize)
+        .cloned()
+        .ok_or_else(|| Error::Malformed(format!("no field {index} in {fields:?}")))
+}
+
+fn nullable_id(union_layout: UnionLayout) -> Result<TagIdIntType, Error> {
+    match union_layout {
+        UnionLayout::NullableWrapped { nullable_id, .. } => Ok(nullable_id),
+        UnionLayout::NullableUnwrapped { nullable_id, .. } => Ok(nullable_id as TagIdIntType),
+        _ => Err(Error::Malformed(format!(
+            "null pointer for a union that isn't nullable: {union_layout:?}"
+        ))),
+    }
+}
+
+/// The label of the `Switch` branch for `value`. Integer labels are stored as `i128 as u64`, so
+/// signed values are sign-extended to match them.
+fn switch_label(value: &Value) -> Result<u64, Error> {
+    match value {
+        Value::Int(int) => Ok(int.as_i128() as u64),
+        Value::Bool(bool) => Ok(*bool as u64),
+        other => Err(expected("an integer to switch on", other)),
+    }
+}
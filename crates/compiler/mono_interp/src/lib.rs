@@ -0,0 +1,56 @@
+//! A reference interpreter for the mono IR.
+//!
+//! It runs [Proc](roc_mono::ir::Proc)s directly, so that the results of the code generation
+//! backends can be compared against it. Strings, lists, boxes and recursive tag unions live on a
+//! [Heap] whose refcounts only change through the IR's explicit
+//! [ModifyRc](roc_mono::ir::ModifyRc) statements and the low-level operations that consume their
+//! arguments, which lets the interpreter catch use-after-free and leaks.
+#![warn(clippy::dbg_macro)]
+// See github.com/roc-lang/roc/issues/800 for discussion of the large_enum_variant check.
+#![allow(clippy::large_enum_variant)]
+
+mod heap;
+mod interp;
+mod lowlevel;
+mod value;
+
+use std::fmt;
+
+use roc_module::symbol::Symbol;
+use roc_mono::ir::CrashTag;
+
+pub use heap::{Addr, Heap, HeapData, RefCount};
+pub use interp::Interpreter;
+pub use value::{IntValue, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The program crashed, either with `crash` or with an error like integer overflow that
+    /// compiled code crashes on too
+    Crash { message: String, tag: CrashTag },
+    /// An allocation was used after it was freed, or after it was reset for reuse
+    UseAfterFree(Addr),
+    /// The allocations that were still alive after the result of the program was dropped
+    Leak(Vec<Addr>),
+    /// A call to a proc that isn't one of the procs being interpreted
+    UnknownProc(Symbol),
+    /// Something the interpreter doesn't implement, like foreign calls or hashing
+    Unsupported(String),
+    /// The IR doesn't make sense, like when it looks up a symbol that isn't defined
+    Malformed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Crash { message, tag } => write!(f, "{tag:?} crash: {message}"),
+            Error::UseAfterFree(addr) => write!(f, "use after free of {addr:?}"),
+            Error::Leak(addrs) => write!(f, "leaked {} allocations: {addrs:?}", addrs.len()),
+            Error::UnknownProc(symbol) => write!(f, "unknown proc {symbol:?}"),
+            Error::Unsupported(what) => write!(f, "unsupported: {what}"),
+            Error::Malformed(what) => write!(f, "malformed IR: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
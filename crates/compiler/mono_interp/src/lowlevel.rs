@@ -0,0 +1,1185 @@
+// The integer and float operations are written once for every width with `with_int_type!` and
+// `with_float_type!`, which makes some of the casts no-ops for the widest types.
+#![allow(clippy::unnecessary_cast)]
+
+use std::cmp::Ordering;
+
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_module::low_level::LowLevel;
+use roc_mono::inc_dec::lowlevel_borrow_signature;
+use roc_mono::ir::CrashTag;
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr};
+use roc_std::{RocDec, RocDecRounding};
+
+use crate::interp::expected;
+use crate::{Error, HeapData, IntValue, Interpreter, Value};
+
+/// Runs `$body` with `$T` bound to the Rust integer type of `$width`.
+macro_rules! with_int_type {
+    ($width:expr, $T:ident => $body:expr) => {
+        match $width {
+            IntWidth::U8 => {
+                type $T = u8;
+                $body
+            }
+            IntWidth::U16 => {
+                type $T = u16;
+                $body
+            }
+            IntWidth::U32 => {
+                type $T = u32;
+                $body
+            }
+            IntWidth::U64 => {
+                type $T = u64;
+                $body
+            }
+            IntWidth::U128 => {
+                type $T = u128;
+                $body
+            }
+            IntWidth::I8 => {
+                type $T = i8;
+                $body
+            }
+            IntWidth::I16 => {
+                type $T = i16;
+                $body
+            }
+            IntWidth::I32 => {
+                type $T = i32;
+                $body
+            }
+            IntWidth::I64 => {
+                type $T = i64;
+                $body
+            }
+            IntWidth::I128 => {
+                type $T = i128;
+                $body
+            }
+        }
+    };
+}
+
+/// Runs `$body` with `$T` bound to the Rust float type of `$width`.
+macro_rules! with_float_type {
+    ($width:expr, $T:ident => $body:expr) => {
+        match $width {
+            FloatWidth::F32 => {
+                type $T = f32;
+                $body
+            }
+            FloatWidth::F64 => {
+                type $T = f64;
+                $body
+            }
+        }
+    };
+}
+
+impl<'a, 'r> Interpreter<'a, 'r> {
+    /// Runs a low-level operation. Like the builtins, it consumes the arguments that
+    /// [lowlevel_borrow_signature] says it owns, and the result holds its own references to
+    /// anything it shares with the arguments.
+    pub(crate) fn lowlevel(
+        &mut self,
+        op: LowLevel,
+        arguments: Vec<Value<'a>>,
+        argument_layouts: &[InLayout<'a>],
+        ret_layout: InLayout<'a>,
+    ) -> Result<Value<'a>, Error> {
+        use LowLevel::*;
+
+        match op {
+            // These read and write memory directly, without touching any refcounts
+            PtrStore => {
+                let [ptr, value] = arity(&arguments)?;
+                *self.place(ptr)? = value.clone();
+
+                Ok(Value::UNIT)
+            }
+            PtrLoad => {
+                let [ptr] = arity(&arguments)?;
+
+                Ok(self.place(ptr)?.clone())
+            }
+            PtrCast | PtrClearTagId => {
+                let [ptr] = arity(&arguments)?;
+
+                Ok(ptr.clone())
+            }
+            RefCountIsUnique => {
+                let [value] = arity(&arguments)?;
+
+                match value {
+                    Value::Str(addr) | Value::List(addr) | Value::Pointer(addr) => {
+                        Ok(Value::Bool(self.heap.is_unique(*addr)?))
+                    }
+                    _ => Ok(Value::Bool(false)),
+                }
+            }
+            // The element isn't incremented; inc_dec increments the result when it needs to
+            ListGetUnsafe => {
+                let [list, index] = arity(&arguments)?;
+
+                match list {
+                    Value::List(addr) => element(self.heap.list(*addr)?, index),
+                    other => Err(expected("a list", other)),
+                }
+            }
+            ListSortWith => Err(Error::Malformed(
+                "ListSortWith should be a higher-order call".into(),
+            )),
+            BoxExpr | UnboxExpr | ListIncref | ListDecref | RefCountIncRcPtr | RefCountDecRcPtr
+            | RefCountIncDataPtr | RefCountDecDataPtr | SetJmp | LongJmp | SetLongJmpBuffer => Err(
+                Error::Malformed(format!("{op:?} should only be generated by the backends")),
+            ),
+            _ => {
+                let result = self.lowlevel_result(op, &arguments, argument_layouts, ret_layout)?;
+
+                for (argument, ownership) in arguments.iter().zip(lowlevel_borrow_signature(op)) {
+                    if ownership.is_owned() {
+                        self.heap.dec(argument)?;
+                    }
+                }
+
+                Ok(result)
+            }
+        }
+    }
+
+    fn lowlevel_result(
+        &mut self,
+        op: LowLevel,
+        arguments: &[Value<'a>],
+        argument_layouts: &[InLayout<'a>],
+        ret_layout: InLayout<'a>,
+    ) -> Result<Value<'a>, Error> {
+        use LowLevel::*;
+
+        let ret = self.interner.runtime_representation(ret_layout);
+
+        match op {
+            StrConcat => {
+                let [a, b] = arity(arguments)?;
+                let mut bytes = self.str_bytes(a)?;
+                bytes.extend(self.str_bytes(b)?);
+
+                Ok(self.new_str(bytes))
+            }
+            StrJoinWith => {
+                let [list, separator] = arity(arguments)?;
+                let separator = self.str_bytes(separator)?;
+                let mut bytes = Vec::new();
+
+                for (index, string) in self.list_elements(list)?.iter().enumerate() {
+                    if index > 0 {
+                        bytes.extend(&separator);
+                    }
+
+                    bytes.extend(self.str_bytes(string)?);
+                }
+
+                Ok(self.new_str(bytes))
+            }
+            StrIsEmpty => {
+                let [string] = arity(arguments)?;
+
+                Ok(Value::Bool(self.str_bytes(string)?.is_empty()))
+            }
+            StrStartsWith | StrEndsWith => {
+                let [string, affix] = arity(arguments)?;
+                let string = self.str_bytes(string)?;
+                let affix = self.str_bytes(affix)?;
+
+                Ok(Value::Bool(match op {
+                    StrStartsWith => string.starts_with(&affix),
+                    _ => string.ends_with(&affix),
+                }))
+            }
+            StrSplitOn => {
+                let [string, separator] = arity(arguments)?;
+                let string = self.str_bytes(string)?;
+                let separator = self.str_bytes(separator)?;
+                let parts = split(&string, &separator)
+                    .into_iter()
+                    .map(|part| self.new_str(part.to_vec()))
+                    .collect();
+
+                Ok(self.new_list(parts))
+            }
+            StrCountUtf8Bytes => {
+                let [string] = arity(arguments)?;
+                let len = self.str_bytes(string)?.len();
+
+                Ok(int(int_width(ret)?, len as u128))
+            }
+            StrFromUtf8 => {
+                let [list] = arity(arguments)?;
+                let bytes = self.bytes(list)?;
+
+                Ok(self.str_from_utf8(bytes))
+            }
+            StrFromUtf8Lossy => {
+                let [list] = arity(arguments)?;
+                let bytes = self.bytes(list)?;
+                let string = String::from_utf8_lossy(&bytes).into_owned();
+
+                Ok(self.new_str(string.into_bytes()))
+            }
+            StrToUtf8 => {
+                let [string] = arity(arguments)?;
+                let bytes = self.str_bytes(string)?;
+
+                Ok(self.new_list(bytes_to_values(&bytes)))
+            }
+            StrRepeat => {
+                let [string, count] = arity(arguments)?;
+                let bytes = self
+                    .str_bytes(string)?
+                    .repeat(as_int(count)?.bits() as usize);
+
+                Ok(self.new_str(bytes))
+            }
+            StrTrim | StrTrimStart | StrTrimEnd => {
+                let [string] = arity(arguments)?;
+                let string = self.string(string)?;
+                let trimmed = match op {
+                    StrTrim => string.trim(),
+                    StrTrimStart => string.trim_start(),
+                    _ => string.trim_end(),
+                };
+
+                Ok(self.new_str(trimmed.as_bytes().to_vec()))
+            }
+            StrToNum => {
+                let [string] = arity(arguments)?;
+                let string = self.string(string)?;
+
+                self.str_to_num(&string, ret)
+            }
+            StrGetUnsafe => {
+                let [string, index] = arity(arguments)?;
+                let bytes = self.str_bytes(string)?;
+                let index = as_int(index)?.bits() as usize;
+
+                match bytes.get(index) {
+                    Some(byte) => Ok(int(IntWidth::U8, *byte as u128)),
+                    None => Err(Error::Malformed(format!(
+                        "byte {index} of a string of length {}",
+                        bytes.len()
+                    ))),
+                }
+            }
+            StrSubstringUnsafe => {
+                let [string, start, len] = arity(arguments)?;
+                let bytes = self.str_bytes(string)?;
+                let start = as_int(start)?.bits() as usize;
+                let end = start + as_int(len)?.bits() as usize;
+
+                match bytes.get(start..end) {
+                    Some(substring) => Ok(self.new_str(substring.to_vec())),
+                    None => Err(Error::Malformed(format!(
+                        "substring {start}..{end} of a string of length {}",
+                        bytes.len()
+                    ))),
+                }
+            }
+            StrReserve
+            | StrReleaseExcessCapacity
+            | StrWithAsciiLowercased
+            | StrWithAsciiUppercased => {
+                let mut bytes = self.str_bytes(&arguments[0])?;
+
+                match op {
+                    StrWithAsciiLowercased => bytes.make_ascii_lowercase(),
+                    StrWithAsciiUppercased => bytes.make_ascii_uppercase(),
+                    _ => {}
+                }
+
+                Ok(self.new_str(bytes))
+            }
+            StrWithCapacity => Ok(self.new_str(Vec::new())),
+            StrCaselessAsciiEquals => {
+                let [a, b] = arity(arguments)?;
+
+                Ok(Value::Bool(
+                    self.str_bytes(a)?.eq_ignore_ascii_case(&self.str_bytes(b)?),
+                ))
+            }
+
+            ListLenU64 | ListLenUsize => {
+                let [list] = arity(arguments)?;
+                let len = self.list_elements(list)?.len();
+
+                Ok(int(int_width(ret)?, len as u128))
+            }
+            ListGetCapacity => {
+                let [list] = arity(arguments)?;
+                let capacity = self.list_capacity(list)?;
+
+                Ok(int(int_width(ret)?, capacity as u128))
+            }
+            ListIsUnique => {
+                let [list] = arity(arguments)?;
+
+                match list {
+                    Value::List(addr) => Ok(Value::Bool(self.heap.is_unique(*addr)?)),
+                    other => Err(expected("a list", other)),
+                }
+            }
+            ListWithCapacity => {
+                let [capacity] = arity(arguments)?;
+
+                self.list_of_copies(Vec::new(), as_int(capacity)?.bits() as usize)
+            }
+            ListReserve => {
+                let [list, spare] = arity(arguments)?;
+                let elements = self.list_elements(list)?.to_vec();
+                let capacity = self
+                    .list_capacity(list)?
+                    .max(elements.len() + as_int(spare)?.bits() as usize);
+
+                self.list_of_copies(elements, capacity)
+            }
+            ListReleaseExcessCapacity | ListClone => {
+                let [list] = arity(arguments)?;
+                let elements = self.list_elements(list)?.to_vec();
+
+                self.list_of_copies(elements, 0)
+            }
+            ListAppendUnsafe | ListPrepend => {
+                let [list, value] = arity(arguments)?;
+                let mut elements = self.list_elements(list)?.to_vec();
+
+                match op {
+                    ListAppendUnsafe => elements.push(value.clone()),
+                    _ => elements.insert(0, value.clone()),
+                }
+
+                let capacity = self.list_capacity(list)?;
+
+                self.list_of_copies(elements, capacity)
+            }
+            ListReplaceUnsafe => {
+                let [list, index, value] = arity(arguments)?;
+                let mut elements = self.list_elements(list)?.to_vec();
+                let index = as_int(index)?.bits() as usize;
+
+                let old = match elements.get_mut(index) {
+                    Some(old) => std::mem::replace(old, value.clone()),
+                    None => {
+                        return Err(Error::Malformed(format!(
+                            "replace element {index} of a list of length {}",
+                            elements.len()
+                        )))
+                    }
+                };
+                self.heap.inc(&old, 1)?;
+
+                let capacity = self.list_capacity(list)?;
+                let list = self.list_of_copies(elements, capacity)?;
+
+                // the fields of the record are sorted by alignment, so the list may come first
+                // or second
+                match ret {
+                    LayoutRepr::Struct([first, _])
+                        if self.interner.eq_repr(*first, argument_layouts[0]) =>
+                    {
+                        Ok(Value::Struct(vec![list, old]))
+                    }
+                    LayoutRepr::Struct([_, _]) => Ok(Value::Struct(vec![old, list])),
+                    other => Err(Error::Malformed(format!(
+                        "ListReplaceUnsafe returning {other:?}"
+                    ))),
+                }
+            }
+            ListConcat => {
+                let [a, b] = arity(arguments)?;
+                let mut elements = self.list_elements(a)?.to_vec();
+                elements.extend(self.list_elements(b)?.iter().cloned());
+
+                self.list_of_copies(elements, 0)
+            }
+            ListSublist => {
+                let [list, start, len] = arity(arguments)?;
+                let elements = self.list_elements(list)?;
+                let start = (as_int(start)?.bits() as usize).min(elements.len());
+                let end = start + (as_int(len)?.bits() as usize).min(elements.len() - start);
+                let elements = elements[start..end].to_vec();
+
+                self.list_of_copies(elements, 0)
+            }
+            ListDropAt => {
+                let [list, index] = arity(arguments)?;
+                let mut elements = self.list_elements(list)?.to_vec();
+                let index = as_int(index)?.bits() as usize;
+
+                if index < elements.len() {
+                    elements.remove(index);
+                }
+
+                self.list_of_copies(elements, 0)
+            }
+            ListSwap => {
+                let [list, a, b] = arity(arguments)?;
+                let mut elements = self.list_elements(list)?.to_vec();
+                let a = as_int(a)?.bits() as usize;
+                let b = as_int(b)?.bits() as usize;
+
+                if a < elements.len() && b < elements.len() {
+                    elements.swap(a, b);
+                }
+
+                self.list_of_copies(elements, 0)
+            }
+            ListConcatUtf8 => {
+                let [list, string] = arity(arguments)?;
+                let elements = self.list_elements(list)?.to_vec();
+                let bytes = self.str_bytes(string)?;
+
+                let list = self.list_of_copies(elements, 0)?;
+
+                if let Value::List(addr) = list {
+                    if let HeapData::List { elements, .. } = self.heap.get_mut(addr)? {
+                        elements.extend(bytes_to_values(&bytes));
+                    }
+                }
+
+                Ok(list)
+            }
+
+            NumAdd | NumAddWrap | NumAddChecked | NumAddSaturated | NumSub | NumSubWrap
+            | NumSubChecked | NumSubSaturated | NumMul | NumMulWrap | NumMulSaturated
+            | NumMulChecked | NumGt | NumGte | NumLt | NumLte | NumCompare | NumDivFrac
+            | NumDivTruncUnchecked | NumDivCeilUnchecked | NumRemUnchecked | NumIsMultipleOf
+            | NumPow | NumPowInt | NumBitwiseAnd | NumBitwiseXor | NumBitwiseOr => {
+                let [a, b] = arity(arguments)?;
+
+                match (a, b) {
+                    (Value::Int(a), Value::Int(b)) => int_binop(op, *a, *b),
+                    (Value::Float(width, a), Value::Float(_, b)) => float_binop(op, *width, *a, *b),
+                    (Value::Dec(a), Value::Dec(b)) => dec_binop(op, *a, *b),
+                    _ => Err(Error::Malformed(format!("{op:?} on {a:?} and {b:?}"))),
+                }
+            }
+            NumShiftLeftBy | NumShiftRightBy | NumShiftRightZfBy => {
+                let [a, b] = arity(arguments)?;
+
+                Ok(Value::Int(shift(op, as_int(a)?, as_int(b)?.bits())))
+            }
+            NumAbs
+            | NumNeg
+            | NumSin
+            | NumCos
+            | NumTan
+            | NumSqrtUnchecked
+            | NumLogUnchecked
+            | NumRound
+            | NumCeiling
+            | NumFloor
+            | NumToFrac
+            | NumIsNan
+            | NumIsInfinite
+            | NumIsFinite
+            | NumAtan
+            | NumAcos
+            | NumAsin
+            | NumIntCast
+            | NumToIntChecked
+            | NumToFloatCast
+            | NumToStr
+            | NumCountLeadingZeroBits
+            | NumCountTrailingZeroBits
+            | NumCountOneBits
+            | NumWithoutDecimalPoint
+            | NumWithDecimalPoint
+            | NumF32ToBits
+            | NumF64ToBits
+            | NumDecToBits
+            | NumF32FromBits
+            | NumF64FromBits
+            | NumDecFromBits => {
+                let [a] = arity(arguments)?;
+
+                match a {
+                    Value::Int(a) => self.int_unary(op, *a, ret),
+                    Value::Float(width, a) => float_unary(op, *width, *a, ret),
+                    Value::Dec(a) => self.dec_unary(op, *a, ret),
+                    other => Err(expected("a number", other)),
+                }
+            }
+
+            Eq | NotEq => {
+                let [a, b] = arity(arguments)?;
+
+                Ok(Value::Bool(self.equal(a, b)? == (op == Eq)))
+            }
+            Not => match arity(arguments)? {
+                [Value::Bool(bool)] => Ok(Value::Bool(!bool)),
+                [other] => Err(expected("a Bool", other)),
+            },
+            // Dicts work with any seed, so a constant one keeps runs reproducible
+            DictPseudoSeed => Ok(int(int_width(ret)?, 0x5eed)),
+            Unreachable => Err(crash("Reached unreachable code")),
+
+            _ => Err(Error::Unsupported(format!(
+                "the low-level operation {op:?}"
+            ))),
+        }
+    }
+
+    fn int_unary(
+        &mut self,
+        op: LowLevel,
+        a: IntValue,
+        ret: LayoutRepr<'a>,
+    ) -> Result<Value<'a>, Error> {
+        use LowLevel::*;
+
+        let size = IntValue::bit_size(a.width);
+
+        match op {
+            NumAbs if !a.width.is_signed() => Ok(Value::Int(a)),
+            NumAbs => {
+                let abs = IntValue::from_i128(a.width, a.as_i128().wrapping_abs());
+
+                if abs.is_negative() {
+                    Err(crash(
+                        "Integer absolute overflowed because its argument is the minimum value",
+                    ))
+                } else {
+                    Ok(Value::Int(abs))
+                }
+            }
+            NumNeg => {
+                let negated = IntValue::from_i128(a.width, a.as_i128().wrapping_neg());
+
+                if a.bits() != 0 && (!a.width.is_signed() || negated == a) {
+                    Err(crash(
+                        "Integer negation overflowed because its argument is the minimum value",
+                    ))
+                } else {
+                    Ok(Value::Int(negated))
+                }
+            }
+            NumCountLeadingZeroBits => {
+                let count = a.bits().leading_zeros() - (128 - size);
+
+                Ok(int(int_width(ret)?, count as u128))
+            }
+            NumCountTrailingZeroBits => {
+                let count = a.bits().trailing_zeros().min(size);
+
+                Ok(int(int_width(ret)?, count as u128))
+            }
+            NumCountOneBits => Ok(int(int_width(ret)?, a.bits().count_ones() as u128)),
+            NumIntCast => Ok(Value::Int(a.cast(int_width(ret)?))),
+            NumToIntChecked => {
+                let width = int_width(self.struct_field(ret, 0)?)?;
+                let cast = a.cast(width);
+
+                Ok(Value::Struct(vec![
+                    Value::Int(cast),
+                    Value::Bool(!cast.same_number(a)),
+                ]))
+            }
+            NumToFrac | NumToFloatCast => match ret {
+                LayoutRepr::Builtin(Builtin::Float(width)) => Ok(float(width, a.to_f64())),
+                LayoutRepr::Builtin(Builtin::Decimal)
+                    if !a.is_negative() || a.width.is_signed() =>
+                {
+                    RocDec::try_from(a.as_i128())
+                        .map(Value::Dec)
+                        .map_err(|_| crash("Decimal conversion overflowed!"))
+                }
+                other => Err(Error::Malformed(format!("{op:?} returning {other:?}"))),
+            },
+            NumToStr => Ok(self.new_str(a.to_string().into_bytes())),
+            NumWithDecimalPoint | NumDecFromBits => Ok(Value::Dec(RocDec::new(a.as_i128()))),
+            NumF32FromBits => Ok(float(
+                FloatWidth::F32,
+                f32::from_bits(a.bits() as u32) as f64,
+            )),
+            NumF64FromBits => Ok(float(FloatWidth::F64, f64::from_bits(a.bits() as u64))),
+            _ => Err(Error::Malformed(format!("{op:?} on {a:?}"))),
+        }
+    }
+
+    fn dec_unary(
+        &mut self,
+        op: LowLevel,
+        a: RocDec,
+        ret: LayoutRepr<'a>,
+    ) -> Result<Value<'a>, Error> {
+        use LowLevel::*;
+
+        let zero = RocDec::default();
+        let raw = i128::from_ne_bytes(a.to_ne_bytes());
+
+        match op {
+            NumAbs if a >= zero => Ok(Value::Dec(a)),
+            NumAbs | NumNeg => a
+                .checked_neg()
+                .map(Value::Dec)
+                .ok_or_else(|| crash("Decimal negation overflowed!")),
+            NumSin => dec_from_f64(a.to_f64().sin()),
+            NumCos => dec_from_f64(a.to_f64().cos()),
+            NumTan => dec_from_f64(a.to_f64().tan()),
+            NumAtan => dec_from_f64(a.to_f64().atan()),
+            NumAcos => dec_from_f64(a.to_f64().acos()),
+            NumAsin => dec_from_f64(a.to_f64().asin()),
+            NumSqrtUnchecked => dec_from_f64(a.to_f64().sqrt()),
+            NumLogUnchecked => dec_from_f64(a.to_f64().ln()),
+            NumRound | NumFloor | NumCeiling => {
+                let rounding = match op {
+                    NumRound => RocDecRounding::HalfAwayFromZero,
+                    NumFloor => RocDecRounding::Down,
+                    _ => RocDecRounding::Up,
+                };
+
+                Ok(Value::Int(IntValue::from_i128(
+                    int_width(ret)?,
+                    a.to_i128(rounding),
+                )))
+            }
+            NumToFloatCast => match ret {
+                LayoutRepr::Builtin(Builtin::Float(width)) => Ok(float(width, a.to_f64())),
+                other => Err(Error::Malformed(format!("{op:?} returning {other:?}"))),
+            },
+            NumToStr => Ok(self.new_str(a.to_string().into_bytes())),
+            NumWithoutDecimalPoint | NumDecToBits => {
+                Ok(Value::Int(IntValue::from_i128(int_width(ret)?, raw)))
+            }
+            _ => Err(Error::Malformed(format!("{op:?} on {a:?}"))),
+        }
+    }
+
+    /// Structural equality, which looks through the heap
+    fn equal(&self, a: &Value<'a>, b: &Value<'a>) -> Result<bool, Error> {
+        let all_equal = |xs: &[Value<'a>], ys: &[Value<'a>]| -> Result<bool, Error> {
+            if xs.len() != ys.len() {
+                return Ok(false);
+            }
+
+            for (x, y) in xs.iter().zip(ys) {
+                if !self.equal(x, y)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        };
+
+        match (a, b) {
+            (Value::Str(x), Value::Str(y)) => Ok(self.heap.str(*x)? == self.heap.str(*y)?),
+            (Value::List(x), Value::List(y)) => all_equal(self.heap.list(*x)?, self.heap.list(*y)?),
+            (Value::Struct(xs), Value::Struct(ys)) => all_equal(xs, ys),
+            (Value::Union(x, xs), Value::Union(y, ys)) => Ok(x == y && all_equal(xs, ys)?),
+            (Value::Pointer(x), Value::Pointer(y)) => {
+                let (x, xs) = self.heap.tag(*x)?;
+                let (y, ys) = self.heap.tag(*y)?;
+
+                Ok(x == y && all_equal(xs, ys)?)
+            }
+            (Value::Pointer(_), Value::Null) | (Value::Null, Value::Pointer(_)) => Ok(false),
+            _ => Ok(a == b),
+        }
+    }
+
+    fn str_from_utf8(&mut self, bytes: Vec<u8>) -> Value<'a> {
+        let (string, byte_index, is_ok, problem) = match std::str::from_utf8(&bytes) {
+            Ok(_) => (self.new_str(bytes), 0, true, 0),
+            Err(error) => {
+                let index = error.valid_up_to();
+                let problem = utf8_problem(&bytes[index..], error.error_len());
+
+                (self.new_str(Vec::new()), index, false, problem)
+            }
+        };
+
+        // { a_byte_index : U64, b_string : Str, c_is_ok : Bool, d_problem_code : U8 }
+        Value::Struct(vec![
+            int(IntWidth::U64, byte_index as u128),
+            string,
+            Value::Bool(is_ok),
+            int(IntWidth::U8, problem),
+        ])
+    }
+
+    fn str_to_num(&self, string: &str, ret: LayoutRepr<'a>) -> Result<Value<'a>, Error> {
+        let (parsed, zero) = match self.struct_field(ret, 0)? {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
+                (parse_int(width, string).map(Value::Int), int(width, 0))
+            }
+            LayoutRepr::Builtin(Builtin::Float(width)) => (
+                string.parse().ok().map(|value| float(width, value)),
+                float(width, 0.0),
+            ),
+            LayoutRepr::Builtin(Builtin::Decimal) => (
+                RocDec::from_str(string).map(Value::Dec),
+                Value::Dec(RocDec::default()),
+            ),
+            other => return Err(Error::Malformed(format!("StrToNum returning {other:?}"))),
+        };
+
+        // { aresult : Num *, berrorcode : U8 }
+        Ok(match parsed {
+            Some(number) => Value::Struct(vec![number, int(IntWidth::U8, 0)]),
+            None => Value::Struct(vec![zero, int(IntWidth::U8, 1)]),
+        })
+    }
+
+    /// The value that a `PtrStore` or `PtrLoad` of `ptr` accesses
+    fn place(&mut self, ptr: &Value<'a>) -> Result<&mut Value<'a>, Error> {
+        match ptr {
+            Value::Ref(addr, field) => match (self.heap.get_mut(*addr)?, field) {
+                (HeapData::Cell(value), None) => Ok(value),
+                (HeapData::Tag { fields, .. }, Some(index)) => {
+                    let len = fields.len();
+
+                    fields.get_mut(*index).ok_or_else(|| {
+                        Error::Malformed(format!("no field {index} in a tag with {len} fields"))
+                    })
+                }
+                (data, _) => Err(Error::Malformed(format!(
+                    "{ptr:?} doesn't point into {data:?}"
+                ))),
+            },
+            other => Err(expected("a pointer", other)),
+        }
+    }
+
+    fn struct_field(&self, repr: LayoutRepr<'a>, index: usize) -> Result<LayoutRepr<'a>, Error> {
+        match repr {
+            LayoutRepr::Struct(fields) if index < fields.len() => {
+                Ok(self.interner.runtime_representation(fields[index]))
+            }
+            other => Err(Error::Malformed(format!(
+                "expected a struct with a field {index}, got {other:?}"
+            ))),
+        }
+    }
+
+    fn new_str(&mut self, bytes: Vec<u8>) -> Value<'a> {
+        Value::Str(self.heap.alloc(HeapData::Str(bytes)))
+    }
+
+    /// A new list of `elements`, which are copied from the arguments and so get an extra
+    /// reference each
+    fn list_of_copies(
+        &mut self,
+        elements: Vec<Value<'a>>,
+        capacity: usize,
+    ) -> Result<Value<'a>, Error> {
+        for element in elements.iter() {
+            self.heap.inc(element, 1)?;
+        }
+
+        let capacity = capacity.max(elements.len());
+
+        Ok(Value::List(
+            self.heap.alloc(HeapData::List { elements, capacity }),
+        ))
+    }
+
+    fn str_bytes(&self, value: &Value<'a>) -> Result<Vec<u8>, Error> {
+        match value {
+            Value::Str(addr) => Ok(self.heap.str(*addr)?.to_vec()),
+            other => Err(expected("a string", other)),
+        }
+    }
+
+    fn string(&self, value: &Value<'a>) -> Result<String, Error> {
+        String::from_utf8(self.str_bytes(value)?)
+            .map_err(|error| Error::Malformed(format!("a string that isn't UTF-8: {error}")))
+    }
+
+    fn list_elements(&self, value: &Value<'a>) -> Result<&[Value<'a>], Error> {
+        match value {
+            Value::List(addr) => self.heap.list(*addr),
+            other => Err(expected("a list", other)),
+        }
+    }
+
+    fn list_capacity(&self, value: &Value<'a>) -> Result<usize, Error> {
+        match value {
+            Value::List(addr) => match self.heap.get(*addr)? {
+                HeapData::List { capacity, .. } => Ok(*capacity),
+                other => Err(Error::Malformed(format!("expected a list, got {other:?}"))),
+            },
+            other => Err(expected("a list", other)),
+        }
+    }
+
+    /// The bytes of a `List U8`
+    fn bytes(&self, value: &Value<'a>) -> Result<Vec<u8>, Error> {
+        self.list_elements(value)?
+            .iter()
+            .map(|byte| as_int(byte).map(|byte| byte.bits() as u8))
+            .collect()
+    }
+}
+
+fn int_binop<'a>(op: LowLevel, a: IntValue, b: IntValue) -> Result<Value<'a>, Error> {
+    use LowLevel::*;
+
+    let width = a.width;
+
+    with_int_type!(width, T => {
+        let x = a.bits() as T;
+        let y = b.bits() as T;
+
+        let int = |n: T| Value::Int(IntValue::from_bits(width, n as u128));
+        let checked = |n: Option<T>, message: &str| n.map(int).ok_or_else(|| crash(message));
+        let overflowing =
+            |(n, overflowed): (T, bool)| Value::Struct(vec![int(n), Value::Bool(overflowed)]);
+
+        match op {
+            NumAdd => checked(x.checked_add(y), "Integer addition overflowed!"),
+            NumSub => checked(x.checked_sub(y), "Integer subtraction overflowed!"),
+            NumMul => checked(x.checked_mul(y), "Integer multiplication overflowed!"),
+            NumAddWrap => Ok(int(x.wrapping_add(y))),
+            NumSubWrap => Ok(int(x.wrapping_sub(y))),
+            NumMulWrap => Ok(int(x.wrapping_mul(y))),
+            NumAddSaturated => Ok(int(x.saturating_add(y))),
+            NumSubSaturated => Ok(int(x.saturating_sub(y))),
+            NumMulSaturated => Ok(int(x.saturating_mul(y))),
+            NumAddChecked => Ok(overflowing(x.overflowing_add(y))),
+            NumSubChecked => Ok(overflowing(x.overflowing_sub(y))),
+            NumMulChecked => Ok(overflowing(x.overflowing_mul(y))),
+            NumGt => Ok(Value::Bool(x > y)),
+            NumGte => Ok(Value::Bool(x >= y)),
+            NumLt => Ok(Value::Bool(x < y)),
+            NumLte => Ok(Value::Bool(x <= y)),
+            NumCompare => Ok(order(x.cmp(&y))),
+            NumDivTruncUnchecked | NumDivCeilUnchecked | NumRemUnchecked if y == 0 => {
+                Err(crash("Integer division by 0!"))
+            }
+            NumDivTruncUnchecked => Ok(int(x.wrapping_div(y))),
+            NumRemUnchecked => Ok(int(x.wrapping_rem(y))),
+            NumDivCeilUnchecked => {
+                let quotient = x.wrapping_div(y);
+                let remainder = x.wrapping_rem(y);
+
+                if remainder != 0 && (remainder > 0) == (y > 0) {
+                    Ok(int(quotient + 1))
+                } else {
+                    Ok(int(quotient))
+                }
+            }
+            NumIsMultipleOf => Ok(Value::Bool(if y == 0 {
+                x == 0
+            } else {
+                x.wrapping_rem(y) == 0
+            })),
+            NumBitwiseAnd => Ok(int(x & y)),
+            NumBitwiseXor => Ok(int(x ^ y)),
+            NumBitwiseOr => Ok(int(x | y)),
+            NumPowInt => match u32::try_from(b.as_i128()) {
+                Ok(exponent) => checked(x.checked_pow(exponent), "Integer raised to power overflowed!"),
+                Err(_) => Err(crash("Integer raised to power overflowed!")),
+            },
+            _ => Err(Error::Malformed(format!("{op:?} on {a:?} and {b:?}"))),
+        }
+    })
+}
+
+fn float_binop<'a>(op: LowLevel, width: FloatWidth, a: f64, b: f64) -> Result<Value<'a>, Error> {
+    use LowLevel::*;
+
+    with_float_type!(width, T => {
+        let x = a as T;
+        let y = b as T;
+
+        let float = |n: T| Value::Float(width, n as f64);
+        let overflowing = |n: T| Value::Struct(vec![float(n), Value::Bool(!n.is_finite())]);
+
+        match op {
+            NumAdd | NumAddWrap | NumAddSaturated => Ok(float(x + y)),
+            NumSub | NumSubWrap | NumSubSaturated => Ok(float(x - y)),
+            NumMul | NumMulWrap | NumMulSaturated => Ok(float(x * y)),
+            NumAddChecked => Ok(overflowing(x + y)),
+            NumSubChecked => Ok(overflowing(x - y)),
+            NumMulChecked => Ok(overflowing(x * y)),
+            NumDivFrac => Ok(float(x / y)),
+            NumPow => Ok(float(x.powf(y))),
+            NumGt => Ok(Value::Bool(x > y)),
+            NumGte => Ok(Value::Bool(x >= y)),
+            NumLt => Ok(Value::Bool(x < y)),
+            NumLte => Ok(Value::Bool(x <= y)),
+            NumCompare => Ok(order(if x < y {
+                Ordering::Less
+            } else if x > y {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            })),
+            _ => Err(Error::Malformed(format!("{op:?} on {a:?} and {b:?}"))),
+        }
+    })
+}
+
+fn dec_binop<'a>(op: LowLevel, x: RocDec, y: RocDec) -> Result<Value<'a>, Error> {
+    use LowLevel::*;
+
+    let zero = RocDec::default();
+    let checked =
+        |n: Option<RocDec>, message: &str| n.map(Value::Dec).ok_or_else(|| crash(message));
+    let overflowing = |n: Option<RocDec>, wrapped: RocDec| {
+        Value::Struct(vec![
+            Value::Dec(n.unwrap_or(wrapped)),
+            Value::Bool(n.is_none()),
+        ])
+    };
+
+    match op {
+        NumAdd => checked(x.checked_add(y), "Decimal addition overflowed!"),
+        NumSub => checked(x.checked_sub(y), "Decimal subtraction overflowed!"),
+        NumMul => checked(x.checked_mul(y), "Decimal multiplication overflowed!"),
+        NumAddWrap => Ok(Value::Dec(x.wrapping_add(y))),
+        NumSubWrap => Ok(Value::Dec(x.wrapping_sub(y))),
+        NumMulWrap => Ok(Value::Dec(x.wrapping_mul(y))),
+        NumAddChecked => Ok(overflowing(x.checked_add(y), x.wrapping_add(y))),
+        NumSubChecked => Ok(overflowing(x.checked_sub(y), x.wrapping_sub(y))),
+        NumMulChecked => Ok(overflowing(x.checked_mul(y), x.wrapping_mul(y))),
+        NumAddSaturated => Ok(Value::Dec(x.checked_add(y).unwrap_or(if y > zero {
+            RocDec::MAX
+        } else {
+            RocDec::MIN
+        }))),
+        NumSubSaturated => Ok(Value::Dec(x.checked_sub(y).unwrap_or(if y > zero {
+            RocDec::MIN
+        } else {
+            RocDec::MAX
+        }))),
+        NumMulSaturated => Ok(Value::Dec(x.checked_mul(y).unwrap_or(
+            if (x < zero) == (y < zero) {
+                RocDec::MAX
+            } else {
+                RocDec::MIN
+            },
+        ))),
+        NumDivFrac if y == zero => Err(crash("Decimal division by 0!")),
+        NumDivFrac => checked(x.checked_div(y), "Decimal division overflowed!"),
+        NumPow => dec_from_f64(x.to_f64().powf(y.to_f64())),
+        NumGt => Ok(Value::Bool(x > y)),
+        NumGte => Ok(Value::Bool(x >= y)),
+        NumLt => Ok(Value::Bool(x < y)),
+        NumLte => Ok(Value::Bool(x <= y)),
+        NumCompare => Ok(order(x.cmp(&y))),
+        _ => Err(Error::Malformed(format!("{op:?} on {x:?} and {y:?}"))),
+    }
+}
+
+fn float_unary<'a>(
+    op: LowLevel,
+    width: FloatWidth,
+    a: f64,
+    ret: LayoutRepr<'a>,
+) -> Result<Value<'a>, Error> {
+    use LowLevel::*;
+
+    match op {
+        NumAbs => Ok(float(width, a.abs())),
+        NumNeg => Ok(float(width, -a)),
+        NumSin => Ok(float(width, a.sin())),
+        NumCos => Ok(float(width, a.cos())),
+        NumTan => Ok(float(width, a.tan())),
+        NumAtan => Ok(float(width, a.atan())),
+        NumAcos => Ok(float(width, a.acos())),
+        NumAsin => Ok(float(width, a.asin())),
+        NumSqrtUnchecked => Ok(float(width, a.sqrt())),
+        NumLogUnchecked => Ok(float(width, a.ln())),
+        NumIsNan => Ok(Value::Bool(a.is_nan())),
+        NumIsInfinite => Ok(Value::Bool(a.is_infinite())),
+        NumIsFinite => Ok(Value::Bool(a.is_finite())),
+        NumRound | NumFloor | NumCeiling => {
+            let rounded = match op {
+                NumRound => a.round(),
+                NumFloor => a.floor(),
+                _ => a.ceil(),
+            };
+
+            Ok(Value::Int(IntValue::from_i128(
+                int_width(ret)?,
+                rounded as i128,
+            )))
+        }
+        NumToFloatCast => match ret {
+            LayoutRepr::Builtin(Builtin::Float(width)) => Ok(float(width, a)),
+            LayoutRepr::Builtin(Builtin::Decimal) => dec_from_f64(a),
+            other => Err(Error::Malformed(format!("{op:?} returning {other:?}"))),
+        },
+        NumF32ToBits => Ok(int(IntWidth::U32, (a as f32).to_bits() as u128)),
+        NumF64ToBits => Ok(int(IntWidth::U64, a.to_bits() as u128)),
+        NumToStr => Err(Error::Unsupported("converting floats to strings".into())),
+        _ => Err(Error::Malformed(format!("{op:?} on {a:?}"))),
+    }
+}
+
+fn shift(op: LowLevel, a: IntValue, amount: u128) -> IntValue {
+    let size = IntValue::bit_size(a.width) as u128;
+
+    match op {
+        LowLevel::NumShiftLeftBy if amount < size => {
+            IntValue::from_bits(a.width, a.bits() << amount)
+        }
+        LowLevel::NumShiftRightBy if a.width.is_signed() => {
+            IntValue::from_i128(a.width, a.as_i128() >> amount.min(127))
+        }
+        LowLevel::NumShiftRightBy | LowLevel::NumShiftRightZfBy if amount < size => {
+            IntValue::from_bits(a.width, a.bits() >> amount)
+        }
+        _ => IntValue::from_bits(a.width, 0),
+    }
+}
+
+fn parse_int(width: IntWidth, string: &str) -> Option<IntValue> {
+    if width.is_signed() {
+        let value = string.parse::<i128>().ok()?;
+        let int = IntValue::from_i128(width, value);
+
+        (int.as_i128() == value).then_some(int)
+    } else {
+        let value = string.parse::<u128>().ok()?;
+        let int = IntValue::from_bits(width, value);
+
+        (int.bits() == value).then_some(int)
+    }
+}
+
+/// Splits `bytes` on every occurrence of `separator`, like `Str.split_on`
+fn split<'b>(bytes: &'b [u8], separator: &[u8]) -> Vec<&'b [u8]> {
+    if separator.is_empty() {
+        return vec![bytes];
+    }
+
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut index = 0;
+
+    while index + separator.len() <= bytes.len() {
+        if bytes[index..].starts_with(separator) {
+            parts.push(&bytes[start..index]);
+            index += separator.len();
+            start = index;
+        } else {
+            index += 1;
+        }
+    }
+
+    parts.push(&bytes[start..]);
+
+    parts
+}
+
+/// The tag id of the `Utf8Problem` for the invalid bytes at the start of `invalid`. The tags are
+/// sorted by name: CodepointTooLarge, EncodesSurrogateHalf, ExpectedContinuation,
+/// InvalidStartByte, OverlongEncoding, UnexpectedEndOfSequence.
+fn utf8_problem(invalid: &[u8], error_len: Option<usize>) -> u128 {
+    const CODEPOINT_TOO_LARGE: u128 = 0;
+    const ENCODES_SURROGATE_HALF: u128 = 1;
+    const EXPECTED_CONTINUATION: u128 = 2;
+    const INVALID_START_BYTE: u128 = 3;
+    const OVERLONG_ENCODING: u128 = 4;
+    const UNEXPECTED_END_OF_SEQUENCE: u128 = 5;
+
+    if error_len.is_none() {
+        return UNEXPECTED_END_OF_SEQUENCE;
+    }
+
+    match invalid {
+        [0x80..=0xBF, ..] | [0xF8..=0xFF, ..] => INVALID_START_BYTE,
+        [0xC0 | 0xC1, ..] | [0xE0, 0x80..=0x9F, ..] | [0xF0, 0x80..=0x8F, ..] => OVERLONG_ENCODING,
+        [0xED, 0xA0..=0xBF, ..] => ENCODES_SURROGATE_HALF,
+        [0xF4, 0x90..=0xBF, ..] | [0xF5..=0xF7, ..] => CODEPOINT_TOO_LARGE,
+        _ => EXPECTED_CONTINUATION,
+    }
+}
+
+fn bytes_to_values<'a>(bytes: &[u8]) -> Vec<Value<'a>> {
+    bytes
+        .iter()
+        .map(|byte| int(IntWidth::U8, *byte as u128))
+        .collect()
+}
+
+fn arity<'v, 'a, const N: usize>(arguments: &'v [Value<'a>]) -> Result<&'v [Value<'a>; N], Error> {
+    arguments
+        .try_into()
+        .map_err(|_| Error::Malformed(format!("expected {N} arguments, got {}", arguments.len())))
+}
+
+fn as_int(value: &Value) -> Result<IntValue, Error> {
+    match value {
+        Value::Int(int) => Ok(*int),
+        other => Err(expected("an integer", other)),
+    }
+}
+
+fn element<'a>(elements: &[Value<'a>], index: &Value<'a>) -> Result<Value<'a>, Error> {
+    let index = as_int(index)?.bits() as usize;
+
+    elements.get(index).cloned().ok_or_else(|| {
+        Error::Malformed(format!(
+            "element {index} of a list of length {}",
+            elements.len()
+        ))
+    })
+}
+
+fn int_width(repr: LayoutRepr) -> Result<IntWidth, Error> {
+    match repr {
+        LayoutRepr::Builtin(Builtin::Int(width)) => Ok(width),
+        other => Err(Error::Malformed(format!(
+            "expected an integer layout, got {other:?}"
+        ))),
+    }
+}
+
+fn int<'a>(width: IntWidth, bits: u128) -> Value<'a> {
+    Value::Int(IntValue::from_bits(width, bits))
+}
+
+/// A float of the given width; `F32`s are rounded to the nearest `f32`
+pub(crate) fn float<'a>(width: FloatWidth, value: f64) -> Value<'a> {
+    match width {
+        FloatWidth::F32 => Value::Float(width, value as f32 as f64),
+        FloatWidth::F64 => Value::Float(width, value),
+    }
+}
+
+fn dec_from_f64<'a>(value: f64) -> Result<Value<'a>, Error> {
+    RocDec::from_f64(value, RocDecRounding::TowardZero)
+        .map(Value::Dec)
+        .ok_or_else(|| crash("Decimal conversion overflowed!"))
+}
+
+/// A value of the builtin `Order` type, whose tags are sorted by name: EQ, GT, LT
+fn order<'a>(ordering: Ordering) -> Value<'a> {
+    let tag_id = match ordering {
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+        Ordering::Less => 2,
+    };
+
+    int(IntWidth::U8, tag_id)
+}
+
+pub(crate) fn order_from_value(value: &Value) -> Result<Ordering, Error> {
+    match as_int(value)?.bits() {
+        0 => Ok(Ordering::Equal),
+        1 => Ok(Ordering::Greater),
+        2 => Ok(Ordering::Less),
+        other => Err(Error::Malformed(format!("{other} is not an Order"))),
+    }
+}
+
+fn crash(message: &str) -> Error {
+    Error::Crash {
+        message: message.into(),
+        tag: CrashTag::Roc,
+    }
+}
@@ -0,0 +1,134 @@
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_mono::layout::{LambdaName, TagIdIntType};
+use roc_std::RocDec;
+
+use crate::heap::Addr;
+
+/// A value computed by the interpreter.
+///
+/// Values that compiled code keeps on the heap (strings, lists, boxes and the tags of recursive
+/// tag unions) point into the interpreter's [Heap](crate::Heap), where their refcounts are tracked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    Int(IntValue),
+    /// An `F32` is stored as the `f64` with the same value
+    Float(FloatWidth, f64),
+    Dec(RocDec),
+    Bool(bool),
+    Str(Addr),
+    List(Addr),
+    Struct(Vec<Value<'a>>),
+    /// A value of a non-recursive tag union, which compiled code keeps on the stack
+    Union(TagIdIntType, Vec<Value<'a>>),
+    /// A box, or a value of a recursive tag union
+    Pointer(Addr),
+    /// The empty tag of a nullable tag union, or a reuse token for memory that can't be reused
+    Null,
+    /// A pointer to the memory of an `Alloca`, or to a field of a tag from `GetElementPointer`
+    Ref(Addr, Option<usize>),
+    FunctionPointer(LambdaName<'a>),
+}
+
+impl<'a> Value<'a> {
+    pub const UNIT: Self = Value::Struct(Vec::new());
+
+    /// Calls `f` on each heap allocation this value points to, without looking inside them.
+    pub(crate) fn for_each_addr(&self, f: &mut impl FnMut(Addr)) {
+        match self {
+            Value::Str(addr) | Value::List(addr) | Value::Pointer(addr) => f(*addr),
+            Value::Struct(fields) | Value::Union(_, fields) => {
+                for field in fields {
+                    field.for_each_addr(f);
+                }
+            }
+            Value::Int(_)
+            | Value::Float(..)
+            | Value::Dec(_)
+            | Value::Bool(_)
+            | Value::Null
+            | Value::Ref(..)
+            | Value::FunctionPointer(_) => {}
+        }
+    }
+}
+
+/// An integer of any width, stored as its two's complement bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntValue {
+    pub width: IntWidth,
+    bits: u128,
+}
+
+impl IntValue {
+    /// Truncates `bits` to `width`.
+    pub fn from_bits(width: IntWidth, bits: u128) -> Self {
+        let size = Self::bit_size(width);
+        let bits = if size == 128 {
+            bits
+        } else {
+            bits & ((1 << size) - 1)
+        };
+
+        Self { width, bits }
+    }
+
+    /// Wraps `value` around to `width`.
+    pub fn from_i128(width: IntWidth, value: i128) -> Self {
+        Self::from_bits(width, value as u128)
+    }
+
+    /// The bits, zero-extended to 128 bits
+    pub fn bits(self) -> u128 {
+        self.bits
+    }
+
+    /// The bits, sign-extended to 128 bits if the width is signed.
+    /// `U128` values above `i128::MAX` come out negative.
+    pub fn as_i128(self) -> i128 {
+        let size = Self::bit_size(self.width);
+
+        if self.width.is_signed() && size < 128 {
+            let shift = 128 - size;
+            ((self.bits << shift) as i128) >> shift
+        } else {
+            self.bits as i128
+        }
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.width.is_signed() && self.as_i128() < 0
+    }
+
+    /// Converts to `width` the way `NumIntCast` does: sign-extending signed values, and
+    /// truncating when `width` is smaller.
+    pub fn cast(self, width: IntWidth) -> Self {
+        Self::from_i128(width, self.as_i128())
+    }
+
+    /// Whether both are the same number, regardless of their widths
+    pub fn same_number(self, other: Self) -> bool {
+        self.is_negative() == other.is_negative() && self.as_i128() == other.as_i128()
+    }
+
+    pub fn to_f64(self) -> f64 {
+        if self.width.is_signed() {
+            self.as_i128() as f64
+        } else {
+            self.bits as f64
+        }
+    }
+
+    pub(crate) fn bit_size(width: IntWidth) -> u32 {
+        width.stack_size() * 8
+    }
+}
+
+impl std::fmt::Display for IntValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.width.is_signed() {
+            write!(f, "{}", self.as_i128())
+        } else {
+            write!(f, "{}", self.bits)
+        }
+    }
+}
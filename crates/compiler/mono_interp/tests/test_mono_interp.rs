@@ -0,0 +1,366 @@
+#![warn(clippy::dbg_macro)]
+// See github.com/roc-lang/roc/issues/800 for discussion of the large_enum_variant check.
+#![allow(clippy::large_enum_variant)]
+#![allow(clippy::missing_safety_doc)]
+
+use core::ffi::c_void;
+
+use bumpalo::Bump;
+use indoc::indoc;
+use roc_load::{EntryPoint, ExecutionMode, FunctionKind, LoadConfig, Threading};
+use roc_mono::inline::InlineBudget;
+use roc_mono::ir::CrashTag;
+use roc_mono_interp::{Error, Heap, HeapData, Interpreter, Value};
+use roc_packaging::cache::RocCacheDir;
+
+// roc_std's strings and lists, which the interpreter doesn't use, still need these to link
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_alloc(size: usize, _alignment: u32) -> *mut c_void {
+    libc::malloc(size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_realloc(
+    c_ptr: *mut c_void,
+    new_size: usize,
+    _old_size: usize,
+    _alignment: u32,
+) -> *mut c_void {
+    libc::realloc(c_ptr, new_size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
+    libc::free(c_ptr)
+}
+
+fn promote_expr_to_module(src: &str) -> String {
+    let mut buffer = String::from("app \"test\" provides [main] to \"./platform\"\n\nmain =\n");
+
+    for line in src.lines() {
+        // indent the body!
+        buffer.push_str("    ");
+        buffer.push_str(line);
+        buffer.push('\n');
+    }
+
+    buffer
+}
+
+/// Interprets `main` with the same settings the dev backend compiles it with, drops the result
+/// and checks that nothing leaked, and returns the result rendered as a string.
+fn eval(src: &str) -> Result<String, Error> {
+    use std::path::PathBuf;
+
+    let arena = &Bump::new();
+    let module_src = if src.starts_with("app") {
+        src.to_string()
+    } else {
+        promote_expr_to_module(src)
+    };
+
+    let load_config = LoadConfig {
+        target: roc_target::Target::LinuxX64,
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+        function_kind: FunctionKind::LambdaSet,
+        inline_budget: Some(InlineBudget::DEFAULT),
+        simplify: true,
+        module_cache_dir: None,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        &module_src,
+        PathBuf::from("fake/test/path"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .expect("failed to load module");
+
+    let (_, main_symbol, main_layout) = match loaded.entry_point {
+        EntryPoint::Executable {
+            exposed_to_host, ..
+        } => exposed_to_host[0],
+        EntryPoint::Test => unreachable!(),
+    };
+
+    let mut interpreter = Interpreter::new(&loaded.layout_interner, &loaded.procedures);
+    let result = interpreter.call(main_symbol, main_layout, Vec::new())?;
+    let rendered = render(interpreter.heap(), &result);
+    interpreter.drop_and_check_leaks(&result)?;
+
+    Ok(rendered)
+}
+
+fn render(heap: &Heap, value: &Value) -> String {
+    let render_all = |values: &[Value]| {
+        values
+            .iter()
+            .map(|value| render(heap, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match value {
+        Value::Int(int) => int.to_string(),
+        Value::Float(_, float) => float.to_string(),
+        Value::Dec(dec) => dec.to_string(),
+        Value::Bool(bool) => bool.to_string(),
+        Value::Str(addr) => format!("{:?}", String::from_utf8_lossy(heap.str(*addr).unwrap())),
+        Value::List(addr) => format!("[{}]", render_all(heap.list(*addr).unwrap())),
+        Value::Struct(fields) => format!("{{{}}}", render_all(fields)),
+        Value::Union(tag_id, fields) => format!("#{tag_id}({})", render_all(fields)),
+        Value::Pointer(addr) => {
+            let (tag_id, fields) = heap.tag(*addr).unwrap();
+
+            format!("#{tag_id}({})", render_all(fields))
+        }
+        Value::Null => "null".into(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn crash_message(result: Result<String, Error>) -> String {
+    match result {
+        Err(Error::Crash { message, .. }) => message,
+        other => panic!("expected a crash, got {other:?}"),
+    }
+}
+
+#[test]
+fn int_arithmetic() {
+    assert_eq!(eval("1 + 2 * 3").unwrap(), "7");
+    assert_eq!(eval("Num.div_trunc(-7i64, 2)").unwrap(), "-3");
+    assert_eq!(eval("Num.div_ceil(7u8, 2)").unwrap(), "4");
+    assert_eq!(eval("Num.add_wrap(255u8, 1)").unwrap(), "0");
+    assert_eq!(eval("Num.shift_right_by(-8i32, 1)").unwrap(), "-4");
+    assert_eq!(eval("Num.count_leading_zero_bits(1u16)").unwrap(), "15");
+}
+
+#[test]
+fn int_overflow_crashes() {
+    assert_eq!(
+        crash_message(eval("Num.max_i64 + 1")),
+        "Integer addition overflowed!"
+    );
+    assert_eq!(
+        crash_message(eval("Num.div_trunc(1i64, 0)")),
+        "Integer division by 0!"
+    );
+}
+
+#[test]
+fn floats_and_decimals() {
+    assert_eq!(eval("1.5f64 * 2").unwrap(), "3");
+    assert_eq!(eval("0.1 + 0.2").unwrap(), "0.3");
+    assert_eq!(eval("Num.round(2.5f64)").unwrap(), "3");
+}
+
+#[test]
+fn strings() {
+    assert_eq!(
+        eval(r#"Str.concat("Hello, ", "World!")"#).unwrap(),
+        r#""Hello, World!""#
+    );
+    assert_eq!(
+        eval(r#"Str.join_with(["a", "b", "c"], ", ")"#).unwrap(),
+        r#""a, b, c""#
+    );
+    assert_eq!(
+        eval(r#"Str.split_on("a,b,,c", ",")"#).unwrap(),
+        r#"["a", "b", "", "c"]"#
+    );
+    assert_eq!(eval(r#"Str.to_utf8("hi")"#).unwrap(), "[104, 105]");
+}
+
+#[test]
+fn lists() {
+    assert_eq!(
+        eval("List.map([1, 2, 3], \\x -> x * 10)").unwrap(),
+        "[10, 20, 30]"
+    );
+    assert_eq!(
+        eval("List.concat([1, 2], [3]) |> List.append(4)").unwrap(),
+        "[1, 2, 3, 4]"
+    );
+    assert_eq!(eval("List.sum([1, 2, 3, 4])").unwrap(), "10");
+    assert_eq!(
+        eval(r#"List.map(["a", "b"], \s -> Str.concat(s, "!"))"#).unwrap(),
+        r#"["a!", "b!"]"#
+    );
+}
+
+#[test]
+fn sort_with() {
+    assert_eq!(
+        eval("List.sort_with([3, 1, 2], Num.compare)").unwrap(),
+        "[1, 2, 3]"
+    );
+    assert_eq!(
+        eval(indoc!(
+            r#"
+            offset = 10
+
+            List.sort_with(["bb", "a", "ccc"], \a, b -> Num.compare(Str.count_utf8_bytes(a) + offset, Str.count_utf8_bytes(b) + offset))
+            "#
+        ))
+        .unwrap(),
+        r#"["a", "bb", "ccc"]"#
+    );
+}
+
+#[test]
+fn records() {
+    assert_eq!(
+        eval(r#"{ name: "Roc", count: 3u8 }"#).unwrap(),
+        r#"{"Roc", 3}"#
+    );
+}
+
+#[test]
+fn recursive_tag_unions() {
+    assert_eq!(
+        eval(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LinkedList : [Nil, Cons I64 LinkedList]
+
+            from_n : I64 -> LinkedList
+            from_n = \n -> if n == 0 then Nil else Cons(n, from_n((n - 1)))
+
+            sum : LinkedList -> I64
+            sum = \list ->
+                when list is
+                    Nil -> 0
+                    Cons(x, rest) -> x + sum(rest)
+
+            increment : LinkedList -> LinkedList
+            increment = \list ->
+                when list is
+                    Nil -> Nil
+                    Cons(x, rest) -> Cons((x + 1), increment(rest))
+
+            main = sum(increment(from_n(10)))
+            "#
+        ))
+        .unwrap(),
+        "65"
+    );
+}
+
+#[test]
+fn shared_recursive_tag_unions() {
+    assert_eq!(
+        eval(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LinkedList : [Nil, Cons Str LinkedList]
+
+            shout : LinkedList -> LinkedList
+            shout = \list ->
+                when list is
+                    Nil -> Nil
+                    Cons(s, rest) -> Cons(Str.concat(s, "!"), shout(rest))
+
+            to_list : LinkedList -> List Str
+            to_list = \list ->
+                when list is
+                    Nil -> []
+                    Cons(s, rest) -> List.prepend(to_list(rest), s)
+
+            main =
+                words = Cons("a", Cons("b", Nil))
+
+                List.concat(to_list(shout(words)), to_list(words))
+            "#
+        ))
+        .unwrap(),
+        r#"["a!", "b!", "a", "b"]"#
+    );
+}
+
+#[test]
+fn string_conversions() {
+    assert_eq!(
+        eval(r#"Str.to_i64("-42") |> Result.with_default(0)"#).unwrap(),
+        "-42"
+    );
+    assert_eq!(
+        eval(r#"Str.to_u8("256") |> Result.with_default(7)"#).unwrap(),
+        "7"
+    );
+    assert_eq!(
+        eval(r#"Str.from_utf8([104, 105]) |> Result.with_default("")"#).unwrap(),
+        r#""hi""#
+    );
+    assert_eq!(
+        eval(r#"Str.from_utf8([104, 255]) |> Result.with_default("bad")"#).unwrap(),
+        r#""bad""#
+    );
+}
+
+#[test]
+fn crash() {
+    let result = eval(indoc!(
+        r#"
+        if Str.is_empty("") then crash "oops" else 1u8
+        "#
+    ));
+
+    assert_eq!(
+        result,
+        Err(Error::Crash {
+            message: "oops".into(),
+            tag: CrashTag::User,
+        })
+    );
+}
+
+#[test]
+fn double_dec_is_a_use_after_free() {
+    let mut heap = Heap::default();
+    let string = Value::Str(heap.alloc(HeapData::Str(b"hello".to_vec())));
+
+    heap.dec(&string).unwrap();
+
+    assert!(matches!(heap.dec(&string), Err(Error::UseAfterFree(_))));
+    assert!(matches!(
+        heap.str(match string {
+            Value::Str(addr) => addr,
+            _ => unreachable!(),
+        }),
+        Err(Error::UseAfterFree(_))
+    ));
+}
+
+#[test]
+fn dec_frees_children() {
+    let mut heap = Heap::default();
+    let element = Value::Str(heap.alloc(HeapData::Str(b"element".to_vec())));
+    let list = Value::List(heap.alloc(HeapData::List {
+        elements: vec![element],
+        capacity: 1,
+    }));
+
+    heap.dec(&list).unwrap();
+
+    assert!(heap.live_allocations().is_empty());
+}
+
+#[test]
+fn missing_dec_is_a_leak() {
+    let mut heap = Heap::default();
+    let string = Value::Str(heap.alloc(HeapData::Str(b"hello".to_vec())));
+
+    heap.inc(&string, 1).unwrap();
+    heap.dec(&string).unwrap();
+
+    assert_eq!(heap.live_allocations().len(), 1);
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::io;
 use std::path::PathBuf;
 
@@ -266,7 +267,7 @@ pub enum Problem {
     InterpolatedStringNotAllowed(Region),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ScopeModuleSource {
     Builtin,
     Current,
@@ -274,6 +275,115 @@ pub enum ScopeModuleSource {
 }
 
 impl Problem {
+    /// The [Problem::code]s of the problems reported as warnings.
+    pub const WARNING_CODES: &'static [&'static str] = &[
+        "unused-definition",
+        "unused-import",
+        "explicit-builtin-import",
+        "unused-argument",
+        "definition-only-used-in-recursion",
+        "unused-type-alias-parameter",
+        "duplicate-field-name",
+        "duplicate-bound-ability",
+        "duplicate-implementation",
+        "not-an-ability",
+        "unnecessary-implementations",
+        "unnecessary-definition",
+        "overloaded-specialization",
+        "unnecessary-wildcard",
+        "underapplied-try",
+        "return-outside-of-function",
+        "unreachable-code",
+        "unnecessary-return",
+        "missing-exclamation",
+        "unnecessary-exclamation",
+        "empty-tuple-type",
+        "unbound-type-variables-in-as",
+    ];
+
+    /// A stable identifier for this kind of problem, e.g. `unused-import`. Tools match on it,
+    /// and projects use it to set the levels of warnings.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Problem::UnusedDef(..) => "unused-definition",
+            Problem::UnusedImport(..) => "unused-import",
+            Problem::UnusedModuleImport(..) => "unused-import",
+            Problem::ExposedButNotDefined(_) => "missing-definition",
+            Problem::ImportNameConflict { .. } => "import-name-conflict",
+            Problem::ExplicitBuiltinImport(..) => "explicit-builtin-import",
+            Problem::ExplicitBuiltinTypeImport(..) => "explicit-builtin-import",
+            Problem::ImportShadowsSymbol { .. } => "import-shadows-name",
+            Problem::UnusedArgument(..) => "unused-argument",
+            Problem::UnusedBranchDef(..) => "unused-definition",
+            Problem::DefsOnlyUsedInRecursion(..) => "definition-only-used-in-recursion",
+            Problem::PrecedenceProblem(_) => "precedence-problem",
+            Problem::UnsupportedPattern(..) => "unsupported-pattern",
+            Problem::Shadowing { .. } => "shadowing",
+            Problem::CyclicAlias(..) => "cyclic-alias",
+            Problem::BadRecursion(_) => "circular-definition",
+            Problem::PhantomTypeArgument { .. } => "unused-type-alias-parameter",
+            Problem::UndeclaredTypeVar { .. } => "undeclared-type-variable",
+            Problem::DuplicateRecordFieldValue { .. } => "duplicate-field-name",
+            Problem::DuplicateRecordFieldType { .. } => "duplicate-field-type",
+            Problem::InvalidOptionalValue { .. } => "invalid-optional-value",
+            Problem::DuplicateTag { .. } => "duplicate-tag-name",
+            Problem::RuntimeError(runtime_error) => runtime_error.code(),
+            Problem::SignatureDefMismatch { .. } => "signature-name-mismatch",
+            Problem::InvalidAliasRigid { .. } => "invalid-alias-variable",
+            Problem::InvalidInterpolation(_) => "invalid-interpolation",
+            Problem::InvalidHexadecimal(_) => "invalid-unicode-hexadecimal",
+            Problem::InvalidUnicodeCodePt(_) => "invalid-unicode-code-point",
+            Problem::NestedDatatype { .. } => "nested-datatype",
+            Problem::InvalidExtensionType { .. } => "invalid-extension-type",
+            Problem::AbilityHasTypeVariables { .. } => "ability-has-type-variables",
+            Problem::ImplementsClauseIsNotAbility { .. } => "implements-clause-is-not-an-ability",
+            Problem::IllegalImplementsClause { .. } => "illegal-implements-clause",
+            Problem::DuplicateImplementsAbility { .. } => "duplicate-bound-ability",
+            Problem::AbilityMemberMissingImplementsClause { .. } => {
+                "ability-member-missing-implements-clause"
+            }
+            Problem::AbilityMemberMultipleBoundVars { .. } => {
+                "ability-member-binds-multiple-variables"
+            }
+            Problem::AbilityNotOnToplevel { .. } => "ability-not-on-top-level",
+            Problem::AbilityUsedAsType(..) => "ability-used-as-type",
+            Problem::NestedSpecialization(..) => "specialization-not-on-top-level",
+            Problem::IllegalDerivedAbility(_) => "illegal-derive",
+            Problem::ImplementationNotFound { .. } => "implementation-not-found",
+            Problem::NotAnAbilityMember { .. } => "not-an-ability-member",
+            Problem::OptionalAbilityImpl { .. } => "optional-ability-implementation",
+            Problem::QualifiedAbilityImpl { .. } => "qualified-ability-implementation",
+            Problem::AbilityImplNotIdent { .. } => "ability-implementation-not-identifier",
+            Problem::DuplicateImpl { .. } => "duplicate-implementation",
+            Problem::NotAnAbility(_) => "not-an-ability",
+            Problem::ImplementsNonRequired { .. } => "unnecessary-implementations",
+            Problem::DoesNotImplementAbility { .. } => "incomplete-ability-implementation",
+            Problem::NotBoundInAllPatterns { .. } => "name-not-bound-in-all-patterns",
+            Problem::NoIdentifiersIntroduced(_) => "unnecessary-definition",
+            Problem::OverloadedSpecialization { .. } => "overloaded-specialization",
+            Problem::UnnecessaryOutputWildcard { .. } => "unnecessary-wildcard",
+            Problem::MultipleListRestPattern { .. } => "multiple-list-rest-patterns",
+            Problem::BadTypeArguments { .. } => "wrong-number-of-type-arguments",
+            Problem::UnappliedCrash { .. } => "unapplied-crash",
+            Problem::OverAppliedCrash { .. } => "overapplied-crash",
+            Problem::UnappliedDbg { .. } => "unapplied-dbg",
+            Problem::OverAppliedDbg { .. } => "overapplied-dbg",
+            Problem::UnderAppliedTry { .. } => "underapplied-try",
+            Problem::FileProblem { .. } => "file-problem",
+            Problem::WildcardNotAllowed { .. } => "wildcard-not-allowed",
+            Problem::UnderscoreNotAllowed { .. } => "underscore-not-allowed",
+            Problem::ReturnOutsideOfFunction { .. } => "return-outside-of-function",
+            Problem::StatementsAfterReturn { .. } => "unreachable-code",
+            Problem::ReturnAtEndOfFunction { .. } => "unnecessary-return",
+            Problem::UnsuffixedEffectfulRecordField(_) => "missing-exclamation",
+            Problem::SuffixedPureRecordField(_) => "unnecessary-exclamation",
+            Problem::EmptyTupleType(_) => "empty-tuple-type",
+            Problem::UnboundTypeVarsInAs(_) => "unbound-type-variables-in-as",
+            Problem::InvalidIgnoredValue { .. } => "invalid-ignored-value",
+            Problem::InterpolatedStringNotAllowed(_) => "interpolated-string-not-allowed",
+        }
+    }
+
     pub fn severity(&self) -> Severity {
         use Severity::{Fatal, RuntimeError, Warning};
 
@@ -694,6 +804,53 @@ pub enum RuntimeError {
 }
 
 impl RuntimeError {
+    /// Like [Problem::code], for problems that are only reported as runtime errors.
+    pub fn code(&self) -> &'static str {
+        use RuntimeError::*;
+
+        match self {
+            Shadowing { .. } => "shadowing",
+            InvalidOptionalValue { .. } => "invalid-optional-value",
+            InvalidIgnoredValue { .. } => "invalid-ignored-value",
+            UnsupportedPattern(_) => "unsupported-pattern",
+            MalformedPattern(..) => "malformed-pattern",
+            UnresolvedTypeVar => "unresolved-type-variable",
+            ErroneousType => "erroneous-type",
+            LookupNotInScope { .. } => "unrecognized-name",
+            OpaqueNotDefined { .. } => "opaque-type-not-defined",
+            OpaqueOutsideScope { .. } => "opaque-type-declared-outside-scope",
+            OpaqueNotApplied(_) => "opaque-type-not-applied",
+            OpaqueAppliedToMultipleArgs(_) => "opaque-type-applied-to-too-many-args",
+            ValueNotExposed { .. } => "not-exposed",
+            ModuleNotImported { .. } => "module-not-imported",
+            ReadIngestedFileError { .. } => "ingested-file-error",
+            InvalidPrecedence(..) => "precedence-problem",
+            MalformedIdentifier(..) => "malformed-identifier",
+            MalformedTypeName(..) => "malformed-type-name",
+            InvalidRecordUpdate { .. } => "invalid-record-update",
+            InvalidFloat(..) => "invalid-float",
+            InvalidInt(..) => "invalid-int",
+            CircularDef(_) => "circular-definition",
+            NonExhaustivePattern => "non-exhaustive-pattern",
+            InvalidInterpolation(_) => "invalid-interpolation",
+            InvalidHexadecimal(_) => "invalid-unicode-hexadecimal",
+            InvalidUnicodeCodePt(_) => "invalid-unicode-code-point",
+            NoImplementationNamed { .. } => "implementation-not-found",
+            NoImplementation => "implementation-not-found",
+            VoidValue => "void-value",
+            ExposedButNotDefined(_) => "missing-definition",
+            EmptySingleQuote(_) => "empty-single-quote",
+            MultipleCharsInSingleQuote(_) => "multiple-chars-in-single-quote",
+            DegenerateBranch(_) => "degenerate-branch",
+            EmptyRecordBuilder(_) => "empty-record-builder",
+            SingleFieldRecordBuilder(_) => "not-enough-fields-in-record-builder",
+            OptionalFieldInRecordBuilder { .. } => "optional-field-in-record-builder",
+            NonFunctionHostedAnnotation(_) => "non-function-hosted-annotation",
+            InvalidTupleIndex(_) => "invalid-tuple-index",
+            IngestedFilePathError(_) => "ingested-file-error",
+        }
+    }
+
     pub fn runtime_message(self) -> String {
         use RuntimeError::*;
 
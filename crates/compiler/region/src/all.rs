@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug};
 
-#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct Region {
     start: Position,
     end: Position,
@@ -78,6 +79,10 @@ impl Region {
     pub const fn between(start: Position, end: Position) -> Self {
         Self::new(start, end)
     }
+
+    pub fn byte_range(&self) -> std::ops::Range<usize> {
+        self.start.byte_offset()..self.end.byte_offset()
+    }
 }
 
 // Region is used all over the place. Avoid increasing its size!
@@ -96,7 +101,7 @@ impl fmt::Debug for Region {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct Position {
     pub offset: u32,
 }
@@ -289,7 +294,7 @@ impl fmt::Debug for LineColumnRegion {
     }
 }
 
-#[derive(Clone, Eq, Copy, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Eq, Copy, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Loc<T> {
     pub region: Region,
     pub value: T,
@@ -340,7 +345,7 @@ impl<T> Loc<T> {
     }
 
     pub fn byte_range(&self) -> std::ops::Range<usize> {
-        self.region.start.byte_offset()..self.region.end.byte_offset()
+        self.region.byte_range()
     }
 }
 
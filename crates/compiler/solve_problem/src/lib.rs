@@ -54,6 +54,55 @@ pub enum TypeError {
 }
 
 impl TypeError {
+    /// The [TypeError::code]s of the problems reported as warnings.
+    pub const WARNING_CODES: &'static [&'static str] = &[
+        "redundant-pattern",
+        "unmatchable-pattern",
+        "unexpected-module-params",
+        "leftover-statement",
+        "unnecessary-definition",
+        "effect-in-pure-function",
+        "effect-in-top-level",
+        "missing-exclamation",
+        "unnecessary-exclamation",
+    ];
+
+    /// A stable identifier for this kind of problem, like [roc_problem::can::Problem::code].
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::BadExpr(..) => "type-mismatch",
+            TypeError::BadPattern(..) => "type-mismatch",
+            TypeError::CircularType(..) => "circular-type",
+            TypeError::CircularDef(_) => "circular-definition",
+            TypeError::UnexposedLookup(..) => "unrecognized-name",
+            TypeError::UnfulfilledAbility(_) => "incomplete-ability-implementation",
+            TypeError::BadExprMissingAbility(..) => "type-mismatch",
+            TypeError::BadPatternMissingAbility(..) => "type-mismatch",
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(..)) => "unsafe-pattern",
+            TypeError::Exhaustive(roc_exhaustive::Error::Redundant { .. }) => "redundant-pattern",
+            TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable { .. }) => {
+                "unmatchable-pattern"
+            }
+            TypeError::StructuralSpecialization { .. } => "illegal-specialization",
+            TypeError::WrongSpecialization { .. } => "wrong-specialization-type",
+            TypeError::IngestedFileBadUtf8(..) => "invalid-utf8-in-ingested-file",
+            TypeError::IngestedFileUnsupportedType(..) => "invalid-type-for-ingested-file",
+            TypeError::UnexpectedModuleParams(..) => "unexpected-module-params",
+            TypeError::MissingModuleParams(..) => "missing-module-params",
+            TypeError::ModuleParamsMismatch(..) => "module-params-mismatch",
+            TypeError::FxInPureFunction(..) => "effect-in-pure-function",
+            TypeError::FxInTopLevel(..) => "effect-in-top-level",
+            TypeError::ExpectedEffectful(_, ExpectEffectfulReason::Stmt) => "leftover-statement",
+            TypeError::ExpectedEffectful(_, ExpectEffectfulReason::Ignored) => {
+                "unnecessary-definition"
+            }
+            TypeError::UnsuffixedEffectfulFunction(..) => "missing-exclamation",
+            TypeError::SuffixedPureFunction(..) => "unnecessary-exclamation",
+            TypeError::InvalidTryTarget(..) => "invalid-try-target",
+            TypeError::TypeIsNotGeneralized(..) => "type-variable-is-not-generic",
+        }
+    }
+
     pub fn severity(&self) -> Severity {
         use Severity::*;
         match self {
@@ -0,0 +1,72 @@
+#![cfg(feature = "gen-llvm")]
+
+#[allow(unused_imports)]
+use indoc::indoc;
+
+const SRC: &str = indoc!(
+    r#"
+    app "test" provides [main] to "./platform"
+
+    double : I64 -> I64
+    double = \x ->
+        y = x * 2
+        y
+
+    main =
+        greeting = Str.concat "Hello, " "World!"
+        if Str.is_empty greeting then 0 else double 21
+    "#
+);
+
+/// Whether a single line of `ir` contains all of `parts`
+fn has_line(ir: &str, parts: &[&str]) -> bool {
+    ir.lines()
+        .any(|line| parts.iter().all(|part| line.contains(part)))
+}
+
+#[test]
+fn llvm_line_table() {
+    let ir = crate::helpers::llvm::llvm_ir(SRC);
+
+    assert!(
+        has_line(&ir, &["!DIFile(filename: \"Test.roc\""]),
+        "no file for Test.roc in:\n{ir}"
+    );
+    assert!(
+        has_line(&ir, &["!DISubprogram(", "double", "line: 4,"]),
+        "no subprogram for double at line 4 in:\n{ir}"
+    );
+    assert!(
+        has_line(&ir, &["!DILocation(line: 5,"]),
+        "no location at line 5 in:\n{ir}"
+    );
+    assert!(
+        has_line(&ir, &["!DILocation(line: 10,"]),
+        "no location at line 10 in:\n{ir}"
+    );
+}
+
+#[test]
+fn llvm_local_variables() {
+    let ir = crate::helpers::llvm::llvm_ir(SRC);
+
+    assert!(
+        has_line(&ir, &["!DILocalVariable(name: \"x\", arg: 1,", "line: 4,"]),
+        "no parameter x in:\n{ir}"
+    );
+    assert!(
+        has_line(&ir, &["!DILocalVariable(name: \"y\",", "line: 5,"]),
+        "no variable y in:\n{ir}"
+    );
+    assert!(
+        has_line(&ir, &["!DILocalVariable(name: \"greeting\",", "line: 9,"]),
+        "no variable greeting in:\n{ir}"
+    );
+    assert!(
+        has_line(
+            &ir,
+            &["!DICompositeType(tag: DW_TAG_structure_type, name: \"Str\""]
+        ),
+        "no debug type for Str in:\n{ir}"
+    );
+}
@@ -550,3 +550,96 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[cfg(feature = "gen-llvm")]
+thread_local! {
+    static APP_DICT_SEED: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// Hashes keys with the seed of the app the current test compiled.
+#[cfg(feature = "gen-llvm")]
+struct AppSeed;
+
+#[cfg(feature = "gen-llvm")]
+impl roc_std::DictSeed for AppSeed {
+    fn seed() -> u64 {
+        APP_DICT_SEED.with(|seed| seed.get())
+    }
+}
+
+#[cfg(feature = "gen-llvm")]
+type AppDict = roc_std::RocDict<u64, RocStr, AppSeed>;
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn dict_round_trip_with_rust() {
+    use crate::helpers::llvm::{
+        dict_pseudo_seed, helper, try_run_lib_function_with_arg, HelperConfig, OPT_LEVEL,
+    };
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+    use roc_load::FunctionKind;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main : Dict U64 Str -> Dict U64 Str
+        main = \dict ->
+            dict
+            |> Dict.insert 1 "one"
+            |> Dict.insert 2 "two"
+            |> Dict.insert 3 "three"
+        "#
+    );
+
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        emit_debug_info: false,
+        opt_level: OPT_LEVEL,
+    };
+
+    let (main_fn_name, errors, lib) =
+        helper(&arena, config, src, &context, FunctionKind::LambdaSet);
+
+    assert!(errors.is_empty(), "Encountered errors:\n{errors}");
+
+    APP_DICT_SEED.with(|seed| seed.set(dict_pseudo_seed(&lib)));
+
+    let run = |dict: AppDict| -> AppDict {
+        try_run_lib_function_with_arg(main_fn_name, &lib, dict)
+            .unwrap_or_else(|(msg, _)| panic!(r#"Roc failed with message: "{msg}""#))
+    };
+
+    // A Rust dict that has no buckets yet goes to Roc, and Roc's dict comes back
+    let mut dict = run(AppDict::empty());
+
+    assert_eq!(dict.len(), 3);
+    assert_eq!(dict.get(&1), Some(&RocStr::from("one")));
+    assert_eq!(dict.get(&2), Some(&RocStr::from("two")));
+    assert_eq!(dict.get(&3), Some(&RocStr::from("three")));
+    assert_eq!(dict.get(&4), None);
+
+    // Changed in Rust and sent back
+    let old = dict.insert(2, RocStr::from("TWO"));
+
+    assert_eq!(old, Some(RocStr::from("two")));
+    assert_eq!(dict.insert(4, RocStr::from("four")), None);
+
+    let dict = run(dict);
+
+    assert_eq!(dict.len(), 4);
+    assert_eq!(dict.get(&2), Some(&RocStr::from("two")));
+    assert_eq!(dict.get(&4), Some(&RocStr::from("four")));
+
+    // Roc finds the keys Rust put in the dict, so it replaces them instead of adding them again
+    let dict = run([(3, RocStr::from("drei")), (5, RocStr::from("five"))]
+        .into_iter()
+        .collect());
+
+    assert_eq!(dict.len(), 4);
+    assert_eq!(dict.get(&3), Some(&RocStr::from("three")));
+    assert_eq!(dict.get(&5), Some(&RocStr::from("five")));
+}
@@ -561,22 +561,34 @@ fn i64_abs() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn various_sized_abs() {
     assert_evals_to!("Num.abs -6i8", 6, i8);
     assert_evals_to!("Num.abs -6i16", 6, i16);
     assert_evals_to!("Num.abs -6i32", 6, i32);
     assert_evals_to!("Num.abs -6i64", 6, i64);
-    if !cfg!(feature = "gen-wasm") {
-        assert_evals_to!("Num.abs -6i128", I128::from(6), I128);
-    }
+    assert_evals_to!("Num.abs -6i128", I128::from(6), I128);
     assert_evals_to!("Num.abs 6u8", 6, u8);
     assert_evals_to!("Num.abs 6u16", 6, u16);
     assert_evals_to!("Num.abs 6u32", 6, u32);
     assert_evals_to!("Num.abs 6u64", 6, u64);
-    if !cfg!(feature = "gen-wasm") {
-        assert_evals_to!("Num.abs 6u128", U128::from(6), U128);
-    }
+    assert_evals_to!("Num.abs 6u128", U128::from(6), U128);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn abs_dec() {
+    assert_evals_to!("Num.abs -1.5dec", RocDec::from_str("1.5").unwrap(), RocDec);
+    assert_evals_to!("Num.abs 1.5dec", RocDec::from_str("1.5").unwrap(), RocDec);
+}
+
+#[test]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
+#[should_panic(
+    expected = r#"Roc failed with message: "Integer absolute overflowed because its argument is the minimum value"#
+)]
+fn abs_min_i128_overflow() {
+    assert_evals_to!("Num.abs Num.min_i128", I128::from(0), I128);
 }
 
 #[test]
@@ -1161,6 +1173,26 @@ fn gen_div_u64() {
     assert_evals_to!("1000u64 // 10", 100, u64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn gen_div_i128() {
+    assert_evals_to!(
+        "-100_000_000_000_000_000_000i128 // 7",
+        I128::from(-14_285_714_285_714_285_714i128),
+        I128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn gen_div_u128() {
+    assert_evals_to!(
+        "100_000_000_000_000_000_000u128 // 7",
+        U128::from(14_285_714_285_714_285_714u128),
+        U128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 #[should_panic(expected = r#"User crash with message: "Integer division by 0!"#)]
@@ -1194,6 +1226,32 @@ fn gen_rem_i64() {
     assert_evals_to!("Num.rem 8 3", 2, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn gen_rem_i128() {
+    assert_evals_to!(
+        "Num.rem -100_000_000_000_000_000_000i128 7",
+        I128::from(-2),
+        I128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn gen_rem_u128() {
+    assert_evals_to!(
+        "Num.rem 100_000_000_000_000_000_000u128 7",
+        U128::from(2),
+        U128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn gen_rem_u32() {
+    assert_evals_to!("Num.rem 4_000_000_000u32 7", 4, u32);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 #[should_panic(expected = r#"User crash with message: "Integer division by 0!"#)]
@@ -1352,6 +1410,22 @@ fn bitwise_or() {
     assert_evals_to!("Num.bitwise_or 1 2", 3, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn bitwise_128() {
+    assert_evals_to!(
+        "Num.bitwise_and 0xFFFF_0000_0000_0000_0000_0000_0000_FF00u128 0x0FF0_0000_0000_0000_0000_0000_0000_0FF0",
+        U128::from(0x0FF0_0000_0000_0000_0000_0000_0000_0F00u128),
+        U128
+    );
+    assert_evals_to!(
+        "Num.bitwise_or 0xFF00_0000_0000_0000_0000_0000_0000_0000u128 0xFF",
+        U128::from(0xFF00_0000_0000_0000_0000_0000_0000_00FFu128),
+        U128
+    );
+    assert_evals_to!("Num.bitwise_xor -1i128 1", I128::from(-2), I128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn lt_u8() {
@@ -1635,6 +1709,10 @@ fn num_negate() {
     assert_evals_to!("Num.neg Num.max_i64", -i64::MAX, i64);
     assert_evals_to!("Num.neg (Num.min_i64 + 1)", i64::MAX, i64);
 
+    assert_evals_to!("Num.neg 123i128", I128::from(-123), I128);
+    assert_evals_to!("Num.neg Num.max_i128", I128::from(-i128::MAX), I128);
+    assert_evals_to!("Num.neg (Num.min_i128 + 1)", I128::from(i128::MAX), I128);
+
     assert_evals_to!("Num.neg 12.3f32", -12.3, f32);
     assert_evals_to!("Num.neg 0.0f32", -0.0, f32);
     assert_evals_to!("Num.neg Num.max_f32", -f32::MAX, f32);
@@ -1915,6 +1993,35 @@ fn floor_f64() {
     assert_evals_to!("Num.floor -0.5f64", -1, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn floor_ceiling_to_i128() {
+    assert_evals_to!(
+        indoc!(
+            r"
+            x : I128
+            x = Num.floor -1.5f64
+
+            x
+            "
+        ),
+        I128::from(-2),
+        I128
+    );
+    assert_evals_to!(
+        indoc!(
+            r"
+            x : I128
+            x = Num.ceiling 1.5dec
+
+            x
+            "
+        ),
+        I128::from(2),
+        I128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn floor_dec() {
@@ -2540,6 +2647,23 @@ fn shift_right_zf_by() {
     assert_evals_to!("Num.shift_right_zf_by 0b1000_0000u8 12", 0b0000_0000u8, u8);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn shift_128() {
+    assert_evals_to!(
+        "Num.shift_left_by 1u128 100",
+        U128::from(1u128 << 100),
+        U128
+    );
+    assert_evals_to!(
+        "Num.shift_right_by (Num.shift_left_by 1i128 100) 99",
+        I128::from(2),
+        I128
+    );
+    assert_evals_to!("Num.shift_right_by -256i128 4", I128::from(-16), I128);
+    assert_evals_to!("Num.shift_right_zf_by -1i128 127", I128::from(1), I128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn shift_right_cast_i8() {
@@ -2742,12 +2866,14 @@ num_conversion_tests! {
         to_i64_sign_extend_i8, "-15i8", -15, ["gen-wasm", "gen-dev"]
         to_i64_sign_extend_i16, "-15i16", -15, ["gen-wasm", "gen-dev"]
         to_i64_sign_extend_i32, "-15i32", -15, ["gen-wasm", "gen-dev"]
-        to_i64_truncate, "115i128", 115
-        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616
+        to_i64_truncate, "115i128", 115, ["gen-wasm", "gen-dev"]
+        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616, ["gen-wasm", "gen-dev"]
     )
     "Num.to_i128", i128, (
-        to_i128_same_width, "15u128", 15, ["gen-dev"]
-        to_i128_extend, "15i8", 15
+        to_i128_same_width, "15u128", 15, ["gen-wasm", "gen-dev"]
+        to_i128_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
+        to_i128_sign_extend_i8, "-15i8", -15, ["gen-wasm", "gen-dev"]
+        to_i128_sign_extend_i64, "-15i64", -15, ["gen-wasm", "gen-dev"]
     )
     "Num.to_u8", u8, (
         to_u8_same_width, "15i8", 15, ["gen-wasm", "gen-dev"]
@@ -2769,48 +2895,50 @@ num_conversion_tests! {
     "Num.to_u64", u64, (
         to_u64_same_width, "15i64", 15, ["gen-wasm", "gen-dev"]
         to_u64_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
-        to_u64_truncate, "115i128", 115
-        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128
+        to_u64_truncate, "115i128", 115, ["gen-wasm", "gen-dev"]
+        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128, ["gen-wasm", "gen-dev"]
     )
     "Num.to_u128", u128, (
-        to_u128_same_width, "15i128", 15, ["gen-dev"]
-        to_u128_extend, "15i8", 15
-        to_u128_big, "11562537357600483583u64", 11562537357600483583, ["gen-dev"]
+        to_u128_same_width, "15i128", 15, ["gen-wasm", "gen-dev"]
+        to_u128_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
+        to_u128_big, "11562537357600483583u64", 11562537357600483583, ["gen-wasm", "gen-dev"]
     )
     "Num.to_f32", f32, (
         to_f32_from_i8, "15i8", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i16, "15i16", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i32, "15i32", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i64, "15i64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f32_from_i128, "15i128", 15.0, ["gen-dev"]
+        to_f32_from_i128, "15i128", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u8, "15u8", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u16, "15u16", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u32, "15u32", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u64, "15u64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f32_from_u128, "15u128", 15.0, ["gen-dev"]
+        to_f32_from_u128, "15u128", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_f32, "1.5f32", 1.5, ["gen-wasm", "gen-dev"]
         to_f32_from_f64, "1.5f64", 1.5, ["gen-wasm", "gen-dev"]
+        to_f32_from_dec, "1.5dec", 1.5, ["gen-wasm", "gen-dev"]
     )
     "Num.to_f64", f64, (
         to_f64_from_i8, "15i8", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_i16, "15i16", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_i32, "15i32", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_i64, "15i64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f64_from_i128, "15i128", 15.0, ["gen-dev"]
+        to_f64_from_i128, "15i128", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u8, "15u8", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u16, "15u16", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u32, "15u32", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u64, "15u64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f64_from_u128, "15u128", 15.0, ["gen-dev"]
-        to_f64_from_f32, "1.5f32", 1.5, ["gen-dev"]
+        to_f64_from_u128, "15u128", 15.0, ["gen-wasm", "gen-dev"]
+        to_f64_from_f32, "1.5f32", 1.5, ["gen-wasm", "gen-dev"]
         to_f64_from_f64, "1.5f64", 1.5, ["gen-wasm", "gen-dev"]
+        to_f64_from_dec, "1.5dec", 1.5, ["gen-wasm", "gen-dev"]
     )
 }
 
 macro_rules! to_int_checked_tests {
     ($($fn:expr, $typ:ty, ($($test_name:ident, $input:expr, $output:expr)*))*) => {$($(
         #[test]
-        #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+        #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
         fn $test_name() {
             let sentinel = 23;
             // Some n = Ok n, None = OutOfBounds
@@ -2931,35 +3059,35 @@ fn wrap_with_default(test_roc_code: &str) -> String {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_smaller_width_pos() {
     let test_roc_code = wrap_with_default("Num.to_i128_checked 15i8");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_smaller_width_neg() {
     let test_roc_code = wrap_with_default("Num.to_i128_checked -15i8");
     assert_evals_to!(&test_roc_code, I128::from(-15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same() {
     let test_roc_code = wrap_with_default("Num.to_i128_checked 15i128");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same_width_unsigned_fits() {
     let test_roc_code = wrap_with_default("Num.to_i128_checked 15u128");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same_width_unsigned_oob() {
     let test_roc_code =
         "Result.is_err (Num.to_i128_checked 170141183460469231731687303715884105728u128)";
@@ -2967,40 +3095,81 @@ fn to_i128_checked_same_width_unsigned_oob() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_smaller_width_pos() {
     let test_roc_code = wrap_with_default("Num.to_u128_checked 15i8");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_smaller_width_neg_oob() {
     let test_roc_code = "Result.is_err (Num.to_u128_checked -15i8)";
     assert_evals_to!(&test_roc_code, true, bool)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same() {
     let test_roc_code = wrap_with_default("Num.to_u128_checked 15u128");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same_width_signed_fits() {
     let test_roc_code = wrap_with_default("Num.to_u128_checked 15i128");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same_width_signed_oob() {
     let test_roc_code = "Result.is_err (Num.to_u128_checked -1i128)";
     assert_evals_to!(&test_roc_code, true, bool)
 }
 
+macro_rules! to_float_checked_tests {
+    ($($fn:expr, $typ:ty, ($($test_name:ident, $input:expr, $output:expr)*))*) => {$($(
+        #[test]
+        #[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
+        fn $test_name() {
+            let sentinel = 23.0;
+            // Some n = Ok n, None = OutOfBounds
+            let expected = match $output.into() {
+                None => sentinel,
+                Some(n) => {
+                    assert_ne!(n, sentinel);
+                    n
+                }
+            };
+            let input = format!("Result.with_default ({} {}) {}", $fn, $input, sentinel);
+            assert_evals_to!(&input, expected, $typ)
+        }
+    )*)*}
+}
+
+to_float_checked_tests! {
+    "Num.to_f32_checked", f32, (
+        to_f32_checked_from_i8,         "-15i8",          -15.0
+        to_f32_checked_from_u64,        "15u64",          15.0
+        to_f32_checked_from_i128,       "-15i128",        -15.0
+        to_f32_checked_from_u128_max,   "Num.max_u128",   u128::MAX as f32
+        to_f32_checked_from_f32,        "1.5f32",         1.5
+        to_f32_checked_from_f64,        "1.5f64",         1.5
+        to_f32_checked_from_f64_oob,    "Num.max_f64",    None
+        to_f32_checked_from_f64_inf,    "Num.infinity_f64", f32::INFINITY
+        to_f32_checked_from_dec,        "1.5dec",         1.5
+    )
+    "Num.to_f64_checked", f64, (
+        to_f64_checked_from_i64,        "-15i64",         -15.0
+        to_f64_checked_from_u128,       "15u128",         15.0
+        to_f64_checked_from_f32,        "1.5f32",         1.5
+        to_f64_checked_from_f64_max,    "Num.max_f64",    f64::MAX
+        to_f64_checked_from_dec,        "-1.5dec",        -1.5
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn is_multiple_of_signed() {
@@ -4650,3 +4650,52 @@ fn issue_6139_prefixes() {
         RocList<RocStr>
     );
 }
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn dbg_sends_message_to_host() {
+    let (value, host_output) = crate::helpers::wasm::host_output_help::<i64>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main =
+                x = 2i64
+                dbg x
+
+                x + 1
+            "#
+        ),
+        std::marker::PhantomData,
+    )
+    .unwrap();
+
+    assert_eq!(value, 3);
+    assert_eq!(host_output, ["[Test.roc:5] x = 2"]);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn inline_expect_reports_failure_to_host() {
+    let (value, host_output) = crate::helpers::wasm::host_output_help::<i64>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main =
+                x = 2i64
+                expect x == 2
+                expect x == 3
+
+                x + 1
+            "#
+        ),
+        std::marker::PhantomData,
+    )
+    .unwrap();
+
+    // The region is the failed condition, `x == 3`, and the app is the first module after
+    // the builtins.
+    assert_eq!(value, 3);
+    assert_eq!(host_output, ["expect failed at 93-99 in module 16"]);
+}
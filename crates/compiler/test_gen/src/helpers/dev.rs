@@ -1,6 +1,8 @@
+use super::IrSettings;
 use libloading::Library;
 use roc_build::link::{link, LinkType};
 use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::CrashTag;
 use roc_mono::ir::SingleEntryPoint;
 use roc_packaging::cache::RocCacheDir;
@@ -30,6 +32,12 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
+pub const IR_SETTINGS: IrSettings = IrSettings {
+    target: roc_target::Target::LinuxX64,
+    inline_budget: Some(InlineBudget::DEFAULT),
+    simplify: true,
+};
+
 #[allow(dead_code)]
 pub fn helper(
     arena: &bumpalo::Bump,
@@ -56,12 +64,15 @@ pub fn helper(
     }
 
     let load_config = LoadConfig {
-        target: roc_target::Target::LinuxX64,
+        target: IR_SETTINGS.target,
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
         function_kind: FunctionKind::LambdaSet,
+        inline_budget: IR_SETTINGS.inline_budget,
+        simplify: IR_SETTINGS.simplify,
+        module_cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -197,6 +208,8 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        module_sources: MutMap::default(),
+        inline_budget: IR_SETTINGS.inline_budget,
     };
 
     let target = target_lexicon::Triple::host().into();
@@ -325,6 +338,7 @@ pub(crate) fn asm_evals_to<T, U, F>(
     src: &str,
     expected: U,
     transform: F,
+    check: impl FnOnce(&T),
     leak: bool,
     lazy_literals: bool,
 ) where
@@ -351,6 +365,7 @@ pub(crate) fn asm_evals_to<T, U, F>(
     match result {
         Ok(value) => {
             let expected = expected;
+            check(&value);
             #[allow(clippy::redundant_closure_call)]
             let given = transform(value);
             assert_eq!(&given, &expected, "output is different");
@@ -391,6 +406,7 @@ macro_rules! assert_evals_to {
             $src,
             $expected,
             $transform,
+            $crate::helpers::check_against_interp!($src, $ty, $crate::helpers::dev::IR_SETTINGS),
             $leak,
             $lazy_literals,
         );
@@ -0,0 +1,348 @@
+//! Checks the result of every backend against the mono IR interpreter.
+//!
+//! With the `check-interp` feature, `assert_evals_to!` also runs the program in
+//! [roc_mono_interp], converts its result to the type the test asked for, and fails the test if
+//! the two disagree, or if the interpreter finds a use-after-free or a leak. Programs that use
+//! something the interpreter doesn't implement, and result types that have no
+//! [FromInterpValue] impl, are only checked by the backend. Set `ROC_PRINT_SKIPPED_INTERP_CHECKS`
+//! to print the programs that were skipped, and why.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use bumpalo::Bump;
+use roc_debug_flags::{dbg_set, ROC_PRINT_SKIPPED_INTERP_CHECKS};
+use roc_load::{EntryPoint, ExecutionMode, FunctionKind, LoadConfig, Threading};
+use roc_mono_interp::{Error, Heap, Interpreter, Value};
+use roc_packaging::cache::RocCacheDir;
+use roc_std::{RocBox, RocDec, RocList, RocRefcounted, RocResult, RocStr, I128, U128};
+
+use super::IrSettings;
+
+/// The interpreter recurses for every Roc call, so deeply recursive tests need a big stack.
+const INTERP_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+fn promote_expr_to_module(src: &str) -> String {
+    let mut buffer = String::from("app \"test\" provides [main] to \"./platform\"\n\nmain =\n");
+
+    for line in src.lines() {
+        // indent the body!
+        buffer.push_str("    ");
+        buffer.push_str(line);
+        buffer.push('\n');
+    }
+
+    buffer
+}
+
+/// Converts a value computed by the interpreter to the Rust type a test reads its result as.
+pub trait FromInterpValue: Sized {
+    /// `None` if the value doesn't have the shape of `Self`.
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self>;
+}
+
+/// Records with a single field have the layout of that field.
+fn unwrap_single_field<'v, 'a>(mut value: &'v Value<'a>) -> &'v Value<'a> {
+    while let Value::Struct(fields) = value {
+        match fields.as_slice() {
+            [field] => value = field,
+            _ => break,
+        }
+    }
+
+    value
+}
+
+macro_rules! from_interp_int {
+    ($($ty:ty),*) => {$(
+        impl FromInterpValue for $ty {
+            fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+                match unwrap_single_field(value) {
+                    Value::Int(int) => Some(int.bits() as $ty),
+                    // tag unions without payloads
+                    Value::Union(tag_id, fields) if fields.is_empty() => Some(*tag_id as $ty),
+                    _ => None,
+                }
+            }
+        }
+    )*};
+}
+
+from_interp_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+
+impl FromInterpValue for I128 {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        i128::from_interp_value(heap, value).map(I128::from)
+    }
+}
+
+impl FromInterpValue for U128 {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        u128::from_interp_value(heap, value).map(U128::from)
+    }
+}
+
+impl FromInterpValue for f32 {
+    fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Float(_, float) => Some(*float as f32),
+            _ => None,
+        }
+    }
+}
+
+impl FromInterpValue for f64 {
+    fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Float(_, float) => Some(*float),
+            _ => None,
+        }
+    }
+}
+
+impl FromInterpValue for bool {
+    fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Bool(bool) => Some(*bool),
+            _ => None,
+        }
+    }
+}
+
+impl FromInterpValue for RocDec {
+    fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Dec(dec) => Some(*dec),
+            _ => None,
+        }
+    }
+}
+
+impl FromInterpValue for () {
+    fn from_interp_value(_heap: &Heap, value: &Value) -> Option<Self> {
+        match value {
+            Value::Struct(fields) if fields.is_empty() => Some(()),
+            _ => None,
+        }
+    }
+}
+
+impl FromInterpValue for RocStr {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Str(addr) => {
+                let bytes = heap.str(*addr).ok()?;
+
+                Some(RocStr::from(std::str::from_utf8(bytes).ok()?))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromInterpValue + RocRefcounted> FromInterpValue for RocList<T> {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::List(addr) => heap
+                .list(*addr)
+                .ok()?
+                .iter()
+                .map(|element| T::from_interp_value(heap, element))
+                .collect::<Option<Vec<_>>>()
+                .map(RocList::from_iter),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromInterpValue + RocRefcounted> FromInterpValue for RocBox<T> {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        match unwrap_single_field(value) {
+            Value::Pointer(addr) => match heap.tag(*addr).ok()? {
+                (_, [contents]) => T::from_interp_value(heap, contents).map(RocBox::new),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromInterpValue, E: FromInterpValue> FromInterpValue for RocResult<T, E> {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        let (tag_id, payload) = match unwrap_single_field(value) {
+            Value::Union(tag_id, fields) => match fields.as_slice() {
+                [payload] => (*tag_id, payload.clone()),
+                fields => (*tag_id, Value::Struct(fields.to_vec())),
+            },
+            // both payloads are empty
+            Value::Int(int) => (int.bits() as _, Value::UNIT),
+            _ => return None,
+        };
+
+        // the tags are sorted by name
+        match tag_id {
+            0 => E::from_interp_value(heap, &payload).map(RocResult::err),
+            1 => T::from_interp_value(heap, &payload).map(RocResult::ok),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromInterpValue, const N: usize> FromInterpValue for [T; N] {
+    fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+        match value {
+            Value::Struct(fields) if fields.len() == N => fields
+                .iter()
+                .map(|field| T::from_interp_value(heap, field))
+                .collect::<Option<Vec<_>>>()?
+                .try_into()
+                .ok(),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! from_interp_tuple {
+    ($($T:ident $field:ident),*) => {
+        impl<$($T: FromInterpValue),*> FromInterpValue for ($($T,)*) {
+            fn from_interp_value(heap: &Heap, value: &Value) -> Option<Self> {
+                match value {
+                    Value::Struct(fields) => match fields.as_slice() {
+                        [$($field),*] => Some(($($T::from_interp_value(heap, $field)?,)*)),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+from_interp_tuple!(A a, B b);
+from_interp_tuple!(A a, B b, C c);
+from_interp_tuple!(A a, B b, C c, D d);
+
+/// Runs `src` in the interpreter and formats its result like the backend's. `Ok(None)` means the
+/// result can't be converted to `T`.
+fn interpret<T: FromInterpValue + Debug>(
+    src: &str,
+    settings: IrSettings,
+) -> Result<Option<String>, Error> {
+    use std::path::PathBuf;
+
+    let arena = &Bump::new();
+
+    let module_src;
+    let temp;
+    if src.starts_with("app") {
+        // this is already a module
+        module_src = src;
+    } else {
+        // this is an expression, promote it to a module
+        temp = promote_expr_to_module(src);
+        module_src = &temp;
+    }
+
+    let load_config = LoadConfig {
+        target: settings.target,
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+        function_kind: FunctionKind::LambdaSet,
+        inline_budget: settings.inline_budget,
+        simplify: settings.simplify,
+        module_cache_dir: None,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        module_src,
+        PathBuf::from("fake/test/path"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .expect("failed to load module");
+
+    let (_, main_symbol, main_layout) = match loaded.entry_point {
+        EntryPoint::Executable {
+            exposed_to_host, ..
+        } => exposed_to_host[0],
+        EntryPoint::Test => unreachable!(),
+    };
+
+    let mut interpreter = Interpreter::new(&loaded.layout_interner, &loaded.procedures);
+    let result = interpreter.call(main_symbol, main_layout, Vec::new())?;
+    let converted =
+        T::from_interp_value(interpreter.heap(), &result).map(|value| format!("{value:?}"));
+    interpreter.drop_and_check_leaks(&result)?;
+
+    Ok(converted)
+}
+
+/// Panics if the interpreter disagrees with the `actual` result of a backend that loaded `src`
+/// with `settings`.
+pub fn check_against_interp<T: FromInterpValue + Debug>(
+    src: &str,
+    settings: IrSettings,
+    actual: &T,
+) {
+    let interpreted = std::thread::scope(|scope| {
+        let handle = std::thread::Builder::new()
+            .stack_size(INTERP_STACK_SIZE)
+            .spawn_scoped(scope, || interpret::<T>(src, settings))
+            .expect("Error while spawning the interpreter thread");
+
+        match handle.join() {
+            Ok(interpreted) => interpreted,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    });
+
+    match interpreted {
+        Ok(Some(expected)) => assert_eq!(
+            format!("{actual:?}"),
+            expected,
+            "the backend and the mono IR interpreter disagree"
+        ),
+        Ok(None) => report_skipped(src, "the result can't be read as the test's type"),
+        Err(Error::Unsupported(what)) => report_skipped(src, &format!("unsupported: {what}")),
+        Err(error) => panic!("the mono IR interpreter failed: {error}"),
+    }
+}
+
+fn report_skipped(src: &str, reason: &str) {
+    if dbg_set!(ROC_PRINT_SKIPPED_INTERP_CHECKS) {
+        eprintln!("skipped the mono IR interpreter check ({reason}) for:\n{src}\n");
+    }
+}
+
+/// Lets `check_against_interp!` skip result types that have no [FromInterpValue] impl: method
+/// resolution picks [CheckAgainstInterp] when `T` has one, and falls back to [SkipInterpCheck],
+/// which is implemented for a reference to the same type.
+pub struct InterpCheck<T>(PhantomData<T>);
+
+impl<T> Default for InterpCheck<T> {
+    fn default() -> Self {
+        InterpCheck(PhantomData)
+    }
+}
+
+pub trait CheckAgainstInterp<T> {
+    fn check(&self, src: &str, settings: IrSettings, actual: &T);
+}
+
+impl<T: FromInterpValue + Debug> CheckAgainstInterp<T> for InterpCheck<T> {
+    fn check(&self, src: &str, settings: IrSettings, actual: &T) {
+        check_against_interp(src, settings, actual)
+    }
+}
+
+pub trait SkipInterpCheck<T> {
+    fn check(&self, src: &str, _settings: IrSettings, _actual: &T) {
+        report_skipped(src, "the test's type has no FromInterpValue impl");
+    }
+}
+
+impl<T> SkipInterpCheck<T> for &InterpCheck<T> {}
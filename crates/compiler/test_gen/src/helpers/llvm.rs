@@ -1,4 +1,4 @@
-use std::mem::MaybeUninit;
+use std::mem::{ManuallyDrop, MaybeUninit};
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
@@ -7,6 +7,7 @@ use libloading::Library;
 use roc_build::link::llvm_module_to_dylib;
 use roc_collections::all::MutSet;
 use roc_command_utils::zig;
+use roc_gen_llvm::llvm::debug_info::DebugSources;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult};
 use roc_load::{
@@ -18,6 +19,8 @@ use roc_region::all::LineInfo;
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
 use roc_target::Target;
 
+use crate::helpers::IrSettings;
+
 #[cfg(feature = "gen-llvm-wasm")]
 use crate::helpers::from_wasm32_memory::FromWasm32Memory;
 
@@ -47,6 +50,15 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
+/// LLVM does its own inlining and constant folding, so the mono passes that do that are off.
+pub fn ir_settings(target: Target) -> IrSettings {
+    IrSettings {
+        target,
+        inline_budget: None,
+        simplify: false,
+    }
+}
+
 fn create_llvm_module<'a>(
     arena: &'a bumpalo::Bump,
     src: &str,
@@ -69,13 +81,17 @@ fn create_llvm_module<'a>(
         module_src = &temp;
     }
 
+    let settings = ir_settings(target);
     let load_config = LoadConfig {
-        target,
+        target: settings.target,
         function_kind,
         render: RenderTarget::ColorTerminal,
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        inline_budget: settings.inline_budget,
+        simplify: settings.simplify,
+        module_cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -112,11 +128,11 @@ fn create_llvm_module<'a>(
     // errors whose reporting we delay (so we can see that code gen generates runtime errors)
     let mut delayed_errors = Vec::new();
 
-    for (home, (module_path, src)) in loaded.sources {
+    for (home, (module_path, src)) in loaded.sources.iter() {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 
-        let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
-        let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
+        let can_problems = loaded.can_problems.remove(home).unwrap_or_default();
+        let type_problems = loaded.type_problems.remove(home).unwrap_or_default();
 
         let error_count = can_problems.len() + type_problems.len();
 
@@ -124,12 +140,12 @@ fn create_llvm_module<'a>(
             continue;
         }
 
-        let line_info = LineInfo::new(&src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
 
         // Report parsing and canonicalization problems
-        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+        let alloc = RocDocAllocator::new(&src_lines, *home, &interns);
 
         use roc_problem::can::Problem::*;
         for problem in can_problems.into_iter() {
@@ -200,7 +216,10 @@ fn create_llvm_module<'a>(
     for function in module.get_functions() {
         let name = function.get_name().to_str().unwrap();
         if name.starts_with("roc_builtins") {
-            if name.starts_with("roc_builtins.expect") {
+            // the dict seed stays visible so tests can hash keys the way the app does
+            if name.starts_with("roc_builtins.expect")
+                || name == roc_builtins::bitcode::UTILS_DICT_PSEUDO_SEED
+            {
                 function.set_linkage(Linkage::External);
             } else {
                 function.set_linkage(Linkage::Internal);
@@ -229,6 +248,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: DebugSources::new(&dibuilder, &loaded.sources),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -336,6 +356,31 @@ pub fn helper<'a>(
     (main_fn_name, delayed_errors, lib)
 }
 
+/// The LLVM IR that the tests of `src` run, before it is compiled to machine code
+#[allow(dead_code)]
+pub fn llvm_ir(src: &str) -> String {
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        emit_debug_info: true,
+        opt_level: OptLevel::Development,
+    };
+    let target = target_lexicon::Triple::host().into();
+
+    let (_main_fn_name, _delayed_errors, module) = create_llvm_module(
+        &arena,
+        src,
+        config,
+        &context,
+        target,
+        FunctionKind::LambdaSet,
+    );
+
+    module.print_to_string().to_string()
+}
+
 #[allow(dead_code)]
 fn write_final_wasm() -> bool {
     #[allow(unused_imports)]
@@ -484,6 +529,12 @@ macro_rules! assert_wasm_evals_to {
         ) {
             Err(msg) => panic!("Wasm test failed: {}", msg),
             Ok(actual) => {
+                #[allow(clippy::redundant_closure_call)]
+                $crate::helpers::check_against_interp!(
+                    $src,
+                    $ty,
+                    $crate::helpers::llvm::ir_settings(roc_target::Target::Wasm32)
+                )(&actual);
                 assert_eq!($transform(actual), $expected, "Wasm test failed")
             }
         }
@@ -523,6 +574,42 @@ pub fn try_run_lib_function<T>(
     }
 }
 
+/// Like `try_run_lib_function`, for a `main` that takes one argument that's passed by reference,
+/// like a `Dict`. Roc takes ownership of the argument.
+#[allow(dead_code)]
+pub fn try_run_lib_function_with_arg<A, T>(
+    main_fn_name: &str,
+    lib: &libloading::Library,
+    arg: A,
+) -> Result<T, (String, CrashTag)> {
+    unsafe {
+        let main: libloading::Symbol<unsafe extern "C" fn(*const A, *mut RocCallResult<T>)> = lib
+            .get(main_fn_name.as_bytes())
+            .ok()
+            .ok_or(format!("Unable to JIT compile `{main_fn_name}`"))
+            .expect("errored");
+
+        let arg = ManuallyDrop::new(arg);
+        let mut main_result = MaybeUninit::uninit();
+        main(&*arg, main_result.as_mut_ptr());
+
+        main_result.assume_init().into()
+    }
+}
+
+/// The seed the app's `Dict`s hash their keys with. The test app brings its own `roc_alloc`,
+/// so this isn't the address of the one in the test binary.
+#[allow(dead_code)]
+pub fn dict_pseudo_seed(lib: &libloading::Library) -> u64 {
+    unsafe {
+        let seed: libloading::Symbol<unsafe extern "C" fn() -> u64> = lib
+            .get(roc_builtins::bitcode::UTILS_DICT_PSEUDO_SEED.as_bytes())
+            .expect("the dict seed builtin is not exported");
+
+        seed()
+    }
+}
+
 #[allow(dead_code)]
 // only used in tests
 #[allow(dead_code)]
@@ -530,6 +617,7 @@ pub(crate) fn llvm_evals_to<T, U, F>(
     src: &str,
     expected: U,
     transform: F,
+    check: impl FnOnce(&T),
     ignore_problems: bool,
     function_kind: FunctionKind,
 ) where
@@ -559,6 +647,8 @@ pub(crate) fn llvm_evals_to<T, U, F>(
             // only if there are no exceptions thrown, check for errors
             assert!(errors.is_empty(), "Encountered errors:\n{errors}");
 
+            check(&raw);
+
             #[allow(clippy::redundant_closure_call)]
             let given = transform(raw);
             assert_eq!(&given, &expected, "LLVM test failed");
@@ -581,6 +671,11 @@ macro_rules! assert_llvm_evals_to {
             $src,
             $expected,
             $transform,
+            $crate::helpers::check_against_interp!(
+                $src,
+                $ty,
+                $crate::helpers::llvm::ir_settings(target_lexicon::Triple::host().into())
+            ),
             $ignore_problems,
             roc_load::FunctionKind::LambdaSet,
         );
@@ -646,6 +741,8 @@ macro_rules! assert_evals_to_erased {
             $src,
             $expected,
             $crate::helpers::llvm::identity,
+            // the interpreter doesn't run type-erased functions
+            |_: &$ty| {},
             false,
             roc_load::FunctionKind::Erased,
         );
@@ -5,11 +5,46 @@ pub mod platform_functions;
 #[cfg(feature = "gen-dev")]
 pub mod dev;
 pub mod from_wasm32_memory;
+#[cfg(feature = "check-interp")]
+pub mod interp;
 #[cfg(feature = "gen-llvm")]
 pub mod llvm;
 #[cfg(any(feature = "gen-wasm", feature = "gen-llvm-wasm"))]
 pub mod wasm;
 
+/// The target and the optional mono passes a backend loads its tests with. The interpreter check
+/// loads the program with the same settings, so that it runs the IR the backend compiled.
+#[derive(Debug, Clone, Copy)]
+pub struct IrSettings {
+    pub target: roc_target::Target,
+    pub inline_budget: Option<roc_mono::inline::InlineBudget>,
+    pub simplify: bool,
+}
+
+/// A closure that checks a backend's result against the mono IR interpreter, or does nothing
+/// unless the `check-interp` feature is on. See [interp].
+#[cfg(feature = "check-interp")]
+macro_rules! check_against_interp {
+    ($src:expr, $ty:ty, $settings:expr) => {
+        |actual: &$ty| {
+            #[allow(unused_imports)]
+            use $crate::helpers::interp::{CheckAgainstInterp, SkipInterpCheck};
+
+            (&$crate::helpers::interp::InterpCheck::<$ty>::default()).check($src, $settings, actual)
+        }
+    };
+}
+
+#[cfg(not(feature = "check-interp"))]
+macro_rules! check_against_interp {
+    ($src:expr, $ty:ty, $settings:expr) => {
+        |_: &$ty| {}
+    };
+}
+
+#[allow(unused_imports)]
+pub(crate) use check_against_interp;
+
 #[allow(dead_code)]
 pub(crate) fn src_hash(src: &str) -> u64 {
     use std::collections::hash_map::DefaultHasher;
@@ -1,10 +1,11 @@
-use super::{RefCount, RefCountLoc};
+use super::{IrSettings, RefCount, RefCountLoc};
 use crate::helpers::from_wasm32_memory::FromWasm32Memory;
 use bumpalo::Bump;
 use roc_collections::all::MutSet;
 use roc_gen_wasm::wasm32_result::Wasm32Result;
 use roc_gen_wasm::DEBUG_SETTINGS;
 use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_mono::inline::InlineBudget;
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
 use roc_solve::FunctionKind;
@@ -37,6 +38,12 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
+pub const IR_SETTINGS: IrSettings = IrSettings {
+    target: roc_target::Target::Wasm32,
+    inline_budget: Some(InlineBudget::DEFAULT),
+    simplify: true,
+};
+
 fn write_final_wasm() -> bool {
     use roc_debug_flags::dbg_do;
 
@@ -88,12 +95,15 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     }
 
     let load_config = LoadConfig {
-        target: roc_target::Target::Wasm32,
+        target: IR_SETTINGS.target,
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         palette: DEFAULT_PALETTE_HTML,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
         function_kind: FunctionKind::LambdaSet,
+        inline_budget: IR_SETTINGS.inline_budget,
+        simplify: IR_SETTINGS.simplify,
+        module_cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -130,6 +140,8 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         module_id,
         exposed_to_host,
         stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        runs_expects: true,
+        inline_budget: IR_SETTINGS.inline_budget,
     };
 
     let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap_or_else(|e| {
@@ -187,8 +199,35 @@ where
     run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
 }
 
+/// Run a test and also return the messages the app sent to the host through `dbg` and `expect`
+#[allow(dead_code)]
+pub fn host_output_help<T>(src: &str, phantom: PhantomData<T>) -> Result<(T, Vec<String>), String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    let arena = Bump::new();
+
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+
+    let require_relocatable = false;
+    let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
+        .map_err(|e| format!("{:?}", e))?;
+    run_wasm_test_module_with_output(&arena, TEST_WRAPPER_NAME, &module)
+}
+
 struct TestDispatcher<'a> {
     wasi: WasiDispatcher<'a>,
+    /// Messages from `roc_dbg` and `roc_expect_failed`, in the order they were sent
+    host_output: Vec<String>,
+}
+
+impl TestDispatcher<'_> {
+    fn new() -> Self {
+        TestDispatcher {
+            wasi: wasi::WasiDispatcher::default(),
+            host_output: Vec::new(),
+        }
+    }
 }
 
 impl<'a> ImportDispatcher for TestDispatcher<'a> {
@@ -211,6 +250,22 @@ impl<'a> ImportDispatcher for TestDispatcher<'a> {
                 _ => format!(r#"Got an invald panic tag: "{panic_tag}""#),
             };
             panic!("{}", msg)
+        } else if module_name == "env" && function_name == "send_dbg_msg_to_rust" {
+            let [location, msg, source] = [0, 1, 2].map(|i| {
+                let ptr = arguments[i].expect_i32().unwrap();
+                RocStr::decode(memory, ptr as _)
+            });
+            self.host_output
+                .push(format!("[{location}] {source} = {msg}"));
+            None
+        } else if module_name == "env" && function_name == "send_expect_failed_to_rust" {
+            let start = arguments[0].expect_i32().unwrap();
+            let end = arguments[1].expect_i32().unwrap();
+            let module_id = arguments[2].expect_i32().unwrap();
+            self.host_output.push(format!(
+                "expect failed at {start}-{end} in module {module_id}"
+            ));
+            None
         } else {
             panic!(
                 "TestDispatcher does not implement {}.{}",
@@ -242,16 +297,25 @@ pub(crate) fn run_wasm_test_module<'a, T>(
 where
     T: FromWasm32Memory + Wasm32Result,
 {
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    run_wasm_test_module_with_output(arena, test_wrapper_name, module).map(|(output, _)| output)
+}
+
+fn run_wasm_test_module_with_output<'a, T>(
+    arena: &'a Bump,
+    test_wrapper_name: &str,
+    module: &WasmModule<'a>,
+) -> Result<(T, Vec<String>), String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    let dispatcher = TestDispatcher::new();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
     let opt_value = inst.call_export(test_wrapper_name, [])?;
     let addr_value = opt_value.ok_or("No return address from Wasm test")?;
     let addr = addr_value.expect_i32().map_err(|e| format!("{:?}", e))?;
     let output = <T as FromWasm32Memory>::decode(&inst.memory, addr as u32);
-    Ok(output)
+    Ok((output, inst.import_dispatcher.host_output))
 }
 
 #[allow(dead_code)]
@@ -271,9 +335,7 @@ where
     let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
         .map_err(|e| format!("{:?}", e))?;
 
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = TestDispatcher::new();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
 
@@ -386,6 +448,12 @@ macro_rules! assert_evals_to {
         match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom) {
             Err(msg) => panic!("{}", msg),
             Ok(actual) => {
+                #[allow(clippy::redundant_closure_call)]
+                $crate::helpers::check_against_interp!(
+                    $src,
+                    $ty,
+                    $crate::helpers::wasm::IR_SETTINGS
+                )(&actual);
                 assert_eq!($transform(actual), $expected)
             }
         }
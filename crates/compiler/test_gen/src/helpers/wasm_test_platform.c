@@ -132,8 +132,21 @@ void roc_panic(void* msg, unsigned int panic_tag)
     exit(101);
 }
 
-// TODO: add a way to send dbg to rust.
-void roc_debug(void* loc, void* msg) {}
+extern void send_dbg_msg_to_rust(void* loc, void* msg, void* src);
+
+void roc_dbg(void* loc, void* msg, void* src)
+{
+    send_dbg_msg_to_rust(loc, msg, src);
+}
+
+//--------------------------
+
+extern void send_expect_failed_to_rust(uint32_t region_start, uint32_t region_end, uint32_t module_id);
+
+void roc_expect_failed(uint32_t region_start, uint32_t region_end, uint32_t module_id)
+{
+    send_expect_failed_to_rust(region_start, region_end, module_id);
+}
 
 //--------------------------
 
@@ -20,6 +20,7 @@ use roc_load::LoadMonomorphizedError;
 use roc_load::Threading;
 use roc_module::symbol::Interns;
 use roc_module::symbol::Symbol;
+use roc_mono::inline::InlineBudget;
 use roc_mono::ir::Proc;
 use roc_mono::ir::ProcLayout;
 use roc_mono::layout::STLayoutInterner;
@@ -124,7 +125,15 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: bool, no_check: bool) {
+fn compiles_to_ir(
+    test_name: &str,
+    src: &str,
+    mode: &str,
+    allow_type_errors: bool,
+    no_check: bool,
+    inline: bool,
+    simplify: bool,
+) {
     use roc_packaging::cache::RocCacheDir;
     use std::path::PathBuf;
 
@@ -158,6 +167,9 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        inline_budget: inline.then_some(InlineBudget::DEFAULT),
+        simplify,
+        module_cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -3711,3 +3723,144 @@ fn return_annotated() {
         "#
     )
 }
+
+#[mono_test(inline = "true")]
+fn inline_straight_line_helper() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        double : I64 -> I64
+        double = \x -> x * 2
+
+        main = double 3 + double 4
+        "#
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_branching_helper() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        clamp_to_ten : I64 -> I64
+        clamp_to_ten = \x -> if x > 10 then 10 else x
+
+        main = clamp_to_ten 3 + clamp_to_ten 20
+        "#
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_nested_helpers() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        inc : I64 -> I64
+        inc = \x -> x + 1
+
+        double_inc : I64 -> I64
+        double_inc = \x -> inc (inc x)
+
+        main = double_inc 40
+        "#
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_skips_recursive_procs() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        count_down : I64 -> I64
+        count_down = \n -> if n == 0 then 0 else count_down (n - 1)
+
+        is_even : I64 -> Bool
+        is_even = \n -> if n == 0 then Bool.true else is_odd (n - 1)
+
+        is_odd : I64 -> Bool
+        is_odd = \n -> if n == 0 then Bool.false else is_even (n - 1)
+
+        main = if is_even (count_down 10) then 1i64 else 0
+        "#
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_higher_order_lowlevel() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = List.sort_with [3, 1, 2] Num.compare
+        "#
+    )
+}
+
+#[mono_test(simplify = "true")]
+fn simplify_fold_arithmetic() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 1 + 2 * 3 - 4
+        "#
+    )
+}
+
+#[mono_test(simplify = "true")]
+fn simplify_keeps_overflowing_arithmetic() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = (255u8 + 1, Num.add_wrap 255u8 1, Num.add_saturated 255u8 1)
+        "#
+    )
+}
+
+#[mono_test(simplify = "true")]
+fn simplify_known_condition() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = if 1 > 2 then "yes" else "no"
+        "#
+    )
+}
+
+#[mono_test(simplify = "true")]
+fn simplify_known_tag() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            x : [A, B I64, C Str]
+            x = B 42
+
+            when x is
+                A -> 0
+                B n -> n
+                C _ -> 1
+        "#
+    )
+}
+
+#[mono_test(inline = "true", simplify = "true")]
+fn simplify_after_inlining() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        clamp_to_ten : I64 -> I64
+        clamp_to_ten = \x -> if x > 10 then 10 else x
+
+        main = clamp_to_ten 3 + clamp_to_ten 20
+        "#
+    )
+}
@@ -84,6 +84,7 @@ pub fn run_load_and_infer<'a>(
             function_kind,
             roc_reporting::report::RenderTarget::Generic,
             RocCacheDir::Disallowed,
+            None,
             roc_reporting::report::DEFAULT_PALETTE,
         );
 
@@ -1,10 +1,11 @@
 use roc_module::symbol::Symbol;
 
 use crate::subs::Variable;
+use serde::{Deserialize, Serialize};
 
 /// A bound placed on a number because of its literal value.
 /// e.g. `-5` cannot be unsigned, and 300 does not fit in a U8
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumericRange {
     IntAtLeastSigned(IntLitWidth),
     IntAtLeastEitherSign(IntLitWidth),
@@ -165,7 +166,7 @@ impl IntSignedness {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum IntLitWidth {
     U8,
     U16,
@@ -331,14 +332,14 @@ impl IntLitWidth {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum FloatWidth {
     Dec,
     F32,
     F64,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum SignDemand {
     /// Can be signed or unsigned.
     NoDemand,
@@ -347,7 +348,7 @@ pub enum SignDemand {
 }
 
 /// Describes a bound on the width of an integer.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum IntBound {
     /// There is no bound on the width.
     None,
@@ -360,13 +361,13 @@ pub enum IntBound {
     },
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum FloatBound {
     None,
     Exact(FloatWidth),
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum NumBound {
     None,
     /// Must be an integer of a certain size, or any float.
@@ -376,7 +377,7 @@ pub enum NumBound {
     },
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum SingleQuoteBound {
     AtLeast { width: IntLitWidth },
 }
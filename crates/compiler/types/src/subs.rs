@@ -10,6 +10,7 @@ use roc_collections::{VecMap, VecSet};
 use roc_error_macros::internal_error;
 use roc_module::ident::{Lowercase, TagName, Uppercase};
 use roc_module::symbol::{ModuleId, Symbol};
+use serde::{Deserialize, Serialize};
 use soa::{Index, Slice};
 use std::fmt;
 use std::iter::{self, Iterator};
@@ -874,7 +875,7 @@ impl std::fmt::Debug for DebugUtable<'_> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VarStore {
     next: u32,
 }
@@ -914,7 +915,7 @@ impl VarStore {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct OptVariable(u32);
 
 impl OptVariable {
@@ -989,7 +990,7 @@ impl From<OptVariable> for Option<Variable> {
 }
 
 /// Marks whether a when expression is exhaustive using a variable.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExhaustiveMark(Variable);
 
 impl ExhaustiveMark {
@@ -1013,7 +1014,7 @@ impl ExhaustiveMark {
 }
 
 /// Marks whether a when branch is redundant using a variable.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RedundantMark(Variable);
 
 impl RedundantMark {
@@ -1044,7 +1045,7 @@ pub fn new_marks(var_store: &mut VarStore) -> (RedundantMark, ExhaustiveMark) {
 }
 
 /// Marks whether a recursive let-cycle was determined to be illegal during solving.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IllegalCycleMark(OptVariable);
 
 impl IllegalCycleMark {
@@ -1073,7 +1074,7 @@ impl IllegalCycleMark {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Variable(u32);
 
 macro_rules! define_const_var {
@@ -13,6 +13,7 @@ use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_region::all::{Loc, Region};
+use serde::{Deserialize, Serialize};
 use soa::{Index, Slice};
 use std::fmt;
 use std::fmt::Write;
@@ -40,7 +41,7 @@ const GREEK_LETTERS: &[char] = &[
 ///     Can only unify with Required and Demanded, to prevent an optional field being typed as Required
 /// - RigidOptional: introduced by annotations, e.g. { x ? Str}
 ///     Can only unify with Optional, to prevent a required field being typed as Optional
-#[derive(PartialEq, Eq, Clone, Hash)]
+#[derive(PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub enum RecordField<T> {
     Demanded(T),
     Required(T),
@@ -207,7 +208,7 @@ impl RecordField<Type> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct LambdaSet(pub Type);
 
 impl LambdaSet {
@@ -231,7 +232,7 @@ impl LambdaSet {
     }
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct AliasCommon {
     pub symbol: Symbol,
     pub type_arguments: Vec<Loc<OptAbleType>>,
@@ -251,7 +252,7 @@ pub struct AliasCommon {
 ///
 /// In the future we might want to do some small-vec optimizations, though that may be trivialized
 /// away with a SoA representation of canonicalized types.
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
 pub struct AbilitySet(Vec<Symbol>);
 
 impl AbilitySet {
@@ -306,7 +307,7 @@ impl FromIterator<Symbol> for AbilitySet {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OptAbleVar {
     pub var: Variable,
     pub opt_abilities: Option<AbilitySet>,
@@ -321,7 +322,7 @@ impl OptAbleVar {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct OptAbleType {
     pub typ: Type,
     pub opt_abilities: Option<AbilitySet>,
@@ -1665,7 +1666,7 @@ impl std::ops::Index<Slice<AsideTypeSlice>> for Types {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Serialize, Deserialize)]
 pub enum Type {
     EmptyRec,
     EmptyTagUnion,
@@ -1717,7 +1718,7 @@ pub enum Type {
 /// usage site. Unspecialized lambda sets aid us in recovering those lambda sets; when we
 /// instantiate `a` with a proper type `T`, we'll know to resolve the lambda set by extracting
 /// it at region "1" from the specialization of "default" for `T`.
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Uls(pub Variable, pub Symbol, pub u8);
 
 impl std::fmt::Debug for Uls {
@@ -1813,13 +1814,13 @@ impl Clone for OptAbleType {
 /// marker of openness-polymorphism - it can only be inferred to be polymorphic in size, or closed,
 /// but can't grow more monomorphic tags.
 /// E.g. `[]_a` can unify with `[]` or `[]*` but not `[A, B]`.
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum ExtImplicitOpenness {
     Yes,
     No,
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum TypeExtension {
     Open(Box<Type>, ExtImplicitOpenness),
     Closed,
@@ -3348,7 +3349,7 @@ fn variables_help_detailed(tipe: &Type, accum: &mut VariableDetail) {
 }
 
 /// Either a field name for a record or an index into a tuple
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndexOrField {
     Field(Lowercase),
     Index(usize),
@@ -3542,7 +3543,7 @@ pub enum Category {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EarlyReturnKind {
     Return,
     Try,
@@ -3567,7 +3568,7 @@ pub enum PatternCategory {
     Character,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AliasKind {
     /// A structural alias is something like
     ///   List a : [Nil, Cons a (List a)]
@@ -3596,7 +3597,7 @@ impl AliasKind {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AliasVar {
     pub name: Lowercase,
     pub var: Variable,
@@ -3623,7 +3624,7 @@ impl From<&AliasVar> for OptAbleVar {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MemberImpl {
     /// The implementation is claimed to be at the given symbol.
     /// During solving we validate that the impl is really there.
@@ -3632,7 +3633,7 @@ pub enum MemberImpl {
     Error,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Alias {
     pub region: Region,
     pub type_variables: Vec<Loc<AliasVar>>,
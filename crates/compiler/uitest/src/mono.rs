@@ -49,6 +49,9 @@ pub(crate) fn write_compiled_ir<'a>(
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
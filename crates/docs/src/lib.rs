@@ -699,6 +699,9 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        inline_budget: None,
+        simplify: false,
+        module_cache_dir: Some(cache::roc_cache_modules_dir()),
     };
     match roc_load::load_and_typecheck(
         &arena,
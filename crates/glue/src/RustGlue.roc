@@ -2204,16 +2204,16 @@ type_name = \types, id ->
         Num(F32) -> "f32"
         Num(F64) -> "f64"
         Num(Dec) -> "roc_std:RocDec"
-        RocDict(_key, _value) ->
-            # key_name = type_name(types, key)
-            # value_name = type_name(types, value)
-            # "roc_std::RocDict<${key_name}, ${value_name}>"
-            crash("RocDict is not yet supported in rust")
-
-        RocSet(_elem) ->
-            # elem_name = type_name(types, elem)
-            # "roc_std::RocSet<${elem_name}>"
-            crash("RocSet is not yet supported in rust")
+        RocDict(key, value) ->
+            key_name = type_name(types, key)
+            value_name = type_name(types, value)
+
+            "roc_std::RocDict<${key_name}, ${value_name}, roc_std::HostSeed>"
+
+        RocSet(elem) ->
+            elem_name = type_name(types, elem)
+
+            "roc_std::RocSet<${elem_name}, roc_std::HostSeed>"
 
         RocList(elem) ->
             elem_name = type_name(types, elem)
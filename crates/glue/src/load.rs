@@ -17,6 +17,7 @@ use roc_mono::ir::{generate_glue_procs, CrashTag, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_reporting::warnings::WarningConfig;
 use roc_target::{Architecture, Target, TargetFromTripleError::TripleUnsupported};
 use roc_types::subs::{Subs, Variable};
 use std::fs::File;
@@ -91,7 +92,9 @@ pub fn generate(
                     None,
                     RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
                     load_config,
+                    &WarningConfig::default(),
                     Some(dylib_dir.path()),
+                    None,
                     false,
                 ),
                 Err(_) => {
@@ -106,6 +109,7 @@ pub fn generate(
                     problems,
                     total_time,
                     expect_metadata: _,
+                    source_paths: _,
                 }) => {
                     // TODO: Should binary_path be update to deal with extensions?
                     use roc_target::OperatingSystem;
@@ -188,10 +192,15 @@ pub fn generate(
 
                     Ok(0)
                 }
-                Err(BuildFileError::ErrorModule { module, total_time }) => {
-                    handle_error_module(module, total_time)
+                Err(BuildFileError::ErrorModule { module, total_time }) => handle_error_module(
+                    module,
+                    total_time,
+                    RenderTarget::ColorTerminal,
+                    &WarningConfig::default(),
+                ),
+                Err(BuildFileError::LoadingProblem(problem)) => {
+                    handle_loading_problem(problem, RenderTarget::ColorTerminal)
                 }
-                Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
             };
 
             // Extend the lifetime of the tempdir to after we're done with everything,
@@ -430,6 +439,9 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            inline_budget: None,
+            simplify: false,
+            module_cache_dir: Some(cache::roc_cache_modules_dir()),
         },
     )
     .unwrap_or_else(|problem| match problem {
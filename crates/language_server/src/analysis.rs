@@ -8,6 +8,7 @@ use bumpalo::Bump;
 
 use parking_lot::Mutex;
 use roc_can::{abilities::AbilitiesStore, expr::Declarations};
+use roc_cli::{fix_edits, FixEdit};
 use roc_collections::{MutMap, MutSet, VecMap};
 use roc_load::{docs::ModuleDocumentation, CheckedModule, LoadedModule};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
@@ -23,9 +24,13 @@ mod analysed_doc;
 mod annotation_visitor;
 mod completion;
 mod parse_ast;
+mod references;
 mod semantic_tokens;
+mod symbols;
 mod tokens;
+mod type_hints;
 mod utils;
+mod when_branches;
 
 use crate::convert::diag::{IntoLspDiagnostic, ProblemFmt};
 
@@ -94,6 +99,10 @@ pub(super) struct AnalyzedModule {
     // ModuleIds are not stable between compilations, so a ModuleId visible to
     // one module may not be true global to the language server.
     module_id_to_url: ModuleIdToUrl,
+    /// Automatic fixes for the warnings in this module
+    fixes: Vec<FixEdit>,
+    /// Fixes for the `when` expressions with missing or redundant branches
+    branch_fixes: Vec<FixEdit>,
 }
 #[derive(Debug, Clone)]
 pub struct AnalysisResult {
@@ -116,6 +125,8 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
         roc_load::FunctionKind::LambdaSet,
         roc_reporting::report::RenderTarget::LanguageServer,
         RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+        // every edit would add cache entries, and the module cache is never pruned
+        None,
         roc_reporting::report::DEFAULT_PALETTE,
     );
 
@@ -302,6 +313,17 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             declarations = self.declarations_by_id.remove(&module_id).unwrap();
         }
 
+        let fixes = self
+            .can_problems
+            .get(&module_id)
+            .map(|problems| fix_edits(&source, problems, self.interns, &declarations))
+            .unwrap_or_default();
+        let branch_fixes = self
+            .type_problems
+            .get(&module_id)
+            .map(|problems| when_branches::branch_fixes(&source, problems, self.interns))
+            .unwrap_or_default();
+
         let analyzed_module = AnalyzedModule {
             exposed_imports,
             imports_by_module: imports,
@@ -312,6 +334,8 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             modules_info: self.modules_info.clone(),
             interns: self.interns.clone(),
             module_id_to_url: self.module_id_to_url.clone(),
+            fixes,
+            branch_fixes,
         };
 
         let line_info = LineInfo::new(&source);
@@ -1,19 +1,24 @@
 use log::{debug, info};
 
-use roc_cli::{annotation_edit, annotation_edits};
+use roc_cli::{annotation_edit, annotation_edits, FixEdit};
 use roc_fmt::MigrationFlags;
 use std::collections::HashMap;
 
 use bumpalo::Bump;
 
-use roc_module::symbol::{ModuleId, Symbol};
+use roc_module::{
+    ident::Lowercase,
+    symbol::{Interns, ModuleId, Symbol},
+};
 
 use roc_region::all::{LineInfo, Position as RocPosition, Region};
 
 use tower_lsp::lsp_types::{
-    CodeAction, CodeActionKind, CompletionItem, Diagnostic, GotoDefinitionResponse, Hover,
-    HoverContents, LanguageString, Location, MarkedString, Position, Range, SemanticTokens,
-    SemanticTokensResult, TextEdit, Url, WorkspaceEdit,
+    CodeAction, CodeActionKind, CompletionItem, Diagnostic, DocumentSymbol, DocumentSymbolResponse,
+    Documentation, GotoDefinitionResponse, Hover, HoverContents, InlayHint, InlayHintKind,
+    InlayHintLabel, LanguageString, Location, MarkedString, ParameterInformation, ParameterLabel,
+    Position, Range, SemanticTokens, SemanticTokensResult, SignatureHelp, SignatureInformation,
+    SymbolInformation, TextEdit, Url, WorkspaceEdit,
 };
 
 use crate::{
@@ -27,13 +32,32 @@ use crate::{
 use super::{
     annotation_visitor::{find_declaration_at, FoundDeclaration, NotFound},
     parse_ast::Ast,
+    references::{find_annotation_fields, find_occurrences, Occurrence, RecordShape, Referent},
     semantic_tokens::arrange_semantic_tokens,
-    utils::{format_var_type, is_roc_identifier_char},
+    type_hints::{find_call_at, find_type_hints},
+    utils::{
+        format_var_type, function_parameter_ranges, fuzzy_match_score, is_roc_identifier_char,
+    },
     AnalysisResult, AnalyzedModule,
 };
 
 pub(super) type ModuleIdToUrl = HashMap<ModuleId, Url>;
 
+/// Something that can be referenced by name, in a form that any document can look up.
+/// Symbols can't be compared directly, because each analysis hands out its own ModuleIds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalReferent {
+    /// A top-level value or ability member, which other modules may import.
+    TopLevel { module_url: Url, name: String },
+    /// Anything else is only visible within the document that defines it.
+    Local { url: Url, symbol: Symbol },
+    /// A record field, which is the same field in every record of a matching shape.
+    Field {
+        label: Lowercase,
+        record: RecordShape,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct AnalyzedDocument {
     pub doc_info: DocInfo,
@@ -131,6 +155,16 @@ impl DocInfo {
             data,
         }))
     }
+
+    pub fn document_symbols(&self) -> Option<DocumentSymbolResponse> {
+        let source = &self.source;
+        let arena = &Bump::new();
+
+        let ast = Ast::parse(arena, source).ok()?;
+        let symbols = ast.document_symbols(source, &self.line_info);
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
 }
 
 impl AnalyzedDocument {
@@ -221,6 +255,111 @@ impl AnalyzedDocument {
         })
     }
 
+    pub fn signature_help(&self, position: Position) -> Option<SignatureHelp> {
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            modules_info,
+            ..
+        } = self.module()?;
+
+        let position = position.to_roc_position(self.line_info());
+        let call = find_call_at(position, declarations)?;
+
+        let signature = format_var_type(call.fn_var, &mut subs.clone(), module_id, interns);
+        let parameter_ranges = function_parameter_ranges(&signature)?;
+
+        // Inline closures have no name to show.
+        let prefix = match call.fn_symbol {
+            Some(_) => {
+                let region = call.fn_region;
+                let name = self
+                    .doc_info
+                    .source
+                    .get(region.start().offset as usize..region.end().offset as usize)?;
+                format!("{name} : ")
+            }
+            None => String::new(),
+        };
+
+        let label = format!("{prefix}{signature}");
+
+        // LSP counts label offsets in UTF-16 code units, like positions.
+        let utf16_offset = |byte_offset: usize| label[..byte_offset].encode_utf16().count() as u32;
+
+        let parameters = parameter_ranges
+            .into_iter()
+            .map(|range| ParameterInformation {
+                label: ParameterLabel::LabelOffsets([
+                    utf16_offset(prefix.len() + range.start),
+                    utf16_offset(prefix.len() + range.end),
+                ]),
+                documentation: None,
+            })
+            .collect::<Vec<_>>();
+
+        let documentation = call.fn_symbol.and_then(|symbol| {
+            modules_info
+                .get_docs(&symbol.module_id())?
+                .get_doc_for_symbol(&symbol)
+                .map(Documentation::String)
+        });
+
+        let active_parameter = call.active_argument.min(parameters.len().saturating_sub(1)) as u32;
+
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label,
+                documentation,
+                parameters: Some(parameters),
+                active_parameter: Some(active_parameter),
+            }],
+            active_signature: Some(0),
+            active_parameter: Some(active_parameter),
+        })
+    }
+
+    pub fn inlay_hints(&self, range: Range) -> Option<Vec<InlayHint>> {
+        let AnalyzedModule {
+            subs,
+            declarations,
+            abilities,
+            module_id,
+            interns,
+            ..
+        } = self.module()?;
+
+        let line_info = self.line_info();
+        let region = range.to_region(line_info);
+        let mut subs = subs.clone();
+
+        let hints = find_type_hints(declarations, abilities, &self.doc_info.source)
+            .into_iter()
+            .filter(|(position, var)| {
+                region.contains_pos(*position) && !subs.var_contains_error(*var)
+            })
+            .map(|(position, var)| {
+                let type_str = format_var_type(var, &mut subs, module_id, interns);
+                let line_column = line_info.convert_pos(position);
+
+                InlayHint {
+                    position: Position::new(line_column.line, line_column.column),
+                    label: InlayHintLabel::String(format!(": {type_str}")),
+                    kind: Some(InlayHintKind::TYPE),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: None,
+                    data: None,
+                }
+            })
+            .collect();
+
+        Some(hints)
+    }
+
     pub fn definition(&self, symbol: Symbol) -> Option<GotoDefinitionResponse> {
         let AnalyzedModule { declarations, .. } = self.module()?;
 
@@ -235,6 +374,295 @@ impl AnalyzedDocument {
         self.module()?.module_id_to_url.get(&module_id).cloned()
     }
 
+    /// Every name in this document that can be located, including the record fields in type
+    /// definitions.
+    fn occurrences(&self) -> Vec<Occurrence> {
+        let Some(AnalyzedModule {
+            interns,
+            subs,
+            declarations,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+
+        let source = &self.doc_info.source;
+        let mut occurrences = find_occurrences(declarations, interns, subs, source);
+
+        let arena = Bump::new();
+        if let Ok(ast) = Ast::parse(&arena, source) {
+            for annotation in ast.type_def_annotations() {
+                find_annotation_fields(annotation, 0, &mut occurrences);
+            }
+        }
+
+        occurrences
+    }
+
+    /// The range of the name at `position`, and what it refers to.
+    pub(crate) fn referent_at(&self, position: Position) -> Option<(Range, GlobalReferent)> {
+        let position = position.to_roc_position(self.line_info());
+        let AnalyzedModule {
+            module_id,
+            interns,
+            declarations,
+            abilities,
+            ..
+        } = self.module()?;
+
+        let found = self
+            .occurrences()
+            .into_iter()
+            .find(|occurrence| occurrence.region.contains_pos(position))
+            .map(|occurrence| (occurrence.region, occurrence.referent));
+
+        let (region, referent) = match found {
+            Some(found) => found,
+            None => self.exposed_referent_at(position)?,
+        };
+
+        let referent = match referent {
+            Referent::Field { label, record } => GlobalReferent::Field { label, record },
+            Referent::Symbol(symbol) => {
+                let is_top_level = symbol.module_id() != *module_id
+                    || declarations.symbols.iter().any(|s| s.value == symbol)
+                    || abilities.is_ability_member_name(symbol);
+
+                if is_top_level {
+                    GlobalReferent::TopLevel {
+                        module_url: self.module_url(symbol.module_id())?,
+                        name: symbol_name(interns, symbol)?.to_string(),
+                    }
+                } else {
+                    GlobalReferent::Local {
+                        url: self.url().clone(),
+                        symbol,
+                    }
+                }
+            }
+        };
+
+        Some((region.to_range(self.line_info()), referent))
+    }
+
+    /// Names in the module header and in `import ... exposing` clauses don't make it into the
+    /// canonical AST, so we look for them in the parsed one.
+    fn exposed_referent_at(&self, position: RocPosition) -> Option<(Region, Referent)> {
+        let AnalyzedModule {
+            module_id, interns, ..
+        } = self.module()?;
+
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, &self.doc_info.source).ok()?;
+
+        let (name_module_id, name) = match ast
+            .exposed_names()
+            .into_iter()
+            .find(|name| name.region.contains_pos(position))
+        {
+            Some(name) => (*module_id, name),
+            None => {
+                let (module_name, name) = ast
+                    .imported_names()
+                    .into_iter()
+                    .find(|(_, name)| name.region.contains_pos(position))?;
+
+                (
+                    interns.module_ids.get_id(&module_name.as_str().into())?,
+                    name,
+                )
+            }
+        };
+
+        let ident_id = interns
+            .all_ident_ids
+            .get(&name_module_id)?
+            .get_id(name.value)?;
+
+        Some((
+            name.region,
+            Referent::Symbol(Symbol::new(name_module_id, ident_id)),
+        ))
+    }
+
+    /// Every place in this document that names `referent`.
+    fn find_references(&self, referent: &GlobalReferent) -> Vec<Occurrence> {
+        let Some(AnalyzedModule {
+            module_id,
+            interns,
+            abilities,
+            module_id_to_url,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+
+        let source = &self.doc_info.source;
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, source).ok();
+
+        let mut references = Vec::new();
+
+        match referent {
+            GlobalReferent::Local { url, symbol } => {
+                if url == self.url() {
+                    references.extend(
+                        self.occurrences()
+                            .into_iter()
+                            .filter(|occurrence| occurrence.referent == Referent::Symbol(*symbol)),
+                    );
+                }
+            }
+            GlobalReferent::TopLevel { module_url, name } => {
+                let Some(defining_module_id) = module_id_to_url
+                    .iter()
+                    .find_map(|(id, url)| (url == module_url).then_some(*id))
+                else {
+                    return vec![];
+                };
+                let Some(ident_id) = interns
+                    .all_ident_ids
+                    .get(&defining_module_id)
+                    .and_then(|ident_ids| ident_ids.get_id(name))
+                else {
+                    return vec![];
+                };
+                let symbol = Symbol::new(defining_module_id, ident_id);
+
+                references.extend(
+                    self.occurrences()
+                        .into_iter()
+                        .filter(|occurrence| occurrence.referent == Referent::Symbol(symbol)),
+                );
+
+                let exposed_names = if defining_module_id == *module_id {
+                    if let Some(member) = abilities.member_def(symbol) {
+                        references.push(Occurrence {
+                            referent: Referent::Symbol(symbol),
+                            region: member.region,
+                            is_pun: false,
+                        });
+                    }
+
+                    ast.iter()
+                        .flat_map(|ast| ast.exposed_names())
+                        .collect::<Vec<_>>()
+                } else {
+                    let module_name = interns.module_ids.get_name(defining_module_id);
+
+                    ast.iter()
+                        .flat_map(|ast| ast.imported_names())
+                        .filter(|(imported_from, _)| {
+                            module_name.is_some_and(|m| m.as_str() == imported_from.as_str())
+                        })
+                        .map(|(_, name)| name)
+                        .collect()
+                };
+
+                references.extend(
+                    exposed_names
+                        .into_iter()
+                        .filter(|exposed| exposed.value == name.as_str())
+                        .map(|exposed| Occurrence {
+                            referent: Referent::Symbol(symbol),
+                            region: exposed.region,
+                            is_pun: false,
+                        }),
+                );
+            }
+            GlobalReferent::Field { label, record } => {
+                references.extend(self.occurrences().into_iter().filter(|occurrence| {
+                    matches!(
+                        &occurrence.referent,
+                        Referent::Field { label: l, record: r } if l == label && r.may_be_same_as(record)
+                    )
+                }));
+            }
+        }
+
+        references.sort_by_key(|occurrence| occurrence.region.start());
+        references.dedup_by_key(|occurrence| occurrence.region);
+        references
+    }
+
+    pub(crate) fn reference_locations(&self, referent: &GlobalReferent) -> Vec<Location> {
+        self.find_references(referent)
+            .into_iter()
+            .map(|occurrence| self.location(occurrence.region.to_range(self.line_info())))
+            .collect()
+    }
+
+    pub(crate) fn rename_edits(&self, referent: &GlobalReferent, new_name: &str) -> Vec<TextEdit> {
+        self.find_references(referent)
+            .into_iter()
+            .map(|occurrence| {
+                let region = occurrence.region;
+                let old_name = &self.doc_info.source
+                    [region.start().offset as usize..region.end().offset as usize];
+
+                // `{ x }` both names a field and binds (or looks up) a variable, so renaming
+                // the variable turns it into `{ x: y }`, and renaming the field into `{ y: x }`.
+                let new_text = match (occurrence.is_pun, &occurrence.referent) {
+                    (false, _) => new_name.to_string(),
+                    (true, Referent::Field { .. }) => format!("{new_name}: {old_name}"),
+                    (true, Referent::Symbol(_)) => format!("{old_name}: {new_name}"),
+                };
+
+                TextEdit {
+                    range: region.to_range(self.line_info()),
+                    new_text,
+                }
+            })
+            .collect()
+    }
+
+    /// The symbols in this document whose names fuzzy-match `query`, each with its score.
+    #[allow(deprecated)] // for `SymbolInformation::deprecated`, which has no default
+    pub fn workspace_symbols(&self, query: &str) -> Vec<(u32, SymbolInformation)> {
+        let Some(DocumentSymbolResponse::Nested(symbols)) = self.doc_info.document_symbols() else {
+            return vec![];
+        };
+
+        let mut matches = Vec::new();
+        let mut pending = symbols
+            .into_iter()
+            .map(|symbol| (None, symbol))
+            .collect::<Vec<_>>();
+
+        while let Some((container_name, symbol)) = pending.pop() {
+            let DocumentSymbol {
+                name,
+                kind,
+                range,
+                children,
+                ..
+            } = symbol;
+
+            pending.extend(
+                children
+                    .into_iter()
+                    .flatten()
+                    .map(|child| (Some(name.clone()), child)),
+            );
+
+            if let Some(score) = fuzzy_match_score(query, &name) {
+                let symbol = SymbolInformation {
+                    name,
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    location: self.location(range),
+                    container_name,
+                };
+                matches.push((score, symbol));
+            }
+        }
+
+        matches
+    }
+
     pub fn completion_items(
         &self,
         position: Position,
@@ -339,6 +767,77 @@ impl AnalyzedDocument {
         }
     }
 
+    /// Quick fixes for the problems at `range`, and one to fix all the warnings in the document
+    /// when there is more than one.
+    pub fn quick_fixes(&self, range: Range) -> Vec<CodeAction> {
+        let Some(AnalyzedModule {
+            fixes,
+            branch_fixes,
+            ..
+        }) = self.module()
+        else {
+            return Vec::new();
+        };
+
+        let region = range.to_region(self.line_info());
+        let code_action = |title: String, kind: CodeActionKind, fixes: &[&FixEdit]| {
+            let edits = fixes
+                .iter()
+                .map(|fix| TextEdit {
+                    range: Region::new(
+                        RocPosition::new(fix.range.start as u32),
+                        RocPosition::new(fix.range.end as u32),
+                    )
+                    .to_range(self.line_info()),
+                    new_text: fix.new_text.clone(),
+                })
+                .collect();
+
+            CodeAction {
+                title,
+                kind: Some(kind),
+                edit: Some(WorkspaceEdit::new(HashMap::from([(
+                    self.url().clone(),
+                    edits,
+                )]))),
+                ..Default::default()
+            }
+        };
+
+        let mut actions: Vec<_> = fixes
+            .iter()
+            .chain(branch_fixes)
+            .filter(|fix| {
+                fix.problem.start() <= region.end() && region.start() <= fix.problem.end()
+            })
+            .map(|fix| code_action(fix.title.clone(), CodeActionKind::QUICKFIX, &[fix]))
+            .collect();
+
+        // Missing branches are filled in with crashes, so they aren't fixed along with the rest
+        if fixes.len() > 1 {
+            // Overlapping edits aren't allowed in a single workspace edit
+            let mut all_fixes: Vec<_> = fixes.iter().collect();
+            all_fixes.sort_by_key(|fix| (fix.range.start, fix.range.end));
+
+            let mut end = 0;
+            all_fixes.retain(|fix| {
+                let keep = fix.range.start >= end;
+                if keep {
+                    end = fix.range.end;
+                }
+                keep
+            });
+
+            actions.push(code_action(
+                "Fix all auto-fixable problems".to_owned(),
+                CodeActionKind::SOURCE_FIX_ALL,
+                &all_fixes,
+            ));
+        }
+
+        actions
+    }
+
     fn annnotate_top_level(&self) -> Option<CodeAction> {
         let AnalyzedModule {
             module_id,
@@ -410,3 +909,10 @@ impl AnalyzedDocument {
         })
     }
 }
+
+fn symbol_name(interns: &Interns, symbol: Symbol) -> Option<&str> {
+    interns
+        .all_ident_ids
+        .get(&symbol.module_id())?
+        .get_name(symbol.ident_id())
+}
@@ -1,15 +1,19 @@
 use bumpalo::Bump;
 use roc_fmt::{Buf, MigrationFlags};
 use roc_parse::{
-    ast::{Defs, Header, SpacesBefore},
-    header::parse_module_defs,
+    ast::{Defs, Header, Spaced, SpacesBefore, TypeAnnotation, TypeDef, ValueDef},
+    header::{parse_module_defs, ExposedName},
     parser::SyntaxError,
 };
-use roc_region::all::Loc;
+use roc_region::all::{LineInfo, Loc};
+use tower_lsp::lsp_types::DocumentSymbol;
 
 use self::format::FormattedAst;
 
-use super::tokens::{IterTokens, Token};
+use super::{
+    symbols::defs_symbols,
+    tokens::{IterTokens, Token},
+};
 
 mod format;
 
@@ -58,4 +62,66 @@ impl<'a> Ast<'a> {
 
         header_tokens.into_iter().chain(body_tokens)
     }
+
+    pub fn document_symbols(&self, source: &str, line_info: &LineInfo) -> Vec<DocumentSymbol> {
+        defs_symbols(&self.defs, source, line_info, true)
+    }
+
+    /// The values listed in the module header, i.e. what a module exposes or an app provides.
+    pub fn exposed_names(&self) -> Vec<Loc<&'a str>> {
+        let exposes = match &self.module.item {
+            Header::Module(header) => header.exposes.items,
+            Header::Hosted(header) => header.exposes.items,
+            Header::App(header) => header.provides.items,
+            Header::Package(_) | Header::Platform(_) => &[],
+        };
+
+        exposes.iter().map(exposed_name).collect()
+    }
+
+    /// The types of type aliases, opaque types and ability members, which don't make it into
+    /// the canonical declarations.
+    pub fn type_def_annotations(&self) -> Vec<&Loc<TypeAnnotation<'a>>> {
+        self.defs
+            .type_defs
+            .iter()
+            .flat_map(|type_def| match type_def {
+                TypeDef::Alias { ann, .. } => vec![ann],
+                TypeDef::Opaque { typ, .. } => vec![typ],
+                TypeDef::Ability { members, .. } => {
+                    members.iter().map(|member| &member.typ).collect()
+                }
+            })
+            .collect()
+    }
+
+    /// The values listed in `import ... exposing [...]` clauses, along with the name of the
+    /// module they are imported from, e.g. `pf.Stdout`.
+    pub fn imported_names(&self) -> Vec<(String, Loc<&'a str>)> {
+        self.defs
+            .value_defs
+            .iter()
+            .filter_map(|value_def| match value_def {
+                ValueDef::ModuleImport(import) => Some(import),
+                _ => None,
+            })
+            .flat_map(|import| {
+                let name = import.name.value;
+                let module_name = match name.package {
+                    Some(package) => format!("{}.{}", package, name.name.as_str()),
+                    None => name.name.as_str().to_string(),
+                };
+
+                let exposed = import.exposed.map_or(&[][..], |exposed| exposed.item.items);
+
+                exposed
+                    .iter()
+                    .map(move |loc_name| (module_name.clone(), exposed_name(loc_name)))
+            })
+            .collect()
+    }
+}
+
+fn exposed_name<'a>(loc_name: &Loc<Spaced<'a, ExposedName<'a>>>) -> Loc<&'a str> {
+    loc_name.map(|spaced| (*spaced.item()).into())
 }
@@ -0,0 +1,455 @@
+use bumpalo::Bump;
+use roc_can::{
+    def::Def,
+    expr::{Declarations, Expr, Field, StructAccessorData},
+    pattern::{DestructType, Pattern, RecordDestruct},
+    traverse::{self, DeclarationInfo, Visitor},
+};
+use roc_module::{
+    ident::Lowercase,
+    symbol::{Interns, Symbol},
+};
+use roc_parse::{
+    ast::{AssignedField, Tag, TypeAnnotation},
+    parser::Parser,
+    state::State,
+    type_annotation,
+};
+use roc_region::all::{Loc, Position, Region};
+use roc_types::{
+    subs::{Content, FlatType, Subs, Variable},
+    types::{gather_fields_unsorted_iter, IndexOrField},
+};
+
+/// Something that can be referenced by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Referent {
+    Symbol(Symbol),
+    /// Record fields have no symbol of their own, so they are matched by label and by the shape
+    /// of the record they belong to.
+    Field {
+        label: Lowercase,
+        record: RecordShape,
+    },
+}
+
+/// The labels of a record type. Records are typed structurally, so this is all there is to
+/// tell two record types apart, and unlike a `Variable` it means the same in every module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordShape {
+    /// Sorted, and including the field that is referenced.
+    pub labels: Vec<Lowercase>,
+    /// Whether the record may have more fields, like the argument of `\r -> r.x`.
+    pub is_open: bool,
+}
+
+impl RecordShape {
+    fn of_var(subs: &Subs, mut var: Variable) -> Option<Self> {
+        loop {
+            match subs.get_content_without_compacting(var) {
+                Content::Alias(_, _, real_var, _) => var = *real_var,
+                Content::RecursionVar { structure, .. } => var = *structure,
+                Content::Structure(FlatType::Record(fields, ext)) => {
+                    let (fields, ext) = gather_fields_unsorted_iter(subs, *fields, *ext).ok()?;
+                    let labels = fields.map(|(label, _)| label.clone()).collect();
+                    let is_open = !matches!(
+                        subs.get_content_without_compacting(ext),
+                        Content::Structure(FlatType::EmptyRecord)
+                    );
+
+                    return Some(Self::new(labels, is_open));
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn new(mut labels: Vec<Lowercase>, is_open: bool) -> Self {
+        labels.sort();
+        labels.dedup();
+
+        Self { labels, is_open }
+    }
+
+    /// Whether a value of one of the shapes could be used where the other one is expected, in
+    /// which case renaming a field of one has to rename it in the other too.
+    pub fn may_be_same_as(&self, other: &Self) -> bool {
+        let is_subset = |a: &Self, b: &Self| a.labels.iter().all(|l| b.labels.contains(l));
+
+        match (self.is_open, other.is_open) {
+            (false, false) => self.labels == other.labels,
+            (true, false) => is_subset(self, other),
+            (false, true) => is_subset(other, self),
+            (true, true) => is_subset(self, other) || is_subset(other, self),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub referent: Referent,
+    /// The region of just the name, e.g. `bar` in `Foo.bar`.
+    pub region: Region,
+    /// Set for record field puns like `{ x }`, where one identifier is both a field label and
+    /// a variable. Renaming either of them means spelling out the other one.
+    pub is_pun: bool,
+}
+
+/// Finds every value and record field named in `decls` whose name can be located in `source`.
+pub fn find_occurrences(
+    decls: &Declarations,
+    interns: &Interns,
+    subs: &Subs,
+    source: &str,
+) -> Vec<Occurrence> {
+    let mut visitor = Collector {
+        interns,
+        subs,
+        source,
+        occurrences: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+    return visitor.occurrences;
+
+    struct Collector<'a> {
+        interns: &'a Interns,
+        subs: &'a Subs,
+        source: &'a str,
+        occurrences: Vec<Occurrence>,
+    }
+
+    impl<'a> Collector<'a> {
+        fn symbol_name(&self, symbol: Symbol) -> Option<&'a str> {
+            self.interns
+                .all_ident_ids
+                .get(&symbol.module_id())?
+                .get_name(symbol.ident_id())
+        }
+
+        fn text(&self, region: Region) -> Option<&'a str> {
+            self.source
+                .get(region.start().offset as usize..region.end().offset as usize)
+        }
+
+        fn push(&mut self, referent: Referent, start: u32, name: &str, is_pun: bool) {
+            let start = Position::new(start);
+            let end = Position::new(start.offset + name.len() as u32);
+
+            self.occurrences.push(Occurrence {
+                referent,
+                region: Region::new(start, end),
+                is_pun,
+            });
+        }
+
+        /// For nodes whose region begins with the name, like patterns and record fields.
+        fn push_leading(&mut self, referent: Referent, name: &str, region: Region, is_pun: bool) {
+            if self.text(region).is_some_and(|text| text.starts_with(name)) {
+                self.push(referent, region.start().offset, name, is_pun);
+            }
+        }
+
+        /// For nodes whose region ends with the name, like `Foo.bar` or `record.field`.
+        fn push_trailing(&mut self, referent: Referent, name: &str, region: Region) {
+            if self.text(region).is_some_and(|text| text.ends_with(name)) {
+                let start = region.end().offset - name.len() as u32;
+                self.push(referent, start, name, false);
+            }
+        }
+
+        fn push_symbol_leading(&mut self, symbol: Symbol, region: Region, is_pun: bool) {
+            if let Some(name) = self.symbol_name(symbol) {
+                self.push_leading(Referent::Symbol(symbol), name, region, is_pun);
+            }
+        }
+
+        fn push_symbol_trailing(&mut self, symbol: Symbol, region: Region) {
+            if let Some(name) = self.symbol_name(symbol) {
+                self.push_trailing(Referent::Symbol(symbol), name, region);
+            }
+        }
+
+        fn field_referent(&self, label: &Lowercase, record_var: Variable) -> Option<Referent> {
+            Some(Referent::Field {
+                label: label.clone(),
+                record: RecordShape::of_var(self.subs, record_var)?,
+            })
+        }
+
+        fn push_field_leading(
+            &mut self,
+            label: &Lowercase,
+            record_var: Variable,
+            region: Region,
+            is_pun: bool,
+        ) {
+            if let Some(referent) = self.field_referent(label, record_var) {
+                self.push_leading(referent, label.as_str(), region, is_pun);
+            }
+        }
+
+        fn push_field_trailing(&mut self, label: &Lowercase, record_var: Variable, region: Region) {
+            if let Some(referent) = self.field_referent(label, record_var) {
+                self.push_trailing(referent, label.as_str(), region);
+            }
+        }
+
+        /// Annotations only remember the region of the type, so we look for the name in front
+        /// of the `:` that precedes it, and parse the type again to find its record fields.
+        fn push_annotation(&mut self, symbol: Symbol, annotation_region: Region) {
+            if let Some(text) = self.text(annotation_region) {
+                let arena = Bump::new();
+                let state = State::new(text.as_bytes());
+
+                let parsed = type_annotation::located(true).parse(&arena, state, 0);
+
+                if let Ok((_, annotation, _)) = parsed {
+                    find_annotation_fields(
+                        &annotation,
+                        annotation_region.start().offset,
+                        &mut self.occurrences,
+                    );
+                }
+            }
+
+            let Some(name) = self.symbol_name(symbol) else {
+                return;
+            };
+            let Some(before) = self.source.get(..annotation_region.start().offset as usize) else {
+                return;
+            };
+            let Some(before) = before.trim_end().strip_suffix(':') else {
+                return;
+            };
+            let before = before.trim_end();
+
+            if before.ends_with(name) {
+                let start = (before.len() - name.len()) as u32;
+                self.push(Referent::Symbol(symbol), start, name, false);
+            }
+        }
+
+        fn visit_fields<'b>(
+            &mut self,
+            record_var: Variable,
+            fields: impl Iterator<Item = (&'b Lowercase, &'b Field)>,
+        ) {
+            for (label, field) in fields {
+                // `{ x }` is sugar for `{ x: x }`, where the lookup starts where the label does.
+                let is_pun = field.region.start() == field.loc_expr.region.start();
+
+                match &field.loc_expr.value {
+                    Expr::Var(symbol, _) if is_pun => {
+                        self.push_symbol_leading(*symbol, field.loc_expr.region, true);
+                        self.push_field_leading(label, record_var, field.loc_expr.region, true);
+                    }
+                    _ => {
+                        self.push_field_leading(label, record_var, field.region, false);
+                        self.visit_expr(&field.loc_expr.value, field.loc_expr.region, field.var);
+                    }
+                }
+            }
+        }
+
+        fn visit_destructs(&mut self, whole_var: Variable, destructs: &[Loc<RecordDestruct>]) {
+            for Loc { value, region } in destructs {
+                match value.typ {
+                    DestructType::Guard(..) => {
+                        self.push_field_leading(&value.label, whole_var, *region, false)
+                    }
+                    DestructType::Required | DestructType::Optional(..) => {
+                        self.push_symbol_leading(value.symbol, *region, true);
+                        self.push_field_leading(&value.label, whole_var, *region, true);
+                    }
+                }
+
+                traverse::walk_record_destruct(self, value);
+            }
+        }
+    }
+
+    impl Visitor for Collector<'_> {
+        fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+            match &decl {
+                DeclarationInfo::Value {
+                    loc_symbol,
+                    annotation: Some(annotation),
+                    ..
+                }
+                | DeclarationInfo::Function {
+                    loc_symbol,
+                    annotation: Some(annotation),
+                    ..
+                } => self.push_annotation(loc_symbol.value, annotation.region),
+                _ => {}
+            }
+
+            traverse::walk_decl(self, decl);
+        }
+
+        fn visit_def(&mut self, def: &Def) {
+            if let (Pattern::Identifier(symbol), Some(annotation)) =
+                (&def.loc_pattern.value, &def.annotation)
+            {
+                self.push_annotation(*symbol, annotation.region);
+            }
+
+            traverse::walk_def(self, def);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(symbol, _)
+                | Expr::ParamsVar { symbol, .. }
+                | Expr::AbilityMember(symbol, _, _) => self.push_symbol_trailing(*symbol, region),
+                Expr::RecordAccess {
+                    record_var, field, ..
+                } => self.push_field_trailing(field, *record_var, region),
+                Expr::RecordAccessor(StructAccessorData {
+                    record_var,
+                    field: IndexOrField::Field(field),
+                    ..
+                }) => self.push_field_trailing(field, *record_var, region),
+                Expr::Record { record_var, fields } => {
+                    self.visit_fields(*record_var, fields.iter());
+                    return;
+                }
+                Expr::RecordUpdate {
+                    record_var,
+                    symbol,
+                    updates,
+                    ..
+                } => {
+                    // Only the region of the whole `{ record & ... }` is kept.
+                    if let Some(after_brace) = self.text(region).and_then(|t| t.strip_prefix('{')) {
+                        let spaces = after_brace.len() - after_brace.trim_start().len();
+                        let start = Position::new(region.start().offset + 1 + spaces as u32);
+                        self.push_symbol_leading(*symbol, Region::new(start, region.end()), false);
+                    }
+
+                    self.visit_fields(*record_var, updates.iter());
+                    return;
+                }
+                _ => {}
+            }
+
+            traverse::walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::Shadowed(_, _, symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                    self.push_symbol_leading(*symbol, region, false)
+                }
+                Pattern::As(_, symbol) => self.push_symbol_trailing(*symbol, region),
+                Pattern::RecordDestructure {
+                    whole_var,
+                    destructs,
+                    ..
+                } => {
+                    self.visit_destructs(*whole_var, destructs);
+                    return;
+                }
+                _ => {}
+            }
+
+            traverse::walk_pattern(self, pattern);
+        }
+    }
+}
+
+/// Finds the record fields named in a parsed type annotation, whose regions start at `offset`.
+pub fn find_annotation_fields(
+    annotation: &Loc<TypeAnnotation<'_>>,
+    offset: u32,
+    occurrences: &mut Vec<Occurrence>,
+) {
+    let shift = |region: Region| {
+        Region::new(
+            Position::new(region.start().offset + offset),
+            Position::new(region.end().offset + offset),
+        )
+    };
+
+    let mut pending = vec![&annotation.value];
+
+    while let Some(annotation) = pending.pop() {
+        match annotation {
+            TypeAnnotation::Record { fields, ext } => {
+                let labels = fields
+                    .iter()
+                    .filter_map(|field| field_label(&field.value))
+                    .collect::<Vec<_>>();
+                let record = RecordShape::new(
+                    labels.iter().map(|label| label.value.into()).collect(),
+                    ext.is_some(),
+                );
+
+                occurrences.extend(labels.iter().map(|label| Occurrence {
+                    referent: Referent::Field {
+                        label: label.value.into(),
+                        record: record.clone(),
+                    },
+                    region: shift(label.region),
+                    is_pun: false,
+                }));
+
+                pending.extend(
+                    fields
+                        .iter()
+                        .filter_map(|field| field.value.value())
+                        .map(|value| &value.value),
+                );
+                pending.extend(ext.map(|ext| &ext.value));
+            }
+            TypeAnnotation::Function(args, _, ret) => {
+                pending.extend(args.iter().map(|arg| &arg.value));
+                pending.push(&ret.value);
+            }
+            TypeAnnotation::Apply(_, _, args) => pending.extend(args.iter().map(|arg| &arg.value)),
+            TypeAnnotation::As(inner, _, _) | TypeAnnotation::Where(inner, _) => {
+                pending.push(&inner.value)
+            }
+            TypeAnnotation::Tuple { elems, ext } => {
+                pending.extend(elems.iter().map(|elem| &elem.value));
+                pending.extend(ext.map(|ext| &ext.value));
+            }
+            TypeAnnotation::TagUnion { ext, tags } => {
+                for tag in tags.iter() {
+                    let mut tag = &tag.value;
+                    while let Tag::SpaceBefore(inner, _) | Tag::SpaceAfter(inner, _) = tag {
+                        tag = inner;
+                    }
+                    if let Tag::Apply { args, .. } = tag {
+                        pending.extend(args.iter().map(|arg| &arg.value));
+                    }
+                }
+                pending.extend(ext.map(|ext| &ext.value));
+            }
+            TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+                pending.push(inner)
+            }
+            TypeAnnotation::BoundVariable(_)
+            | TypeAnnotation::Inferred
+            | TypeAnnotation::Wildcard
+            | TypeAnnotation::Malformed(_) => {}
+        }
+    }
+}
+
+/// The label of a record field in a type annotation, unless it's an ignored one like `_ : Str`.
+fn field_label<'a>(mut field: &AssignedField<'a, TypeAnnotation<'a>>) -> Option<Loc<&'a str>> {
+    loop {
+        match field {
+            AssignedField::RequiredValue(label, _, _)
+            | AssignedField::OptionalValue(label, _, _)
+            | AssignedField::LabelOnly(label) => return Some(*label),
+            AssignedField::IgnoredValue(..) => return None,
+            AssignedField::SpaceBefore(inner, _) | AssignedField::SpaceAfter(inner, _) => {
+                field = inner
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use roc_parse::ast::{AssignedField, Defs, Expr, Pattern, TypeAnnotation, TypeDef, ValueDef};
+use roc_region::all::{LineInfo, Loc, Region};
+use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
+
+use crate::convert::ToRange;
+
+/// Builds the outline of `defs`: types, abilities and their members, and values along with
+/// the defs nested inside them.
+pub(super) fn defs_symbols(
+    defs: &Defs<'_>,
+    source: &str,
+    line_info: &LineInfo,
+    is_top_level: bool,
+) -> Vec<DocumentSymbol> {
+    let outline = Outline { source, line_info };
+
+    defs.loc_defs()
+        .filter_map(|loc_def| match loc_def {
+            Ok(type_def) => outline.type_def(&type_def),
+            Err(value_def) => outline.value_def(&value_def, is_top_level),
+        })
+        .collect()
+}
+
+struct Outline<'a> {
+    source: &'a str,
+    line_info: &'a LineInfo,
+}
+
+impl Outline<'_> {
+    fn text(&self, region: Region) -> &str {
+        self.source
+            .get(region.start().offset as usize..region.end().offset as usize)
+            .unwrap_or_default()
+    }
+
+    #[allow(deprecated)] // for `DocumentSymbol::deprecated`, which has no default
+    fn symbol(
+        &self,
+        name: &str,
+        detail: Option<String>,
+        kind: SymbolKind,
+        region: Region,
+        name_region: Region,
+        children: Vec<DocumentSymbol>,
+    ) -> DocumentSymbol {
+        DocumentSymbol {
+            name: name.to_string(),
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: region.to_range(self.line_info),
+            selection_range: name_region.to_range(self.line_info),
+            children: (!children.is_empty()).then_some(children),
+        }
+    }
+
+    fn type_def(&self, type_def: &Loc<TypeDef<'_>>) -> Option<DocumentSymbol> {
+        let region = type_def.region;
+
+        let symbol = match &type_def.value {
+            TypeDef::Alias { header, ann } => self.symbol(
+                header.name.value,
+                Some(self.text(ann.region).to_string()),
+                SymbolKind::CLASS,
+                region,
+                header.name.region,
+                vec![],
+            ),
+            TypeDef::Opaque { header, .. } => self.symbol(
+                header.name.value,
+                None,
+                SymbolKind::STRUCT,
+                region,
+                header.name.region,
+                vec![],
+            ),
+            TypeDef::Ability {
+                header, members, ..
+            } => {
+                let members = members
+                    .iter()
+                    .map(|member| {
+                        self.symbol(
+                            member.name.value.item(),
+                            Some(self.text(member.typ.region).to_string()),
+                            SymbolKind::METHOD,
+                            Region::span_across(&member.name.region, &member.typ.region),
+                            member.name.region,
+                            vec![],
+                        )
+                    })
+                    .collect();
+
+                self.symbol(
+                    header.name.value,
+                    None,
+                    SymbolKind::INTERFACE,
+                    region,
+                    header.name.region,
+                    members,
+                )
+            }
+        };
+
+        Some(symbol)
+    }
+
+    fn value_def(
+        &self,
+        value_def: &Loc<ValueDef<'_>>,
+        is_top_level: bool,
+    ) -> Option<DocumentSymbol> {
+        let (pattern, annotation, body) = match &value_def.value {
+            ValueDef::Annotation(pattern, annotation) => (pattern, Some(annotation), None),
+            ValueDef::Body(pattern, body) => (*pattern, None, Some(*body)),
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                body_expr,
+                ..
+            } => (*ann_pattern, Some(*ann_type), Some(*body_expr)),
+            ValueDef::Dbg { .. }
+            | ValueDef::Expect { .. }
+            | ValueDef::ModuleImport(_)
+            | ValueDef::IngestedFileImport(_)
+            | ValueDef::Stmt(_)
+            | ValueDef::StmtAfterExpr => return None,
+        };
+
+        let name = match strip_pattern_spaces(&pattern.value) {
+            Pattern::Identifier { ident } => ident.to_string(),
+            // Destructures don't have a single name, so show the whole pattern instead.
+            _ => collapse_whitespace(self.text(pattern.region)),
+        };
+
+        let is_function = annotation.is_some_and(|ann| {
+            matches!(
+                strip_annotation_spaces(&ann.value),
+                TypeAnnotation::Function(..)
+            )
+        }) || body
+            .is_some_and(|body| matches!(strip_expr_spaces(&body.value), Expr::Closure(..)));
+
+        let kind = if is_function {
+            SymbolKind::FUNCTION
+        } else if is_top_level {
+            SymbolKind::CONSTANT
+        } else {
+            SymbolKind::VARIABLE
+        };
+
+        let mut children = Vec::new();
+        if let Some(body) = body {
+            self.nested_symbols(&body.value, &mut children);
+        }
+
+        Some(self.symbol(
+            &name,
+            annotation.map(|ann| self.text(ann.region).to_string()),
+            kind,
+            value_def.region,
+            pattern.region,
+            children,
+        ))
+    }
+
+    /// Collects the defs nested in `expr`. Defs nested inside of those become their children,
+    /// rather than siblings.
+    fn nested_symbols(&self, expr: &Expr<'_>, symbols: &mut Vec<DocumentSymbol>) {
+        match expr {
+            Expr::Defs(defs, final_expr) => {
+                symbols.extend(defs_symbols(defs, self.source, self.line_info, false));
+                self.nested_symbols(&final_expr.value, symbols);
+            }
+            Expr::Closure(_, body) => self.nested_symbols(&body.value, symbols),
+            Expr::Apply(function, args, _) => {
+                self.nested_symbols(&function.value, symbols);
+                for arg in args.iter() {
+                    self.nested_symbols(&arg.value, symbols);
+                }
+            }
+            Expr::PncApply(function, args) => {
+                self.nested_symbols(&function.value, symbols);
+                for arg in args.iter() {
+                    self.nested_symbols(&arg.value, symbols);
+                }
+            }
+            Expr::BinOps(operands, last) => {
+                for (operand, _) in operands.iter() {
+                    self.nested_symbols(&operand.value, symbols);
+                }
+                self.nested_symbols(&last.value, symbols);
+            }
+            Expr::If {
+                if_thens,
+                final_else,
+                ..
+            } => {
+                for (condition, then) in if_thens.iter() {
+                    self.nested_symbols(&condition.value, symbols);
+                    self.nested_symbols(&then.value, symbols);
+                }
+                self.nested_symbols(&final_else.value, symbols);
+            }
+            Expr::When(condition, branches) => {
+                self.nested_symbols(&condition.value, symbols);
+                for branch in branches.iter() {
+                    self.nested_symbols(&branch.value.value, symbols);
+                }
+            }
+            Expr::Return(value, after) => {
+                self.nested_symbols(&value.value, symbols);
+                if let Some(after) = after {
+                    self.nested_symbols(&after.value, symbols);
+                }
+            }
+            Expr::Record(fields) => self.field_symbols(fields.items, symbols),
+            Expr::RecordUpdate { update, fields } => {
+                self.nested_symbols(&update.value, symbols);
+                self.field_symbols(fields.items, symbols);
+            }
+            Expr::RecordBuilder { mapper, fields } => {
+                self.nested_symbols(&mapper.value, symbols);
+                self.field_symbols(fields.items, symbols);
+            }
+            Expr::List(items) | Expr::Tuple(items) => {
+                for item in items.iter() {
+                    self.nested_symbols(&item.value, symbols);
+                }
+            }
+            Expr::DbgStmt {
+                first,
+                extra_args,
+                continuation,
+                ..
+            } => {
+                self.nested_symbols(&first.value, symbols);
+                for arg in extra_args.iter() {
+                    self.nested_symbols(&arg.value, symbols);
+                }
+                self.nested_symbols(&continuation.value, symbols);
+            }
+            Expr::UnaryOp(expr, _) => self.nested_symbols(&expr.value, symbols),
+            Expr::SpaceBefore(expr, _) | Expr::SpaceAfter(expr, _) | Expr::ParensAround(expr) => {
+                self.nested_symbols(expr, symbols)
+            }
+            _ => {}
+        }
+    }
+
+    fn field_symbols(
+        &self,
+        fields: &[Loc<AssignedField<'_, Expr<'_>>>],
+        symbols: &mut Vec<DocumentSymbol>,
+    ) {
+        for value in fields.iter().filter_map(|field| field.value.value()) {
+            self.nested_symbols(&value.value, symbols);
+        }
+    }
+}
+
+fn strip_expr_spaces<'a, 'b>(mut expr: &'b Expr<'a>) -> &'b Expr<'a> {
+    while let Expr::SpaceBefore(inner, _) | Expr::SpaceAfter(inner, _) = expr {
+        expr = *inner;
+    }
+    expr
+}
+
+fn strip_pattern_spaces<'a, 'b>(mut pattern: &'b Pattern<'a>) -> &'b Pattern<'a> {
+    while let Pattern::SpaceBefore(inner, _) | Pattern::SpaceAfter(inner, _) = pattern {
+        pattern = *inner;
+    }
+    pattern
+}
+
+/// Also looks past `where` clauses, so that constrained functions are still functions.
+fn strip_annotation_spaces<'a, 'b>(mut ann: &'b TypeAnnotation<'a>) -> &'b TypeAnnotation<'a> {
+    loop {
+        ann = match ann {
+            TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => *inner,
+            TypeAnnotation::Where(inner, _) => &inner.value,
+            _ => return ann,
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
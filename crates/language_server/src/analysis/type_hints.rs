@@ -0,0 +1,283 @@
+use roc_can::{
+    abilities::AbilitiesStore,
+    def::{Def, DefKind},
+    expr::{ClosureData, Declarations, Expr, WhenBranch},
+    pattern::{DestructType, Pattern, RecordDestruct},
+    traverse::{self, DeclarationInfo, Visitor},
+};
+use roc_module::{called_via::BinOp, called_via::CalledVia, symbol::Symbol};
+use roc_problem::can::RuntimeError;
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::Variable;
+
+pub struct FoundCall {
+    /// The type of the function being called.
+    pub fn_var: Variable,
+    pub fn_region: Region,
+    /// Set when the function is called by name, rather than e.g. an inline closure.
+    pub fn_symbol: Option<Symbol>,
+    pub active_argument: usize,
+}
+
+/// Finds the innermost function call around `position`, for showing its signature.
+/// Calls that come from desugaring an operator, other than `|>`, are ignored.
+pub fn find_call_at(position: Position, decls: &Declarations) -> Option<FoundCall> {
+    let mut visitor = Finder {
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        position: Position,
+        found: Option<FoundCall>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region.contains_pos(self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if !region.contains_pos(self.position) {
+                return;
+            }
+
+            if let Expr::Call(function, args, called_via) = expr {
+                let is_written_call = matches!(
+                    called_via,
+                    CalledVia::Space | CalledVia::BinOp(BinOp::Pizza)
+                );
+
+                if is_written_call && !args.is_empty() {
+                    let (fn_var, loc_fn, ..) = &**function;
+
+                    let fn_symbol = match loc_fn.value {
+                        Expr::Var(symbol, _)
+                        | Expr::ParamsVar { symbol, .. }
+                        | Expr::AbilityMember(symbol, _, _) => Some(symbol),
+                        _ => None,
+                    };
+
+                    let active_argument = args
+                        .iter()
+                        .position(|(_, arg)| self.position <= arg.region.end())
+                        .unwrap_or(args.len() - 1);
+
+                    self.found = Some(FoundCall {
+                        fn_var: *fn_var,
+                        fn_region: loc_fn.region,
+                        fn_symbol,
+                        active_argument,
+                    });
+                }
+            }
+
+            traverse::walk_expr(self, expr, var);
+        }
+    }
+}
+
+/// Finds the places where an inferred type could be shown next to a name: after unannotated
+/// defs, after the parameters of closures that aren't annotated, and after the names bound by
+/// `when` patterns.
+pub fn find_type_hints(
+    decls: &Declarations,
+    abilities: &AbilitiesStore,
+    source: &str,
+) -> Vec<(Position, Variable)> {
+    let mut visitor = Collector {
+        abilities,
+        source,
+        hints: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+    return visitor.hints;
+
+    struct Collector<'a> {
+        abilities: &'a AbilitiesStore,
+        source: &'a str,
+        hints: Vec<(Position, Variable)>,
+    }
+
+    impl Collector<'_> {
+        fn push(&mut self, region: Region, var: Variable) {
+            self.hints.push((region.end(), var));
+        }
+
+        fn visit_bindings(&mut self, pattern: &Loc<Pattern>, var: Variable) {
+            self.visit_pattern(&pattern.value, pattern.region, Some(var));
+        }
+
+        fn visit_closure(&mut self, closure: &ClosureData, is_annotated: bool) {
+            if !is_annotated {
+                for (var, _, argument) in closure.arguments.iter() {
+                    self.visit_bindings(argument, *var);
+                }
+            }
+
+            let body = &closure.loc_body;
+            self.visit_expr(&body.value, body.region, closure.return_type);
+        }
+
+        fn visit_when_branch(
+            &mut self,
+            branch: &WhenBranch,
+            cond_var: Variable,
+            expr_var: Variable,
+        ) {
+            for branch_pattern in branch.patterns.iter() {
+                self.visit_bindings(&branch_pattern.pattern, cond_var);
+            }
+
+            if let Some(guard) = &branch.guard {
+                self.visit_expr(&guard.value, guard.region, Variable::BOOL);
+            }
+            self.visit_expr(&branch.value.value, branch.value.region, expr_var);
+        }
+    }
+
+    impl Visitor for Collector<'_> {
+        fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+            match decl {
+                DeclarationInfo::Value {
+                    loc_symbol,
+                    loc_expr,
+                    expr_var,
+                    annotation,
+                    ..
+                } => {
+                    let is_hidden = matches!(
+                        loc_expr.value,
+                        Expr::ImportParams(..)
+                            | Expr::RuntimeError(RuntimeError::ExposedButNotDefined(..))
+                    ) || self.abilities.is_specialization_name(loc_symbol.value);
+
+                    if annotation.is_none() && !is_hidden {
+                        self.push(loc_symbol.region, expr_var);
+                    }
+
+                    match &loc_expr.value {
+                        Expr::Closure(closure) => self.visit_closure(closure, annotation.is_some()),
+                        expr => self.visit_expr(expr, loc_expr.region, expr_var),
+                    }
+                }
+                DeclarationInfo::Function {
+                    loc_symbol,
+                    loc_body,
+                    expr_var,
+                    function,
+                    annotation,
+                    ..
+                } => {
+                    let is_annotated = annotation.is_some()
+                        || self.abilities.is_specialization_name(loc_symbol.value);
+
+                    if !is_annotated {
+                        self.push(loc_symbol.region, expr_var);
+
+                        for (var, _, argument) in function.value.arguments.iter() {
+                            self.visit_bindings(argument, *var);
+                        }
+                    }
+
+                    let return_type = function.value.return_type;
+                    self.visit_expr(&loc_body.value, loc_body.region, return_type);
+                }
+                DeclarationInfo::Destructure {
+                    loc_pattern,
+                    opt_pattern_var,
+                    loc_expr,
+                    expr_var,
+                    annotation,
+                } => {
+                    if annotation.is_none() {
+                        self.visit_pattern(&loc_pattern.value, loc_pattern.region, opt_pattern_var);
+                    }
+
+                    self.visit_expr(&loc_expr.value, loc_expr.region, expr_var);
+                }
+                DeclarationInfo::Expectation { loc_condition } => {
+                    self.visit_expr(&loc_condition.value, loc_condition.region, Variable::BOOL);
+                }
+            }
+        }
+
+        fn visit_def(&mut self, def: &Def) {
+            let is_annotated = def.annotation.is_some();
+
+            if !is_annotated && !matches!(def.kind, DefKind::Stmt(..) | DefKind::Ignored(..)) {
+                let var = match def.loc_pattern.value {
+                    Pattern::Identifier(..) => Some(def.expr_var),
+                    _ => def.loc_pattern.value.opt_var(),
+                };
+                self.visit_pattern(&def.loc_pattern.value, def.loc_pattern.region, var);
+            }
+
+            match &def.loc_expr.value {
+                Expr::Closure(closure) => self.visit_closure(closure, is_annotated),
+                expr => self.visit_expr(expr, def.loc_expr.region, def.expr_var),
+            }
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, _region: Region, var: Variable) {
+            match expr {
+                Expr::Closure(closure) => self.visit_closure(closure, false),
+                Expr::When {
+                    cond_var,
+                    expr_var,
+                    loc_cond,
+                    branches,
+                    ..
+                } => {
+                    self.visit_expr(&loc_cond.value, loc_cond.region, *cond_var);
+
+                    for branch in branches.iter() {
+                        self.visit_when_branch(branch, *cond_var, *expr_var);
+                    }
+                }
+                _ => traverse::walk_expr(self, expr, var),
+            }
+        }
+
+        /// Only called for patterns that bind names which should get a hint.
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, opt_var: Option<Variable>) {
+            match (pattern, opt_var) {
+                (Pattern::Identifier(_) | Pattern::Shadowed(..), Some(var)) => {
+                    self.push(region, var)
+                }
+                (Pattern::As(subpattern, _), Some(var)) => {
+                    self.push(region, var);
+                    self.visit_pattern(&subpattern.value, subpattern.region, Some(var));
+                    return;
+                }
+                _ => {}
+            }
+
+            traverse::walk_pattern(self, pattern);
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            match &destruct.typ {
+                DestructType::Required | DestructType::Optional(..) => {
+                    // `{ x ? 0 }` binds `x`, so the hint goes right after the name.
+                    let label = destruct.label.as_str();
+                    let start = region.start().offset as usize;
+
+                    if self
+                        .source
+                        .get(start..)
+                        .is_some_and(|s| s.starts_with(label))
+                    {
+                        let end = Position::new((start + label.len()) as u32);
+                        self.push(Region::new(region.start(), end), destruct.var);
+                    }
+                }
+                DestructType::Guard(var, pattern) => {
+                    self.visit_pattern(&pattern.value, pattern.region, Some(*var));
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use roc_module::symbol::{Interns, ModuleId};
 use roc_types::subs::{Subs, Variable};
 
@@ -22,3 +24,81 @@ pub(super) fn format_var_type(
 pub(super) fn is_roc_identifier_char(char: &char) -> bool {
     matches!(char,'a'..='z'|'A'..='Z'|'0'..='9'|'.')
 }
+
+/// Scores how well `query` matches `candidate`, where every character of the query has to
+/// appear in the candidate in order, ignoring case. Higher scores are better matches: runs of
+/// consecutive characters and matches at the start of a word count for more.
+pub(super) fn fuzzy_match_score(query: &str, candidate: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut candidate_chars = candidate.chars();
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for query_char in query.chars() {
+        loop {
+            let candidate_char = candidate_chars.next()?;
+
+            let is_match = candidate_char.to_lowercase().eq(query_char.to_lowercase());
+            let starts_word = match previous {
+                None => true,
+                Some(prev) => {
+                    !prev.is_alphanumeric()
+                        || (prev.is_lowercase() && candidate_char.is_uppercase())
+                }
+            };
+
+            previous = Some(candidate_char);
+
+            if is_match {
+                score += 1;
+                if previous_matched {
+                    score += 2;
+                }
+                if starts_word {
+                    score += 3;
+                }
+                previous_matched = true;
+                break;
+            }
+
+            previous_matched = false;
+        }
+    }
+
+    Some(score)
+}
+
+/// Finds the parameters in a printed function type like `Str, (a -> b) -> List b`, returning
+/// the byte range of each one. Returns `None` when the type isn't a function.
+pub(super) fn function_parameter_ranges(signature: &str) -> Option<Vec<Range<usize>>> {
+    let bytes = signature.as_bytes();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut ranges = Vec::new();
+
+    let trimmed = |range: Range<usize>| {
+        let text = &signature[range.clone()];
+        let start = range.start + (text.len() - text.trim_start().len());
+        let end = range.end - (text.len() - text.trim_end().len());
+        start..end.max(start)
+    };
+
+    for (index, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                ranges.push(trimmed(start..index));
+                start = index + 1;
+            }
+            // Both pure (`->`) and effectful (`=>`) functions.
+            b'-' | b'=' if depth == 0 && bytes.get(index + 1) == Some(&b'>') => {
+                ranges.push(trimmed(start..index));
+                return Some(ranges);
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
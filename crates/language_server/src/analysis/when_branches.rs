@@ -0,0 +1,300 @@
+//! Fixes for the exhaustiveness problems of `when` expressions: adding the branches that are
+//! missing, and removing the ones that can never match.
+use std::ops::Range;
+
+use bumpalo::Bump;
+use roc_can::exhaustive::{GUARD_CTOR, NONEXHAUSIVE_CTOR};
+use roc_cli::{line_end, line_start, FixEdit};
+use roc_exhaustive::{Context, CtorName, ListArity, Literal, Pattern, RenderAs};
+use roc_fmt::{annotation::Formattable, Buf, MigrationFlags};
+use roc_module::{called_via::CalledVia, ident::TagName, symbol::Interns};
+use roc_parse::ast::{self, Collection, StrLiteral, WhenBranch};
+use roc_region::all::{Loc, Region};
+use roc_solve_problem::TypeError;
+
+/// The indentation `roc format` gives the branches of a `when` at the start of a line
+const BRANCH_INDENT: &str = "    ";
+
+pub(super) fn branch_fixes(
+    src: &str,
+    type_problems: &[TypeError],
+    interns: &Interns,
+) -> Vec<FixEdit> {
+    type_problems
+        .iter()
+        .filter_map(|problem| match problem {
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(
+                region,
+                Context::BadCase,
+                missing,
+            )) => add_missing_branches(src, *region, missing, interns),
+            TypeError::Exhaustive(roc_exhaustive::Error::Redundant { branch_region, .. }) => {
+                Some(FixEdit {
+                    title: "Remove redundant branch".to_owned(),
+                    problem: *branch_region,
+                    range: branch_pattern_range(src, branch_region.byte_range())?,
+                    new_text: String::new(),
+                })
+            }
+            TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable { branch_region, .. }) => {
+                Some(FixEdit {
+                    title: "Remove unmatchable branch".to_owned(),
+                    problem: *branch_region,
+                    range: branch_pattern_range(src, branch_region.byte_range())?,
+                    new_text: String::new(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Adds a `crash "todo"` branch for each of the `missing` patterns after the last branch of the
+/// `when` at `region`, which spans from its condition to the end of its last branch.
+fn add_missing_branches(
+    src: &str,
+    region: Region,
+    missing: &[Pattern],
+    interns: &Interns,
+) -> Option<FixEdit> {
+    let arena = Bump::new();
+
+    let patterns = missing
+        .iter()
+        .map(|pattern| Some(Loc::at_zero(to_ast_pattern(&arena, pattern, interns)?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    let indent = branch_indent(src, region.byte_range())?;
+    let mut new_text = String::new();
+
+    for line in format_branches(&arena, &patterns).lines() {
+        new_text.push('\n');
+
+        if !line.is_empty() {
+            new_text.push_str(indent);
+            new_text.push_str(line.strip_prefix(BRANCH_INDENT).unwrap_or(line));
+        }
+    }
+
+    let end = region.byte_range().end;
+
+    Some(FixEdit {
+        title: "Add missing branches".to_owned(),
+        problem: region,
+        range: end..end,
+        new_text,
+    })
+}
+
+/// Formats a `when` branch with a `crash "todo"` body for each pattern, the way `roc format` would.
+/// Each branch is indented by [BRANCH_INDENT].
+fn format_branches<'a>(arena: &'a Bump, patterns: &[Loc<ast::Pattern<'a>>]) -> &'a str {
+    let crash = arena.alloc(Loc::at_zero(ast::Expr::Crash));
+    let todo: &Loc<ast::Expr> =
+        arena.alloc(Loc::at_zero(ast::Expr::Str(StrLiteral::PlainLine("todo"))));
+    let body = Loc::at_zero(ast::Expr::Apply(
+        crash,
+        arena.alloc([todo]),
+        CalledVia::Space,
+    ));
+
+    let branches = arena.alloc_slice_fill_iter(patterns.iter().map(|pattern| {
+        &*arena.alloc(WhenBranch {
+            patterns: arena.alloc([*pattern]),
+            value: body,
+            guard: None,
+        })
+    }));
+
+    // The condition is thrown away along with the rest of the first line
+    let condition = arena.alloc(Loc::at_zero(ast::Expr::Var {
+        module_name: "",
+        ident: "x",
+    }));
+    let when = ast::Expr::When(condition, branches);
+
+    let mut buf = Buf::new_in(
+        arena,
+        MigrationFlags {
+            snakify: false,
+            parens_and_commas: false,
+        },
+    );
+    when.format(&mut buf, 0);
+
+    let formatted = buf.into_bump_str();
+
+    formatted
+        .split_once('\n')
+        .map_or("", |(_when_line, branches)| branches)
+}
+
+/// Converts a pattern the exhaustiveness checker found to be missing to one we can format.
+///
+/// Returns [None] for patterns that can't be written in source, like `Bool.true`.
+fn to_ast_pattern<'a>(
+    arena: &'a Bump,
+    pattern: &Pattern,
+    interns: &Interns,
+) -> Option<ast::Pattern<'a>> {
+    let loc_patterns = |patterns: &[Pattern]| {
+        patterns
+            .iter()
+            .map(|pattern| Some(Loc::at_zero(to_ast_pattern(arena, pattern, interns)?)))
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let ast_pattern = match pattern {
+        Pattern::Anything => ast::Pattern::Underscore(""),
+        Pattern::Literal(literal) => match literal {
+            Literal::Int(int) => {
+                ast::Pattern::NumLiteral(arena.alloc_str(&i128::from_ne_bytes(*int).to_string()))
+            }
+            Literal::U128(int) => {
+                ast::Pattern::NumLiteral(arena.alloc_str(&u128::from_ne_bytes(*int).to_string()))
+            }
+            Literal::Byte(byte) => ast::Pattern::NumLiteral(arena.alloc_str(&byte.to_string())),
+            Literal::Float(bits) => {
+                ast::Pattern::FloatLiteral(arena.alloc_str(&f64::from_bits(*bits).to_string()))
+            }
+            Literal::Str(string) => {
+                if string.contains(['"', '\\', '$', '\n']) {
+                    return None;
+                }
+
+                ast::Pattern::StrLiteral(StrLiteral::PlainLine(arena.alloc_str(string)))
+            }
+            Literal::Bit(_) | Literal::Decimal(_) => return None,
+        },
+        Pattern::List(arity, patterns) => {
+            let mut items = loc_patterns(patterns)?;
+
+            if let ListArity::Slice(before, _) = arity {
+                items.insert(*before, Loc::at_zero(ast::Pattern::ListRest(None)));
+            }
+
+            ast::Pattern::List(Collection::with_items(arena.alloc_slice_copy(&items)))
+        }
+        Pattern::Ctor(union, tag_id, args) => match &union.render_as {
+            // #Guard <fake-condition-tag> <unexhausted-pattern>
+            RenderAs::Guard => to_ast_pattern(arena, args.get(1)?, interns)?,
+            RenderAs::Record(field_names) => {
+                if args.iter().all(|arg| matches!(arg, Pattern::Anything)) {
+                    return Some(ast::Pattern::Underscore(""));
+                }
+
+                // Fields that match anything can be left out
+                let mut fields = Vec::with_capacity(args.len());
+                for (label, arg) in field_names.iter().zip(args) {
+                    if !matches!(arg, Pattern::Anything) {
+                        let field_pattern = to_ast_pattern(arena, arg, interns)?;
+
+                        fields.push(Loc::at_zero(ast::Pattern::RequiredField(
+                            arena.alloc_str(label.as_str()),
+                            arena.alloc(Loc::at_zero(field_pattern)),
+                        )));
+                    }
+                }
+
+                ast::Pattern::RecordDestructure(Collection::with_items(
+                    arena.alloc_slice_copy(&fields),
+                ))
+            }
+            RenderAs::Tuple => ast::Pattern::Tuple(Collection::with_items(
+                arena.alloc_slice_copy(&loc_patterns(args)?),
+            )),
+            RenderAs::Tag | RenderAs::Opaque => {
+                let ctor = union.alternatives.get(tag_id.0 as usize)?;
+
+                let name = match &ctor.name {
+                    CtorName::Tag(TagName(name)) if name.as_str() == NONEXHAUSIVE_CTOR => {
+                        return Some(ast::Pattern::Underscore(""));
+                    }
+                    CtorName::Tag(TagName(name)) if name.as_str() == GUARD_CTOR => return None,
+                    CtorName::Tag(TagName(name)) => {
+                        ast::Pattern::Tag(arena.alloc_str(name.as_str()))
+                    }
+                    CtorName::Opaque(symbol) => ast::Pattern::OpaqueRef(
+                        arena.alloc_str(&format!("@{}", symbol.as_str(interns))),
+                    ),
+                };
+
+                if args.is_empty() {
+                    name
+                } else {
+                    ast::Pattern::Apply(
+                        arena.alloc(Loc::at_zero(name)),
+                        arena.alloc_slice_copy(&loc_patterns(args)?),
+                    )
+                }
+            }
+        },
+    };
+
+    Some(ast_pattern)
+}
+
+/// The indentation of the branches of the `when` whose condition starts `when_region`.
+fn branch_indent(src: &str, when_region: Range<usize>) -> Option<&str> {
+    let when_src = src.get(when_region.clone())?;
+
+    // The branches start on the line after the `is` that ends the `when ... is` line
+    let is_end = when_src
+        .match_indices("is")
+        .map(|(offset, is)| offset + is.len())
+        .find(|end| {
+            when_src[*end..]
+                .trim_start_matches([' ', '\t'])
+                .starts_with('\n')
+        })?;
+
+    when_src[is_end..]
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+}
+
+/// The range to remove to take the pattern at `pattern` out of its `when` branch.
+/// That's the whole branch if it is the branch's only pattern, and just the pattern and the `|`
+/// next to it when the branch has alternatives.
+fn branch_pattern_range(src: &str, pattern: Range<usize>) -> Option<Range<usize>> {
+    let before = src[..pattern.start].trim_end();
+    let after = src[pattern.end..].trim_start();
+
+    if before.ends_with('|') && !before.ends_with("||") {
+        // `A | B` loses ` | B`
+        Some(before[..before.len() - 1].trim_end().len()..pattern.end)
+    } else if after.starts_with('|') && !after.starts_with("||") {
+        // `A | B` loses `A | `
+        let next = src.len() - after[1..].trim_start().len();
+
+        Some(pattern.start..next)
+    } else {
+        let start = line_start(src, pattern.start);
+        let base_indent = pattern.start - start;
+
+        if !src[start..pattern.start].trim().is_empty() {
+            return None;
+        }
+
+        // The branch's body continues on the lines that are indented further than its pattern
+        let mut end = line_end(src, pattern.start);
+        let mut next = end;
+
+        while next < src.len() {
+            let line = &src[next..line_end(src, next)];
+
+            if line.trim().is_empty() {
+                next += line.len();
+            } else if line.len() - line.trim_start().len() > base_indent {
+                next += line.len();
+                end = next;
+            } else {
+                break;
+            }
+        }
+
+        Some(start..end)
+    }
+}
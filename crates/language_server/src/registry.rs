@@ -10,7 +10,9 @@ use tokio::sync::{Mutex, MutexGuard};
 
 use tower_lsp::lsp_types::{
     CodeActionOrCommand, CodeActionResponse, CompletionResponse, Diagnostic,
-    GotoDefinitionResponse, Hover, Position, Range, SemanticTokensResult, TextEdit, Url,
+    DocumentSymbolResponse, GotoDefinitionResponse, Hover, InlayHint, Location, Position,
+    PrepareRenameResponse, Range, SemanticTokensResult, SignatureHelp, SymbolInformation, TextEdit,
+    Url, WorkspaceEdit,
 };
 
 use crate::analysis::{AnalyzedDocument, DocInfo};
@@ -164,6 +166,23 @@ impl Registry {
         .ok()
     }
 
+    /// Every document we know of, without waiting for any that are still being analyzed.
+    /// `document` replaces whatever version of itself the registry holds.
+    async fn all_documents(&self, document: Arc<AnalyzedDocument>) -> Vec<Arc<AnalyzedDocument>> {
+        let documents = self.documents.lock().await;
+
+        let others = documents
+            .iter()
+            .filter(|(url, _)| *url != document.url())
+            .map(|(_, pair)| match pair.latest_document.get() {
+                Some(latest) if latest.type_checked() => latest.clone(),
+                _ => pair.last_good_document.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        std::iter::once(document).chain(others).collect()
+    }
+
     pub async fn diagnostics(&self, url: &Url) -> Vec<Diagnostic> {
         let Some(document) = self.latest_document_by_url(url).await else {
             return vec![];
@@ -175,6 +194,16 @@ impl Registry {
         self.latest_document_by_url(url).await?.hover(position)
     }
 
+    pub async fn signature_help(&self, url: &Url, position: Position) -> Option<SignatureHelp> {
+        self.latest_document_by_url(url)
+            .await?
+            .signature_help(position)
+    }
+
+    pub async fn inlay_hints(&self, url: &Url, range: Range) -> Option<Vec<InlayHint>> {
+        self.latest_document_by_url(url).await?.inlay_hints(range)
+    }
+
     pub async fn goto_definition(
         &self,
         url: &Url,
@@ -187,6 +216,62 @@ impl Registry {
         def_document.definition(symbol)
     }
 
+    pub async fn references(&self, url: &Url, position: Position) -> Option<Vec<Location>> {
+        let document = self.latest_document_by_url(url).await?;
+        let (_, referent) = document.referent_at(position)?;
+
+        let locations = self
+            .all_documents(document)
+            .await
+            .iter()
+            .flat_map(|document| document.reference_locations(&referent))
+            .collect::<Vec<_>>();
+
+        (!locations.is_empty()).then_some(locations)
+    }
+
+    pub async fn prepare_rename(
+        &self,
+        url: &Url,
+        position: Position,
+    ) -> Option<PrepareRenameResponse> {
+        let document = self.latest_document_by_url(url).await?;
+        let (range, _) = document.referent_at(position)?;
+
+        Some(PrepareRenameResponse::Range(range))
+    }
+
+    pub async fn rename(
+        &self,
+        url: &Url,
+        position: Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, String> {
+        if !is_value_name(new_name) {
+            return Err(format!("`{}` is not a valid name for a value", new_name));
+        }
+
+        let Some(document) = self.latest_document_by_url(url).await else {
+            return Ok(None);
+        };
+        let Some((_, referent)) = document.referent_at(position) else {
+            return Ok(None);
+        };
+
+        let changes = self
+            .all_documents(document)
+            .await
+            .iter()
+            .map(|document| {
+                let edits = document.rename_edits(&referent, new_name);
+                (document.url().clone(), edits)
+            })
+            .filter(|(_, edits)| !edits.is_empty())
+            .collect::<HashMap<_, _>>();
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
     pub async fn formatting(&self, url: &Url) -> Option<Vec<TextEdit>> {
         let document = self.document_info_by_url(url).await?;
         document.format()
@@ -196,6 +281,34 @@ impl Registry {
         let document = self.document_info_by_url(url).await?;
         document.semantic_tokens()
     }
+
+    pub async fn document_symbols(&self, url: &Url) -> Option<DocumentSymbolResponse> {
+        let document = self.document_info_by_url(url).await?;
+        document.document_symbols()
+    }
+
+    pub async fn workspace_symbols(&self, query: &str) -> Option<Vec<SymbolInformation>> {
+        let documents = self.documents.lock().await;
+
+        let mut matches = documents
+            .values()
+            .flat_map(|pair| {
+                let document = pair
+                    .latest_document
+                    .get()
+                    .unwrap_or(&pair.last_good_document);
+
+                document.workspace_symbols(query)
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(score_a, a), (score_b, b)| {
+            score_b.cmp(score_a).then_with(|| a.name.cmp(&b.name))
+        });
+
+        Some(matches.into_iter().map(|(_, symbol)| symbol).collect())
+    }
+
     pub async fn completion_items(
         &self,
         url: &Url,
@@ -225,6 +338,21 @@ impl Registry {
         if let Some(edit) = document.annotate(range) {
             responses.push(CodeActionOrCommand::CodeAction(edit));
         }
+        responses.extend(
+            document
+                .quick_fixes(range)
+                .into_iter()
+                .map(CodeActionOrCommand::CodeAction),
+        );
         Some(responses)
     }
 }
+
+/// Values and record fields share the same naming rules.
+fn is_value_name(name: &str) -> bool {
+    let mut chars = name.strip_suffix('!').unwrap_or(name).chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && roc_parse::keyword::is_allowed_identifier(name)
+}
@@ -104,6 +104,27 @@ impl RocServer {
             },
         };
         let code_action_provider = CodeActionProviderCapability::Simple(true);
+        let references_provider = ReferencesOptions {
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let rename_provider = RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let document_symbol_provider = OneOf::Left(true);
+        let workspace_symbol_provider = OneOf::Left(true);
+        let signature_help_provider = SignatureHelpOptions {
+            trigger_characters: Some(vec![" ".to_string(), "(".to_string(), ",".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let inlay_hint_provider = OneOf::Left(true);
         ServerCapabilities {
             text_document_sync: Some(text_document_sync),
             hover_provider: Some(hover_provider),
@@ -112,6 +133,12 @@ impl RocServer {
             semantic_tokens_provider: Some(semantic_tokens_provider),
             completion_provider: Some(completion_provider),
             code_action_provider: Some(code_action_provider),
+            references_provider: Some(OneOf::Right(references_provider)),
+            rename_provider: Some(OneOf::Right(rename_provider)),
+            document_symbol_provider: Some(document_symbol_provider),
+            workspace_symbol_provider: Some(workspace_symbol_provider),
+            signature_help_provider: Some(signature_help_provider),
+            inlay_hint_provider: Some(inlay_hint_provider),
             ..ServerCapabilities::default()
         }
     }
@@ -306,6 +333,88 @@ impl LanguageServer for RocServer {
         .await
     }
 
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let SignatureHelpParams {
+            context: _,
+            text_document_position_params:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .signature_help(&text_document.uri, position),
+        )
+        .await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let InlayHintParams {
+            text_document,
+            range,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.inlay_hints(&text_document.uri, range)).await
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let ReferenceParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            // Declarations are always included.
+            context: _,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.references(&text_document.uri, position)).await
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .prepare_rename(&text_document.uri, position),
+        )
+        .await
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let RenameParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            new_name,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .rename(&text_document.uri, position, &new_name),
+        )
+        .await?
+        .map_err(jsonrpc::Error::invalid_params)
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let DocumentFormattingParams {
             text_document,
@@ -329,6 +438,32 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.semantic_tokens(&text_document.uri)).await
     }
 
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let DocumentSymbolParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.document_symbols(&text_document.uri)).await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let WorkspaceSymbolParams {
+            query,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.workspace_symbols(&query)).await
+    }
+
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
         let doc = params.text_document_position;
         trace!("Got completion request.");
@@ -816,4 +951,654 @@ mod tests {
         "#]]
         .assert_debug_eq(&edit);
     }
+
+    #[tokio::test]
+    async fn test_quick_fix_unused_def() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main =
+                    unused = 10
+                    "Hello, world!"
+            "#},
+            Position::new(4, 6),
+            "Remove unused definition `unused`",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 4,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 5,
+                            character: 0,
+                        },
+                    },
+                    new_text: "",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_quick_fix_unused_argument() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main = \unused ->
+                    "Hello, world!"
+            "#},
+            Position::new(3, 8),
+            "Prefix unused argument `unused` with `_`",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 8,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 8,
+                        },
+                    },
+                    new_text: "_",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_add_missing_branches() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main : [A, B, C] -> U8
+                main = \x ->
+                    when x is
+                        A -> 1
+            "#},
+            Position::new(5, 6),
+            "Add missing branches",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 6,
+                            character: 14,
+                        },
+                        end: Position {
+                            line: 6,
+                            character: 14,
+                        },
+                    },
+                    new_text: "\n        B -> crash \"todo\"\n        C -> crash \"todo\"",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_remove_redundant_branch() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main : [A, B] -> U8
+                main = \x ->
+                    when x is
+                        A -> 1
+                        B -> 2
+                        A -> 3
+            "#},
+            Position::new(8, 8),
+            "Remove redundant branch",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 8,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 9,
+                            character: 0,
+                        },
+                    },
+                    new_text: "",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    /// Flattens a range to `(line, start, end)`, for ranges that don't span lines.
+    fn flat_range(range: Range) -> (u32, u32, u32) {
+        assert_eq!(range.start.line, range.end.line);
+        (range.start.line, range.start.character, range.end.character)
+    }
+
+    async fn references(doc: &str, position: Position) -> Vec<(u32, u32, u32)> {
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let locations = inner
+            .registry
+            .references(&url, position)
+            .await
+            .expect("No references found");
+
+        locations
+            .into_iter()
+            .map(|location| {
+                assert_eq!(location.uri, url);
+                flat_range(location.range)
+            })
+            .collect()
+    }
+
+    async fn rename_edits(
+        doc: &str,
+        position: Position,
+        new_name: &str,
+    ) -> Vec<((u32, u32, u32), String)> {
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let edit = inner
+            .registry
+            .rename(&url, position, new_name)
+            .await
+            .expect("Rename was rejected")
+            .expect("Nothing to rename");
+
+        edit.changes
+            .expect("Edit does not have any changes")
+            .remove(&url)
+            .expect("Edit does not have changes for this file")
+            .into_iter()
+            .map(|edit| (flat_range(edit.range), edit.new_text))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_references_top_level() {
+        let doc = indoc! {r"
+            module [main]
+
+            add = \a, b -> a + b
+
+            main = add 1 (add 2 3)
+        "};
+
+        let actual = references(doc, Position::new(4, 8)).await;
+
+        assert_eq!(actual, vec![(2, 0, 3), (4, 7, 10), (4, 14, 17)]);
+    }
+
+    #[tokio::test]
+    async fn test_references_record_field() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                r = { count: 1 }
+                r.count + (.count r)
+        "};
+
+        let actual = references(doc, Position::new(4, 6)).await;
+
+        assert_eq!(actual, vec![(3, 10, 15), (4, 6, 11), (4, 16, 21)]);
+    }
+
+    #[tokio::test]
+    async fn test_references_record_field_by_shape() {
+        let doc = indoc! {r"
+            module [main]
+
+            Point : { x : I64, y : I64 }
+
+            origin : Point
+            origin = { x: 0, y: 0 }
+
+            main =
+                size = { x: 1 }
+                origin.x + size.x
+        "};
+
+        // `size` has a different shape than a `Point`, so its `x` is a different field.
+        let actual = references(doc, Position::new(9, 11)).await;
+
+        assert_eq!(actual, vec![(2, 10, 11), (5, 11, 12), (9, 11, 12)]);
+    }
+
+    #[tokio::test]
+    async fn test_references_destructured_field() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                { a, b: c } = { a: 1, b: 2 }
+                a + c
+        "};
+
+        let actual = references(doc, Position::new(3, 9)).await;
+
+        assert_eq!(actual, vec![(3, 9, 10), (3, 26, 27)]);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_rename() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                total = 1
+                total
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let response = inner
+            .registry
+            .prepare_rename(&url, Position::new(4, 6))
+            .await;
+
+        let Some(PrepareRenameResponse::Range(range)) = response else {
+            panic!("Expected a range, got {:?}", response);
+        };
+        assert_eq!(flat_range(range), (4, 4, 9));
+    }
+
+    #[tokio::test]
+    async fn test_rename_exposed() {
+        let doc = indoc! {r"
+            module [add]
+
+            add : I64, I64 -> I64
+            add = \a, b -> a + b
+        "};
+
+        let actual = rename_edits(doc, Position::new(3, 1), "sum").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ((0, 8, 11), "sum".to_string()),
+                ((2, 0, 3), "sum".to_string()),
+                ((3, 0, 3), "sum".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_variable_in_pun() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                x = 1
+                r = { x }
+                r.x
+        "};
+
+        let actual = rename_edits(doc, Position::new(3, 4), "y").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ((3, 4, 5), "y".to_string()),
+                ((4, 10, 11), "x: y".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_field_in_pun() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                x = 1
+                r = { x }
+                r.x
+        "};
+
+        let actual = rename_edits(doc, Position::new(5, 6), "z").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ((4, 10, 11), "z: x".to_string()),
+                ((5, 6, 7), "z".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_field_of_open_record() {
+        let doc = indoc! {r"
+            module [main]
+
+            get_x : { x : I64 }a -> I64
+            get_x = \r -> r.x
+
+            main = get_x { x: 1, y: 2 }
+        "};
+
+        let actual = rename_edits(doc, Position::new(3, 16), "z").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ((2, 10, 11), "z".to_string()),
+                ((3, 16, 17), "z".to_string()),
+                ((5, 15, 16), "z".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_invalid_name() {
+        let doc = indoc! {r"
+            module [main]
+
+            main = 1
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let result = inner
+            .registry
+            .rename(&url, Position::new(2, 0), "Main")
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    /// Flattens an outline to `(depth, name, kind)`, parents before their children.
+    fn outline(symbols: &[DocumentSymbol], depth: usize) -> Vec<(usize, String, SymbolKind)> {
+        symbols
+            .iter()
+            .flat_map(|symbol| {
+                let children = symbol.children.as_deref().unwrap_or_default();
+
+                std::iter::once((depth, symbol.name.clone(), symbol.kind))
+                    .chain(outline(children, depth + 1))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_document_symbols() {
+        let doc = indoc! {r"
+            module [main, Point, Shape]
+
+            Point : { x : I64, y : I64 }
+
+            Shape implements
+                area : a -> F64 where a implements Shape
+
+            main : I64 -> I64
+            main = \n ->
+                double = \x -> x * 2
+                limit = 10
+
+                double (Num.min n limit)
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let response = inner
+            .registry
+            .document_symbols(&url)
+            .await
+            .expect("No document symbols");
+        let DocumentSymbolResponse::Nested(symbols) = response else {
+            panic!("Expected nested document symbols");
+        };
+
+        assert_eq!(
+            outline(&symbols, 0),
+            vec![
+                (0, "Point".to_string(), SymbolKind::CLASS),
+                (0, "Shape".to_string(), SymbolKind::INTERFACE),
+                (1, "area".to_string(), SymbolKind::METHOD),
+                (0, "main".to_string(), SymbolKind::FUNCTION),
+                (1, "double".to_string(), SymbolKind::FUNCTION),
+                (1, "limit".to_string(), SymbolKind::VARIABLE),
+            ]
+        );
+
+        let main = &symbols[2];
+        assert_eq!(main.detail.as_deref(), Some("I64 -> I64"));
+        assert_eq!(
+            main.selection_range,
+            Range::new(Position::new(7, 0), Position::new(7, 4))
+        );
+        assert_eq!(main.range.start, Position::new(7, 0));
+        assert_eq!(main.range.end.line, 12);
+    }
+
+    #[tokio::test]
+    async fn test_document_symbols_in_collections() {
+        let doc = indoc! {r"
+            module [main]
+
+            main =
+                shapes = [
+                    \w ->
+                        half = w / 2
+                        half,
+                ]
+                point = {
+                    x: \n ->
+                        twice = n * 2
+                        twice,
+                }
+                pair = (
+                    \a ->
+                        first = a
+                        first,
+                    0,
+                )
+                dbg (
+                    inspected = 1
+                    inspected
+                )
+
+                { point, shapes, pair }
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let response = inner
+            .registry
+            .document_symbols(&url)
+            .await
+            .expect("No document symbols");
+        let DocumentSymbolResponse::Nested(symbols) = response else {
+            panic!("Expected nested document symbols");
+        };
+
+        assert_eq!(
+            outline(&symbols, 0),
+            vec![
+                (0, "main".to_string(), SymbolKind::CONSTANT),
+                (1, "shapes".to_string(), SymbolKind::VARIABLE),
+                (2, "half".to_string(), SymbolKind::VARIABLE),
+                (1, "point".to_string(), SymbolKind::VARIABLE),
+                (2, "twice".to_string(), SymbolKind::VARIABLE),
+                (1, "pair".to_string(), SymbolKind::VARIABLE),
+                (2, "first".to_string(), SymbolKind::VARIABLE),
+                (1, "inspected".to_string(), SymbolKind::VARIABLE),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols() {
+        let doc = indoc! {r"
+            module [parseInt, printLine, main]
+
+            parseInt = \str -> Str.toI64 str
+
+            printLine = \line -> line
+
+            main =
+                pending = 1
+                pending
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let symbols = inner
+            .registry
+            .workspace_symbols("pi")
+            .await
+            .expect("No workspace symbols");
+
+        let actual = symbols
+            .iter()
+            .map(|symbol| {
+                assert_eq!(symbol.location.uri, url);
+                (symbol.name.as_str(), symbol.container_name.as_deref())
+            })
+            .collect::<Vec<_>>();
+
+        // `parseInt` matches both letters at the start of a word, so it ranks first.
+        assert_eq!(
+            actual,
+            vec![
+                ("parseInt", None),
+                ("pending", Some("main")),
+                ("printLine", None),
+            ]
+        );
+    }
+
+    const TYPE_HINTS_DOC: &str = indoc! {r"
+        module [main]
+
+        add2 : I64, I64 -> I64
+        add2 = \a, b -> a + b
+
+        main =
+            inc = \n -> n + 1
+            when Ok 1 is
+                Ok v -> add2 (inc v) 2
+                Err _ -> 0
+    "};
+
+    #[tokio::test]
+    async fn test_signature_help() {
+        let (inner, url) = test_setup(TYPE_HINTS_DOC.to_string()).await;
+
+        let help = inner
+            .registry
+            .signature_help(&url, Position::new(8, 29))
+            .await
+            .expect("No signature help");
+
+        let signature = &help.signatures[0];
+        assert_eq!(signature.label, "add2 : I64, I64 -> I64");
+        assert_eq!(
+            signature.parameters,
+            Some(vec![
+                ParameterInformation {
+                    label: ParameterLabel::LabelOffsets([7, 10]),
+                    documentation: None,
+                },
+                ParameterInformation {
+                    label: ParameterLabel::LabelOffsets([12, 15]),
+                    documentation: None,
+                },
+            ])
+        );
+        assert_eq!(help.active_parameter, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_signature_help_utf16_offsets() {
+        let doc = indoc! {r"
+            module [main]
+
+            größe : I64, I64 -> I64
+            größe = \a, b -> a + b
+
+            main =
+                größe
+                    1
+                    2
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let help = inner
+            .registry
+            .signature_help(&url, Position::new(8, 8))
+            .await
+            .expect("No signature help");
+
+        // `ö` and `ß` take two bytes in UTF-8, but one code unit in UTF-16
+        let signature = &help.signatures[0];
+        assert_eq!(signature.label, "größe : I64, I64 -> I64");
+        assert_eq!(
+            signature.parameters,
+            Some(vec![
+                ParameterInformation {
+                    label: ParameterLabel::LabelOffsets([8, 11]),
+                    documentation: None,
+                },
+                ParameterInformation {
+                    label: ParameterLabel::LabelOffsets([13, 16]),
+                    documentation: None,
+                },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inlay_hints() {
+        let (inner, url) = test_setup(TYPE_HINTS_DOC.to_string()).await;
+
+        let range = Range::new(Position::new(0, 0), Position::new(10, 0));
+        let hints = inner
+            .registry
+            .inlay_hints(&url, range)
+            .await
+            .expect("No inlay hints");
+
+        let actual = hints
+            .into_iter()
+            .map(|hint| {
+                let InlayHintLabel::String(label) = hint.label else {
+                    panic!("Expected a plain label");
+                };
+                (hint.position.line, hint.position.character, label)
+            })
+            .collect::<Vec<_>>();
+
+        // `add2` and its parameters are annotated, so they don't get hints.
+        assert_eq!(
+            actual,
+            vec![
+                (5, 4, ": I64".to_string()),
+                (6, 7, ": Num a -> Num a".to_string()),
+                (6, 12, ": Num a".to_string()),
+                (8, 12, ": I64".to_string()),
+            ]
+        );
+    }
 }
@@ -5,7 +5,7 @@ use {
     std::fs,
 };
 #[cfg(not(target_family = "wasm"))]
-const MAX_DOWNLOAD_BYTES: u64 = 32 * 1_000_000_000; // GB
+pub(crate) const MAX_DOWNLOAD_BYTES: u64 = 32 * 1_000_000_000; // GB
 
 use std::path::{Path, PathBuf};
 
@@ -13,6 +13,12 @@ use std::path::{Path, PathBuf};
 pub enum RocCacheDir<'a> {
     /// Normal scenario: reading from the user's cache dir on disk
     Persistent(&'a Path),
+    /// For `--offline`: like Persistent, but never download anything. Packages that aren't in
+    /// the cache yet get installed from the tarballs `roc vendor` put in the project's vendor dir.
+    Offline {
+        cache_dir: &'a Path,
+        vendor_dir: &'a Path,
+    },
     /// For build.rs and tests where we never want to be downloading anything - yell loudly if we try!
     Disallowed,
     /// For tests only; we don't want to write to the real cache during a test!
@@ -24,6 +30,7 @@ impl RocCacheDir<'_> {
     pub fn as_persistent_path(&self) -> Option<&Path> {
         match self {
             RocCacheDir::Persistent(path) => Some(path),
+            RocCacheDir::Offline { cache_dir, .. } => Some(cache_dir),
             RocCacheDir::Disallowed => None,
             #[cfg(test)]
             RocCacheDir::Temp(_) => None,
@@ -70,6 +77,7 @@ fn nixos_error_if_dynamic(url: &str, dest_dir: &Path) {
 /// have an entry for the given URL. If we do, return its info. If we don't already have it, then:
 ///
 /// - Download and decompress the compressed tarball from the given URL
+///   (or when offline, decompress the tarball for it in the vendor dir)
 /// - Verify its bytes against the hash in the URL
 /// - Extract the tarball's contents into the appropriate cache directory
 ///
@@ -80,120 +88,140 @@ pub fn install_package<'a>(
     roc_cache_dir: RocCacheDir<'_>,
     url: &'a str,
 ) -> Result<(PathBuf, Option<&'a str>), Problem> {
-    use std::io::ErrorKind;
-
     let PackageMetadata {
         cache_subdir,
         content_hash,
         root_module_filename,
     } = PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
 
-    match roc_cache_dir {
-        RocCacheDir::Persistent(cache_dir) => {
-            // e.g. ~/.cache/roc/example.com/roc-packages/
-            let parent_dir = cache_dir.join(cache_subdir);
-            // e.g. ~/.cache/roc/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
-            let dest_dir = parent_dir.join(content_hash);
-
-            if dest_dir.exists() {
-                // If the cache dir exists already, we assume it has the correct contents
-                // (it's a cache, after all!) and return without downloading anything.
-                //
-                #[cfg(target_os = "linux")]
-                {
-                    nixos_error_if_dynamic(url, &dest_dir);
-                }
+    let (cache_dir, opt_vendor_dir) = match roc_cache_dir {
+        RocCacheDir::Persistent(cache_dir) => (cache_dir, None),
+        RocCacheDir::Offline {
+            cache_dir,
+            vendor_dir,
+        } => (cache_dir, Some(vendor_dir)),
+        RocCacheDir::Disallowed => {
+            internal_error!(
+                "Tried to download a package ({:?}) via RocCacheDir::Disallowed - which was explicitly used in order to disallow downloading packages in the current context!",
+                url
+            )
+        }
+        #[cfg(test)]
+        RocCacheDir::Temp(temp_dir) => return Ok((temp_dir.path().to_path_buf(), None)),
+    };
+
+    // e.g. ~/.cache/roc/example.com/roc-packages/
+    let parent_dir = cache_dir.join(cache_subdir);
+    // e.g. ~/.cache/roc/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
+    let dest_dir = parent_dir.join(content_hash);
 
-                Ok((dest_dir, root_module_filename))
-            } else {
-                // Download into a tempdir; only move it to dest_dir if hash verification passes.
+    // If the cache dir exists already, we assume it has the correct contents
+    // (it's a cache, after all!) and return without downloading anything.
+    if !dest_dir.exists() {
+        match opt_vendor_dir {
+            None => {
                 eprintln!(
                     "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
                     cache_dir.display()
                 );
-                let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
-                let tempdir_path = tempdir.path();
-                let downloaded_hash =
-                    https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)?;
-
-                // Download the tarball into memory and verify it.
-                // The tarball name is the hash of its contents.
-                if downloaded_hash == content_hash {
-                    // Now that we've verified the hash, rename the tempdir to the real dir.
-
-                    // Create the destination dir's parent dir, since it may not exist yet.
-                    fs::create_dir_all(parent_dir).or_else(|err| match err.kind() {
-                        // It's fine if the destination dir's parent already exists
-                        ErrorKind::AlreadyExists => Ok(()),
-                        _ => Err(Problem::IoErr(err)),
-                    })?;
 
-                    // This rename should be super cheap if it succeeds - just an inode change.
-                    let rename_err_kind = fs::rename(tempdir_path, &dest_dir)
-                        .err()
-                        .map(|err| err.kind());
-
-                    // It's okay if the rename failed because the destination already existed.
-                    // This could be a race condition between multiple downloads happening concurrently.
-                    // (This has happened in our test suite, for example!) Both downloads should have
-                    // the same content, so the rename failing for that reason should be no problem.
-                    if rename_err_kind.is_some()
-                        && rename_err_kind != Some(ErrorKind::AlreadyExists)
-                    {
-                        // If the rename failed, try a recursive copy -
-                        // it could have failed due to std::io::ErrorKind::CrossesDevices
-                        // (e.g. if the source an destination directories are on different disks)
-                        // which as of this implementation is nightly-only
-                        // https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.CrossesDevices                       match io_err.kind() {
-                        // but if that's what happened, this should work!
-
-                        // fs_extra::dir::copy needs the destination directory to exist already.
-                        fs::create_dir(&dest_dir).or_else(|err| match err.kind() {
-                            // It's fine if the destination dir already exists
-                            ErrorKind::AlreadyExists => Ok(()),
-                            _ => Err(Problem::IoErr(err)),
-                        })?;
-
-                        fs_extra::dir::copy(
-                            tempdir_path,
-                            &dest_dir,
-                            &fs_extra::dir::CopyOptions {
-                                content_only: true,
-                                ..Default::default()
-                            },
-                        )
-                        .or_else(|err| match err.kind {
-                            // It's fine if the destination file already exists; this could be the same
-                            // as the rename race condition mentioned above.
-                            fs_extra::error::ErrorKind::AlreadyExists => Ok(0),
-                            _ => Err(Problem::FsExtraErr(err)),
-                        })?;
-                    }
-
-                    #[cfg(target_os = "linux")]
-                    {
-                        nixos_error_if_dynamic(url, &dest_dir);
-                    }
-
-                    // The package's files are now in the cache. We're done!
-                    Ok((dest_dir, root_module_filename))
-                } else {
-                    Err(Problem::InvalidContentHash {
-                        expected: content_hash.to_string(),
-                        actual: downloaded_hash,
-                    })
-                }
+                install_verified(&parent_dir, &dest_dir, content_hash, |tempdir_path| {
+                    https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)
+                })?;
             }
+            Some(vendor_dir) => match crate::vendor::find_tarball(vendor_dir, content_hash) {
+                Some(tarball) => {
+                    install_verified(&parent_dir, &dest_dir, content_hash, |tempdir_path| {
+                        https::unpack_tarball(&tarball, tempdir_path)
+                    })?;
+                }
+                None => return Err(Problem::NotVendored(vendor_dir.to_path_buf())),
+            },
         }
-        RocCacheDir::Disallowed => {
-            internal_error!(
-                "Tried to download a package ({:?}) via RocCacheDir::Disallowed - which was explicitly used in order to disallow downloading packages in the current context!",
-                url
-            )
-        }
-        #[cfg(test)]
-        RocCacheDir::Temp(temp_dir) => Ok((temp_dir.path().to_path_buf(), None)),
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        nixos_error_if_dynamic(url, &dest_dir);
+    }
+
+    Ok((dest_dir, root_module_filename))
+}
+
+/// Unpack a package into a tempdir using the given function, which returns the hash of the
+/// package's tarball. Only if that hash is the expected one, move the package to dest_dir.
+#[cfg(not(target_family = "wasm"))]
+fn install_verified(
+    parent_dir: &Path,
+    dest_dir: &Path,
+    content_hash: &str,
+    unpack: impl FnOnce(&Path) -> Result<String, Problem>,
+) -> Result<(), Problem> {
+    use std::io::ErrorKind;
+
+    let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
+    let tempdir_path = tempdir.path();
+    let unpacked_hash = unpack(tempdir_path)?;
+
+    // The tarball name is the hash of its contents.
+    if unpacked_hash != content_hash {
+        return Err(Problem::InvalidContentHash {
+            expected: content_hash.to_string(),
+            actual: unpacked_hash,
+        });
+    }
+
+    // Now that we've verified the hash, rename the tempdir to the real dir.
+
+    // Create the destination dir's parent dir, since it may not exist yet.
+    fs::create_dir_all(parent_dir).or_else(|err| match err.kind() {
+        // It's fine if the destination dir's parent already exists
+        ErrorKind::AlreadyExists => Ok(()),
+        _ => Err(Problem::IoErr(err)),
+    })?;
+
+    // This rename should be super cheap if it succeeds - just an inode change.
+    let rename_err_kind = fs::rename(tempdir_path, dest_dir)
+        .err()
+        .map(|err| err.kind());
+
+    // It's okay if the rename failed because the destination already existed.
+    // This could be a race condition between multiple downloads happening concurrently.
+    // (This has happened in our test suite, for example!) Both downloads should have
+    // the same content, so the rename failing for that reason should be no problem.
+    if rename_err_kind.is_some() && rename_err_kind != Some(ErrorKind::AlreadyExists) {
+        // If the rename failed, try a recursive copy -
+        // it could have failed due to std::io::ErrorKind::CrossesDevices
+        // (e.g. if the source an destination directories are on different disks)
+        // which as of this implementation is nightly-only
+        // https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.CrossesDevices                       match io_err.kind() {
+        // but if that's what happened, this should work!
+
+        // fs_extra::dir::copy needs the destination directory to exist already.
+        fs::create_dir(dest_dir).or_else(|err| match err.kind() {
+            // It's fine if the destination dir already exists
+            ErrorKind::AlreadyExists => Ok(()),
+            _ => Err(Problem::IoErr(err)),
+        })?;
+
+        fs_extra::dir::copy(
+            tempdir_path,
+            dest_dir,
+            &fs_extra::dir::CopyOptions {
+                content_only: true,
+                ..Default::default()
+            },
+        )
+        .or_else(|err| match err.kind {
+            // It's fine if the destination file already exists; this could be the same
+            // as the rename race condition mentioned above.
+            fs_extra::error::ErrorKind::AlreadyExists => Ok(0),
+            _ => Err(Problem::FsExtraErr(err)),
+        })?;
+    }
+
+    // The package's files are now in the cache. We're done!
+    Ok(())
 }
 
 #[cfg(windows)]
@@ -267,3 +295,15 @@ pub fn roc_cache_packages_dir() -> PathBuf {
     const PACKAGES_DIR_NAME: &str = "packages";
     roc_cache_dir().join(PACKAGES_DIR_NAME)
 }
+
+/// Returns a path of the form roc_cache_dir().join("repl_history")
+pub fn roc_cache_repl_history_path() -> PathBuf {
+    const REPL_HISTORY_FILE_NAME: &str = "repl_history";
+    roc_cache_dir().join(REPL_HISTORY_FILE_NAME)
+}
+
+/// Returns a path of the form roc_cache_dir().join("modules")
+pub fn roc_cache_modules_dir() -> PathBuf {
+    const MODULES_DIR_NAME: &str = "modules";
+    roc_cache_dir().join(MODULES_DIR_NAME)
+}
@@ -1,6 +1,7 @@
 use std::{
+    fs::File,
     io::{self, Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use crate::tarball::Compression;
@@ -241,6 +242,9 @@ pub enum Problem {
     /// The Content-Length header of the response exceeded max_download_bytes
     DownloadTooBig(u64),
     NotFound,
+    /// The package isn't in the cache, and we're not allowed to download it.
+    /// This is the directory we looked for a vendored tarball of it in.
+    NotVendored(PathBuf),
 }
 
 pub fn download_and_hash(
@@ -248,6 +252,71 @@ pub fn download_and_hash(
     dest_dir: &Path,
     max_download_bytes: u64,
 ) -> Result<String, Problem> {
+    let (resp, encoding) = get(url, max_download_bytes)?;
+
+    decompress_into(dest_dir, encoding, resp)
+}
+
+/// Download the tarball at the given URL into dest_dir without unpacking it, and return its path.
+/// The file is named after the given content hash, but that hash is not verified here;
+/// [unpack_tarball] and [hash_tarball] do that.
+pub fn download_tarball(
+    url: &str,
+    content_hash: &str,
+    dest_dir: &Path,
+    max_download_bytes: u64,
+) -> Result<PathBuf, Problem> {
+    let (mut resp, encoding) = get(url, max_download_bytes)?;
+
+    // Keep the tarball compressed the way the server sent it, so vendored packages stay small.
+    // Nothing can tell a deflated file from an uncompressed one by its extension, though,
+    // so store those uncompressed.
+    let ext = match encoding {
+        Encoding::Brotli => Compression::Brotli.file_ext(),
+        Encoding::Gzip => Compression::Gzip.file_ext(),
+        Encoding::Deflate | Encoding::Uncompressed => Compression::Uncompressed.file_ext(),
+    };
+    let dest_path = dest_dir.join(format!("{content_hash}{ext}"));
+
+    // Write to a tempfile first, so an interrupted download doesn't leave a partial tarball behind.
+    let mut file = tempfile::NamedTempFile::new_in(dest_dir).map_err(Problem::IoErr)?;
+
+    match encoding {
+        Encoding::Deflate => io::copy(&mut flate2::read::DeflateDecoder::new(&mut resp), &mut file),
+        _ => io::copy(&mut resp, &mut file),
+    }
+    .map_err(Problem::IoErr)?;
+
+    file.persist(&dest_path)
+        .map_err(|err| Problem::IoErr(err.error))?;
+
+    Ok(dest_path)
+}
+
+/// Unpack a tarball on disk (e.g. one from [download_tarball]) into dest_dir, and return the
+/// base64url-encoded BLAKE3 hash of its contents. It's decompressed according to its extension.
+pub fn unpack_tarball(tarball: &Path, dest_dir: &Path) -> Result<String, Problem> {
+    let file = File::open(tarball).map_err(Problem::IoErr)?;
+
+    decompress_into(dest_dir, tarball_encoding(tarball)?, file)
+}
+
+/// The base64url-encoded BLAKE3 hash of the contents of a tarball on disk.
+pub fn hash_tarball(tarball: &Path) -> Result<String, Problem> {
+    let file = File::open(tarball).map_err(Problem::IoErr)?;
+    let mut hash_reader = HashReader::new(decoder(tarball_encoding(tarball)?, file));
+
+    io::copy(&mut hash_reader, &mut io::sink()).map_err(Problem::IoErr)?;
+
+    Ok(base64_url::encode(hash_reader.finalize().as_bytes()))
+}
+
+fn tarball_encoding(tarball: &Path) -> Result<Encoding, Problem> {
+    Encoding::new("", &tarball.to_string_lossy())
+}
+
+/// Send a GET request for the given URL, and return the body along with how it's encoded.
+fn get(url: &str, max_download_bytes: u64) -> Result<(impl Read, Encoding), Problem> {
     // TODO apparently it really improves performance to construct a Client once and then reuse it,
     // instead of making a new Client for every request.
     // Per https://github.com/seanmonstar/reqwest/issues/1454#issuecomment-1026076701
@@ -285,7 +354,8 @@ pub fn download_and_hash(
     // Use .take to prevent a malicious server from sending back bytes
     // until system resources are exhausted!
     let resp = ProgressReporter::new(resp.take(max_download_bytes), content_length);
-    decompress_into(dest_dir, encoding, resp)
+
+    Ok((resp, encoding))
 }
 
 /// The content encodings we support
@@ -371,17 +441,19 @@ fn decompress_into(
     encoding: Encoding,
     reader: impl Read,
 ) -> Result<String, Problem> {
+    hash_and_unpack(dest_dir, decoder(encoding, reader))
+}
+
+/// Wrap the given reader in one that decompresses its bytes according to the given encoding.
+fn decoder<'r>(encoding: Encoding, reader: impl Read + 'r) -> Box<dyn Read + 'r> {
     match encoding {
-        Encoding::Brotli => hash_and_unpack(
-            dest_dir,
-            brotli::Decompressor::new(reader, BROTLI_BUFFER_BYTES),
-        ),
+        Encoding::Brotli => Box::new(brotli::Decompressor::new(reader, BROTLI_BUFFER_BYTES)),
         Encoding::Gzip => {
             // Note: GzDecoder::new immediately parses the gzip header (so, calls read())
-            hash_and_unpack(dest_dir, flate2::read::GzDecoder::new(reader))
+            Box::new(flate2::read::GzDecoder::new(reader))
         }
-        Encoding::Deflate => hash_and_unpack(dest_dir, flate2::read::DeflateDecoder::new(reader)),
-        Encoding::Uncompressed => hash_and_unpack(dest_dir, reader),
+        Encoding::Deflate => Box::new(flate2::read::DeflateDecoder::new(reader)),
+        Encoding::Uncompressed => Box::new(reader),
     }
 }
 
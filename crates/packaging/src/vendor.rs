@@ -0,0 +1,543 @@
+//! `roc vendor`, which puts every package a project uses in a vendor directory next to its root
+//! module, so the project can be built with `--offline` on a machine that can't download them.
+use crate::cache::{self, RocCacheDir, MAX_DOWNLOAD_BYTES};
+use crate::https::{self, PackageMetadata, Problem};
+use crate::tarball::Compression;
+use bumpalo::Bump;
+use roc_parse::ast::{ExtractSpaces, Header};
+use roc_parse::header::{parse_header, AppHeader, PackageHeader, PlatformHeader};
+use roc_parse::state::State;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The directory next to the root module that `roc vendor` puts package tarballs in.
+pub const VENDOR_DIR_NAME: &str = "vendor";
+
+/// The file next to the root module that records which packages are vendored.
+pub const LOCK_FILENAME: &str = "roc.lock";
+
+/// The root module of a package whose URL doesn't name one, same as in the loader.
+const DEFAULT_ROOT_MODULE: &str = "main.roc";
+
+/// The vendor directory of the project whose root module is at the given path.
+pub fn vendor_dir(root_module: &Path) -> PathBuf {
+    root_module.with_file_name(VENDOR_DIR_NAME)
+}
+
+/// The vendored tarball of the package with the given content hash, if there is one.
+pub(crate) fn find_tarball(vendor_dir: &Path, content_hash: &str) -> Option<PathBuf> {
+    [
+        Compression::Brotli,
+        Compression::Gzip,
+        Compression::Uncompressed,
+    ]
+    .into_iter()
+    .map(|compression| vendor_dir.join(format!("{content_hash}{}", compression.file_ext())))
+    .find(|path| path.is_file())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub url: String,
+    /// The base64url-encoded BLAKE3 hash of the package's tarball
+    pub content_hash: String,
+    /// The tarball's filename in the vendor directory
+    pub tarball: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockFile {
+    /// Sorted by URL
+    pub packages: Vec<LockedPackage>,
+}
+
+impl LockFile {
+    /// Parses a lock file written by `roc vendor`. The error is the number (starting at 1) of the
+    /// first line that isn't a package.
+    pub fn parse(src: &str) -> Result<Self, usize> {
+        let mut packages = Vec::new();
+
+        for (index, line) in src.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let [url, content_hash, path] = words[..] else {
+                return Err(index + 1);
+            };
+            let tarball = path
+                .strip_prefix(VENDOR_DIR_NAME)
+                .and_then(|path| path.strip_prefix('/'))
+                .ok_or(index + 1)?;
+
+            packages.push(LockedPackage {
+                url: url.to_string(),
+                content_hash: content_hash.to_string(),
+                tarball: tarball.to_string(),
+            });
+        }
+
+        packages.sort_by(|a, b| a.url.cmp(&b.url));
+
+        Ok(LockFile { packages })
+    }
+}
+
+impl fmt::Display for LockFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "# Written by `roc vendor`. Each line is a package this project uses, the BLAKE3 hash"
+        )?;
+        writeln!(
+            f,
+            "# of its tarball, and that tarball in the vendor directory."
+        )?;
+
+        for LockedPackage {
+            url,
+            content_hash,
+            tarball,
+        } in self.packages.iter()
+        {
+            writeln!(f, "{url} {content_hash} {VENDOR_DIR_NAME}/{tarball}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum VendorProblem {
+    /// The package at this URL couldn't be vendored
+    Package {
+        url: String,
+        problem: Problem,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// The header of this module couldn't be parsed, so we can't tell which packages it uses
+    InvalidHeader(PathBuf),
+    /// `--offline` needs a lock file, and there isn't one at this path
+    MissingLockFile(PathBuf),
+    /// This line (starting at 1) of the lock file isn't a package
+    InvalidLockFile {
+        path: PathBuf,
+        line: usize,
+    },
+    /// A module of the project uses the package at this URL, but the lock file doesn't list it
+    NotLocked {
+        url: String,
+        lock_file: PathBuf,
+    },
+}
+
+/// Put a tarball of every package the project at root_module uses (directly or through other
+/// packages) in its vendor directory, and write the lock file next to root_module.
+/// Packages that aren't in the vendor directory yet get downloaded, and every tarball's hash
+/// gets verified.
+pub fn vendor(root_module: &Path, cache_dir: &Path) -> Result<LockFile, VendorProblem> {
+    let vendor_dir = vendor_dir(root_module);
+
+    fs::create_dir_all(&vendor_dir).map_err(|error| VendorProblem::Io {
+        path: vendor_dir.clone(),
+        error,
+    })?;
+
+    let mut packages = BTreeMap::new();
+
+    walk_packages(root_module, |url| {
+        if packages.contains_key(&url) {
+            return Ok(None);
+        }
+
+        let (locked, package_root_module) =
+            vendor_package(&url, cache_dir, &vendor_dir).map_err(|problem| {
+                VendorProblem::Package {
+                    url: url.clone(),
+                    problem,
+                }
+            })?;
+
+        packages.insert(url, locked);
+
+        Ok(Some(package_root_module))
+    })?;
+
+    let lock_file = LockFile {
+        packages: packages.into_values().collect(),
+    };
+    let lock_file_path = root_module.with_file_name(LOCK_FILENAME);
+
+    fs::write(&lock_file_path, lock_file.to_string()).map_err(|error| VendorProblem::Io {
+        path: lock_file_path,
+        error,
+    })?;
+
+    Ok(lock_file)
+}
+
+/// For `--offline`: reads the lock file of the project at root_module, and checks that it agrees
+/// with the project. Every package that the project's modules on disk use must be locked, and
+/// every locked package must have the hash in its URL, with a tarball in the vendor dir that still
+/// has that hash.
+pub fn verify_lock_file(root_module: &Path) -> Result<LockFile, VendorProblem> {
+    let lock_file_path = root_module.with_file_name(LOCK_FILENAME);
+
+    let src = match fs::read_to_string(&lock_file_path) {
+        Ok(src) => src,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(VendorProblem::MissingLockFile(lock_file_path));
+        }
+        Err(error) => {
+            return Err(VendorProblem::Io {
+                path: lock_file_path,
+                error,
+            })
+        }
+    };
+
+    let lock_file = LockFile::parse(&src).map_err(|line| VendorProblem::InvalidLockFile {
+        path: lock_file_path.clone(),
+        line,
+    })?;
+
+    // Packages in the cache or the vendor dir are only used through the ones on disk, so only
+    // the modules on disk can use packages that `roc vendor` didn't see
+    walk_packages(root_module, |url| {
+        if lock_file.packages.iter().any(|locked| locked.url == url) {
+            Ok(None)
+        } else {
+            Err(VendorProblem::NotLocked {
+                url,
+                lock_file: lock_file_path.clone(),
+            })
+        }
+    })?;
+
+    let vendor_dir = vendor_dir(root_module);
+
+    for locked in lock_file.packages.iter() {
+        let package_problem = |problem| VendorProblem::Package {
+            url: locked.url.clone(),
+            problem,
+        };
+
+        let PackageMetadata { content_hash, .. } =
+            PackageMetadata::try_from(locked.url.as_str())
+                .map_err(|problem| package_problem(Problem::InvalidUrl(problem)))?;
+
+        if content_hash != locked.content_hash {
+            return Err(package_problem(Problem::InvalidContentHash {
+                expected: content_hash.to_string(),
+                actual: locked.content_hash.clone(),
+            }));
+        }
+
+        let tarball = vendor_dir.join(&locked.tarball);
+
+        if !tarball.is_file() {
+            return Err(package_problem(Problem::NotVendored(vendor_dir)));
+        }
+
+        let actual_hash = https::hash_tarball(&tarball).map_err(package_problem)?;
+
+        if actual_hash != locked.content_hash {
+            return Err(package_problem(Problem::InvalidContentHash {
+                expected: locked.content_hash.clone(),
+                actual: actual_hash,
+            }));
+        }
+    }
+
+    Ok(lock_file)
+}
+
+/// Calls on_url with the URL of every package that the module at root_module uses, directly or
+/// through packages on disk. When on_url returns the root module of the package at that URL,
+/// the packages that one uses are visited too.
+fn walk_packages(
+    root_module: &Path,
+    mut on_url: impl FnMut(String) -> Result<Option<PathBuf>, VendorProblem>,
+) -> Result<(), VendorProblem> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![root_module.to_path_buf()];
+
+    while let Some(module_path) = stack.pop() {
+        if !visited.insert(module_path.clone()) {
+            continue;
+        }
+
+        for package_name in package_names(&module_path)? {
+            if package_name.starts_with("https://") {
+                if let Some(package_root_module) = on_url(package_name)? {
+                    stack.push(package_root_module);
+                }
+            } else {
+                // A package on disk, relative to the module that uses it
+                let module_dir = module_path.parent().unwrap_or(Path::new(""));
+
+                stack.push(module_dir.join(package_name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Make sure the vendor dir has a verified tarball of the package at the given URL, and install
+/// it into the cache from there. Returns the package's root module in the cache.
+fn vendor_package(
+    url: &str,
+    cache_dir: &Path,
+    vendor_dir: &Path,
+) -> Result<(LockedPackage, PathBuf), Problem> {
+    let PackageMetadata { content_hash, .. } =
+        PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
+
+    let tarball = match find_tarball(vendor_dir, content_hash) {
+        Some(tarball) => tarball,
+        None => {
+            eprintln!(
+                "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
+                vendor_dir.display()
+            );
+
+            https::download_tarball(url, content_hash, vendor_dir, MAX_DOWNLOAD_BYTES)?
+        }
+    };
+
+    let actual_hash = https::hash_tarball(&tarball)?;
+
+    if actual_hash != content_hash {
+        // Don't leave a tarball behind that `--offline` would reject anyway
+        let _ = fs::remove_file(&tarball);
+
+        return Err(Problem::InvalidContentHash {
+            expected: content_hash.to_string(),
+            actual: actual_hash,
+        });
+    }
+
+    let (package_dir, opt_root_module) = cache::install_package(
+        RocCacheDir::Offline {
+            cache_dir,
+            vendor_dir,
+        },
+        url,
+    )?;
+
+    let locked = LockedPackage {
+        url: url.to_string(),
+        content_hash: content_hash.to_string(),
+        tarball: tarball
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+
+    Ok((
+        locked,
+        package_dir.join(opt_root_module.unwrap_or(DEFAULT_ROOT_MODULE)),
+    ))
+}
+
+/// The names (URLs or paths) of the packages in the header of the module at the given path.
+fn package_names(module_path: &Path) -> Result<Vec<String>, VendorProblem> {
+    let src = fs::read(module_path).map_err(|error| VendorProblem::Io {
+        path: module_path.to_path_buf(),
+        error,
+    })?;
+    let arena = Bump::new();
+    let (header, _) = parse_header(&arena, State::new(&src))
+        .map_err(|_| VendorProblem::InvalidHeader(module_path.to_path_buf()))?;
+
+    let entries = match header.item {
+        Header::App(AppHeader { packages, .. })
+        | Header::Package(PackageHeader { packages, .. }) => packages.value.items,
+        Header::Platform(PlatformHeader { packages, .. }) => packages.item.items,
+        Header::Module(_) | Header::Hosted(_) => &[],
+    };
+
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            let entry = entry.value.extract_spaces().item;
+
+            entry.package_name.value.to_str().to_string()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+fn write_package(dir: &Path, header: &str) -> PathBuf {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(DEFAULT_ROOT_MODULE), header).unwrap();
+
+    dir.join(DEFAULT_ROOT_MODULE)
+}
+
+/// Builds a tarball of a package with the given header, moves it into vendor_dir,
+/// and returns its URL.
+#[cfg(test)]
+fn vendored_package_url(package_dir: &Path, header: &str, vendor_dir: &Path) -> String {
+    let root_module = write_package(package_dir, header);
+    let tarball = crate::tarball::build(&root_module, Compression::Brotli).unwrap();
+
+    fs::create_dir_all(vendor_dir).unwrap();
+    fs::rename(
+        root_module.with_file_name(&tarball),
+        vendor_dir.join(&tarball),
+    )
+    .unwrap();
+
+    format!("https://example.com/packages/{tarball}")
+}
+
+#[test]
+fn offline_install_from_vendor_dir() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let vendor_dir = tempdir.path().join(VENDOR_DIR_NAME);
+    let cache_dir = tempdir.path().join("cache");
+    let url = vendored_package_url(&tempdir.path().join("pkg"), "package [] {}\n", &vendor_dir);
+
+    let (package_dir, opt_root_module) = cache::install_package(
+        RocCacheDir::Offline {
+            cache_dir: &cache_dir,
+            vendor_dir: &vendor_dir,
+        },
+        &url,
+    )
+    .unwrap();
+
+    assert!(package_dir.starts_with(&cache_dir));
+    assert!(package_dir.join(DEFAULT_ROOT_MODULE).is_file());
+    assert_eq!(opt_root_module, None);
+}
+
+#[test]
+fn offline_install_without_vendored_tarball() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let vendor_dir = tempdir.path().join(VENDOR_DIR_NAME);
+    let cache_dir = tempdir.path().join("cache");
+
+    let result = cache::install_package(
+        RocCacheDir::Offline {
+            cache_dir: &cache_dir,
+            vendor_dir: &vendor_dir,
+        },
+        "https://example.com/packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE.tar.br",
+    );
+
+    assert!(matches!(result, Err(Problem::NotVendored(dir)) if dir == vendor_dir));
+}
+
+#[test]
+fn vendor_writes_lock_file() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let app_dir = tempdir.path().join("app");
+    let vendor_dir = app_dir.join(VENDOR_DIR_NAME);
+    let url = vendored_package_url(&tempdir.path().join("pkg"), "package [] {}\n", &vendor_dir);
+
+    // The app uses the package directly and through a package on disk
+    write_package(
+        &app_dir.join("local"),
+        &format!("package [] {{ dep: \"{url}\" }}\n"),
+    );
+    let root_module = write_package(
+        &app_dir,
+        &format!("app [main] {{ dep: \"{url}\", local: \"local/main.roc\" }}\n"),
+    );
+
+    let lock_file = vendor(&root_module, &tempdir.path().join("cache")).unwrap();
+    let tarball = url.rsplit_once('/').unwrap().1;
+    let content_hash = tarball.strip_suffix(".tar.br").unwrap();
+
+    assert_eq!(
+        lock_file.packages,
+        vec![LockedPackage {
+            url: url.clone(),
+            content_hash: content_hash.to_string(),
+            tarball: tarball.to_string(),
+        }]
+    );
+    assert_eq!(
+        fs::read_to_string(app_dir.join(LOCK_FILENAME)).unwrap(),
+        lock_file.to_string()
+    );
+    assert!(lock_file
+        .to_string()
+        .ends_with(&format!("{url} {content_hash} vendor/{tarball}\n")));
+}
+
+#[test]
+fn lock_file_round_trip() {
+    let lock_file = LockFile {
+        packages: vec![LockedPackage {
+            url: "https://example.com/packages/hash.tar.br".to_string(),
+            content_hash: "hash".to_string(),
+            tarball: "hash.tar.br".to_string(),
+        }],
+    };
+
+    assert_eq!(LockFile::parse(&lock_file.to_string()), Ok(lock_file));
+    assert_eq!(
+        LockFile::parse("# comment\nhttps://example.com/hash.tar.br hash\n"),
+        Err(2)
+    );
+    assert_eq!(
+        LockFile::parse("https://example.com/hash.tar.br hash elsewhere/hash.tar.br\n"),
+        Err(1)
+    );
+}
+
+#[test]
+fn verify_lock_file_against_project() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let app_dir = tempdir.path().join("app");
+    let vendor_dir = app_dir.join(VENDOR_DIR_NAME);
+    let url = vendored_package_url(&tempdir.path().join("pkg"), "package [] {}\n", &vendor_dir);
+    let root_module = write_package(&app_dir, &format!("app [main] {{ dep: \"{url}\" }}\n"));
+
+    assert!(matches!(
+        verify_lock_file(&root_module),
+        Err(VendorProblem::MissingLockFile(path)) if path == app_dir.join(LOCK_FILENAME)
+    ));
+
+    let lock_file = vendor(&root_module, &tempdir.path().join("cache")).unwrap();
+    assert_eq!(verify_lock_file(&root_module).unwrap(), lock_file);
+
+    // The header uses a package that wasn't vendored
+    let other_url = vendored_package_url(
+        &tempdir.path().join("other"),
+        "package [] {}\n\n# Not the same tarball as pkg\n",
+        &tempdir.path().join("elsewhere"),
+    );
+    write_package(
+        &app_dir,
+        &format!("app [main] {{ dep: \"{url}\", other: \"{other_url}\" }}\n"),
+    );
+    assert!(matches!(
+        verify_lock_file(&root_module),
+        Err(VendorProblem::NotLocked { url, .. }) if url == other_url
+    ));
+
+    // The vendored tarball changed since it was locked
+    write_package(&app_dir, &format!("app [main] {{ dep: \"{url}\" }}\n"));
+    fs::write(vendor_dir.join(&lock_file.packages[0].tarball), b"tampered").unwrap();
+    assert!(matches!(
+        verify_lock_file(&root_module),
+        Err(VendorProblem::Package {
+            problem: Problem::InvalidContentHash { .. } | Problem::IoErr(_),
+            ..
+        })
+    ));
+}
@@ -5,6 +5,7 @@ use bumpalo::Bump;
 use const_format::concatcp;
 use roc_load::MonomorphizedModule;
 use roc_mono::ir::OptLevel;
+use roc_packaging::cache::roc_cache_repl_history_path;
 use roc_repl_eval::gen::Problems;
 use roc_repl_ui::colors::{CYAN, END_COL};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
@@ -20,6 +21,7 @@ use rustyline_derive::{Completer, Helper, Hinter};
 use std::any::Any;
 use std::backtrace::Backtrace;
 use std::borrow::Cow;
+use std::fs;
 use std::panic::{AssertUnwindSafe, PanicInfo};
 use std::sync::{Arc, Mutex, OnceLock};
 use target_lexicon::Triple;
@@ -93,6 +95,10 @@ pub fn main(has_color: bool, has_header: bool) -> i32 {
     let target = Triple::host().into();
     let mut arena = Bump::new();
 
+    // History is kept across sessions. It's fine if there isn't any yet.
+    let history_path = roc_cache_repl_history_path();
+    let _ = editor.load_history(&history_path);
+
     loop {
         match editor.readline(&strip_colors_if_necessary(PROMPT)) {
             Ok(line) => {
@@ -100,6 +106,12 @@ pub fn main(has_color: bool, has_header: bool) -> i32 {
 
                 editor.add_history_entry(line);
 
+                // Save after every line, so history isn't lost if the REPL crashes.
+                if let Some(cache_dir) = history_path.parent() {
+                    let _ = fs::create_dir_all(cache_dir);
+                }
+                let _ = editor.save_history(&history_path);
+
                 let repl_state = &mut editor
                     .helper_mut()
                     .expect("Editor helper was not set")
@@ -121,6 +133,16 @@ pub fn main(has_color: bool, has_header: bool) -> i32 {
                             println!("{}", strip_colors_if_necessary(&output));
                         }
                     }
+                    ReplAction::Type { output, problems } => {
+                        let output = format_output(ANSI_STYLE_CODES, output, problems);
+
+                        if !output.is_empty() {
+                            println!("{}", strip_colors_if_necessary(&output));
+                        }
+                    }
+                    ReplAction::Message(message) => {
+                        println!("{message}");
+                    }
                     ReplAction::Exit => {
                         return 0;
                     }
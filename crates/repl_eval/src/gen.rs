@@ -13,6 +13,7 @@ use roc_region::all::LineInfo;
 use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 use roc_solve::FunctionKind;
 use roc_target::Target;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 #[derive(Debug)]
 pub struct ReplOutput {
@@ -75,6 +76,10 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             palette,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            inline_budget: None,
+            simplify: false,
+            // every line would add cache entries, and the module cache is never pruned
+            module_cache_dir: None,
         },
     );
 
@@ -174,6 +179,20 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     (Some(loaded), problems)
 }
 
+/// The type of the expression that [compile_to_mono] was given, printed the way the REPL shows it.
+pub fn expr_type_str(loaded: &mut MonomorphizedModule<'_>) -> Option<String> {
+    let (_, main_fn_var) = loaded.exposed_to_host.top_level_values.iter().next()?;
+    let main_fn_var = *main_fn_var;
+
+    Some(name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    ))
+}
+
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
     defs: I,
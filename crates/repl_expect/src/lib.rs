@@ -100,9 +100,18 @@ mod test {
     use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
     use target_lexicon::Triple;
 
-    use crate::run::expect_mono_module_to_dylib;
+    use crate::run::{expect_mono_module_to_dylib, expect_mono_module_to_dylib_dev};
 
     fn run_expect_test(source: &str, expected: &str) {
+        run_expect_test_help(source, expected, false)
+    }
+
+    /// Builds the expects with the dev backend instead of LLVM
+    fn run_expect_test_dev(source: &str, expected: &str) {
+        run_expect_test_help(source, expected, true)
+    }
+
+    fn run_expect_test_help(source: &str, expected: &str, dev_backend: bool) {
         let arena = bumpalo::Bump::new();
         let arena = &arena;
 
@@ -124,6 +133,9 @@ mod test {
             palette: DEFAULT_PALETTE,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            inline_budget: None,
+            simplify: false,
+            module_cache_dir: None,
         };
         let loaded = match roc_load::load_and_monomorphize_from_str(
             arena,
@@ -147,9 +159,12 @@ mod test {
 
         let interns = loaded.interns.clone();
 
-        let (dy_lib, expects_by_module, layout_interner) =
+        let (dy_lib, expects_by_module, layout_interner) = if dev_backend {
+            expect_mono_module_to_dylib_dev(arena, target, loaded)
+        } else {
             expect_mono_module_to_dylib(arena, target, loaded, opt_level, LlvmBackendMode::CliTest)
-                .unwrap();
+        }
+        .unwrap();
 
         let arena = &bumpalo::Bump::new();
         let interns = arena.alloc(interns);
@@ -168,7 +183,7 @@ mod test {
 
         let global_layout_interner = layout_interner.into_global();
         for (_, expect_funcs) in expects_by_module {
-            let (_failed, _passed) = crate::run::run_expects_with_memory(
+            let _outcomes = crate::run::run_expects_with_memory(
                 &mut writer,
                 RenderTarget::ColorTerminal,
                 arena,
@@ -1249,4 +1264,228 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn dev_backend_lookup_integer() {
+        run_expect_test_dev(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                expect
+                    a = 1
+                    b = 2
+
+                    a == b
+                "#
+            ),
+            indoc!(
+                r"
+                This expectation failed:
+
+                5│>  expect
+                6│>      a = 1
+                7│>      b = 2
+                8│>
+                9│>      a == b
+
+                When it failed, these variables had these values:
+
+                a : Num *
+                a = 1
+
+                b : Num *
+                b = 2
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn dev_backend_struct_with_strings() {
+        run_expect_test_dev(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                expect
+                    a = {
+                        utopia: "Astra mortemque praestare gradatim",
+                        brillist: "Profundum et fundamentum",
+                    }
+
+                    a != a
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                 5│>  expect
+                 6│>      a = {
+                 7│>          utopia: "Astra mortemque praestare gradatim",
+                 8│>          brillist: "Profundum et fundamentum",
+                 9│>      }
+                10│>
+                11│>      a != a
+
+                When it failed, these variables had these values:
+
+                a : {
+                    brillist : Str,
+                    utopia : Str,
+                }
+                a = { brillist: "Profundum et fundamentum", utopia: "Astra mortemque praestare gradatim" }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn dev_backend_result_with_strings() {
+        run_expect_test_dev(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                expect
+                    a = Ok "Astra mortemque praestare gradatim"
+                    b = Err "Profundum et fundamentum"
+
+                    a == b
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                5│>  expect
+                6│>      a = Ok "Astra mortemque praestare gradatim"
+                7│>      b = Err "Profundum et fundamentum"
+                8│>
+                9│>      a == b
+
+                When it failed, these variables had these values:
+
+                a : [
+                    Err Str,
+                    Ok Str,
+                ]
+                a = Ok "Astra mortemque praestare gradatim"
+
+                b : [
+                    Err Str,
+                    Ok Str,
+                ]
+                b = Err "Profundum et fundamentum"
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn dev_backend_linked_list() {
+        run_expect_test_dev(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                ConsList a : [ Nil, Cons a (ConsList a) ]
+
+                cons = \list, x -> Cons x list
+
+                expect
+                    a : ConsList Str
+                    a = Nil
+
+                    b : ConsList Str
+                    b = Nil
+                        |> cons "Astra mortemque praestare gradatim"
+                        |> cons "Profundum et fundamentum"
+
+                    a == b
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                 9│>  expect
+                10│>      a : ConsList Str
+                11│>      a = Nil
+                12│>
+                13│>      b : ConsList Str
+                14│>      b = Nil
+                15│>          |> cons "Astra mortemque praestare gradatim"
+                16│>          |> cons "Profundum et fundamentum"
+                17│>
+                18│>      a == b
+
+                When it failed, these variables had these values:
+
+                a : ConsList Str
+                a = Nil
+
+                b : ConsList Str
+                b = Cons "Profundum et fundamentum" (Cons "Astra mortemque praestare gradatim" Nil)
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn dev_backend_nullable_tree() {
+        run_expect_test_dev(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                Tree a : [ Empty, Leaf a, Node (Tree a) (Tree a) ]
+
+                cons = \list, x -> Cons x list
+
+                expect
+                    a : Tree Str
+                    a = Leaf "Astra mortemque praestare gradatim"
+
+                    b : Tree Str
+                    b = Node Empty Empty
+
+                    a == b
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                 9│>  expect
+                10│>      a : Tree Str
+                11│>      a = Leaf "Astra mortemque praestare gradatim"
+                12│>
+                13│>      b : Tree Str
+                14│>      b = Node Empty Empty
+                15│>
+                16│>      a == b
+
+                When it failed, these variables had these values:
+
+                a : Tree Str
+                a = Leaf "Astra mortemque praestare gradatim"
+
+                b : Tree Str
+                b = Node Empty Empty
+                "#
+            ),
+        );
+    }
 }
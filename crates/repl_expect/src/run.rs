@@ -1,6 +1,9 @@
-use std::sync::{
-    atomic::{AtomicBool, AtomicU32},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use bumpalo::collections::Vec as BumpVec;
@@ -11,7 +14,7 @@ use roc_can::expr::ExpectLookup;
 use roc_collections::{MutMap, MutSet, VecMap};
 use roc_error_macros::internal_error;
 use roc_gen_llvm::{
-    llvm::{build::LlvmBackendMode, externs::add_default_roc_externs},
+    llvm::{build::LlvmBackendMode, debug_info::DebugSources, externs::add_default_roc_externs},
     run_roc::RocCallResult,
     run_roc_dylib,
 };
@@ -113,8 +116,48 @@ impl<'a> ExpectMemory<'a> {
     }
 }
 
+/// How running a single top-level `expect` went.
+#[derive(Debug)]
+pub struct ExpectOutcome<'a> {
+    pub expect: ToplevelExpect<'a>,
+    pub duration: Duration,
+    /// The failure or crash as plain text, or `None` if the expect passed.
+    pub failure: Option<String>,
+}
+
+impl ExpectOutcome<'_> {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectRunOptions {
+    /// How many expects may run at the same time, each in its own process.
+    pub max_workers: usize,
+    /// An expect that runs for longer than this is stopped and reported as a failure.
+    pub timeout: Duration,
+}
+
+/// How an expect ended, before its failures are rendered.
+enum ExpectEnd {
+    Passed,
+    /// The failures are in the shared memory, so they can be rendered from there.
+    Failed {
+        failure_count: usize,
+    },
+    Crashed {
+        message: String,
+    },
+    TimedOut,
+}
+
+/// Runs every expect in a child process of its own, so an expect that crashes the process or
+/// never finishes only fails itself. Up to `options.max_workers` of them run at the same time.
+/// Their failures are written to `writer` in the order of `expects`, regardless of which one
+/// finished first.
 #[allow(clippy::too_many_arguments)]
-pub fn run_toplevel_expects<'a, W: std::io::Write>(
+pub fn run_toplevel_expects<'a, 'e, W: std::io::Write>(
     writer: &mut W,
     render_target: RenderTarget,
     arena: &'a Bump,
@@ -122,26 +165,238 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     layout_interner: &GlobalLayoutInterner<'a>,
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
-    expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
-    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
-    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    expects: ExpectFunctions<'e>,
+    options: ExpectRunOptions,
+) -> std::io::Result<Vec<ExpectOutcome<'e>>> {
+    let worker_count = options.max_workers.clamp(1, expects.pure.len().max(1));
+
+    // Every worker has its own shared memory, which a child writes its failures to. It stays
+    // mapped in this process after the child exits, so we can render them from here.
+    let mut memories: Vec<ExpectMemory> = (0..worker_count)
+        .map(|worker| {
+            let shm_name = format!("/roc_expect_buffer_{}_{worker}", std::process::id());
+            ExpectMemory::create_or_reuse_mmap(&shm_name)
+        })
+        .collect();
 
-    run_expects_with_memory(
-        writer,
-        render_target,
-        arena,
-        interns,
-        layout_interner,
-        lib,
-        expectations,
-        expects,
-        &mut memory,
-    )
+    let mut idle_workers: Vec<usize> = (0..worker_count).rev().collect();
+    let mut running: Vec<RunningExpect<'e>> = Vec::with_capacity(worker_count);
+    let mut finished: Vec<Option<(ExpectOutcome<'e>, Vec<u8>)>> =
+        expects.pure.iter().map(|_| None).collect();
+
+    let mut pending = expects.pure.iter().copied().enumerate();
+
+    loop {
+        while let Some(&worker) = idle_workers.last() {
+            let Some((index, expect)) = pending.next() else {
+                break;
+            };
+
+            idle_workers.pop();
+            running.push(RunningExpect::spawn(
+                lib,
+                &mut memories[worker],
+                worker,
+                index,
+                expect,
+            )?);
+        }
+
+        if running.is_empty() {
+            break;
+        }
+
+        let mut any_finished = false;
+        let mut i = 0;
+
+        while i < running.len() {
+            let worker = running[i].worker;
+
+            match running[i].try_finish(&memories[worker], options.timeout)? {
+                None => i += 1,
+                Some(end) => {
+                    let child = running.swap_remove(i);
+
+                    let mut rendered = Vec::new();
+                    let outcome = finish_expect(
+                        &mut rendered,
+                        render_target,
+                        arena,
+                        interns,
+                        layout_interner,
+                        expectations,
+                        memories[worker].ptr,
+                        child.expect,
+                        child.start_time.elapsed(),
+                        options.timeout,
+                        end,
+                    )?;
+
+                    finished[child.index] = Some((outcome, rendered));
+                    idle_workers.push(worker);
+                    any_finished = true;
+                }
+            }
+        }
+
+        if !any_finished {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    let mut outcomes = Vec::with_capacity(finished.len());
+
+    for (outcome, rendered) in finished.into_iter().flatten() {
+        writer.write_all(&rendered)?;
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+/// An expect that is running in a child process.
+struct RunningExpect<'e> {
+    pid: libc::pid_t,
+    /// The read end of a pipe, which the child writes the message of a `crash` to.
+    crash_message: std::fs::File,
+    worker: usize,
+    index: usize,
+    expect: ToplevelExpect<'e>,
+    start_time: Instant,
+}
+
+impl<'e> RunningExpect<'e> {
+    /// Crash messages longer than this are cut off, so the child never blocks on a full pipe.
+    const MAX_CRASH_MESSAGE_LEN: usize = 4096;
+
+    /// The exit codes of a child whose expect failed, and whose expect crashed after it wrote
+    /// the crash message. They are unusual, so we notice when something else ends the child.
+    const FAILED_EXIT_CODE: i32 = 42;
+    const CRASHED_EXIT_CODE: i32 = 43;
+
+    fn spawn(
+        lib: &libloading::Library,
+        memory: &mut ExpectMemory,
+        worker: usize,
+        index: usize,
+        expect: ToplevelExpect<'e>,
+    ) -> std::io::Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let [read_fd, write_fd] = fds;
+
+        memory.reset();
+
+        match unsafe { libc::fork() } {
+            -1 => Err(std::io::Error::last_os_error()),
+            0 => {
+                // we are the child
+                use roc_gen_llvm::try_run_jit_function;
+                use std::io::Write;
+
+                unsafe { libc::close(read_fd) };
+
+                memory.set_shared_buffer(lib);
+
+                let result: Result<(), (String, _)> =
+                    try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+
+                let sequence = ExpectSequence { ptr: memory.ptr };
+
+                let exit_code = match result {
+                    Ok(()) if sequence.count_failures() > 0 => Self::FAILED_EXIT_CODE,
+                    Ok(()) => 0,
+                    Err((message, _roc_panic_tag)) => {
+                        let mut pipe = unsafe { std::fs::File::from_raw_fd(write_fd) };
+                        let len = floor_char_boundary(&message, Self::MAX_CRASH_MESSAGE_LEN);
+                        let _ = pipe.write_all(&message.as_bytes()[..len]);
+
+                        Self::CRASHED_EXIT_CODE
+                    }
+                };
+
+                // Skip destructors and `atexit` handlers, which belong to the parent.
+                unsafe { libc::_exit(exit_code) }
+            }
+            pid => {
+                unsafe { libc::close(write_fd) };
+
+                Ok(Self {
+                    pid,
+                    crash_message: unsafe { std::fs::File::from_raw_fd(read_fd) },
+                    worker,
+                    index,
+                    expect,
+                    start_time: Instant::now(),
+                })
+            }
+        }
+    }
+
+    /// Returns how the expect ended if the child has exited, and stops it if it ran out of time.
+    fn try_finish(
+        &mut self,
+        memory: &ExpectMemory,
+        timeout: Duration,
+    ) -> std::io::Result<Option<ExpectEnd>> {
+        use std::io::Read;
+
+        let mut status = 0;
+
+        match unsafe { libc::waitpid(self.pid, &mut status, libc::WNOHANG) } {
+            -1 => Err(std::io::Error::last_os_error()),
+            0 if self.start_time.elapsed() < timeout => Ok(None),
+            0 => {
+                unsafe {
+                    libc::kill(self.pid, libc::SIGKILL);
+                    libc::waitpid(self.pid, &mut status, 0);
+                }
+
+                Ok(Some(ExpectEnd::TimedOut))
+            }
+            _ if libc::WIFEXITED(status) => match libc::WEXITSTATUS(status) {
+                0 => Ok(Some(ExpectEnd::Passed)),
+                Self::FAILED_EXIT_CODE => {
+                    let sequence = ExpectSequence { ptr: memory.ptr };
+
+                    Ok(Some(ExpectEnd::Failed {
+                        failure_count: sequence.count_failures(),
+                    }))
+                }
+                Self::CRASHED_EXIT_CODE => {
+                    let mut message = String::new();
+                    self.crash_message.read_to_string(&mut message)?;
+
+                    Ok(Some(ExpectEnd::Crashed { message }))
+                }
+                code => Ok(Some(ExpectEnd::Crashed {
+                    message: format!("The process running it exited with code {code}."),
+                })),
+            },
+            _ if libc::WIFSIGNALED(status) => Ok(Some(ExpectEnd::Crashed {
+                message: format!(
+                    "The process running it was stopped by signal {}.",
+                    libc::WTERMSIG(status)
+                ),
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn floor_char_boundary(string: &str, index: usize) -> usize {
+    (0..=index.min(string.len()))
+        .rev()
+        .find(|i| string.is_char_boundary(*i))
+        .unwrap_or(0)
 }
 
 #[allow(clippy::too_many_arguments)]
-pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
+pub(crate) fn run_expects_with_memory<'a, 'e, W: std::io::Write>(
     writer: &mut W,
     render_target: RenderTarget,
     arena: &'a Bump,
@@ -149,16 +404,15 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     layout_interner: &GlobalLayoutInterner<'a>,
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
-    expects: ExpectFunctions<'_>,
+    expects: ExpectFunctions<'e>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
+) -> std::io::Result<Vec<ExpectOutcome<'e>>> {
+    let mut outcomes = Vec::with_capacity(expects.pure.len());
 
     memory.set_shared_buffer(lib);
 
     for expect in expects.pure {
-        let result = run_expect_pure(
+        let outcome = run_expect_pure(
             writer,
             render_target,
             arena,
@@ -170,17 +424,14 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        outcomes.push(outcome);
     }
 
-    Ok((failed, passed))
+    Ok(outcomes)
 }
 
 #[allow(clippy::too_many_arguments)]
-fn run_expect_pure<'a, W: std::io::Write>(
+fn run_expect_pure<'a, 'e, W: std::io::Write>(
     writer: &mut W,
     render_target: RenderTarget,
     arena: &'a Bump,
@@ -189,35 +440,146 @@ fn run_expect_pure<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     shared_memory: &mut ExpectMemory,
-    expect: ToplevelExpect<'_>,
-) -> std::io::Result<bool> {
+    expect: ToplevelExpect<'e>,
+) -> std::io::Result<ExpectOutcome<'e>> {
     use roc_gen_llvm::try_run_jit_function;
 
     let sequence = ExpectSequence::new(shared_memory.ptr.cast());
 
+    let start_time = Instant::now();
     let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+    let duration = start_time.elapsed();
+
+    let end = match result {
+        Err((message, _roc_panic_tag)) => ExpectEnd::Crashed { message },
+        Ok(()) if sequence.count_failures() > 0 => ExpectEnd::Failed {
+            failure_count: sequence.count_failures(),
+        },
+        Ok(()) => ExpectEnd::Passed,
+    };
+
+    finish_expect(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        shared_memory.ptr,
+        expect,
+        duration,
+        Duration::MAX,
+        end,
+    )
+}
+
+/// Renders the failures of an expect that ended, to `writer` and as plain text.
+#[allow(clippy::too_many_arguments)]
+fn finish_expect<'a, 'e, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    shared_memory_ptr: *const u8,
+    expect: ToplevelExpect<'e>,
+    duration: Duration,
+    timeout: Duration,
+    end: ExpectEnd,
+) -> std::io::Result<ExpectOutcome<'e>> {
+    if let ExpectEnd::Passed = end {
+        return Ok(ExpectOutcome {
+            expect,
+            duration,
+            failure: None,
+        });
+    }
+
+    let module_id = expect.symbol.module_id();
+    let data = expectations.get_mut(&module_id).unwrap();
 
-    let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
+    let path = &data.path;
+    let filename = data.path.to_owned();
+    let source = std::fs::read_to_string(path).unwrap();
 
-    if result.is_err() || sequence.count_failures() > 0 {
-        let module_id = expect.symbol.module_id();
-        let data = expectations.get_mut(&module_id).unwrap();
+    let renderer = Renderer::new(
+        arena,
+        interns,
+        render_target,
+        module_id,
+        filename.clone(),
+        &source,
+    );
 
-        let path = &data.path;
-        let filename = data.path.to_owned();
-        let source = std::fs::read_to_string(path).unwrap();
+    render_expect_end(
+        writer,
+        &renderer,
+        arena,
+        expect,
+        expectations,
+        interns,
+        layout_interner,
+        shared_memory_ptr,
+        timeout,
+        &end,
+    )?;
 
-        let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+    writeln!(writer)?;
 
-        if let Err((roc_panic_message, _roc_panic_tag)) = result {
-            renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
+    // Render the failure again without colors, for reports read by other tools.
+    let plain_renderer = Renderer::new(
+        arena,
+        interns,
+        RenderTarget::Generic,
+        module_id,
+        filename,
+        &source,
+    );
+    let mut plain_text = Vec::new();
+
+    render_expect_end(
+        &mut plain_text,
+        &plain_renderer,
+        arena,
+        expect,
+        expectations,
+        interns,
+        layout_interner,
+        shared_memory_ptr,
+        timeout,
+        &end,
+    )?;
+
+    Ok(ExpectOutcome {
+        expect,
+        duration,
+        failure: Some(String::from_utf8_lossy(&plain_text).into_owned()),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_expect_end<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expect: ToplevelExpect,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    shared_memory_ptr: *const u8,
+    timeout: Duration,
+    end: &ExpectEnd,
+) -> std::io::Result<()> {
+    match end {
+        ExpectEnd::Passed => {}
+        ExpectEnd::Failed { failure_count } => {
             let mut offset = ExpectSequence::START_OFFSET;
 
-            for _ in 0..sequence.count_failures() {
+            for _ in 0..*failure_count {
                 offset = render_expect_failure(
                     writer,
-                    &renderer,
+                    renderer,
                     arena,
                     Some(expect),
                     expectations,
@@ -228,13 +590,15 @@ fn run_expect_pure<'a, W: std::io::Write>(
                 )?;
             }
         }
-
-        writeln!(writer)?;
-
-        Ok(false)
-    } else {
-        Ok(true)
+        ExpectEnd::Crashed { message } => {
+            renderer.render_panic(writer, message, expect.region)?;
+        }
+        ExpectEnd::TimedOut => {
+            renderer.render_timeout(writer, timeout, expect.region)?;
+        }
     }
+
+    Ok(())
 }
 
 pub fn render_expects_in_memory<'a>(
@@ -496,6 +860,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: DebugSources::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -559,3 +924,88 @@ pub fn expect_mono_module_to_dylib<'a>(
     llvm_module_to_dylib(env.module, target, opt_level)
         .map(|dy_lib| (dy_lib, modules_expects, layout_interner))
 }
+
+/// Like [expect_mono_module_to_dylib], but builds the expects with the dev backend.
+pub fn expect_mono_module_to_dylib_dev<'a>(
+    arena: &'a Bump,
+    target: Target,
+    loaded: MonomorphizedModule<'a>,
+) -> Result<
+    (
+        libloading::Library,
+        MutMap<ModuleId, ExpectFunctions<'a>>,
+        STLayoutInterner<'a>,
+    ),
+    libloading::Error,
+> {
+    let MonomorphizedModule {
+        module_id,
+        toplevel_expects,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        ..
+    } = loaded;
+
+    let env = roc_gen_dev::Env {
+        arena,
+        module_id,
+        exposed_to_host: toplevel_expects
+            .values()
+            .flat_map(|expects| expects.pure.keys().copied())
+            .collect(),
+        lazy_literals: true,
+        mode: roc_gen_dev::AssemblyBackendMode::Expect,
+        module_sources: MutMap::default(),
+        inline_budget: None,
+    };
+
+    let module_object =
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+
+    let mut modules_expects: MutMap<ModuleId, ExpectFunctions> = MutMap::default();
+
+    for (module_id, expects) in toplevel_expects.into_iter() {
+        let expects_pure = BumpVec::from_iter_in(
+            expects
+                .pure
+                .into_iter()
+                .map(|(symbol, region)| ToplevelExpect {
+                    name: arena.alloc_str(&roc_gen_dev::expect_symbol_name(symbol, &interns)),
+                    symbol,
+                    region,
+                }),
+            arena,
+        );
+
+        modules_expects.insert(module_id, ExpectFunctions { pure: expects_pure });
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let app_o_file = dir.path().join("app.o");
+
+    let module_out = module_object
+        .write()
+        .expect("failed to build output object");
+    std::fs::write(&app_o_file, module_out).expect("failed to write object to file");
+
+    let builtins_host_tempfile =
+        roc_bitcode::host_tempfile().expect("failed to write host builtins object to tempfile");
+
+    let (mut child, dylib_path) = roc_build::link::link(
+        target,
+        app_o_file.clone(),
+        &[
+            app_o_file.to_str().unwrap(),
+            builtins_host_tempfile.path().to_str().unwrap(),
+        ],
+        roc_build::link::LinkType::Dylib,
+    )
+    .expect("failed to link dynamic library");
+
+    child.wait().unwrap();
+
+    let lib = unsafe { libloading::Library::new(dylib_path) }?;
+
+    Ok((lib, modules_expects, layout_interner))
+}
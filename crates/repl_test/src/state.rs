@@ -1,9 +1,9 @@
 use bumpalo::Bump;
 use indoc::indoc;
 use roc_repl_cli::{evaluate, ReplHelper};
-use roc_repl_ui::is_incomplete;
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_reporting::report::DEFAULT_PALETTE;
+use roc_repl_ui::{format_output, is_incomplete};
+use roc_reporting::report::{ANSI_STYLE_CODES, DEFAULT_PALETTE};
 use rustyline::Editor;
 use target_lexicon::Triple;
 
@@ -145,6 +145,81 @@ fn standalone_annotation() {
     assert!(matches!(action, ReplAction::Nothing));
 }
 
+#[test]
+fn type_without_evaluating() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    command(":type x + 1.5", &mut state, "x + 1.5 : Frac *");
+
+    // Commands that need an argument show how to use them instead
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, ":type", target, DEFAULT_PALETTE);
+    assert!(matches!(action, ReplAction::Help));
+}
+
+#[test]
+fn list_and_reset_defs() {
+    let mut state = ReplState::new();
+
+    command(":defs", &mut state, "There are no definitions yet.");
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("y = \"hi\"", &mut state, "\"hi\" : Str");
+    command(":defs", &mut state, "x = 5\n\ny = \"hi\"");
+
+    command(":reset", &mut state, "Cleared all definitions.");
+    command(":defs", &mut state, "There are no definitions yet.");
+}
+
+#[test]
+fn save_defs_as_module() {
+    let mut state = ReplState::new();
+    let path = std::env::temp_dir().join(format!("ReplSave{}.roc", std::process::id()));
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete(
+        "double = \\n -> n * 2",
+        &mut state,
+        "<function> : Num a -> Num a",
+    );
+
+    command(
+        &format!(":save {}", path.display()),
+        &mut state,
+        &format!("Saved 2 definitions to {}", path.display()),
+    );
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        saved,
+        "module [x, double]\n\nx = 5\n\ndouble = \\n -> n * 2\n"
+    );
+}
+
+/// step the given `:command` and check the output with ANSI escape codes stripped.
+fn command(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, input, target, DEFAULT_PALETTE);
+
+    let output = match action {
+        ReplAction::Type { output, problems } => format_output(ANSI_STYLE_CODES, output, problems),
+        ReplAction::Message(message) => message,
+        _ => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    };
+    let escaped =
+        std::string::String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap();
+
+    assert_eq!(expected, escaped);
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
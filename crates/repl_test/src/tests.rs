@@ -1599,6 +1599,10 @@ fn interpolation_with_nested_interpolation() {
                   - ctrl-v + ctrl-j makes a newline
                   - :q quits
                   - :help shows this text again
+                  - :type <expr> shows the type of an expression without evaluating it
+                  - :load <file.roc> imports a module and everything it exposes
+                  - :defs lists your definitions, and :reset clears them
+                  - :save <file.roc> saves your definitions as a module
             "#
         ),
         // TODO figure out why the tests prints the repl help text at the end, but only after syntax errors or something?
@@ -48,6 +48,38 @@ pub const TIPS: &str = concatcp!(
             ":help",
             END_COL,
             " shows this text again\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":type <expr>",
+            END_COL,
+            " shows the type of an expression without evaluating it\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":load <file.roc>",
+            END_COL,
+            " imports a module and everything it exposes\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":defs",
+            END_COL,
+            " lists your definitions, and ",
+            GREEN,
+            ":reset",
+            END_COL,
+            " clears them\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":save <file.roc>",
+            END_COL,
+            " saves your definitions as a module\n",
         )
     }
 );
@@ -84,9 +116,15 @@ pub fn is_incomplete(input: &str) -> bool {
                 false
             }
         }
-        ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit | ParseOutcome::SyntaxErr => {
-            false
-        }
+        ParseOutcome::Empty
+        | ParseOutcome::Help
+        | ParseOutcome::Exit
+        | ParseOutcome::SyntaxErr
+        | ParseOutcome::Type(_)
+        | ParseOutcome::Load(_)
+        | ParseOutcome::Save(_)
+        | ParseOutcome::Defs
+        | ParseOutcome::Reset => false,
     }
 }
 
@@ -1,16 +1,19 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::{fs, io};
 
 use bumpalo::Bump;
 use roc_collections::MutSet;
 use roc_load::MonomorphizedModule;
-use roc_parse::ast::{Defs, Expr, Pattern, StrLiteral, TypeDef, TypeHeader, ValueDef};
+use roc_parse::ast::{
+    Defs, Expr, Header, Pattern, SpacesBefore, StrLiteral, TypeDef, TypeHeader, ValueDef,
+};
 use roc_parse::expr::parse_repl_defs_and_optional_expr;
+use roc_parse::header::parse_header;
 use roc_parse::parser::EWhen;
 use roc_parse::parser::{EClosure, EExpr, EPattern};
 use roc_parse::state::State;
 use roc_region::all::Loc;
-use roc_repl_eval::gen::{compile_to_mono, Problems};
+use roc_repl_eval::gen::{compile_to_mono, expr_type_str, Problems, ReplOutput};
 use roc_reporting::report::Palette;
 use roc_target::Target;
 
@@ -38,6 +41,13 @@ pub enum ReplAction<'a> {
         opt_mono: Option<MonomorphizedModule<'a>>,
         problems: Problems,
     },
+    /// `:type` shows the type of an expression without evaluating it.
+    Type {
+        output: Option<ReplOutput>,
+        problems: Problems,
+    },
+    /// Text to show as-is, e.g. what `:defs` lists.
+    Message(String),
     Exit,
     Help,
     FileProblem {
@@ -66,6 +76,16 @@ impl ReplState {
         let src: &str = match parse_src(arena, line) {
             ParseOutcome::Empty | ParseOutcome::Help => return ReplAction::Help,
             ParseOutcome::Exit => return ReplAction::Exit,
+            ParseOutcome::Type(expr) => return self.type_of(arena, expr, target, palette),
+            ParseOutcome::Load(filename) => return self.load(arena, filename),
+            ParseOutcome::Save(filename) => return self.save(filename),
+            ParseOutcome::Defs => return ReplAction::Message(self.defs_list()),
+            ParseOutcome::Reset => {
+                self.past_defs.clear();
+                self.past_def_idents.clear();
+
+                return ReplAction::Message("Cleared all definitions.".to_string());
+            }
             ParseOutcome::Incomplete | ParseOutcome::SyntaxErr => {
                 pending_past_def = None;
 
@@ -257,16 +277,8 @@ impl ReplState {
             }
         };
 
-        let (opt_mono, problems) = compile_to_mono(
-            arena,
-            self.past_defs.iter().map(|past_def| match past_def {
-                PastDef::Def { ident: _, src } => src.as_str(),
-                PastDef::Import(src) => src.as_str(),
-            }),
-            src,
-            target,
-            palette,
-        );
+        let (opt_mono, problems) =
+            compile_to_mono(arena, self.past_defs_src(), src, target, palette);
 
         if let Some((ident, src)) = pending_past_def {
             self.add_past_def(ident, src);
@@ -275,6 +287,173 @@ impl ReplState {
         ReplAction::Eval { opt_mono, problems }
     }
 
+    fn past_defs_src(&self) -> impl Iterator<Item = &str> {
+        self.past_defs.iter().map(|past_def| match past_def {
+            PastDef::Def { ident: _, src } => src.as_str(),
+            PastDef::Import(src) => src.as_str(),
+        })
+    }
+
+    fn type_of<'a>(
+        &self,
+        arena: &'a Bump,
+        expr: &str,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let (opt_mono, problems) =
+            compile_to_mono(arena, self.past_defs_src(), expr, target, palette);
+
+        let output = opt_mono.and_then(|mut mono| {
+            Some(ReplOutput {
+                expr: expr.to_string(),
+                expr_type: expr_type_str(&mut mono)?,
+            })
+        });
+
+        ReplAction::Type { output, problems }
+    }
+
+    /// Imports the module in `filename`, exposing everything it exposes.
+    fn load<'a>(&mut self, arena: &'a Bump, filename: &str) -> ReplAction<'a> {
+        let filename = PathBuf::from(filename);
+
+        let src = match fs::read_to_string(&filename) {
+            Ok(src) => arena.alloc_str(&src),
+            Err(err) => {
+                return ReplAction::FileProblem {
+                    filename,
+                    error: err.kind(),
+                }
+            }
+        };
+
+        let Some(module_name) = module_name_from_path(&filename) else {
+            return ReplAction::Message(format!(
+                "I can't load {}. Modules have to be in this directory or one inside it, \
+                and each part of the path has to be a module name, like Json/Decode.roc",
+                filename.display()
+            ));
+        };
+
+        let exposes: Vec<&str> = match parse_header(arena, State::new(src.as_bytes())) {
+            Ok((
+                SpacesBefore {
+                    item: Header::Module(header),
+                    ..
+                },
+                _,
+            )) => {
+                if header.params.is_some() {
+                    return ReplAction::Message(format!(
+                        "{module_name} takes module params, so it has to be imported with `import {module_name} {{ ... }}` instead."
+                    ));
+                }
+
+                header
+                    .exposes
+                    .iter()
+                    .map(|name| (*name.value.item()).into())
+                    .collect()
+            }
+            Ok(_) => {
+                return ReplAction::Message(format!(
+                    "{} isn't a module, so I can't load it. Only files that start with a `module` header can be loaded.",
+                    filename.display()
+                ));
+            }
+            Err(_) => {
+                return ReplAction::Message(format!(
+                    "I can't load {} because its header has a syntax error.",
+                    filename.display()
+                ));
+            }
+        };
+
+        let import = if exposes.is_empty() {
+            format!("import {module_name}")
+        } else {
+            format!("import {module_name} exposing [{}]", exposes.join(", "))
+        };
+
+        self.past_defs.push(PastDef::Import(import.clone()));
+
+        ReplAction::Message(import)
+    }
+
+    /// Writes the past defs to `filename` as a module that exposes all of them.
+    fn save<'a>(&self, filename: &str) -> ReplAction<'a> {
+        let filename = PathBuf::from(filename);
+        let (src, def_count) = self.module_src();
+
+        match fs::write(&filename, src) {
+            Ok(()) => ReplAction::Message(format!(
+                "Saved {def_count} {} to {}",
+                if def_count == 1 {
+                    "definition"
+                } else {
+                    "definitions"
+                },
+                filename.display()
+            )),
+            Err(err) => ReplAction::FileProblem {
+                filename,
+                error: err.kind(),
+            },
+        }
+    }
+
+    fn defs_list(&self) -> String {
+        if self.past_defs.is_empty() {
+            "There are no definitions yet.".to_string()
+        } else {
+            self.past_defs_src().collect::<Vec<_>>().join("\n\n")
+        }
+    }
+
+    /// The source of a module with the imports and defs entered so far, and how many defs it has.
+    fn module_src(&self) -> (String, usize) {
+        let defs: Vec<(&str, &str)> = self
+            .past_defs
+            .iter()
+            .filter_map(|past_def| match past_def {
+                PastDef::Def { ident, src } => Some((ident.as_str(), src.as_str())),
+                PastDef::Import(_) => None,
+            })
+            .collect();
+
+        // A standalone annotation and its body are separate defs with the same name
+        let mut exposes: Vec<&str> = Vec::with_capacity(defs.len());
+
+        for (ident, _) in defs.iter() {
+            if !exposes.contains(ident) {
+                exposes.push(ident);
+            }
+        }
+
+        let mut sections = vec![format!("module [{}]", exposes.join(", "))];
+
+        let imports: Vec<&str> = self
+            .past_defs
+            .iter()
+            .filter_map(|past_def| match past_def {
+                PastDef::Import(src) => Some(src.as_str()),
+                PastDef::Def { .. } => None,
+            })
+            .collect();
+
+        if !imports.is_empty() {
+            sections.push(imports.join("\n"));
+        }
+
+        sections.extend(defs.iter().map(|(_, src)| src.to_string()));
+
+        let mut src = sections.join("\n\n");
+        src.push('\n');
+
+        (src, defs.len())
+    }
+
     fn add_past_def(&mut self, ident: String, src: String) {
         let existing_idents = &mut self.past_def_idents;
 
@@ -292,6 +471,40 @@ pub enum ParseOutcome<'a> {
     Empty,
     Help,
     Exit,
+    Type(&'a str),
+    Load(&'a str),
+    Save(&'a str),
+    Defs,
+    Reset,
+}
+
+/// The module name that `import` uses for the file at `path`, e.g. `Json.Decode` for
+/// `Json/Decode.roc`, since the REPL looks for imported modules in the current directory.
+fn module_name_from_path(path: &Path) -> Option<String> {
+    if path.extension()? != "roc" {
+        return None;
+    }
+
+    let without_extension = path.with_extension("");
+    let parts = without_extension
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<&str>>>()?;
+
+    let is_module_name = |part: &&str| {
+        part.starts_with(|c: char| c.is_ascii_uppercase())
+            && part.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+
+    if parts.is_empty() || !parts.iter().all(is_module_name) {
+        return None;
+    }
+
+    Some(parts.join("."))
 }
 
 /// Special case some syntax errors to allow for multi-line inputs
@@ -307,7 +520,24 @@ fn parse_outcome_for_error(e: EExpr<'_>) -> ParseOutcome<'_> {
 }
 
 pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
-    match line.trim().to_lowercase().as_str() {
+    let trimmed = line.trim();
+    let (command, arg) = match trimmed.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (trimmed, ""),
+    };
+
+    match (command.to_lowercase().as_str(), arg) {
+        // These need an argument, so show how to use them
+        (":type" | ":load" | ":save", "") => return ParseOutcome::Help,
+        (":type", expr) => return ParseOutcome::Type(expr),
+        (":load", filename) => return ParseOutcome::Load(filename),
+        (":save", filename) => return ParseOutcome::Save(filename),
+        (":defs", "") => return ParseOutcome::Defs,
+        (":reset", "") => return ParseOutcome::Reset,
+        _ => {}
+    }
+
+    match trimmed.to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
         // These are all common things beginners try.
@@ -207,6 +207,8 @@ pub async fn entrypoint_from_js(src: String) -> String {
             "The web version of the REPL cannot import files... for now!".to_string()
         }
         ReplAction::Nothing => String::new(),
+        ReplAction::Message(message) => message,
+        ReplAction::Type { output, problems } => format_output(HTML_STYLE_CODES, output, problems),
         ReplAction::Eval { opt_mono, problems } => {
             let opt_output = match opt_mono {
                 Some(mono) => eval_wasm(arena, target, mono).await,
@@ -254,6 +256,8 @@ async fn eval_wasm<'a>(
             arena,
             module_id,
             stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+            runs_expects: false,
+            inline_budget: None,
             exposed_to_host: exposed_to_host
                 .top_level_values
                 .keys()
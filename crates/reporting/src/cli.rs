@@ -6,7 +6,9 @@ use roc_problem::can::Problem;
 use roc_region::all::LineInfo;
 use roc_solve_problem::TypeError;
 
-use crate::report::ANSI_STYLE_CODES;
+use crate::machine_readable::{render_diagnostics, Diagnostic};
+use crate::report::{RenderTarget, ANSI_STYLE_CODES};
+use crate::warnings::WarningConfig;
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
@@ -29,11 +31,16 @@ impl Problems {
 
     // prints e.g. `1 error and 0 warnings found in 63 ms.`
     pub fn print_error_warning_count(&self, total_time: std::time::Duration) {
+        print!("{}", self.error_warning_count(total_time));
+    }
+
+    // e.g. `1 error and 0 warnings found in 63 ms`, for printing somewhere else
+    pub fn error_warning_count(&self, total_time: std::time::Duration) -> String {
         const GREEN: &str = ANSI_STYLE_CODES.green;
         const YELLOW: &str = ANSI_STYLE_CODES.yellow;
         const RESET: &str = ANSI_STYLE_CODES.reset;
 
-        print!(
+        format!(
             "{}{}{} {} and {}{}{} {} found in {} ms",
             match self.errors {
                 0 => GREEN,
@@ -56,15 +63,20 @@ impl Problems {
                 _ => "warnings",
             },
             total_time.as_millis()
-        );
+        )
     }
 }
 
+/// Prints every problem in `can_problems` and `type_problems`. Machine-readable targets print
+/// one document with all of them, while the others only print warnings when there are no errors.
+/// Warnings are reported at the level `warnings` gives them, and not at all when it allows them.
 pub fn report_problems(
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    render: RenderTarget,
+    warnings_config: &WarningConfig,
 ) -> Problems {
     use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
     use roc_problem::Severity::*;
@@ -84,7 +96,9 @@ pub fn report_problems(
     // never need to re-allocate either the warnings or the errors vec!
     let mut warnings = Vec::with_capacity(total_problems);
     let mut errors = Vec::with_capacity(total_problems);
+    let mut diagnostics = Vec::new();
     let mut fatally_errored = false;
+    let mut allowed = 0;
 
     for (home, (module_path, src)) in sources.iter() {
         let mut src_lines: Vec<&str> = Vec::new();
@@ -99,11 +113,30 @@ pub fn report_problems(
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
-            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+            let region = problem.region();
+            let code = problem.code();
+
+            if let Some(mut report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                if report.severity == Warning {
+                    match warnings_config.warning_severity(code, src, region) {
+                        Some(severity) => report.severity = severity,
+                        None => {
+                            allowed += 1;
+                            continue;
+                        }
+                    }
+                }
+
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                if render.is_machine_readable() {
+                    diagnostics.push(Diagnostic::from_report(
+                        report, code, region, &lines, &alloc,
+                    ));
+                } else {
+                    report.render_color_terminal(&mut buf, &alloc, &palette);
+                }
 
                 match severity {
                     Warning => {
@@ -137,11 +170,30 @@ pub fn report_problems(
         ordered.extend(shadowing_errs);
 
         for problem in ordered.into_iter() {
-            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+            let region = problem.region();
+            let code = problem.code();
+            let mut report = can_problem(&alloc, &lines, module_path.clone(), problem);
+
+            if report.severity == Warning {
+                match warnings_config.warning_severity(code, src, region) {
+                    Some(severity) => report.severity = severity,
+                    None => {
+                        allowed += 1;
+                        continue;
+                    }
+                }
+            }
+
             let severity = report.severity;
             let mut buf = String::new();
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            if render.is_machine_readable() {
+                diagnostics.push(Diagnostic::from_report(
+                    report, code, region, &lines, &alloc,
+                ));
+            } else {
+                report.render_color_terminal(&mut buf, &alloc, &palette);
+            }
 
             match severity {
                 Warning => {
@@ -159,12 +211,17 @@ pub fn report_problems(
     }
 
     debug_assert!(can_problems.is_empty() && type_problems.is_empty(), "After reporting problems, there were {:?} can_problems and {:?} type_problems that could not be reported because they did not have corresponding entries in `sources`.", can_problems.len(), type_problems.len());
-    debug_assert_eq!(errors.len() + warnings.len(), total_problems);
+    debug_assert_eq!(errors.len() + warnings.len() + allowed, total_problems);
 
     let problems_reported;
 
-    // Only print warnings if there are no errors
-    if errors.is_empty() {
+    if let Some(document) = render_diagnostics(render, &diagnostics) {
+        println!("{document}");
+
+        // Nothing else can be printed, or the output would no longer be a valid document.
+        problems_reported = 0;
+    } else if errors.is_empty() {
+        // Only print warnings if there are no errors
         problems_reported = warnings.len();
 
         for warning in warnings.iter() {
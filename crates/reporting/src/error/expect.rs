@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use bumpalo::Bump;
 use roc_fmt::MigrationFlags;
@@ -263,4 +264,53 @@ impl<'a> Renderer<'a> {
 
         write!(writer, "{buf}")
     }
+
+    pub fn render_timeout<W>(
+        &self,
+        writer: &mut W,
+        timeout: Duration,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+        let severity = Severity::RuntimeError;
+
+        let seconds = timeout.as_secs();
+        let plural = if seconds == 1 { "" } else { "s" };
+
+        let doc = self.alloc.stack([
+            self.alloc.text(format!(
+                "This expectation was still running after {seconds} second{plural}:"
+            )),
+            self.alloc.region(line_col_region, severity),
+            self.alloc.concat([
+                self.alloc.reflow("It might be stuck in an infinite loop. You can give expectations more time with "),
+                self.alloc.parser_suggestion("--timeout"),
+                self.alloc.reflow("."),
+            ]),
+        ]);
+
+        let report = Report {
+            title: "EXPECT TIMED OUT".into(),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
 }
@@ -5,4 +5,6 @@
 
 pub mod cli;
 pub mod error;
+pub mod machine_readable;
 pub mod report;
+pub mod warnings;
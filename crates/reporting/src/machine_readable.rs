@@ -0,0 +1,282 @@
+//! Problems in formats meant for other tools rather than people, like JSON and SARIF.
+use std::path::{Path, PathBuf};
+
+use roc_problem::Severity;
+use roc_region::all::{LineInfo, Region};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::report::{RenderTarget, Report, RocDocAllocator};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A single problem, with everything a tool needs to show it somewhere else.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    /// What kind of problem this is, e.g. `type-mismatch`. See [roc_problem::can::Problem::code].
+    pub code: String,
+    pub file: Option<PathBuf>,
+    pub region: Option<DiagnosticRegion>,
+    pub title: String,
+    /// The report as it would be printed to a terminal without colors.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// Lines and columns start at 1, and the end column is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DiagnosticRegion {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl From<Severity> for DiagnosticSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::RuntimeError | Severity::Fatal => DiagnosticSeverity::Error,
+            Severity::Warning => DiagnosticSeverity::Warning,
+        }
+    }
+}
+
+impl DiagnosticRegion {
+    pub fn new(region: Region, line_info: &LineInfo) -> Self {
+        let region = line_info.convert_region(region);
+
+        Self {
+            start_line: region.start.line + 1,
+            start_column: region.start.column + 1,
+            end_line: region.end.line + 1,
+            end_column: region.end.column + 1,
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn from_report<'b>(
+        report: Report<'b>,
+        code: &str,
+        region: Option<Region>,
+        line_info: &LineInfo,
+        alloc: &'b RocDocAllocator<'b>,
+    ) -> Self {
+        let severity = report.severity.into();
+        let code = code.to_string();
+        let file = Some(report.filename.clone());
+        let title = report.title.clone();
+
+        let mut message = String::new();
+        report.render_ci(&mut message, alloc);
+
+        Diagnostic {
+            severity,
+            code,
+            file,
+            region: region.map(|region| DiagnosticRegion::new(region, line_info)),
+            title,
+            message,
+        }
+    }
+
+    /// For problems that were already rendered while loading, like a file that failed to parse.
+    pub fn from_loading_problem(message: String) -> Self {
+        Diagnostic {
+            severity: DiagnosticSeverity::Error,
+            code: "loading-problem".to_string(),
+            file: None,
+            region: None,
+            title: "LOADING PROBLEM".to_string(),
+            message,
+        }
+    }
+}
+
+/// Renders `diagnostics` as a single document in the format of a machine-readable target.
+/// Returns `None` for the targets that are meant for people.
+pub fn render_diagnostics(target: RenderTarget, diagnostics: &[Diagnostic]) -> Option<String> {
+    let value = match target {
+        RenderTarget::Json => json!(diagnostics),
+        RenderTarget::Sarif => to_sarif(diagnostics),
+        RenderTarget::ColorTerminal | RenderTarget::Generic | RenderTarget::LanguageServer => {
+            return None
+        }
+    };
+
+    Some(serde_json::to_string_pretty(&value).expect("diagnostics are always valid JSON"))
+}
+
+fn to_sarif(diagnostics: &[Diagnostic]) -> Value {
+    let mut rules: Vec<Value> = Vec::new();
+    let mut rule_ids: Vec<&str> = Vec::new();
+
+    for diagnostic in diagnostics {
+        if !rule_ids.contains(&diagnostic.code.as_str()) {
+            rule_ids.push(&diagnostic.code);
+            rules.push(json!({
+                "id": diagnostic.code,
+                "shortDescription": { "text": diagnostic.title },
+            }));
+        }
+    }
+
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let level = match diagnostic.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+            };
+
+            let locations = match &diagnostic.file {
+                Some(file) => {
+                    let mut physical_location = json!({
+                        "artifactLocation": { "uri": artifact_uri(file) },
+                    });
+
+                    if let Some(region) = diagnostic.region {
+                        physical_location["region"] = json!({
+                            "startLine": region.start_line,
+                            "startColumn": region.start_column,
+                            "endLine": region.end_line,
+                            "endColumn": region.end_column,
+                        });
+                    }
+
+                    vec![json!({ "physicalLocation": physical_location })]
+                }
+                None => vec![],
+            };
+
+            json!({
+                "ruleId": diagnostic.code,
+                "level": level,
+                "message": { "text": diagnostic.message },
+                "locations": locations,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "roc",
+                    "informationUri": "https://www.roc-lang.org",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// SARIF consumers like GitHub code scanning expect paths relative to the repository root,
+/// which we assume is the current directory.
+fn artifact_uri(path: &Path) -> String {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(severity: DiagnosticSeverity, code: &str, title: &str) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: code.to_string(),
+            file: Some(PathBuf::from("main.roc")),
+            region: Some(DiagnosticRegion {
+                start_line: 3,
+                start_column: 5,
+                end_line: 3,
+                end_column: 9,
+            }),
+            title: title.to_string(),
+            message: "Something is wrong.".to_string(),
+        }
+    }
+
+    #[test]
+    fn json_output() {
+        let diagnostics = [diagnostic(
+            DiagnosticSeverity::Error,
+            "type-mismatch",
+            "TYPE MISMATCH",
+        )];
+        let rendered = render_diagnostics(RenderTarget::Json, &diagnostics).unwrap();
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(
+            value,
+            json!([{
+                "severity": "error",
+                "code": "type-mismatch",
+                "file": "main.roc",
+                "region": {
+                    "start_line": 3,
+                    "start_column": 5,
+                    "end_line": 3,
+                    "end_column": 9,
+                },
+                "title": "TYPE MISMATCH",
+                "message": "Something is wrong.",
+            }])
+        );
+    }
+
+    #[test]
+    fn sarif_output() {
+        let diagnostics = [
+            diagnostic(DiagnosticSeverity::Error, "type-mismatch", "TYPE MISMATCH"),
+            diagnostic(
+                DiagnosticSeverity::Warning,
+                "unused-definition",
+                "UNUSED DEFINITION",
+            ),
+            diagnostic(DiagnosticSeverity::Error, "type-mismatch", "TYPE MISMATCH"),
+        ];
+        let rendered = render_diagnostics(RenderTarget::Sarif, &diagnostics).unwrap();
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+
+        let run = &value["runs"][0];
+        let rule_ids = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(rule_ids, ["type-mismatch", "unused-definition"]);
+
+        let result = &run["results"][1];
+        assert_eq!(result["ruleId"], "unused-definition");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "main.roc" },
+                "region": { "startLine": 3, "startColumn": 5, "endLine": 3, "endColumn": 9 },
+            })
+        );
+    }
+
+    #[test]
+    fn text_targets_are_not_machine_readable() {
+        assert_eq!(render_diagnostics(RenderTarget::Generic, &[]), None);
+    }
+}
@@ -111,11 +111,23 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
     header
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RenderTarget {
     ColorTerminal,
     Generic,
     LanguageServer,
+    /// A JSON array of problems, for CI and other tools.
+    Json,
+    /// A SARIF log, e.g. for GitHub code scanning.
+    Sarif,
+}
+
+impl RenderTarget {
+    /// Machine-readable targets collect every problem into one document, instead of printing
+    /// reports one at a time. The message of each problem is rendered like for `Generic`.
+    pub fn is_machine_readable(self) -> bool {
+        matches!(self, RenderTarget::Json | RenderTarget::Sarif)
+    }
 }
 
 /// A textual report.
@@ -135,7 +147,9 @@ impl<'b> Report<'b> {
         palette: &'b Palette,
     ) {
         match target {
-            RenderTarget::Generic => self.render_ci(buf, alloc),
+            RenderTarget::Generic | RenderTarget::Json | RenderTarget::Sarif => {
+                self.render_ci(buf, alloc)
+            }
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
             RenderTarget::LanguageServer => self.render_language_server(buf, alloc),
         }
@@ -1688,6 +1702,33 @@ pub fn to_https_problem_report<'b>(
                 severity: Severity::Fatal,
             }
         }
+        Problem::NotVendored(vendor_dir) => {
+            let doc = alloc.stack([
+                alloc.reflow(r"This project uses the package at this URL:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.reflow(r"I'm running offline, so I can't download it. It isn't in the cache, and I couldn't find a tarball of it in this vendor directory either:"),
+                alloc
+                    .string(vendor_dir.display().to_string())
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Run "),
+                    alloc.keyword("roc vendor"),
+                    alloc.reflow(r" on a machine that can download packages, to put every package this project uses in its vendor directory."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "PACKAGE NOT AVAILABLE OFFLINE".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
     }
 }
 
@@ -0,0 +1,444 @@
+//! Which warnings a project wants to see, promote to errors, or silence.
+//!
+//! Warnings are named by their problem code, e.g. `unused-import` for "UNUSED IMPORT". Their
+//! levels come from `# roc-warnings:` comments in the root module, like
+//!
+//! ```text
+//! # roc-warnings: unused-import = deny, unused-definition = allow
+//! app [main] { pf: platform "..." }
+//! ```
+//!
+//! and from a [WARNINGS_FILENAME] file next to the root module, with one `name = level` per line.
+//! The file wins when both set the level of the same warning.
+//!
+//! Warnings can also be silenced for a single def, by putting a `# roc-allow: name, name`
+//! comment on the line above the def, or above any line that contains the problem.
+use std::path::Path;
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_parse::header::parse_header;
+use roc_parse::state::State;
+use roc_problem::can::Problem;
+use roc_problem::Severity;
+use roc_region::all::Region;
+use roc_solve_problem::TypeError;
+
+/// The file next to the root module that sets warning levels for the whole project.
+pub const WARNINGS_FILENAME: &str = "roc.warnings";
+
+const LEVELS_DIRECTIVE: &str = "roc-warnings:";
+const ALLOW_DIRECTIVE: &str = "roc-allow:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningLevel {
+    /// Don't report the warning at all.
+    Allow,
+    /// Report the warning as a warning.
+    Warn,
+    /// Report the warning as an error.
+    Deny,
+}
+
+impl WarningLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level {
+            "allow" => Some(WarningLevel::Allow),
+            "warn" => Some(WarningLevel::Warn),
+            "deny" => Some(WarningLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WarningConfig {
+    levels: MutMap<String, WarningLevel>,
+    /// Report every warning that isn't allowed as an error, like `--deny-warnings` does.
+    pub deny_warnings: bool,
+}
+
+impl WarningConfig {
+    /// Reads the warning levels of the project whose root module is at `root_module`.
+    /// The error describes the first invalid setting.
+    pub fn load(root_module: &Path, deny_warnings: bool) -> Result<Self, String> {
+        let mut config = WarningConfig {
+            levels: MutMap::default(),
+            deny_warnings,
+        };
+
+        // If the root module can't be read, loading it will report that.
+        if let Ok(src) = std::fs::read_to_string(root_module) {
+            config.add_header_levels(&src).map_err(|err| {
+                format!(
+                    "{}, line {}: {}",
+                    root_module.display(),
+                    err.line,
+                    err.message
+                )
+            })?;
+        }
+
+        let sidecar = root_module.with_file_name(WARNINGS_FILENAME);
+
+        match std::fs::read_to_string(&sidecar) {
+            Ok(src) => config.add_file_levels(&src).map_err(|err| {
+                format!("{}, line {}: {}", sidecar.display(), err.line, err.message)
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(format!("{}: {err}", sidecar.display())),
+        }
+
+        Ok(config)
+    }
+
+    pub fn set_level(&mut self, name: &str, level: WarningLevel) {
+        self.levels.insert(name.to_string(), level);
+    }
+
+    /// The level of the warning called `name`, before `--deny-warnings` is applied.
+    pub fn level(&self, name: &str) -> WarningLevel {
+        self.levels.get(name).copied().unwrap_or(WarningLevel::Warn)
+    }
+
+    /// The severity to report the warning called `name` at `region` of `src` with,
+    /// or [None] if it shouldn't be reported.
+    pub fn warning_severity(
+        &self,
+        name: &str,
+        src: &str,
+        region: Option<Region>,
+    ) -> Option<Severity> {
+        if let Some(region) = region {
+            if is_allowed_inline(src, region.start().byte_offset(), name) {
+                return None;
+            }
+        }
+
+        match self.level(name) {
+            WarningLevel::Allow => None,
+            WarningLevel::Warn if !self.deny_warnings => Some(Severity::Warning),
+            WarningLevel::Warn | WarningLevel::Deny => Some(Severity::RuntimeError),
+        }
+    }
+
+    /// Adds the levels of the `# roc-warnings:` comments in the root module's header, i.e. those
+    /// before the end of the header, so comments in the module's defs don't change any levels.
+    fn add_header_levels(&mut self, src: &str) -> Result<(), ConfigError> {
+        let arena = Bump::new();
+
+        // If the header doesn't parse, loading the module will report that.
+        let Ok((_, state)) = parse_header(&arena, State::new(src.as_bytes())) else {
+            return Ok(());
+        };
+
+        let header = &src[..state.pos().offset as usize];
+
+        for (index, line) in header.lines().enumerate() {
+            if let Some(settings) = directive(line, LEVELS_DIRECTIVE) {
+                for setting in settings.split(',').filter(|s| !s.trim().is_empty()) {
+                    self.add_setting(setting)
+                        .map_err(|message| ConfigError::new(index, message))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the levels of a [WARNINGS_FILENAME] file.
+    fn add_file_levels(&mut self, src: &str) -> Result<(), ConfigError> {
+        for (index, line) in src.lines().enumerate() {
+            let line = line.trim();
+
+            if !line.is_empty() && !line.starts_with('#') {
+                self.add_setting(line)
+                    .map_err(|message| ConfigError::new(index, message))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_setting(&mut self, setting: &str) -> Result<(), String> {
+        let Some((name, level)) = setting.split_once('=') else {
+            return Err(format!(
+                "expected a setting like `unused-import = deny`, but found `{}`",
+                setting.trim()
+            ));
+        };
+
+        let (name, level) = (name.trim(), level.trim());
+
+        if name.is_empty() {
+            return Err("this setting is missing the name of a warning".to_string());
+        }
+
+        if !is_warning_code(name) {
+            return Err(format!("`{name}` is not the name of a warning"));
+        }
+
+        match WarningLevel::parse(level) {
+            Some(level) => {
+                self.set_level(name, level);
+                Ok(())
+            }
+            None => Err(format!(
+                "`{level}` is not a warning level, which are `allow`, `warn` and `deny`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ConfigError {
+    /// Starts at 1
+    line: usize,
+    message: String,
+}
+
+impl ConfigError {
+    fn new(line_index: usize, message: String) -> Self {
+        ConfigError {
+            line: line_index + 1,
+            message,
+        }
+    }
+}
+
+/// Whether `name` is the code of a problem that is reported as a warning.
+fn is_warning_code(name: &str) -> bool {
+    Problem::WARNING_CODES.contains(&name) || TypeError::WARNING_CODES.contains(&name)
+}
+
+/// The text after `name` in a comment like `# name ...`.
+fn directive<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix('#')?
+        .trim_start()
+        .strip_prefix(name)
+}
+
+/// Whether a `# roc-allow:` comment allows the warning called `name` at `offset`. The comment can
+/// be right above the problem's line, or above any less indented line that the problem is nested
+/// under, like the first line of the def that contains it.
+fn is_allowed_inline(src: &str, offset: usize, name: &str) -> bool {
+    let Some(before) = src.get(..offset) else {
+        return false;
+    };
+
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    let mut scope_indent = indentation(&src[line_start..]);
+
+    // Comments right above the line we're looking at, which starts out being the problem's line
+    let mut comments_above = true;
+
+    for line in src[..line_start].lines().rev() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('#') {
+            if comments_above && allows(line, name) {
+                return true;
+            }
+        } else if trimmed.is_empty() {
+            comments_above = false;
+        } else if scope_indent > 0 && indentation(line) < scope_indent {
+            // This line encloses the ones we looked at so far
+            scope_indent = indentation(line);
+            comments_above = true;
+        } else {
+            comments_above = false;
+        }
+    }
+
+    false
+}
+
+fn allows(line: &str, name: &str) -> bool {
+    directive(line, ALLOW_DIRECTIVE)
+        .is_some_and(|names| names.split(',').any(|allowed| allowed.trim() == name))
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_region::all::Position;
+
+    fn region_of(src: &str, needle: &str) -> Option<Region> {
+        let start = src.find(needle).unwrap() as u32;
+
+        Some(Region::new(
+            Position::new(start),
+            Position::new(start + needle.len() as u32),
+        ))
+    }
+
+    #[test]
+    fn levels_from_header_and_file() {
+        let mut config = WarningConfig::default();
+        config
+            .add_header_levels(
+                "# roc-warnings: unused-import = deny, unused-definition = allow\napp [main] {}\n",
+            )
+            .unwrap();
+        config
+            .add_file_levels("# Stricter than the header\nunused-definition = deny\n\n")
+            .unwrap();
+
+        assert_eq!(config.level("unused-import"), WarningLevel::Deny);
+        assert_eq!(config.level("unused-definition"), WarningLevel::Deny);
+        assert_eq!(config.level("unused-argument"), WarningLevel::Warn);
+    }
+
+    #[test]
+    fn invalid_levels() {
+        let mut config = WarningConfig::default();
+
+        let err = config
+            .add_file_levels("unused-import = deny\nunused-definition = error\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.message,
+            "`error` is not a warning level, which are `allow`, `warn` and `deny`"
+        );
+
+        assert!(config.add_file_levels("unused-import\n").is_err());
+        assert!(config
+            .add_header_levels("# roc-warnings: = warn\napp [main] {}\n")
+            .is_err());
+    }
+
+    #[test]
+    fn unknown_warnings() {
+        let mut config = WarningConfig::default();
+
+        let err = config
+            .add_file_levels("unused-import = deny\nunused-imports = allow\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.message, "`unused-imports` is not the name of a warning");
+
+        // Problems that are always errors can't have their level set
+        assert!(config.add_file_levels("type-mismatch = allow\n").is_err());
+
+        // Warnings found by type checking can
+        config
+            .add_file_levels("redundant-pattern = deny\n")
+            .unwrap();
+        assert_eq!(config.level("redundant-pattern"), WarningLevel::Deny);
+    }
+
+    #[test]
+    fn levels_only_from_the_header() {
+        let mut config = WarningConfig::default();
+        config
+            .add_header_levels(concat!(
+                "# roc-warnings: unused-import = deny\n",
+                "app [main] {\n",
+                "    # roc-warnings: unused-argument = allow\n",
+                "    pf: platform \"platform/main.roc\",\n",
+                "}\n",
+                "\n",
+                "# roc-warnings: unused-definition = allow\n",
+                "main = 1\n",
+            ))
+            .unwrap();
+
+        assert_eq!(config.level("unused-import"), WarningLevel::Deny);
+        assert_eq!(config.level("unused-argument"), WarningLevel::Allow);
+        assert_eq!(config.level("unused-definition"), WarningLevel::Warn);
+    }
+
+    #[test]
+    fn severities() {
+        let mut config = WarningConfig::default();
+        config.set_level("unused-import", WarningLevel::Deny);
+        config.set_level("unused-definition", WarningLevel::Allow);
+
+        let src = "x = 1\n";
+        let region = region_of(src, "x");
+
+        assert_eq!(
+            config.warning_severity("unused-import", src, region),
+            Some(Severity::RuntimeError)
+        );
+        assert_eq!(
+            config.warning_severity("unused-definition", src, region),
+            None
+        );
+        assert_eq!(
+            config.warning_severity("unused-argument", src, region),
+            Some(Severity::Warning)
+        );
+
+        config.deny_warnings = true;
+
+        assert_eq!(
+            config.warning_severity("unused-argument", src, region),
+            Some(Severity::RuntimeError)
+        );
+        assert_eq!(
+            config.warning_severity("unused-definition", src, region),
+            None
+        );
+    }
+
+    #[test]
+    fn allowed_inline_for_a_def() {
+        let src = concat!(
+            "# roc-allow: unused-argument, unused-definition\n",
+            "## Docs\n",
+            "f = \\a, b ->\n",
+            "    unused = a\n",
+            "    b\n",
+            "\n",
+            "g = \\c -> 1\n",
+        );
+        let config = WarningConfig {
+            deny_warnings: true,
+            ..WarningConfig::default()
+        };
+
+        for (name, needle) in [
+            ("unused-argument", "a, b"),
+            ("unused-definition", "unused ="),
+        ] {
+            assert_eq!(
+                config.warning_severity(name, src, region_of(src, needle)),
+                None
+            );
+        }
+
+        // Other warnings and other defs aren't allowed
+        assert_eq!(
+            config.warning_severity("unused-import", src, region_of(src, "unused =")),
+            Some(Severity::RuntimeError)
+        );
+        assert_eq!(
+            config.warning_severity("unused-argument", src, region_of(src, "c ->")),
+            Some(Severity::RuntimeError)
+        );
+    }
+
+    #[test]
+    fn allowed_inline_for_a_nested_line() {
+        let src = "main =\n    # roc-allow: unused-definition\n    x = 1\n    y = 2\n    3\n";
+        let config = WarningConfig::default();
+
+        assert_eq!(
+            config.warning_severity("unused-definition", src, region_of(src, "x")),
+            None
+        );
+        assert_eq!(
+            config.warning_severity("unused-definition", src, region_of(src, "y")),
+            Some(Severity::Warning)
+        );
+    }
+}
@@ -13,12 +13,20 @@ use core::str;
 use std::convert::Infallible;
 
 mod roc_box;
+mod roc_dec;
+mod roc_dict;
+mod roc_hash;
 mod roc_list;
+mod roc_set;
 mod roc_str;
 mod storage;
 
 pub use roc_box::RocBox;
+pub use roc_dec::{ParseRocDecError, RocDecOverflow, RocDecRounding};
+pub use roc_dict::{DictSeed, HostSeed, RocDict, RocDictIter};
+pub use roc_hash::{RocHash, RocHasher};
 pub use roc_list::{ReadOnlyRocList, RocList, SendSafeRocList};
+pub use roc_set::{RocSet, RocSetIter};
 pub use roc_str::{InteriorNulError, ReadOnlyRocStr, RocStr, SendSafeRocStr};
 pub use storage::Storage;
 
@@ -0,0 +1,485 @@
+//! Arithmetic and conversions for `RocDec`, following the Zig `dec` builtins: results are
+//! truncated toward zero, and the operators panic where Roc would crash.
+
+use core::fmt;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use core::str::FromStr;
+
+use crate::RocDec;
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+/// How to round when a value doesn't fit in the target exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RocDecRounding {
+    /// Like `Num.round`: to the nearest value, and away from zero when it's half-way.
+    HalfAwayFromZero,
+    /// Like `Num.floor`
+    Down,
+    /// Like `Num.ceiling`
+    Up,
+    /// Drop the digits that don't fit, which is what Dec arithmetic does.
+    TowardZero,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseRocDecError;
+
+impl fmt::Display for ParseRocDecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid Dec literal")
+    }
+}
+
+impl std::error::Error for ParseRocDecError {}
+
+/// The value is outside the range of a Dec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RocDecOverflow;
+
+impl fmt::Display for RocDecOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value is out of range for Dec")
+    }
+}
+
+impl std::error::Error for RocDecOverflow {}
+
+impl RocDec {
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_add(other.as_i128()).map(Self::new)
+    }
+
+    pub fn wrapping_add(self, other: Self) -> Self {
+        Self::new(self.as_i128().wrapping_add(other.as_i128()))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_sub(other.as_i128()).map(Self::new)
+    }
+
+    pub fn wrapping_sub(self, other: Self) -> Self {
+        Self::new(self.as_i128().wrapping_sub(other.as_i128()))
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        match self.overflowing_mul(other) {
+            (answer, false) => Some(answer),
+            (_, true) => None,
+        }
+    }
+
+    pub fn wrapping_mul(self, other: Self) -> Self {
+        self.overflowing_mul(other).0
+    }
+
+    /// Same as the Zig builtin's `mulWithOverflow`
+    fn overflowing_mul(self, other: Self) -> (Self, bool) {
+        let (lhs, rhs) = (self.as_i128(), other.as_i128());
+        let is_answer_negative = (lhs < 0) != (rhs < 0);
+
+        // MIN has no positive counterpart, so it can only be multiplied by 0 and 1.
+        if lhs == i128::MIN || rhs == i128::MIN {
+            let (min, factor) = if lhs == i128::MIN {
+                (self, rhs)
+            } else {
+                (other, lhs)
+            };
+
+            return match factor {
+                0 => (Self::new(0), false),
+                Self::ONE_POINT_ZERO => (min, false),
+                _ if is_answer_negative => (Self::MIN, true),
+                _ => (Self::MAX, true),
+            };
+        }
+
+        let (hi, lo) = mul_u128(lhs.unsigned_abs(), rhs.unsigned_abs());
+        let (hi, lo) = div_u256_by_u64(hi, lo, Self::ONE_POINT_ZERO as u64);
+
+        Self::apply_sign(hi, lo, is_answer_negative)
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        let (numerator, denominator) = (self.as_i128(), other.as_i128());
+
+        if denominator == 0 {
+            return None;
+        }
+
+        if numerator == 0 {
+            return Some(Self::new(0));
+        }
+
+        // Like multiplication, the Zig builtin only divides MIN by 1, and 1 by MIN.
+        if numerator == i128::MIN {
+            return (denominator == Self::ONE_POINT_ZERO).then_some(self);
+        }
+
+        // The Zig builtin returns MIN itself here rather than the true quotient.
+        if denominator == i128::MIN {
+            return (numerator == Self::ONE_POINT_ZERO).then_some(other);
+        }
+
+        match self.overflowing_div(other) {
+            (answer, false) => Some(answer),
+            (_, true) => None,
+        }
+    }
+
+    /// Panics if `other` is zero, like `i128::wrapping_div`.
+    pub fn wrapping_div(self, other: Self) -> Self {
+        if other.as_i128() == 0 {
+            panic!("Decimal division by 0!");
+        }
+
+        self.overflowing_div(other).0
+    }
+
+    fn overflowing_div(self, other: Self) -> (Self, bool) {
+        let (numerator, denominator) = (self.as_i128(), other.as_i128());
+        let is_answer_negative = (numerator < 0) != (denominator < 0);
+
+        let (hi, lo) = mul_u128(numerator.unsigned_abs(), Self::ONE_POINT_ZERO as u128);
+        let (hi, lo) = div_u256_by_u128(hi, lo, denominator.unsigned_abs());
+
+        Self::apply_sign(hi, lo, is_answer_negative)
+    }
+
+    /// The remainder of dividing and truncating toward zero, which has the sign of `self`.
+    /// Roc has no `Dec` remainder, so this is Rust's `%` on the underlying integers.
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_rem(other.as_i128()).map(Self::new)
+    }
+
+    /// Panics if `other` is zero, like `i128::wrapping_rem`.
+    pub fn wrapping_rem(self, other: Self) -> Self {
+        Self::new(self.as_i128().wrapping_rem(other.as_i128()))
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        self.as_i128().checked_neg().map(Self::new)
+    }
+
+    pub fn wrapping_neg(self) -> Self {
+        Self::new(self.as_i128().wrapping_neg())
+    }
+
+    /// The magnitude in (hi, lo) with the sign applied, and whether it didn't fit in an i128.
+    fn apply_sign(hi: u128, lo: u128, is_negative: bool) -> (Self, bool) {
+        let has_overflowed = hi != 0 || lo > i128::MAX as u128;
+        let answer = if is_negative {
+            (lo as i128).wrapping_neg()
+        } else {
+            lo as i128
+        };
+
+        (Self::new(answer), has_overflowed)
+    }
+
+    /// The integer part, after rounding away the fractional part.
+    pub fn to_i128(self, rounding: RocDecRounding) -> i128 {
+        let num = self.as_i128();
+        let whole = num / Self::ONE_POINT_ZERO;
+        let fract = num % Self::ONE_POINT_ZERO;
+
+        match rounding {
+            RocDecRounding::HalfAwayFromZero if fract.abs() >= Self::ONE_POINT_ZERO / 2 => {
+                whole + num.signum()
+            }
+            RocDecRounding::Down if fract < 0 => whole - 1,
+            RocDecRounding::Up if fract > 0 => whole + 1,
+            _ => whole,
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.as_i128() as f64 / Self::ONE_POINT_ZERO as f64
+    }
+
+    /// Returns `None` for NaN, infinities, and values outside the range of a Dec.
+    /// Digits past the 18th decimal place are rounded away.
+    pub fn from_f64(value: f64, rounding: RocDecRounding) -> Option<Self> {
+        let scaled = value * Self::ONE_POINT_ZERO as f64;
+        let rounded = match rounding {
+            RocDecRounding::HalfAwayFromZero => scaled.round(),
+            RocDecRounding::Down => scaled.floor(),
+            RocDecRounding::Up => scaled.ceil(),
+            RocDecRounding::TowardZero => scaled.trunc(),
+        };
+
+        // i128::MIN is a power of 2, so it's the only bound an f64 holds exactly.
+        if rounded >= i128::MIN as f64 && rounded < -(i128::MIN as f64) {
+            Some(Self::new(rounded as i128))
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! roc_dec_binop {
+    ($Trait:ident, $method:ident, $AssignTrait:ident, $assign_method:ident, $checked:ident, $message:expr) => {
+        impl $Trait for RocDec {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                self.$checked(other).unwrap_or_else(|| panic!($message))
+            }
+        }
+
+        impl $AssignTrait for RocDec {
+            fn $assign_method(&mut self, other: Self) {
+                *self = $Trait::$method(*self, other);
+            }
+        }
+    };
+}
+
+roc_dec_binop!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    checked_add,
+    "Decimal addition overflowed!"
+);
+roc_dec_binop!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    checked_sub,
+    "Decimal subtraction overflowed!"
+);
+roc_dec_binop!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    checked_mul,
+    "Decimal multiplication overflowed!"
+);
+
+impl Div for RocDec {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        if other.as_i128() == 0 {
+            panic!("Decimal division by 0!");
+        }
+
+        self.checked_div(other)
+            .unwrap_or_else(|| panic!("Decimal division overflow!"))
+    }
+}
+
+impl DivAssign for RocDec {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl Rem for RocDec {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        if other.as_i128() == 0 {
+            panic!("Decimal division by 0!");
+        }
+
+        // Only MIN % -1 overflows, and its remainder is 0.
+        self.wrapping_rem(other)
+    }
+}
+
+impl RemAssign for RocDec {
+    fn rem_assign(&mut self, other: Self) {
+        *self = *self % other;
+    }
+}
+
+impl Neg for RocDec {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg()
+            .unwrap_or_else(|| panic!("Decimal negation overflow!"))
+    }
+}
+
+macro_rules! roc_dec_from_int {
+    ($($T:ty),+) => {
+        $(
+            impl From<$T> for RocDec {
+                fn from(value: $T) -> Self {
+                    // Every value of these types fits in a Dec.
+                    RocDec::new(RocDec::ONE_POINT_ZERO * value as i128)
+                }
+            }
+        )+
+    };
+}
+
+roc_dec_from_int!(i8, i16, i64, u8, u16, u32, u64);
+
+impl TryFrom<i128> for RocDec {
+    type Error = RocDecOverflow;
+
+    fn try_from(value: i128) -> Result<Self, Self::Error> {
+        value
+            .checked_mul(RocDec::ONE_POINT_ZERO)
+            .map(RocDec::new)
+            .ok_or(RocDecOverflow)
+    }
+}
+
+impl FromStr for RocDec {
+    type Err = ParseRocDecError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        RocDec::from_str(value).ok_or(ParseRocDecError)
+    }
+}
+
+/// Serialized as a string, so no digits are lost to a float.
+#[cfg(feature = "serde")]
+impl Serialize for RocDec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_str_helper(&mut arrayvec::ArrayString::new()))
+    }
+}
+
+/// Accepts strings, integers and floats.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for RocDec {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(RocDecVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct RocDecVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for RocDecVisitor {
+    type Value = RocDec;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a decimal number")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        RocDec::from_str(value).ok_or_else(|| E::custom(ParseRocDecError))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(RocDec::from(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(RocDec::from(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // The shortest decimal that round-trips to this float, so 0.1 becomes exactly 0.1
+        self.visit_str(&value.to_string())
+    }
+}
+
+/// The full 256-bit product, as (hi, lo).
+fn mul_u128(a: u128, b: u128) -> (u128, u128) {
+    const LOWER: u128 = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> 64, a & LOWER);
+    let (b_hi, b_lo) = (b >> 64, b & LOWER);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    // These can't overflow, since each part is at most (2^64 - 1)^2 + 2 * (2^64 - 1)
+    let middle = (lo_lo >> 64) + (hi_lo & LOWER) + (lo_hi & LOWER);
+    let lo = (middle << 64) | (lo_lo & LOWER);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (middle >> 64);
+
+    (hi, lo)
+}
+
+/// Long division by 64-bit digits, which fits in u128 arithmetic since the remainder
+/// is always smaller than the divisor.
+fn div_u256_by_u64(hi: u128, lo: u128, divisor: u64) -> (u128, u128) {
+    let divisor = divisor as u128;
+    let mut remainder = 0;
+    let mut digits = [(hi >> 64) as u64, hi as u64, (lo >> 64) as u64, lo as u64];
+
+    for digit in digits.iter_mut() {
+        let current = (remainder << 64) | *digit as u128;
+
+        *digit = (current / divisor) as u64;
+        remainder = current % divisor;
+    }
+
+    let [d0, d1, d2, d3] = digits.map(u128::from);
+
+    ((d0 << 64) | d1, (d2 << 64) | d3)
+}
+
+/// Binary long division, for divisors that don't fit in 64 bits.
+fn div_u256_by_u128(hi: u128, lo: u128, divisor: u128) -> (u128, u128) {
+    if let Ok(divisor) = u64::try_from(divisor) {
+        return div_u256_by_u64(hi, lo, divisor);
+    }
+
+    let (mut quotient_hi, mut quotient_lo) = (0u128, 0u128);
+    let mut remainder = 0u128;
+
+    for bit in (0..256).rev() {
+        let next = if bit >= 128 {
+            (hi >> (bit - 128)) & 1
+        } else {
+            (lo >> bit) & 1
+        };
+
+        // The remainder is below the divisor, so if shifting it left overflows,
+        // it's definitely at least the divisor afterwards.
+        let overflowed = remainder >> 127 == 1;
+        remainder = (remainder << 1) | next;
+
+        if overflowed || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+
+            if bit >= 128 {
+                quotient_hi |= 1 << (bit - 128);
+            } else {
+                quotient_lo |= 1 << bit;
+            }
+        }
+    }
+
+    (quotient_hi, quotient_lo)
+}
@@ -0,0 +1,277 @@
+use core::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+};
+
+use crate::{DictSeed, RocDict, RocDictIter, RocHash, RocRefcounted};
+
+#[cfg(feature = "serde")]
+use core::marker::PhantomData;
+#[cfg(feature = "serde")]
+use serde::{
+    de::{Deserializer, SeqAccess, Visitor},
+    ser::{SerializeSeq, Serializer},
+    Deserialize, Serialize,
+};
+
+/// A Roc `Set`, which is a `Dict` whose values are all `{}`.
+#[repr(transparent)]
+pub struct RocSet<T, S>(RocDict<T, (), S>)
+where
+    T: RocRefcounted;
+
+impl<T, S> RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    pub fn empty() -> Self {
+        Self(RocDict::empty())
+    }
+
+    /// Create an empty RocSet with enough space preallocated to store
+    /// the requested number of elements.
+    pub fn with_capacity(num_elems: usize) -> Self {
+        Self(RocDict::with_capacity(num_elems))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The elements in the order they were first inserted, same as `Set.to_list`.
+    pub fn iter(&self) -> RocSetIter<'_, T> {
+        RocSetIter {
+            entries: self.0.iter(),
+        }
+    }
+}
+
+impl<T, S> RocSet<T, S>
+where
+    T: RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    pub fn contains(&self, elem: &T) -> bool {
+        self.0.contains_key(elem)
+    }
+}
+
+impl<T, S> RocSet<T, S>
+where
+    T: Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    /// Returns whether the element was newly inserted.
+    pub fn insert(&mut self, elem: T) -> bool {
+        self.0.insert(elem, ()).is_none()
+    }
+}
+
+impl<T, S> Default for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T, S> Clone for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, S> RocRefcounted for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn inc(&mut self) {
+        self.0.inc();
+    }
+
+    fn dec(&mut self) {
+        self.0.dec();
+    }
+
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+/// Sets are equal when they have the same elements, regardless of order, like `Set.is_eq`.
+impl<T, S> PartialEq for RocSet<T, S>
+where
+    T: PartialOrd + RocRefcounted,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T, S> Eq for RocSet<T, S> where T: Ord + RocRefcounted {}
+
+impl<T, S> PartialOrd for RocSet<T, S>
+where
+    T: PartialOrd + RocRefcounted,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T, S> Ord for RocSet<T, S>
+where
+    T: Ord + RocRefcounted,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T, S> Hash for RocSet<T, S>
+where
+    T: Ord + Hash + RocRefcounted,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T, S> Debug for RocSet<T, S>
+where
+    T: Debug + RocRefcounted,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S> FromIterator<T> for RocSet<T, S>
+where
+    T: Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    fn from_iter<I>(into: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self(into.into_iter().map(|elem| (elem, ())).collect())
+    }
+}
+
+impl<T, S, const SIZE: usize> From<[T; SIZE]> for RocSet<T, S>
+where
+    T: Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    fn from(array: [T; SIZE]) -> Self {
+        Self::from_iter(array)
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    type Item = &'a T;
+    type IntoIter = RocSetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct RocSetIter<'a, T> {
+    entries: RocDictIter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for RocSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(elem, ())| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for RocSetIter<'_, T> {}
+
+#[cfg(feature = "serde")]
+impl<T, S> Serialize for RocSet<T, S>
+where
+    T: Serialize + RocRefcounted,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for elem in self {
+            seq.serialize_element(elem)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, S> Deserialize<'de> for RocSet<T, S>
+where
+    T: Deserialize<'de> + Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RocSetVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+struct RocSetVisitor<T, S> {
+    marker: PhantomData<(T, S)>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, S> Visitor<'de> for RocSetVisitor<T, S>
+where
+    T: Deserialize<'de> + Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    type Value = RocSet<T, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut out = match seq.size_hint() {
+            Some(hint) if hint > 0 => RocSet::with_capacity(hint),
+            _ => RocSet::empty(),
+        };
+
+        while let Some(next) = seq.next_element()? {
+            out.insert(next);
+        }
+
+        Ok(out)
+    }
+}
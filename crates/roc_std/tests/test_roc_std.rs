@@ -54,7 +54,21 @@ pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut
 
 #[cfg(test)]
 mod test_roc_std {
-    use roc_std::{RocBox, RocDec, RocList, RocResult, RocStr, SendSafeRocStr};
+    use roc_std::{
+        DictSeed, HostSeed, RocBox, RocDec, RocHasher, RocList, RocResult, RocStr, SendSafeRocStr,
+    };
+
+    /// Dicts that never go to Roc can hash their keys with any seed.
+    struct TestSeed;
+
+    impl DictSeed for TestSeed {
+        fn seed() -> u64 {
+            0x5EED
+        }
+    }
+
+    type RocDict<K, V> = roc_std::RocDict<K, V, TestSeed>;
+    type RocSet<T> = roc_std::RocSet<T, TestSeed>;
 
     fn roc_str_byte_representation(string: &RocStr) -> [u8; RocStr::SIZE] {
         unsafe { core::mem::transmute_copy(string) }
@@ -336,6 +350,182 @@ mod test_roc_std {
         assert_eq!(RocDec::from(0), RocDec::default());
     }
 
+    fn dec(s: &str) -> RocDec {
+        RocDec::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn roc_dec_arithmetic() {
+        assert_eq!(dec("1.5") + dec("2.25"), dec("3.75"));
+        assert_eq!(dec("1.5") - dec("2.25"), dec("-0.75"));
+        assert_eq!(dec("1.5") * dec("-2.25"), dec("-3.375"));
+        assert_eq!(dec("10") / dec("3"), dec("3.333333333333333333"));
+        assert_eq!(dec("-10") / dec("3"), dec("-3.333333333333333333"));
+        assert_eq!(dec("7.5") % dec("2"), dec("1.5"));
+        assert_eq!(-dec("1.5"), dec("-1.5"));
+
+        // multiplication truncates digits beyond the 18th decimal place
+        assert_eq!(dec("0.000000000000000001") * dec("0.5"), RocDec::from(0));
+
+        let mut total = dec("1");
+        total += dec("2");
+        total *= dec("3");
+        total -= dec("1");
+        total /= dec("4");
+        assert_eq!(total, dec("2"));
+    }
+
+    #[test]
+    fn roc_dec_checked_and_wrapping() {
+        assert_eq!(RocDec::MAX.checked_add(dec("0.000000000000000001")), None);
+        assert_eq!(RocDec::MIN.checked_sub(dec("0.000000000000000001")), None);
+        assert_eq!(RocDec::MAX.checked_mul(dec("2")), None);
+        assert_eq!(dec("1").checked_div(RocDec::from(0)), None);
+        assert_eq!(dec("1").checked_rem(RocDec::from(0)), None);
+        assert_eq!(RocDec::MIN.checked_neg(), None);
+
+        assert_eq!(
+            RocDec::MAX.wrapping_add(dec("0.000000000000000001")),
+            RocDec::MIN
+        );
+        assert_eq!(RocDec::MIN.wrapping_neg(), RocDec::MIN);
+
+        assert_eq!(RocDec::MIN.checked_mul(dec("1")), Some(RocDec::MIN));
+        assert_eq!(
+            RocDec::MIN.checked_mul(RocDec::from(0)),
+            Some(RocDec::from(0))
+        );
+        assert_eq!(RocDec::MIN.checked_div(dec("1")), Some(RocDec::MIN));
+        assert_eq!(RocDec::MIN.checked_div(dec("2")), None);
+        assert_eq!(dec("1").checked_div(RocDec::MIN), Some(RocDec::MIN));
+        assert_eq!(dec("2").checked_div(RocDec::MIN), None);
+        assert_eq!(
+            RocDec::from(0).checked_div(RocDec::MIN),
+            Some(RocDec::from(0))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimal division by 0!")]
+    fn roc_dec_div_by_zero() {
+        let _ = dec("1") / RocDec::from(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimal addition overflowed!")]
+    fn roc_dec_add_overflow() {
+        let _ = RocDec::MAX + dec("1");
+    }
+
+    #[test]
+    fn roc_dec_mul_matches_i128() {
+        fn prop(a: i64, b: i64) -> bool {
+            let expected = RocDec::new(a as i128 * b as i128 / 1_000_000_000_000_000_000);
+
+            RocDec::new(a as i128).checked_mul(RocDec::new(b as i128)) == Some(expected)
+        }
+
+        quickcheck::quickcheck(prop as fn(i64, i64) -> bool);
+    }
+
+    #[test]
+    fn roc_dec_div_matches_i128() {
+        fn prop(a: i64, b: i64) -> bool {
+            if b == 0 {
+                return true;
+            }
+
+            let expected = RocDec::new(a as i128 * 1_000_000_000_000_000_000 / b as i128);
+
+            RocDec::new(a as i128).checked_div(RocDec::new(b as i128)) == Some(expected)
+        }
+
+        quickcheck::quickcheck(prop as fn(i64, i64) -> bool);
+    }
+
+    #[test]
+    fn roc_dec_to_i128() {
+        use roc_std::RocDecRounding::*;
+
+        let cases = [
+            ("2.5", [3, 2, 3, 2]),
+            ("-2.5", [-3, -3, -2, -2]),
+            ("2.4", [2, 2, 3, 2]),
+            ("-2.6", [-3, -3, -2, -2]),
+            ("7", [7, 7, 7, 7]),
+        ];
+
+        for (input, expected) in cases {
+            let actual = [HalfAwayFromZero, Down, Up, TowardZero].map(|r| dec(input).to_i128(r));
+
+            assert_eq!(actual, expected, "rounding {input}");
+        }
+    }
+
+    #[test]
+    fn roc_dec_f64_conversions() {
+        use roc_std::RocDecRounding::*;
+
+        assert_eq!(dec("1.5").to_f64(), 1.5);
+        assert_eq!(dec("-0.25").to_f64(), -0.25);
+
+        assert_eq!(RocDec::from_f64(1.5, TowardZero), Some(dec("1.5")));
+        assert_eq!(RocDec::from_f64(-0.25, TowardZero), Some(dec("-0.25")));
+        assert_eq!(RocDec::from_f64(f64::NAN, TowardZero), None);
+        assert_eq!(RocDec::from_f64(f64::INFINITY, TowardZero), None);
+        assert_eq!(RocDec::from_f64(1e30, TowardZero), None);
+
+        let tiny = 1.5e-18;
+        assert_eq!(
+            RocDec::from_f64(tiny, Down),
+            Some(dec("0.000000000000000001"))
+        );
+        assert_eq!(
+            RocDec::from_f64(tiny, Up),
+            Some(dec("0.000000000000000002"))
+        );
+        assert_eq!(
+            RocDec::from_f64(-tiny, Down),
+            Some(dec("-0.000000000000000002"))
+        );
+        assert_eq!(
+            RocDec::from_f64(-tiny, TowardZero),
+            Some(dec("-0.000000000000000001"))
+        );
+    }
+
+    #[test]
+    fn roc_dec_parse_and_convert() {
+        use roc_std::{ParseRocDecError, RocDecOverflow};
+
+        assert_eq!("1.5".parse::<RocDec>(), Ok(dec("1.5")));
+        assert_eq!("one".parse::<RocDec>(), Err(ParseRocDecError));
+
+        assert_eq!(RocDec::from(42u64), dec("42"));
+        assert_eq!(RocDec::from(-42i8), dec("-42"));
+        assert_eq!(
+            RocDec::try_from(170_141_183_460_469_231_731i128),
+            Ok(dec("170141183460469231731"))
+        );
+        assert_eq!(
+            RocDec::try_from(170_141_183_460_469_231_732i128),
+            Err(RocDecOverflow)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn roc_dec_serde() {
+        let value = dec("-1234.5678");
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"-1234.5678\"");
+        assert_eq!(serde_json::from_str::<RocDec>(&json).unwrap(), value);
+
+        assert_eq!(serde_json::from_str::<RocDec>("12").unwrap(), dec("12"));
+        assert_eq!(serde_json::from_str::<RocDec>("-0.5").unwrap(), dec("-0.5"));
+        assert!(serde_json::from_str::<RocDec>("\"nope\"").is_err());
+    }
+
     #[test]
     fn safe_send_no_copy() {
         let x = RocStr::from("This is a long string but still unique. Yay!!!");
@@ -465,6 +655,196 @@ mod test_roc_std {
             ]
         );
     }
+
+    #[test]
+    fn roc_dict_layout() {
+        // buckets, data, max_bucket_capacity, max_load_factor, shifts
+        assert_eq!(core::mem::size_of::<RocDict<u64, u64>>(), 64);
+
+        // Roc puts the more aligned element of a (k, v) tuple first
+        let dict = RocDict::from([(1u8, 2u64)]);
+        let (key, value) = dict.iter().next().unwrap();
+        let key_offset = key as *const u8 as usize - value as *const u64 as usize;
+
+        assert_eq!(key_offset, 8);
+    }
+
+    #[test]
+    fn roc_dict_insert_and_get() {
+        let mut dict = RocDict::empty();
+
+        assert_eq!(dict.insert(RocStr::from("one"), 1), None);
+        assert_eq!(dict.insert(RocStr::from("two"), 2), None);
+        assert_eq!(dict.insert(RocStr::from("one"), 3), Some(1));
+
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(&RocStr::from("one")), Some(&3));
+        assert_eq!(dict.get(&RocStr::from("two")), Some(&2));
+        assert_eq!(dict.get(&RocStr::from("three")), None);
+    }
+
+    #[test]
+    fn roc_dict_from_iter() {
+        let dict: RocDict<u64, RocStr> = [(1, "a"), (2, "b"), (1, "c")]
+            .into_iter()
+            .map(|(key, value)| (key, RocStr::from(value)))
+            .collect();
+
+        // The last value for a key wins, and keys stay where they were first inserted
+        assert_eq!(dict.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            dict.values().map(RocStr::as_str).collect::<Vec<_>>(),
+            vec!["c", "b"]
+        );
+    }
+
+    #[test]
+    fn roc_dict_eq_ignores_order() {
+        let a = RocDict::from([(1u8, 10u8), (2, 20)]);
+        let b = RocDict::from([(2u8, 20u8), (1, 10)]);
+        let c = RocDict::from([(1u8, 10u8), (2, 21)]);
+
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), core::cmp::Ordering::Equal);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn roc_dict_clone_on_insert() {
+        let long_str = RocStr::from("a string too long to be stored inline");
+        let original = RocDict::from([(1u64, long_str.clone())]);
+        let mut copy = original.clone();
+
+        copy.insert(2, long_str.clone());
+        copy.insert(1, RocStr::from("replaced"));
+
+        assert_eq!(original.len(), 1);
+        assert_eq!(original.get(&1), Some(&long_str));
+        assert_eq!(copy.len(), 2);
+        assert_eq!(copy.get(&1), Some(&RocStr::from("replaced")));
+
+        drop(original);
+        drop(copy);
+
+        assert!(long_str.is_unique());
+    }
+
+    #[test]
+    fn roc_dict_grows() {
+        let mut dict = RocDict::with_capacity(3);
+
+        for key in 0..1000u64 {
+            assert_eq!(dict.insert(key, key * 2), None);
+        }
+
+        assert_eq!(dict.len(), 1000);
+        assert!((0..1000u64).all(|key| dict.get(&key) == Some(&(key * 2))));
+        assert_eq!(dict.get(&1000), None);
+    }
+
+    #[test]
+    fn roc_dict_host_seed() {
+        HostSeed::set(0x5EED);
+
+        let mut dict = roc_std::RocDict::<u64, u64, HostSeed>::with_capacity(2);
+        dict.insert(1, 10);
+        dict.insert(2, 20);
+
+        assert_eq!(dict.get(&2), Some(&20));
+        assert_eq!(dict.get(&3), None);
+    }
+
+    #[test]
+    fn roc_hasher_matches_roc() {
+        // The same cases as the LowLevelHasher tests in Dict.roc
+        const SEED: u64 = 0x526F_6352_616E_643F;
+
+        let hash_bytes = |bytes: &[u8]| {
+            let mut hasher = RocHasher::with_seed(SEED);
+            hasher.add_bytes(bytes);
+            hasher.complete()
+        };
+
+        let hash_with = |add: &dyn Fn(&mut RocHasher)| {
+            let mut hasher = RocHasher::with_seed(SEED);
+            add(&mut hasher);
+            hasher.complete()
+        };
+
+        assert_eq!(hash_bytes(&[]), 0xD59C59757DBBE6B3);
+        assert_eq!(hash_bytes(&[0x42]), 0x38CE03D0E61AF963);
+        assert_eq!(hash_with(&|h| h.add_u8(0x42)), 0x38CE03D0E61AF963);
+        assert_eq!(hash_bytes(&[0xFF, 0xFF]), 0xE1CB2FA0D6A64113);
+        assert_eq!(hash_with(&|h| h.add_u16(0xFFFF)), 0xE1CB2FA0D6A64113);
+        assert_eq!(hash_bytes(&[0x36, 0xA7]), 0x26B8319EDAF81B15);
+        assert_eq!(hash_with(&|h| h.add_u16(0xA736)), 0x26B8319EDAF81B15);
+        assert_eq!(hash_bytes(&[0x00, 0x00, 0x00, 0x00]), 0xA187D7CA074F9EE7);
+        assert_eq!(hash_with(&|h| h.add_u32(0)), 0xA187D7CA074F9EE7);
+        assert_eq!(hash_bytes(&[0xA9, 0x2F, 0xEE, 0x21]), 0xA499EFE4C1454D09);
+        assert_eq!(hash_with(&|h| h.add_u32(0x21EE2FA9)), 0xA499EFE4C1454D09);
+        assert_eq!(
+            hash_bytes(&[
+                0x5D, 0x66, 0xB1, 0x8F, 0x68, 0x44, 0xC7, 0x03, 0xE1, 0xDD, 0x23, 0x34, 0xBB, 0x9A,
+                0x42, 0xA7,
+            ]),
+            0xDD39A206AED64C73
+        );
+        assert_eq!(
+            hash_with(&|h| h.add_u128(0xA7429ABB3423DDE103C744688FB1665D)),
+            0xDD39A206AED64C73
+        );
+        assert_eq!(
+            hash_bytes(b"abcdefghijklmnopqrstuvwxyz"),
+            0x51C59DF5B1D15F40
+        );
+        assert_eq!(
+            hash_bytes(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+            0xD8D0A129D97A4E95
+        );
+        assert_eq!(
+            hash_bytes("1234567890".repeat(7).as_bytes()),
+            0x8188065B44FB4AAA
+        );
+        assert_eq!(hash_bytes(&[0x77; 100]), 0x47A2A606EADF3378);
+    }
+
+    #[test]
+    fn roc_set_insert_and_contains() {
+        let mut set = RocSet::from([3u32, 1, 3, 2]);
+
+        assert_eq!(set.len(), 3);
+        assert!(set.insert(4));
+        assert!(!set.insert(1));
+        assert!(set.contains(&4));
+        assert!(!set.contains(&5));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![3, 1, 2, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn roc_dict_serde_roundtrip() {
+        let orig = RocDict::from([(RocStr::from("a"), 1u64), (RocStr::from("b"), 2)]);
+
+        let serialized = serde_json::to_string(&orig).expect("failed to serialize dict");
+        let deserialized = serde_json::from_str::<RocDict<RocStr, u64>>(&serialized)
+            .expect("failed to deserialize dict");
+
+        assert_eq!(serialized, r#"{"a":1,"b":2}"#);
+        assert_eq!(orig, deserialized);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn roc_set_serde_roundtrip() {
+        let orig = RocSet::from([1u8, 3, 3, 7]);
+
+        let serialized = serde_json::to_string(&orig).expect("failed to serialize set");
+        let deserialized =
+            serde_json::from_str::<RocSet<u8>>(&serialized).expect("failed to deserialize set");
+
+        assert_eq!(serialized, "[1,3,7]");
+        assert_eq!(orig, deserialized);
+    }
 }
 
 #[cfg(test)]
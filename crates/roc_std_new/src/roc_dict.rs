@@ -0,0 +1,794 @@
+use core::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    slice,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering},
+};
+
+use crate::roc_ops::RocOps;
+use crate::{roc_refcounted_noop_impl, RocHash, RocHasher, RocList, RocRefcounted};
+
+#[cfg(feature = "serde")]
+use serde::{
+    ser::{SerializeMap, Serializer},
+    Serialize,
+};
+
+/// The shifts of an empty dict in Roc, which has 2^(64 - shifts) buckets once it allocates them.
+const INITIAL_SHIFTS: u8 = 64 - 3;
+
+const DEFAULT_MAX_LOAD_FACTOR: f32 = 0.8;
+
+/// A bucket's distance from where its hash points goes above the 1-byte fingerprint.
+const DIST_INC: u32 = 1 << 8;
+
+const FINGERPRINT_MASK: u32 = DIST_INC - 1;
+
+const MAX_BUCKET_COUNT: u64 = 1 << 32;
+
+/// Gives the seed a `RocDict` hashes its keys with, which has to be the one the Roc app that
+/// built (or will read) the dict uses.
+pub trait DictSeed {
+    fn seed() -> u64;
+}
+
+static HOST_SEED: AtomicU64 = AtomicU64::new(0);
+static HOST_SEED_IS_SET: AtomicBool = AtomicBool::new(false);
+
+/// The seed of the Roc app the host runs, which the host has to set with [`HostSeed::set`]
+/// before any keys get hashed.
+///
+/// Apps derive their seed from where their code ended up in memory, and `RocOps` doesn't pass
+/// it along, so the host has to get it from the app itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HostSeed;
+
+impl HostSeed {
+    pub fn set(seed: u64) {
+        HOST_SEED.store(seed, AtomicOrdering::Relaxed);
+        HOST_SEED_IS_SET.store(true, AtomicOrdering::Release);
+    }
+}
+
+impl DictSeed for HostSeed {
+    fn seed() -> u64 {
+        assert!(
+            HOST_SEED_IS_SET.load(AtomicOrdering::Acquire),
+            "a RocDict hashed a key before HostSeed::set was called"
+        );
+
+        HOST_SEED.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// A Roc `Dict`, with the same memory layout as the builtin.
+///
+/// Keys are hashed and placed in buckets the same way the builtin does it, using the seed
+/// from `S`, so a dict built on either side can be read and changed on the other.
+///
+/// Like `RocList`, this does NOT store a pointer to RocOps, so you must call `decref`
+/// explicitly before it goes out of scope.
+#[repr(C)]
+pub struct RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    // Roc sorts record fields by alignment and then by name, and a U64 is more aligned than a
+    // List on 32-bit targets.
+    #[cfg(target_pointer_width = "32")]
+    max_bucket_capacity: u64,
+    buckets: RocList<Bucket>,
+    data: RocList<RocDictItem<K, V>>,
+    #[cfg(target_pointer_width = "64")]
+    max_bucket_capacity: u64,
+    max_load_factor: f32,
+    shifts: u8,
+    seed: PhantomData<fn() -> S>,
+}
+
+impl<K, V, S> RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    pub fn empty() -> Self {
+        Self {
+            buckets: RocList::empty(),
+            data: RocList::empty(),
+            max_bucket_capacity: 0,
+            max_load_factor: DEFAULT_MAX_LOAD_FACTOR,
+            shifts: INITIAL_SHIFTS,
+            seed: PhantomData,
+        }
+    }
+
+    /// Create an empty RocDict with enough space preallocated to store
+    /// the requested number of entries, same as `Dict.with_capacity`.
+    pub fn with_capacity(num_entries: usize, roc_ops: &RocOps) -> Self {
+        let size = (num_entries as u64).min(MAX_BUCKET_COUNT);
+        let shifts = calc_shifts_for_size(size, DEFAULT_MAX_LOAD_FACTOR);
+        let (buckets, max_bucket_capacity) =
+            alloc_buckets_from_shift(shifts, DEFAULT_MAX_LOAD_FACTOR, roc_ops);
+
+        Self {
+            buckets,
+            data: RocList::with_capacity(size as usize, roc_ops),
+            max_bucket_capacity,
+            max_load_factor: DEFAULT_MAX_LOAD_FACTOR,
+            shifts,
+            seed: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The entries in the order they were first inserted, same as `Dict.to_list`.
+    pub fn iter(&self) -> RocDictIter<'_, K, V> {
+        RocDictIter {
+            items: self.data.as_slice().iter(),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// The entries sorted by key, for comparing and hashing dicts regardless of order. Keys
+    /// always compare, since Roc can't hash floats.
+    fn sorted_entries(&self) -> Vec<(&K, &V)>
+    where
+        K: PartialOrd,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+
+        entries.sort_unstable_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        entries
+    }
+
+    /// Decrement the reference counts of the buckets and entries, and deallocate them if needed.
+    ///
+    /// This must be called explicitly since RocDict does not store the RocOps
+    /// pointer and cannot deallocate in Drop.
+    pub fn decref(&mut self, roc_ops: &RocOps) {
+        self.buckets.decref(roc_ops);
+        self.data.decref(roc_ops);
+        self.max_bucket_capacity = 0;
+        self.shifts = INITIAL_SHIFTS;
+    }
+}
+
+impl<K, V, S> RocDict<K, V, S>
+where
+    K: RocHash + PartialEq + RocRefcounted,
+    V: RocRefcounted,
+    S: DictSeed,
+{
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(RocDictItem::value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    fn find(&self, key: &K) -> Option<&RocDictItem<K, V>> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let hash = hash_key::<K, S>(key);
+        let mut dist_and_fingerprint = dist_and_fingerprint_from_hash(hash);
+        let mut bucket_index = bucket_index_from_hash(hash, self.shifts);
+
+        loop {
+            let bucket = self.buckets[bucket_index];
+
+            if dist_and_fingerprint == bucket.dist_and_fingerprint {
+                let item = &self.data[bucket.data_index as usize];
+
+                if item.key() == key {
+                    return Some(item);
+                }
+            } else if dist_and_fingerprint > bucket.dist_and_fingerprint {
+                return None;
+            }
+
+            bucket_index = next_bucket_index(bucket_index, self.buckets.len());
+            dist_and_fingerprint = dist_and_fingerprint.wrapping_add(DIST_INC);
+        }
+    }
+}
+
+impl<K, V, S> RocDict<K, V, S>
+where
+    K: Clone + RocHash + PartialEq + RocRefcounted,
+    V: Clone + RocRefcounted,
+    S: DictSeed,
+{
+    /// Build a dict from the given entries. If a key is in there more than once, the last value
+    /// for it wins.
+    pub fn from_iter<I>(entries: I, roc_ops: &RocOps) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = entries.into_iter();
+        let mut dict = match iter.size_hint() {
+            (0, _) => Self::empty(),
+            (min_len, _) => Self::with_capacity(min_len, roc_ops),
+        };
+
+        for (key, value) in iter {
+            dict.insert(key, value, roc_ops);
+        }
+
+        dict
+    }
+
+    /// Insert a value at the given key, returning the value that was there before, if any.
+    /// The dict's entries are copied first if they're shared with another dict.
+    pub fn insert(&mut self, key: K, value: V, roc_ops: &RocOps) -> Option<V> {
+        if self.len() as u64 >= self.max_bucket_capacity {
+            self.increase_size(roc_ops);
+        }
+
+        if !self.buckets.is_unique() {
+            let copy = RocList::from_slice(self.buckets.as_slice(), roc_ops);
+
+            mem::replace(&mut self.buckets, copy).decref(roc_ops);
+        }
+
+        if !self.data.is_unique() {
+            let copy = RocList::from_slice(self.data.as_slice(), roc_ops);
+
+            mem::replace(&mut self.data, copy).decref(roc_ops);
+        }
+
+        let hash = hash_key::<K, S>(&key);
+        let mut dist_and_fingerprint = dist_and_fingerprint_from_hash(hash);
+        let mut bucket_index = bucket_index_from_hash(hash, self.shifts);
+
+        loop {
+            let loaded = self.buckets[bucket_index];
+
+            if dist_and_fingerprint == loaded.dist_and_fingerprint {
+                let item = &mut self.data[loaded.data_index as usize];
+
+                if item.key() == &key {
+                    return Some(mem::replace(item.value_mut(), value));
+                }
+            } else if dist_and_fingerprint > loaded.dist_and_fingerprint {
+                self.data.push(RocDictItem::new(key, value), roc_ops);
+
+                let bucket = Bucket {
+                    data_index: (self.data.len() - 1) as u32,
+                    dist_and_fingerprint,
+                };
+
+                place_and_shift_up(&mut self.buckets, bucket, bucket_index);
+
+                return None;
+            }
+
+            bucket_index = next_bucket_index(bucket_index, self.buckets.len());
+            dist_and_fingerprint = dist_and_fingerprint.wrapping_add(DIST_INC);
+        }
+    }
+
+    fn increase_size(&mut self, roc_ops: &RocOps) {
+        if self.max_bucket_capacity == MAX_BUCKET_COUNT {
+            panic!("Dict hit limit of {MAX_BUCKET_COUNT} elements. Unable to grow more.");
+        }
+
+        let shifts = self.shifts - 1;
+        let (mut buckets, max_bucket_capacity) =
+            alloc_buckets_from_shift(shifts, self.max_load_factor, roc_ops);
+
+        for (data_index, item) in self.data.iter().enumerate() {
+            let hash = hash_key::<K, S>(item.key());
+            let mut dist_and_fingerprint = dist_and_fingerprint_from_hash(hash);
+            let mut bucket_index = bucket_index_from_hash(hash, shifts);
+
+            while dist_and_fingerprint < buckets[bucket_index].dist_and_fingerprint {
+                bucket_index = next_bucket_index(bucket_index, buckets.len());
+                dist_and_fingerprint = dist_and_fingerprint.wrapping_add(DIST_INC);
+            }
+
+            let bucket = Bucket {
+                data_index: data_index as u32,
+                dist_and_fingerprint,
+            };
+
+            place_and_shift_up(&mut buckets, bucket, bucket_index);
+        }
+
+        mem::replace(&mut self.buckets, buckets).decref(roc_ops);
+        self.max_bucket_capacity = max_bucket_capacity;
+        self.shifts = shifts;
+    }
+}
+
+fn hash_key<K, S>(key: &K) -> u64
+where
+    K: RocHash,
+    S: DictSeed,
+{
+    let mut hasher = RocHasher::with_seed(S::seed());
+
+    key.roc_hash(&mut hasher);
+
+    hasher.complete()
+}
+
+fn dist_and_fingerprint_from_hash(hash: u64) -> u32 {
+    (hash as u32 & FINGERPRINT_MASK) | DIST_INC
+}
+
+fn bucket_index_from_hash(hash: u64, shifts: u8) -> usize {
+    (hash >> shifts) as usize
+}
+
+fn next_bucket_index(bucket_index: usize, max_buckets: usize) -> usize {
+    if bucket_index + 1 != max_buckets {
+        bucket_index + 1
+    } else {
+        0
+    }
+}
+
+/// Puts the bucket at the given index, moving the buckets after it up one until there's an
+/// empty one.
+fn place_and_shift_up(buckets: &mut [Bucket], mut bucket: Bucket, mut bucket_index: usize) {
+    loop {
+        let loaded = mem::replace(&mut buckets[bucket_index], bucket);
+
+        if loaded.dist_and_fingerprint == 0 {
+            return;
+        }
+
+        bucket = Bucket {
+            dist_and_fingerprint: loaded.dist_and_fingerprint.wrapping_add(DIST_INC),
+            ..loaded
+        };
+        bucket_index = next_bucket_index(bucket_index, buckets.len());
+    }
+}
+
+fn calc_num_buckets(shifts: u8) -> u64 {
+    (1u128 << (64 - shifts)).min(MAX_BUCKET_COUNT as u128) as u64
+}
+
+fn alloc_buckets_from_shift(
+    shifts: u8,
+    max_load_factor: f32,
+    roc_ops: &RocOps,
+) -> (RocList<Bucket>, u64) {
+    let bucket_count = calc_num_buckets(shifts);
+    let mut buckets = RocList::with_capacity(bucket_count as usize, roc_ops);
+
+    for _ in 0..bucket_count {
+        buckets.push(Bucket::EMPTY, roc_ops);
+    }
+
+    if bucket_count == MAX_BUCKET_COUNT {
+        // reached the maximum, make sure we can use each bucket
+        (buckets, MAX_BUCKET_COUNT)
+    } else {
+        let max_bucket_capacity = (bucket_count as f32 * max_load_factor).floor() as u64;
+
+        (buckets, max_bucket_capacity)
+    }
+}
+
+fn calc_shifts_for_size(size: u64, max_load_factor: f32) -> u8 {
+    let mut shifts = INITIAL_SHIFTS;
+
+    while shifts > 0 && ((calc_num_buckets(shifts) as f32 * max_load_factor).floor() as u64) < size
+    {
+        shifts -= 1;
+    }
+
+    shifts
+}
+
+impl<K, V, S> Default for RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<K, V, S> Clone for RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    fn clone(&self) -> Self {
+        // Both lists get their reference counts incremented, so the clone shares the entries.
+        // Each of them needs its own `decref`.
+        Self {
+            buckets: self.buckets.clone(),
+            data: self.data.clone(),
+            max_bucket_capacity: self.max_bucket_capacity,
+            max_load_factor: self.max_load_factor,
+            shifts: self.shifts,
+            seed: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S> RocRefcounted for RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    fn inc(&mut self) {
+        self.buckets.inc();
+        self.data.inc();
+    }
+
+    fn dec(&mut self) {
+        self.buckets.dec();
+        self.data.dec();
+    }
+
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+/// Dicts are equal when they have the same entries, regardless of order, like `Dict.is_eq`.
+impl<K, V, S> PartialEq for RocDict<K, V, S>
+where
+    K: PartialOrd + RocRefcounted,
+    V: PartialEq + RocRefcounted,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.sorted_entries() == other.sorted_entries()
+    }
+}
+
+impl<K, V, S> Eq for RocDict<K, V, S>
+where
+    K: Ord + RocRefcounted,
+    V: Eq + RocRefcounted,
+{
+}
+
+/// Dicts are ordered by their entries sorted by key, so insertion order doesn't matter.
+impl<K, V, S> PartialOrd for RocDict<K, V, S>
+where
+    K: PartialOrd + RocRefcounted,
+    V: PartialOrd + RocRefcounted,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.sorted_entries().partial_cmp(&other.sorted_entries())
+    }
+}
+
+impl<K, V, S> Ord for RocDict<K, V, S>
+where
+    K: Ord + RocRefcounted,
+    V: Ord + RocRefcounted,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorted_entries().cmp(&other.sorted_entries())
+    }
+}
+
+impl<K, V, S> Hash for RocDict<K, V, S>
+where
+    K: Ord + Hash + RocRefcounted,
+    V: Hash + RocRefcounted,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_entries().hash(state);
+    }
+}
+
+impl<K, V, S> Debug for RocDict<K, V, S>
+where
+    K: Debug + RocRefcounted,
+    V: Debug + RocRefcounted,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a RocDict<K, V, S>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = RocDictIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct RocDictIter<'a, K, V> {
+    items: slice::Iter<'a, RocDictItem<K, V>>,
+}
+
+impl<'a, K, V> Iterator for RocDictIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().map(|item| (item.key(), item.value()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for RocDictIter<'_, K, V> {}
+
+#[cfg(feature = "serde")]
+impl<K, V, S> Serialize for RocDict<K, V, S>
+where
+    K: Serialize + RocRefcounted,
+    V: Serialize + RocRefcounted,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+// Note: Deserialize for RocDict is not implemented in the new ABI because
+// deserialization requires allocation, which needs RocOps.
+// Users should deserialize to a map and then convert using RocDict::from_iter.
+
+/// Where a key's entry is in data, and how far it is from the bucket its hash points to.
+/// A `dist_and_fingerprint` of 0 means the bucket is empty.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Bucket {
+    data_index: u32,
+    dist_and_fingerprint: u32,
+}
+
+impl Bucket {
+    const EMPTY: Self = Self {
+        data_index: 0,
+        dist_and_fingerprint: 0,
+    };
+}
+
+roc_refcounted_noop_impl!(Bucket);
+
+/// One of a dict's entries, which Roc stores as a `(k, v)` tuple. Roc sorts a tuple's
+/// elements by alignment (keeping their order when it's equal), so the value comes first
+/// when it's more aligned than the key. Both structs are the same size either way.
+#[repr(C)]
+union RocDictItem<K, V> {
+    key_first: ManuallyDrop<KeyFirst<K, V>>,
+    value_first: ManuallyDrop<ValueFirst<K, V>>,
+}
+
+#[repr(C)]
+struct KeyFirst<K, V> {
+    key: K,
+    value: V,
+}
+
+#[repr(C)]
+struct ValueFirst<K, V> {
+    value: V,
+    key: K,
+}
+
+impl<K, V> RocDictItem<K, V> {
+    const VALUE_FIRST: bool = mem::align_of::<V>() > mem::align_of::<K>();
+
+    fn new(key: K, value: V) -> Self {
+        if Self::VALUE_FIRST {
+            Self {
+                value_first: ManuallyDrop::new(ValueFirst { value, key }),
+            }
+        } else {
+            Self {
+                key_first: ManuallyDrop::new(KeyFirst { key, value }),
+            }
+        }
+    }
+
+    fn key(&self) -> &K {
+        unsafe {
+            if Self::VALUE_FIRST {
+                &self.value_first.key
+            } else {
+                &self.key_first.key
+            }
+        }
+    }
+
+    fn value(&self) -> &V {
+        unsafe {
+            if Self::VALUE_FIRST {
+                &self.value_first.value
+            } else {
+                &self.key_first.value
+            }
+        }
+    }
+
+    fn value_mut(&mut self) -> &mut V {
+        self.parts_mut().1
+    }
+
+    fn parts_mut(&mut self) -> (&mut K, &mut V) {
+        unsafe {
+            if Self::VALUE_FIRST {
+                let ValueFirst { value, key } = &mut *self.value_first;
+
+                (key, value)
+            } else {
+                let KeyFirst { key, value } = &mut *self.key_first;
+
+                (key, value)
+            }
+        }
+    }
+}
+
+impl<K, V> Clone for RocDictItem<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.key().clone(), self.value().clone())
+    }
+}
+
+impl<K, V> RocRefcounted for RocDictItem<K, V>
+where
+    K: RocRefcounted,
+    V: RocRefcounted,
+{
+    fn inc(&mut self) {
+        let (key, value) = self.parts_mut();
+
+        key.inc();
+        value.inc();
+    }
+
+    fn dec(&mut self) {
+        let (key, value) = self.parts_mut();
+
+        key.dec();
+        value.dec();
+    }
+
+    fn is_refcounted() -> bool {
+        K::is_refcounted() || V::is_refcounted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roc_ops::test_helpers::test_roc_ops;
+    use crate::RocStr;
+
+    struct TestSeed;
+
+    impl DictSeed for TestSeed {
+        fn seed() -> u64 {
+            0x5EED
+        }
+    }
+
+    type RocDict<K, V> = super::RocDict<K, V, TestSeed>;
+    type RocSet<T> = crate::RocSet<T, TestSeed>;
+
+    #[test]
+    fn test_dict_size() {
+        // buckets, data, max_bucket_capacity, max_load_factor, shifts
+        assert_eq!(std::mem::size_of::<RocDict<u64, u64>>(), 64);
+    }
+
+    #[test]
+    fn more_aligned_value_comes_first() {
+        let ops = test_roc_ops();
+        let mut dict = RocDict::from_iter([(1u8, 2u64)], &ops);
+        let (key, value) = dict.iter().next().unwrap();
+
+        assert_eq!(key as *const u8 as usize - value as *const u64 as usize, 8);
+
+        dict.decref(&ops);
+    }
+
+    #[test]
+    fn insert_and_get() {
+        let ops = test_roc_ops();
+        let mut dict = RocDict::empty();
+
+        assert_eq!(dict.insert(RocStr::from_str("one", &ops), 1, &ops), None);
+        assert_eq!(dict.insert(RocStr::from_str("two", &ops), 2, &ops), None);
+        assert_eq!(dict.insert(RocStr::from_str("one", &ops), 3, &ops), Some(1));
+
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(&RocStr::from_str("one", &ops)), Some(&3));
+        assert_eq!(dict.get(&RocStr::from_str("three", &ops)), None);
+
+        dict.decref(&ops);
+    }
+
+    #[test]
+    fn insert_into_clone() {
+        let ops = test_roc_ops();
+        let mut original = RocDict::from_iter([(1u64, 10u64)], &ops);
+        let mut copy = original.clone();
+
+        copy.insert(1, 11, &ops);
+        copy.insert(2, 20, &ops);
+
+        assert_eq!(original.get(&1), Some(&10));
+        assert_eq!(original.len(), 1);
+        assert_eq!(copy.get(&1), Some(&11));
+        assert_eq!(copy.len(), 2);
+        assert!(original.data.is_unique());
+
+        original.decref(&ops);
+        copy.decref(&ops);
+    }
+
+    #[test]
+    fn grows() {
+        let ops = test_roc_ops();
+        let mut dict = RocDict::with_capacity(3, &ops);
+
+        for key in 0..1000u64 {
+            assert_eq!(dict.insert(key, key * 2, &ops), None);
+        }
+
+        assert_eq!(dict.len(), 1000);
+        assert!((0..1000u64).all(|key| dict.get(&key) == Some(&(key * 2))));
+        assert_eq!(dict.get(&1000), None);
+
+        dict.decref(&ops);
+    }
+
+    #[test]
+    fn set_from_iter() {
+        let ops = test_roc_ops();
+        let mut set = RocSet::from_iter([3u32, 1, 3, 2], &ops);
+
+        assert_eq!(set.len(), 3);
+        assert!(set.insert(4, &ops));
+        assert!(!set.insert(1, &ops));
+        assert!(set.contains(&4));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![3, 1, 2, 4]);
+
+        set.decref(&ops);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{RocDec, RocList, RocRefcounted, RocStr, I128, U128};
+
+const WYP0: u64 = 0xa0761d6478bd642f;
+const WYP1: u64 = 0xe7037ed1a0b428db;
+const WYP2: u64 = 0x8ebc6af09c88c6e3;
+const WYP3: u64 = 0x589965cc75374cc3;
+
+/// The hasher Roc's `Dict` hashes its keys with (`LowLevelHasher` in Dict.roc), which is based
+/// on wyhash. Given the same seed and the same calls, it completes to the same hash as in Roc.
+#[derive(Clone, Copy, Debug)]
+pub struct RocHasher {
+    initialized_seed: u64,
+    state: u64,
+}
+
+impl RocHasher {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            initialized_seed: wymix(seed ^ WYP0, WYP1) ^ seed,
+            state: seed,
+        }
+    }
+
+    pub fn complete(&self) -> u64 {
+        self.state
+    }
+
+    pub fn add_u8(&mut self, n: u8) {
+        let p0 = n as u64;
+        let a = (p0 << 16) | (p0 << 8) | p0;
+
+        self.combine(a, 0, self.initialized_seed, 1);
+    }
+
+    pub fn add_u16(&mut self, n: u16) {
+        let p0 = (n & 0xFF) as u64;
+        let p1 = (n >> 8) as u64;
+        let a = (p0 << 16) | (p1 << 8) | p1;
+
+        self.combine(a, 0, self.initialized_seed, 2);
+    }
+
+    pub fn add_u32(&mut self, n: u32) {
+        let p0 = n as u64;
+        let a = (p0 << 32) | p0;
+
+        self.combine(a, a, self.initialized_seed, 4);
+    }
+
+    pub fn add_u64(&mut self, n: u64) {
+        let p0 = n & 0xFFFF_FFFF;
+        let p1 = n >> 32;
+        let a = (p0 << 32) | p1;
+        let b = (p1 << 32) | p0;
+
+        self.combine(a, b, self.initialized_seed, 8);
+    }
+
+    pub fn add_u128(&mut self, n: u128) {
+        let lower = n as u64;
+        let upper = (n >> 64) as u64;
+        let p0 = lower & 0xFFFF_FFFF;
+        let p1 = lower >> 32;
+        let p2 = upper & 0xFFFF_FFFF;
+        let p3 = upper >> 32;
+        let a = (p0 << 32) | p2;
+        let b = (p3 << 32) | p1;
+
+        self.combine(a, b, self.initialized_seed, 16);
+    }
+
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        let length = bytes.len();
+        let (a, b, seed) = if length <= 16 {
+            if length >= 4 {
+                let x = (length >> 3) << 2;
+                let a = (wyr4(bytes, 0) << 32) | wyr4(bytes, x);
+                let b = (wyr4(bytes, length - 4) << 32) | wyr4(bytes, length - 4 - x);
+
+                (a, b, self.initialized_seed)
+            } else if length > 0 {
+                (wyr3(bytes, 0, length), 0, self.initialized_seed)
+            } else {
+                (0, 0, self.initialized_seed)
+            }
+        } else if length <= 48 {
+            hash_bytes_helper16(self.initialized_seed, bytes, 0, length)
+        } else {
+            let seed = self.initialized_seed;
+
+            hash_bytes_helper48(seed, seed, seed, bytes, 0, length)
+        };
+
+        self.combine(a, b, seed, length as u64);
+    }
+
+    fn combine(&mut self, a: u64, b: u64, seed: u64, length: u64) {
+        let (lower, upper) = wymum(a ^ WYP1, b ^ seed);
+        let hash = wymix(lower ^ WYP0 ^ length, upper ^ WYP1);
+
+        self.state = wymix(self.state, hash);
+    }
+}
+
+fn hash_bytes_helper48(
+    mut seed: u64,
+    mut see1: u64,
+    mut see2: u64,
+    bytes: &[u8],
+    mut index: usize,
+    mut remaining: usize,
+) -> (u64, u64, u64) {
+    loop {
+        seed = wymix(wyr8(bytes, index) ^ WYP1, wyr8(bytes, index + 8) ^ seed);
+        see1 = wymix(
+            wyr8(bytes, index + 16) ^ WYP2,
+            wyr8(bytes, index + 24) ^ see1,
+        );
+        see2 = wymix(
+            wyr8(bytes, index + 32) ^ WYP3,
+            wyr8(bytes, index + 40) ^ see2,
+        );
+        remaining -= 48;
+        index += 48;
+
+        if remaining <= 48 {
+            break;
+        }
+    }
+
+    let seed = see2 ^ see1 ^ seed;
+
+    if remaining > 16 {
+        hash_bytes_helper16(seed, bytes, index, remaining)
+    } else {
+        (
+            wyr8(bytes, index + remaining - 16),
+            wyr8(bytes, index + remaining - 8),
+            seed,
+        )
+    }
+}
+
+fn hash_bytes_helper16(
+    mut seed: u64,
+    bytes: &[u8],
+    mut index: usize,
+    mut remaining: usize,
+) -> (u64, u64, u64) {
+    loop {
+        seed = wymix(wyr8(bytes, index) ^ WYP1, wyr8(bytes, index + 8) ^ seed);
+        remaining -= 16;
+        index += 16;
+
+        if remaining <= 16 {
+            return (
+                wyr8(bytes, index + remaining - 16),
+                wyr8(bytes, index + remaining - 8),
+                seed,
+            );
+        }
+    }
+}
+
+fn wymix(a: u64, b: u64) -> u64 {
+    let (lower, upper) = wymum(a, b);
+
+    lower ^ upper
+}
+
+fn wymum(a: u64, b: u64) -> (u64, u64) {
+    let r = (a as u128).wrapping_mul(b as u128);
+
+    (r as u64, (r >> 64) as u64)
+}
+
+fn wyr8(bytes: &[u8], index: usize) -> u64 {
+    u64::from_le_bytes(bytes[index..index + 8].try_into().unwrap())
+}
+
+fn wyr4(bytes: &[u8], index: usize) -> u64 {
+    u32::from_le_bytes(bytes[index..index + 4].try_into().unwrap()) as u64
+}
+
+/// Reads up to 3 bytes, the way wyhash does for short inputs.
+fn wyr3(bytes: &[u8], index: usize, k: usize) -> u64 {
+    let p1 = bytes[index] as u64;
+    let p2 = bytes[index + (k >> 1)] as u64;
+    let p3 = bytes[index + k - 1] as u64;
+
+    (p1 << 16) | (p2 << 8) | p3
+}
+
+/// A value that can be hashed the same way Roc's `Hash` ability hashes the matching Roc type,
+/// so it can be used to look up a `RocDict` or `RocSet` that Roc built.
+pub trait RocHash {
+    fn roc_hash(&self, hasher: &mut RocHasher);
+}
+
+macro_rules! roc_hash_int_impl {
+    ( $( $T:ty => $add:ident as $U:ty ),+ ) => {
+        $(
+            impl RocHash for $T {
+                fn roc_hash(&self, hasher: &mut RocHasher) {
+                    hasher.$add(*self as $U);
+                }
+            }
+        )+
+    };
+}
+
+roc_hash_int_impl!(
+    u8 => add_u8 as u8,
+    u16 => add_u16 as u16,
+    u32 => add_u32 as u32,
+    u64 => add_u64 as u64,
+    u128 => add_u128 as u128,
+    i8 => add_u8 as u8,
+    i16 => add_u16 as u16,
+    i32 => add_u32 as u32,
+    i64 => add_u64 as u64,
+    i128 => add_u128 as u128
+);
+
+impl RocHash for U128 {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(u128::from(*self));
+    }
+}
+
+impl RocHash for I128 {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(i128::from(*self) as u128);
+    }
+}
+
+impl RocHash for bool {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u8(*self as u8);
+    }
+}
+
+impl RocHash for RocDec {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(self.as_i128() as u128);
+    }
+}
+
+/// Like `{}` in Roc, which has nothing to hash.
+impl RocHash for () {
+    fn roc_hash(&self, _hasher: &mut RocHasher) {}
+}
+
+impl RocHash for RocStr {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_bytes(self.as_str().as_bytes());
+    }
+}
+
+/// Hashes each element in turn, like `Hash.hash_list`. The length isn't hashed.
+impl<T> RocHash for RocList<T>
+where
+    T: RocHash + RocRefcounted,
+{
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        for elem in self.iter() {
+            elem.roc_hash(hasher);
+        }
+    }
+}
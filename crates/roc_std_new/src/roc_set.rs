@@ -0,0 +1,227 @@
+use core::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+};
+
+use crate::roc_ops::RocOps;
+use crate::{DictSeed, RocDict, RocDictIter, RocHash, RocRefcounted};
+
+#[cfg(feature = "serde")]
+use serde::{
+    ser::{SerializeSeq, Serializer},
+    Serialize,
+};
+
+/// A Roc `Set`, which is a `Dict` whose values are all `{}`, hashed with the seed from `S`.
+///
+/// Like `RocList`, this does NOT store a pointer to RocOps, so you must call `decref`
+/// explicitly before it goes out of scope.
+#[repr(transparent)]
+pub struct RocSet<T, S>(RocDict<T, (), S>)
+where
+    T: RocRefcounted;
+
+impl<T, S> RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    pub fn empty() -> Self {
+        Self(RocDict::empty())
+    }
+
+    /// Create an empty RocSet with enough space preallocated to store
+    /// the requested number of elements.
+    pub fn with_capacity(num_elems: usize, roc_ops: &RocOps) -> Self {
+        Self(RocDict::with_capacity(num_elems, roc_ops))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The elements in the order they were first inserted, same as `Set.to_list`.
+    pub fn iter(&self) -> RocSetIter<'_, T> {
+        RocSetIter {
+            entries: self.0.iter(),
+        }
+    }
+
+    /// Decrement the reference count and deallocate if needed.
+    ///
+    /// This must be called explicitly since RocSet does not store the RocOps
+    /// pointer and cannot deallocate in Drop.
+    pub fn decref(&mut self, roc_ops: &RocOps) {
+        self.0.decref(roc_ops);
+    }
+}
+
+impl<T, S> RocSet<T, S>
+where
+    T: RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    pub fn contains(&self, elem: &T) -> bool {
+        self.0.contains_key(elem)
+    }
+}
+
+impl<T, S> RocSet<T, S>
+where
+    T: Clone + RocHash + PartialEq + RocRefcounted,
+    S: DictSeed,
+{
+    /// Build a set from the given elements, leaving out duplicates.
+    pub fn from_iter<I>(elems: I, roc_ops: &RocOps) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self(RocDict::from_iter(
+            elems.into_iter().map(|elem| (elem, ())),
+            roc_ops,
+        ))
+    }
+
+    /// Returns whether the element was newly inserted.
+    pub fn insert(&mut self, elem: T, roc_ops: &RocOps) -> bool {
+        self.0.insert(elem, (), roc_ops).is_none()
+    }
+}
+
+impl<T, S> Default for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T, S> Clone for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, S> RocRefcounted for RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    fn inc(&mut self) {
+        self.0.inc();
+    }
+
+    fn dec(&mut self) {
+        self.0.dec();
+    }
+
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+/// Sets are equal when they have the same elements, regardless of order, like `Set.is_eq`.
+impl<T, S> PartialEq for RocSet<T, S>
+where
+    T: PartialOrd + RocRefcounted,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T, S> Eq for RocSet<T, S> where T: Ord + RocRefcounted {}
+
+impl<T, S> PartialOrd for RocSet<T, S>
+where
+    T: PartialOrd + RocRefcounted,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T, S> Ord for RocSet<T, S>
+where
+    T: Ord + RocRefcounted,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T, S> Hash for RocSet<T, S>
+where
+    T: Ord + Hash + RocRefcounted,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T, S> Debug for RocSet<T, S>
+where
+    T: Debug + RocRefcounted,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a RocSet<T, S>
+where
+    T: RocRefcounted,
+{
+    type Item = &'a T;
+    type IntoIter = RocSetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct RocSetIter<'a, T> {
+    entries: RocDictIter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for RocSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(elem, ())| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for RocSetIter<'_, T> {}
+
+#[cfg(feature = "serde")]
+impl<T, S> Serialize for RocSet<T, S>
+where
+    T: Serialize + RocRefcounted,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for elem in self {
+            seq.serialize_element(elem)?;
+        }
+        seq.end()
+    }
+}
+
+// Note: Deserialize for RocSet is not implemented in the new ABI because
+// deserialization requires allocation, which needs RocOps.
+// Users should deserialize to a Vec and then convert using RocSet::from_iter.
@@ -5,6 +5,7 @@ use core::{
     hash::{Hash, Hasher},
     marker::PhantomData,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::soa_slice::Slice;
 
@@ -13,6 +14,7 @@ use crate::soa_slice::Slice;
 ///
 /// Unlike a Rust pointer, this is a u32 offset
 /// rather than usize.
+#[derive(Serialize, Deserialize)]
 pub struct Index<T> {
     pub index: u32,
     pub _marker: PhantomData<T>,
@@ -0,0 +1,330 @@
+use crate::wasi::Errno;
+use crate::WasiDispatcher;
+use roc_wasm_module::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PTR_PATH: usize = 0x100;
+const PTR_OUT: usize = 0x200;
+const PTR_IOVS: usize = 0x300;
+const PTR_BUF: usize = 0x400;
+
+const OFLAGS_CREAT: i32 = 1;
+const OFLAGS_DIRECTORY: i32 = 2;
+const RIGHTS_READ_WRITE: i64 = (1 << 1) | (1 << 6);
+
+/// A directory on the host, which is deleted at the end of the test
+struct HostDir(PathBuf);
+
+impl HostDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("roc_wasm_interp_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        HostDir(path)
+    }
+}
+
+impl Drop for HostDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn call(wasi: &mut WasiDispatcher, memory: &mut [u8], name: &str, arguments: &[Value]) -> i32 {
+    match wasi.dispatch(name, arguments, memory) {
+        Some(Value::I32(errno)) => errno,
+        other => panic!("{name} returned {other:?}"),
+    }
+}
+
+fn read_u32(memory: &[u8], addr: usize) -> u32 {
+    u32::from_le_bytes(memory[addr..][..4].try_into().unwrap())
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    u64::from_le_bytes(memory[addr..][..8].try_into().unwrap())
+}
+
+/// Writes `path` into memory and returns the arguments that point to it
+fn path_args(memory: &mut [u8], path: &str) -> [Value; 2] {
+    memory[PTR_PATH..][..path.len()].copy_from_slice(path.as_bytes());
+    [Value::I32(PTR_PATH as i32), Value::I32(path.len() as i32)]
+}
+
+fn path_open(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    dir_fd: i32,
+    path: &str,
+    oflags: i32,
+) -> i32 {
+    let [ptr_path, path_len] = path_args(memory, path);
+    call(
+        wasi,
+        memory,
+        "path_open",
+        &[
+            Value::I32(dir_fd),
+            Value::I32(0),
+            ptr_path,
+            path_len,
+            Value::I32(oflags),
+            Value::I64(RIGHTS_READ_WRITE),
+            Value::I64(RIGHTS_READ_WRITE),
+            Value::I32(0),
+            Value::I32(PTR_OUT as i32),
+        ],
+    )
+}
+
+/// Sets up a single iovec pointing at `len` bytes of `PTR_BUF`
+fn iovs_args(memory: &mut [u8], len: usize) -> [Value; 2] {
+    memory[PTR_IOVS..][..4].copy_from_slice(&(PTR_BUF as u32).to_le_bytes());
+    memory[PTR_IOVS + 4..][..4].copy_from_slice(&(len as u32).to_le_bytes());
+    [Value::I32(PTR_IOVS as i32), Value::I32(1)]
+}
+
+#[test]
+fn test_preopened_dir() {
+    let host_dir = HostDir::new("preopened_dir");
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let fd = wasi.preopen_dir(&host_dir.0, "/data").unwrap();
+    assert_eq!(fd, 3);
+
+    let args = [Value::I32(3), Value::I32(PTR_OUT as i32)];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_prestat_get", &args), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT + 4), 5);
+
+    let args = [Value::I32(3), Value::I32(PTR_BUF as i32), Value::I32(5)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_prestat_dir_name", &args),
+        0
+    );
+    assert_eq!(&memory[PTR_BUF..][..5], b"/data");
+
+    // WASI libc stops looking for preopened directories at the first `Badf`
+    for fd in [0, 4] {
+        let args = [Value::I32(fd), Value::I32(PTR_OUT as i32)];
+        assert_eq!(
+            call(&mut wasi, &mut memory, "fd_prestat_get", &args),
+            Errno::Badf as i32
+        );
+    }
+}
+
+#[test]
+fn test_split_dir_mapping() {
+    let split = WasiDispatcher::split_dir_mapping;
+
+    assert_eq!(split("./data:/data"), ("./data", "/data"));
+    assert_eq!(split("./data"), ("./data", "./data"));
+    assert_eq!(split(r"C:\data:/data"), (r"C:\data", "/data"));
+    assert_eq!(split(r"C:\data"), (r"C:\data", r"C:\data"));
+}
+
+#[test]
+fn test_file_read_write_seek_close() {
+    let host_dir = HostDir::new("file_read_write");
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+    wasi.preopen_dir(&host_dir.0, ".").unwrap();
+
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, 3, "notes.txt", OFLAGS_CREAT),
+        0
+    );
+    let fd = read_u32(&memory, PTR_OUT) as i32;
+    assert_eq!(fd, 4);
+
+    memory[PTR_BUF..][..5].copy_from_slice(b"hello");
+    let [ptr_iovs, iovs_len] = iovs_args(&mut memory, 5);
+    let args = [
+        Value::I32(fd),
+        ptr_iovs,
+        iovs_len,
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_write", &args), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT), 5);
+
+    let args = [
+        Value::I32(fd),
+        Value::I64(1),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_seek", &args), 0);
+    assert_eq!(read_u64(&memory, PTR_OUT), 1);
+
+    memory[PTR_BUF..][..5].fill(0);
+    let [ptr_iovs, iovs_len] = iovs_args(&mut memory, 8);
+    let args = [
+        Value::I32(fd),
+        ptr_iovs,
+        iovs_len,
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_read", &args), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT), 4);
+    assert_eq!(&memory[PTR_BUF..][..4], b"ello");
+
+    let args = [Value::I32(fd), Value::I32(PTR_OUT as i32)];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_filestat_get", &args), 0);
+    assert_eq!(memory[PTR_OUT + 16], 4); // regular file
+    assert_eq!(read_u64(&memory, PTR_OUT + 32), 5);
+
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &[Value::I32(fd)]),
+        0
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &[Value::I32(fd)]),
+        Errno::Badf as i32
+    );
+    assert_eq!(fs::read(host_dir.0.join("notes.txt")).unwrap(), b"hello");
+
+    // The closed file descriptor gets reused
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, 3, "other.txt", OFLAGS_CREAT),
+        0
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 4);
+}
+
+#[test]
+fn test_sandbox() {
+    let host_dir = HostDir::new("sandbox");
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    fs::create_dir(host_dir.0.join("inner")).unwrap();
+    wasi.preopen_dir(host_dir.0.join("inner"), "/inner")
+        .unwrap();
+
+    for path in ["../escaped.txt", "/etc/passwd", "sub/../../escaped.txt"] {
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, 3, path, OFLAGS_CREAT),
+            Errno::Notcapable as i32,
+            "{path}"
+        );
+    }
+    assert!(!host_dir.0.join("escaped.txt").exists());
+
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, 3, "./a/../b.txt", OFLAGS_CREAT),
+        0
+    );
+    assert!(host_dir.0.join("inner/b.txt").exists());
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&host_dir.0, host_dir.0.join("inner/link")).unwrap();
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, 3, "link/escaped.txt", OFLAGS_CREAT),
+            Errno::Notcapable as i32
+        );
+        assert!(!host_dir.0.join("escaped.txt").exists());
+    }
+}
+
+#[test]
+fn test_directories() {
+    let host_dir = HostDir::new("directories");
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+    wasi.preopen_dir(&host_dir.0, "/").unwrap();
+
+    let [ptr_path, path_len] = path_args(&mut memory, "sub");
+    let args = [Value::I32(3), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_create_directory", &args),
+        0
+    );
+    assert!(host_dir.0.join("sub").is_dir());
+    fs::write(host_dir.0.join("sub/b.txt"), "b").unwrap();
+    fs::write(host_dir.0.join("sub/a.txt"), "a").unwrap();
+
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, 3, "sub", OFLAGS_DIRECTORY),
+        0
+    );
+    let sub_fd = read_u32(&memory, PTR_OUT) as i32;
+
+    let args = [
+        Value::I32(sub_fd),
+        Value::I32(PTR_BUF as i32),
+        Value::I32(0x100),
+        Value::I64(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, &mut memory, "fd_readdir", &args), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT), 2 * (24 + 5));
+    assert_eq!(read_u64(&memory, PTR_BUF), 1); // cookie of the next entry
+    assert_eq!(read_u32(&memory, PTR_BUF + 16), 5);
+    assert_eq!(memory[PTR_BUF + 20], 4); // regular file
+    assert_eq!(&memory[PTR_BUF + 24..][..5], b"a.txt");
+    assert_eq!(&memory[PTR_BUF + 2 * 24 + 5..][..5], b"b.txt");
+
+    let [ptr_path, path_len] = path_args(&mut memory, "sub");
+    let args = [Value::I32(3), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_remove_directory", &args),
+        Errno::Notempty as i32
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_unlink_file", &args),
+        Errno::Isdir as i32
+    );
+
+    for name in ["a.txt", "b.txt"] {
+        let [ptr_path, path_len] = path_args(&mut memory, name);
+        let args = [Value::I32(sub_fd), ptr_path, path_len];
+        assert_eq!(call(&mut wasi, &mut memory, "path_unlink_file", &args), 0);
+    }
+
+    let [ptr_path, path_len] = path_args(&mut memory, "sub");
+    let args = [Value::I32(3), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_remove_directory", &args),
+        0
+    );
+    assert!(!host_dir.0.join("sub").exists());
+}
+
+#[test]
+fn test_clocks() {
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let time_get = |wasi: &mut WasiDispatcher, memory: &mut [u8], clock_id| {
+        let args = [
+            Value::I32(clock_id),
+            Value::I64(1),
+            Value::I32(PTR_OUT as i32),
+        ];
+        assert_eq!(call(wasi, memory, "clock_time_get", &args), 0);
+        read_u64(memory, PTR_OUT)
+    };
+
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let realtime = time_get(&mut wasi, &mut memory, 0);
+    let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert!(before.as_nanos() as u64 <= realtime && realtime <= after.as_nanos() as u64);
+
+    let first = time_get(&mut wasi, &mut memory, 1);
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let second = time_get(&mut wasi, &mut memory, 1);
+    assert!(second >= first + 2_000_000);
+
+    let args = [Value::I32(99), Value::I64(1), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "clock_time_get", &args),
+        Errno::Inval as i32
+    );
+}
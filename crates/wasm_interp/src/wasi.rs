@@ -1,14 +1,59 @@
 use rand::prelude::*;
 use roc_wasm_module::Value;
-use std::io::{self, Read, StderrLock, StdoutLock, Write};
+use std::fs::{self, File, FileTimes, Metadata, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, StderrLock, StdoutLock, Write};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
+const FILETYPE_UNKNOWN: u8 = 0;
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+const CLOCK_REALTIME: u32 = 0;
+const CLOCK_MONOTONIC: u32 = 1;
+const CLOCK_PROCESS_CPUTIME_ID: u32 = 2;
+const CLOCK_THREAD_CPUTIME_ID: u32 = 3;
+
+const OFLAGS_CREAT: u16 = 1 << 0;
+const OFLAGS_DIRECTORY: u16 = 1 << 1;
+const OFLAGS_EXCL: u16 = 1 << 2;
+const OFLAGS_TRUNC: u16 = 1 << 3;
+
+const FDFLAGS_APPEND: u16 = 1 << 0;
+
+const LOOKUPFLAGS_SYMLINK_FOLLOW: u32 = 1 << 0;
+
+const FSTFLAGS_ATIM: u16 = 1 << 0;
+const FSTFLAGS_ATIM_NOW: u16 = 1 << 1;
+const FSTFLAGS_MTIM: u16 = 1 << 2;
+const FSTFLAGS_MTIM_NOW: u16 = 1 << 3;
+
+const RIGHTS_FD_READ: u64 = 1 << 1;
+const RIGHTS_FD_SEEK: u64 = 1 << 2;
+const RIGHTS_FD_TELL: u64 = 1 << 5;
+const RIGHTS_FD_WRITE: u64 = 1 << 6;
+const RIGHTS_ALL: u64 = (1 << 29) - 1;
+
+const EVENTTYPE_CLOCK: u8 = 0;
+const SUBCLOCKFLAGS_SUBSCRIPTION_CLOCK_ABSTIME: u16 = 1 << 0;
+
+const SIZE_OF_FILESTAT: usize = 64;
+const SIZE_OF_DIRENT: usize = 24;
+const SIZE_OF_SUBSCRIPTION: usize = 48;
+const SIZE_OF_EVENT: usize = 32;
+
 pub struct WasiDispatcher<'a> {
     pub args: &'a [&'a [u8]],
     pub rng: ThreadRng,
     pub files: Vec<WasiFile>,
+    /// The zero point of the monotonic clocks
+    clock_start: Instant,
 }
 
 impl Default for WasiDispatcher<'_> {
@@ -22,12 +67,28 @@ pub enum WasiFile {
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    /// A file on the host, opened by the program with `path_open`
+    HostFile(File),
+    /// A directory on the host, either preopened or opened by the program with `path_open`
+    Directory(WasiDirectory),
+    /// A file descriptor that the program closed. `path_open` reuses these.
+    Closed,
+}
+
+/// The program can only reach host paths inside the preopened directory it started from,
+/// so every directory it opens keeps track of that sandbox.
+pub struct WasiDirectory {
+    /// The name the program sees, for preopened directories only
+    pub preopen_name: Option<String>,
+    pub host_path: PathBuf,
+    pub sandbox: PathBuf,
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    HostFile(&'a mut File),
 }
 
 /// Implementation of WASI syscalls
@@ -44,6 +105,40 @@ impl<'a> WasiDispatcher<'a> {
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
             ],
+            clock_start: Instant::now(),
+        }
+    }
+
+    /// Give the program access to the `host` directory, which it will see as `guest`.
+    /// Returns the new file descriptor.
+    pub fn preopen_dir(&mut self, host: impl AsRef<Path>, guest: &str) -> io::Result<usize> {
+        let host_path = fs::canonicalize(host)?;
+
+        if !host_path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", host_path.display()),
+            ));
+        }
+
+        // WASI libc looks for preopened directories starting from fd 3 until it finds a gap,
+        // so these always go at the end rather than in a closed slot.
+        self.files.push(WasiFile::Directory(WasiDirectory {
+            preopen_name: Some(guest.to_string()),
+            sandbox: host_path.clone(),
+            host_path,
+        }));
+
+        Ok(self.files.len() - 1)
+    }
+
+    /// Splits a `HOST:GUEST` directory mapping at the last colon, so that Windows paths like
+    /// `C:\data:/data` work. A mapping without a guest name, like `./data` or `C:\data`,
+    /// uses the host name for both.
+    pub fn split_dir_mapping(mapping: &str) -> (&str, &str) {
+        match mapping.rsplit_once(':') {
+            Some((host, guest)) if !is_drive_letter(host) => (host, guest),
+            _ => (mapping, mapping),
         }
     }
 
@@ -107,12 +202,33 @@ impl<'a> WasiDispatcher<'a> {
 
                 success_code
             }
-            "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
-            "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "clock_res_get" => {
+                let clock_id = arg_u32(arguments, 0);
+                let ptr_resolution = arg_usize(arguments, 1);
+
+                // All the clocks come from the host in nanoseconds
+                errno(
+                    self.clock_now(clock_id)
+                        .map(|_| write_u64(memory, ptr_resolution, 1)),
+                )
+            }
+            "clock_time_get" => {
+                let clock_id = arg_u32(arguments, 0);
+                // arguments[1] is the precision the program wants, and we always give our best
+                let ptr_time = arg_usize(arguments, 2);
+
+                errno(
+                    self.clock_now(clock_id)
+                        .map(|time| write_u64(memory, ptr_time, time.as_nanos() as u64)),
+                )
+            }
+            "fd_advise" => {
+                // This is only a hint, so it's fine to ignore it
+                errno(self.check_fd(arg_usize(arguments, 0)))
+            }
+            "fd_allocate" => errno(self.fd_allocate(arguments)),
+            "fd_close" => errno(self.fd_close(arg_usize(arguments, 0))),
+            "fd_datasync" => errno(self.fd_sync(arg_usize(arguments, 0), true)),
             "fd_fdstat_get" => {
                 // (i32, i32) -> i32
 
@@ -132,24 +248,55 @@ impl<'a> WasiDispatcher<'a> {
                         //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
                         //     .fs_rights_base = 0
 
-                        const WASI_FILETYPE_CHARACTER_DEVICE: u8 = 2;
-                        memory[stat_mut_ptr] = WASI_FILETYPE_CHARACTER_DEVICE;
+                        memory[stat_mut_ptr] = FILETYPE_CHARACTER_DEVICE;
 
                         for b in memory[stat_mut_ptr + 1..stat_mut_ptr + 24].iter_mut() {
                             *b = 0;
                         }
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
+                    _ => {
+                        use WasiFile::*;
+
+                        let (filetype, rights) = match self.files.get(fd) {
+                            Some(HostFile(_)) => (FILETYPE_REGULAR_FILE, RIGHTS_ALL),
+                            Some(Directory(_)) => (FILETYPE_DIRECTORY, RIGHTS_ALL),
+                            // These have no position to seek to
+                            Some(ReadOnly(_) | WriteOnly(_) | ReadWrite(_)) => (
+                                FILETYPE_REGULAR_FILE,
+                                RIGHTS_ALL & !(RIGHTS_FD_SEEK | RIGHTS_FD_TELL),
+                            ),
+                            Some(HostSystemFile) => (
+                                FILETYPE_CHARACTER_DEVICE,
+                                RIGHTS_ALL & !(RIGHTS_FD_SEEK | RIGHTS_FD_TELL),
+                            ),
+                            Some(Closed) | None => return errno(Err(Errno::Badf)),
+                        };
+
+                        // We don't restrict anything beyond what the host does, so both the
+                        // rights of the file and of files opened through it are everything.
+                        memory[stat_mut_ptr..][..24].fill(0);
+                        memory[stat_mut_ptr] = filetype;
+                        write_u64(memory, stat_mut_ptr + 8, rights);
+                        write_u64(memory, stat_mut_ptr + 16, rights);
+                    }
                 }
 
                 success_code
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_fdstat_set_flags" => errno(Err(Errno::Notsup)),
+            "fd_fdstat_set_rights" => {
+                // Rights are never enforced, so there is nothing to drop
+                errno(self.check_fd(arg_usize(arguments, 0)))
+            }
+            "fd_filestat_get" => errno(self.fd_filestat_get(arguments, memory)),
+            "fd_filestat_set_size" => {
+                let fd = arg_usize(arguments, 0);
+                let size = arg_u64(arguments, 1);
+
+                errno(self.host_file(fd).and_then(|file| Ok(file.set_len(size)?)))
+            }
+            "fd_filestat_set_times" => errno(self.fd_filestat_set_times(arguments)),
+            "fd_pread" => errno(self.fd_pread(arguments, memory)),
             "fd_prestat_get" => {
                 // The preopened file descriptor to query
                 let fd = arguments[0].expect_i32().unwrap() as usize;
@@ -157,19 +304,32 @@ impl<'a> WasiDispatcher<'a> {
                 //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
                 //  preopen name length: 4 bytes
                 let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {fd} does not exist");
-                    Some(Value::I32(Errno::Badf as i32))
+
+                // WASI libc calls this for fd 3, 4, 5... until it gets `Badf`
+                match self.preopen_name(fd) {
+                    Some(name) => {
+                        write_u32(memory, ptr_buf, 0);
+                        write_u32(memory, ptr_buf + 4, name.len() as u32);
+                        success_code
+                    }
+                    None => Some(Value::I32(Errno::Badf as i32)),
                 }
             }
             "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
+                let fd = arg_usize(arguments, 0);
+                let ptr_path = arg_usize(arguments, 1);
+                let path_len = arg_usize(arguments, 2);
+
+                match self.preopen_name(fd) {
+                    Some(name) => {
+                        let len = name.len().min(path_len);
+                        memory[ptr_path..][..len].copy_from_slice(&name.as_bytes()[..len]);
+                        success_code
+                    }
+                    None => Some(Value::I32(Errno::Badf as i32)),
+                }
             }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_pwrite" => errno(self.fd_pwrite(arguments, memory)),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -178,57 +338,43 @@ impl<'a> WasiDispatcher<'a> {
                 // Array of IO vectors
                 let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
                 // Length of array
-                let iovs_len = arguments[2].expect_i32().unwrap();
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
                 // Out param: number of bytes read
                 let ptr_nread = arguments[3].expect_i32().unwrap() as usize;
 
-                // https://man7.org/linux/man-pages/man2/readv.2.html
-                // struct iovec {
-                //     void  *iov_base;    /* Starting address */
-                //     size_t iov_len;     /* Number of bytes to transfer */
-                // };
-
-                let mut n_read: usize = 0;
-                match self.files.get(fd) {
+                let read_result = match self.files.get_mut(fd) {
                     Some(ReadOnly(content) | ReadWrite(content)) => {
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            let remaining = content.len() - n_read;
-                            let len = remaining.min(iov_len);
-                            if len == 0 {
-                                break;
-                            }
-                            memory[iov_base..][..len].copy_from_slice(&content[n_read..][..len]);
-                            n_read += len;
-                        }
+                        read_iovecs(&mut content.as_slice(), memory, ptr_iovs, iovs_len)
                     }
                     Some(HostSystemFile) if fd == 0 => {
-                        let mut stdin = io::stdin();
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            match stdin.read(&mut memory[iov_base..][..iov_len]) {
-                                Ok(n) => {
-                                    n_read += n;
-                                }
-                                Err(_) => {
-                                    break;
-                                }
-                            }
-                        }
+                        read_iovecs(&mut io::stdin(), memory, ptr_iovs, iovs_len)
                     }
+                    Some(HostFile(file)) => read_iovecs(file, memory, ptr_iovs, iovs_len),
+                    Some(Directory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
-                memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
-                success_code
+                match read_result {
+                    Ok(n_read) => {
+                        memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
+                        success_code
+                    }
+                    Err(e) => errno(Err(e.into())),
+                }
+            }
+            "fd_readdir" => errno(self.fd_readdir(arguments, memory)),
+            "fd_renumber" => errno(self.fd_renumber(arguments)),
+            "fd_seek" => errno(self.fd_seek(arguments, memory)),
+            "fd_sync" => errno(self.fd_sync(arg_usize(arguments, 0), false)),
+            "fd_tell" => {
+                let fd = arg_usize(arguments, 0);
+                let ptr_offset = arg_usize(arguments, 1);
+
+                errno(self.host_file(fd).and_then(|file| {
+                    write_u64(memory, ptr_offset, file.stream_position()?);
+                    Ok(())
+                }))
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_write" => {
                 use WasiFile::*;
 
@@ -252,6 +398,8 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(HostFile(file)) => WriteLock::HostFile(file),
+                    Some(Directory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -280,6 +428,7 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -295,28 +444,40 @@ impl<'a> WasiDispatcher<'a> {
                     );
                 }
 
-                match write_result {
-                    Ok(()) => success_code,
-                    Err(_) => Some(Value::I32(Errno::Io as i32)),
-                }
+                errno(write_result.map_err(Errno::from))
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
-            "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => {
+                let fd = arg_usize(arguments, 0);
+                let ptr_path = arg_usize(arguments, 1);
+                let path_len = arg_usize(arguments, 2);
+
+                errno(
+                    self.resolve_path(fd, memory, ptr_path, path_len, false)
+                        .and_then(|path| Ok(fs::create_dir(path)?)),
+                )
+            }
+            "path_filestat_get" => errno(self.path_filestat_get(arguments, memory)),
+            "path_filestat_set_times" => errno(self.path_filestat_set_times(arguments, memory)),
+            "path_link" => errno(self.path_link(arguments, memory)),
+            "path_open" => errno(self.path_open(arguments, memory)),
+            "path_readlink" => errno(self.path_readlink(arguments, memory)),
+            "path_remove_directory" => errno(self.path_remove_directory(arguments, memory)),
+            "path_rename" => errno(self.path_rename(arguments, memory)),
+            "path_symlink" => {
+                // A symlink could point anywhere on the host, which would get around the sandbox
+                errno(Err(Errno::Notsup))
+            }
+            "path_unlink_file" => errno(self.path_unlink_file(arguments, memory)),
+            "poll_oneoff" => errno(self.poll_oneoff(arguments, memory)),
             "proc_exit" => {
                 let exit_code = arguments[0].expect_i32().unwrap();
                 exit(exit_code);
             }
-            "proc_raise" => todo!("WASI {}({:?})", function_name, arguments),
-            "sched_yield" => todo!("WASI {}({:?})", function_name, arguments),
+            "proc_raise" => errno(Err(Errno::Notsup)),
+            "sched_yield" => {
+                thread::yield_now();
+                success_code
+            }
             "random_get" => {
                 // A pointer to a buffer where the random bytes will be written
                 let ptr_buf = arguments[0].expect_i32().unwrap() as usize;
@@ -327,12 +488,726 @@ impl<'a> WasiDispatcher<'a> {
                 }
                 success_code
             }
-            "sock_recv" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_send" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_shutdown" => todo!("WASI {}({:?})", function_name, arguments),
+            "sock_recv" | "sock_send" | "sock_shutdown" => errno(Err(Errno::Notsup)),
             _ => panic!("Unknown WASI function {function_name}({arguments:?})"),
         }
     }
+
+    /// The current time of a clock. The monotonic clocks start when the dispatcher is created,
+    /// and the CPU time clocks are approximated by the monotonic clock.
+    fn clock_now(&self, clock_id: u32) -> Result<Duration, Errno> {
+        match clock_id {
+            CLOCK_REALTIME => Ok(SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()),
+            CLOCK_MONOTONIC | CLOCK_PROCESS_CPUTIME_ID | CLOCK_THREAD_CPUTIME_ID => {
+                Ok(self.clock_start.elapsed())
+            }
+            _ => Err(Errno::Inval),
+        }
+    }
+
+    fn check_fd(&self, fd: usize) -> Result<(), Errno> {
+        match self.files.get(fd) {
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Use the lowest free file descriptor, like POSIX does
+    fn add_file(&mut self, file: WasiFile) -> usize {
+        match self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed))
+        {
+            Some(fd) => {
+                self.files[fd] = file;
+                fd
+            }
+            None => {
+                self.files.push(file);
+                self.files.len() - 1
+            }
+        }
+    }
+
+    fn host_file(&mut self, fd: usize) -> Result<&mut File, Errno> {
+        match self.files.get_mut(fd) {
+            Some(WasiFile::HostFile(file)) => Ok(file),
+            Some(WasiFile::Directory(_)) => Err(Errno::Isdir),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            // stdio and in-memory files have no position to seek to
+            Some(_) => Err(Errno::Spipe),
+        }
+    }
+
+    fn directory(&self, fd: usize) -> Result<&WasiDirectory, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::Directory(directory)) => Ok(directory),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Notdir),
+        }
+    }
+
+    fn preopen_name(&self, fd: usize) -> Option<&str> {
+        match self.files.get(fd) {
+            Some(WasiFile::Directory(WasiDirectory {
+                preopen_name: Some(name),
+                ..
+            })) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Find the host path for a path that the program gave relative to the directory `fd`.
+    /// Absolute paths, and paths that lead out of the sandbox with `..` or through a symlink,
+    /// are rejected. A symlink in the last component is only checked if the caller follows it.
+    fn resolve_path(
+        &self,
+        fd: usize,
+        memory: &[u8],
+        ptr_path: usize,
+        path_len: usize,
+        follow_symlinks: bool,
+    ) -> Result<PathBuf, Errno> {
+        let directory = self.directory(fd)?;
+        let path =
+            std::str::from_utf8(&memory[ptr_path..][..path_len]).map_err(|_| Errno::Ilseq)?;
+
+        let mut resolved = directory.host_path.clone();
+
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => resolved.push(name),
+                Component::CurDir => {}
+                Component::ParentDir if resolved != directory.sandbox => {
+                    resolved.pop();
+                }
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(Errno::Notcapable);
+                }
+            }
+        }
+
+        let checked = match resolved.parent() {
+            Some(parent) if !follow_symlinks && resolved != directory.sandbox => parent,
+            _ => resolved.as_path(),
+        };
+        let real_path = checked
+            .ancestors()
+            .find_map(|ancestor| fs::canonicalize(ancestor).ok());
+
+        if real_path.is_some_and(|real_path| !real_path.starts_with(&directory.sandbox)) {
+            return Err(Errno::Notcapable);
+        }
+
+        Ok(resolved)
+    }
+
+    fn path_open(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        // arguments[1] is the lookup flags. The host always follows symlinks when opening.
+        let ptr_path = arg_usize(arguments, 2);
+        let path_len = arg_usize(arguments, 3);
+        let oflags = arg_u32(arguments, 4) as u16;
+        let rights = arg_u64(arguments, 5);
+        // arguments[6] is the rights of files opened through this one, which we don't track
+        let fdflags = arg_u32(arguments, 7) as u16;
+        let ptr_fd = arg_usize(arguments, 8);
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, true)?;
+        let create = oflags & OFLAGS_CREAT != 0;
+
+        let file = if oflags & OFLAGS_DIRECTORY != 0 || (!create && path.is_dir()) {
+            if !path.is_dir() {
+                return Err(if path.exists() {
+                    Errno::Notdir
+                } else {
+                    Errno::Noent
+                });
+            }
+
+            WasiFile::Directory(WasiDirectory {
+                preopen_name: None,
+                host_path: path,
+                sandbox: self.directory(fd)?.sandbox.clone(),
+            })
+        } else {
+            if path.is_dir() {
+                return Err(Errno::Isdir);
+            }
+
+            let exclusive = oflags & OFLAGS_EXCL != 0;
+            let truncate = oflags & OFLAGS_TRUNC != 0;
+            let append = fdflags & FDFLAGS_APPEND != 0;
+            // The host needs write access to create or truncate a file
+            let write = rights & RIGHTS_FD_WRITE != 0 || create || truncate;
+            let read = rights & RIGHTS_FD_READ != 0 || !(write || append);
+
+            let file = OpenOptions::new()
+                .read(read)
+                .write(write && !append)
+                .append(append)
+                .create(create && !exclusive)
+                .create_new(create && exclusive)
+                .truncate(truncate)
+                .open(&path)?;
+
+            WasiFile::HostFile(file)
+        };
+
+        let new_fd = self.add_file(file);
+        write_u32(memory, ptr_fd, new_fd as u32);
+
+        Ok(())
+    }
+
+    fn fd_close(&mut self, fd: usize) -> Result<(), Errno> {
+        match self.files.get_mut(fd) {
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(file) => {
+                *file = WasiFile::Closed;
+                Ok(())
+            }
+        }
+    }
+
+    fn fd_renumber(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let from = arg_usize(arguments, 0);
+        let to = arg_usize(arguments, 1);
+
+        self.check_fd(from)?;
+        self.check_fd(to)?;
+
+        if from != to {
+            self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
+        }
+
+        Ok(())
+    }
+
+    fn fd_seek(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let offset = arguments[1].expect_i64().unwrap();
+        let whence = arg_u32(arguments, 2);
+        let ptr_new_offset = arg_usize(arguments, 3);
+
+        let position = match whence {
+            0 => SeekFrom::Start(u64::try_from(offset).map_err(|_| Errno::Inval)?),
+            1 => SeekFrom::Current(offset),
+            2 => SeekFrom::End(offset),
+            _ => return Err(Errno::Inval),
+        };
+
+        let new_offset = self.host_file(fd)?.seek(position)?;
+        write_u64(memory, ptr_new_offset, new_offset);
+
+        Ok(())
+    }
+
+    fn fd_pread(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_iovs = arg_usize(arguments, 1);
+        let iovs_len = arg_usize(arguments, 2);
+        let offset = arg_u64(arguments, 3);
+        let ptr_nread = arg_usize(arguments, 4);
+
+        let file = self.host_file(fd)?;
+        let original_offset = file.stream_position()?;
+
+        file.seek(SeekFrom::Start(offset))?;
+        let read_result = read_iovecs(file, memory, ptr_iovs, iovs_len);
+        file.seek(SeekFrom::Start(original_offset))?;
+
+        write_u32(memory, ptr_nread, read_result? as u32);
+
+        Ok(())
+    }
+
+    fn fd_pwrite(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_iovs = arg_usize(arguments, 1);
+        let iovs_len = arg_usize(arguments, 2);
+        let offset = arg_u64(arguments, 3);
+        let ptr_nwritten = arg_usize(arguments, 4);
+
+        let file = self.host_file(fd)?;
+        let original_offset = file.stream_position()?;
+
+        file.seek(SeekFrom::Start(offset))?;
+        let write_result = write_iovecs(file, memory, ptr_iovs, iovs_len);
+        file.seek(SeekFrom::Start(original_offset))?;
+
+        write_u32(memory, ptr_nwritten, write_result? as u32);
+
+        Ok(())
+    }
+
+    fn fd_sync(&mut self, fd: usize, data_only: bool) -> Result<(), Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) if data_only => Ok(file.sync_data()?),
+            Some(WasiFile::HostFile(file)) => Ok(file.sync_all()?),
+            _ => self.check_fd(fd),
+        }
+    }
+
+    fn fd_allocate(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let offset = arg_u64(arguments, 1);
+        let len = arg_u64(arguments, 2);
+
+        let file = self.host_file(fd)?;
+        let end = offset.checked_add(len).ok_or(Errno::Fbig)?;
+
+        if file.metadata()?.len() < end {
+            file.set_len(end)?;
+        }
+
+        Ok(())
+    }
+
+    fn fd_filestat_get(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        use WasiFile::*;
+
+        let fd = arg_usize(arguments, 0);
+        let ptr_filestat = arg_usize(arguments, 1);
+
+        let metadata = match self.files.get(fd) {
+            Some(HostFile(file)) => file.metadata()?,
+            Some(Directory(directory)) => fs::metadata(&directory.host_path)?,
+            Some(ReadOnly(content) | WriteOnly(content) | ReadWrite(content)) => {
+                memory[ptr_filestat..][..SIZE_OF_FILESTAT].fill(0);
+                memory[ptr_filestat + 16] = FILETYPE_REGULAR_FILE;
+                write_u64(memory, ptr_filestat + 24, 1);
+                write_u64(memory, ptr_filestat + 32, content.len() as u64);
+                return Ok(());
+            }
+            Some(HostSystemFile) => {
+                memory[ptr_filestat..][..SIZE_OF_FILESTAT].fill(0);
+                memory[ptr_filestat + 16] = FILETYPE_CHARACTER_DEVICE;
+                write_u64(memory, ptr_filestat + 24, 1);
+                return Ok(());
+            }
+            None | Some(Closed) => return Err(Errno::Badf),
+        };
+
+        write_filestat(memory, ptr_filestat, &metadata);
+
+        Ok(())
+    }
+
+    fn path_filestat_get(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let follow_symlinks = arg_u32(arguments, 1) & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let ptr_path = arg_usize(arguments, 2);
+        let path_len = arg_usize(arguments, 3);
+        let ptr_filestat = arg_usize(arguments, 4);
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, follow_symlinks)?;
+        let metadata = if follow_symlinks {
+            fs::metadata(path)?
+        } else {
+            fs::symlink_metadata(path)?
+        };
+
+        write_filestat(memory, ptr_filestat, &metadata);
+
+        Ok(())
+    }
+
+    fn fd_filestat_set_times(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let times = file_times(arguments, 1)?;
+
+        match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) => Ok(file.set_times(times)?),
+            Some(WasiFile::Directory(directory)) => {
+                Ok(File::open(&directory.host_path)?.set_times(times)?)
+            }
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Notsup),
+        }
+    }
+
+    fn path_filestat_set_times(&mut self, arguments: &[Value], memory: &[u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        // The host always follows symlinks when opening the file to change
+        let ptr_path = arg_usize(arguments, 2);
+        let path_len = arg_usize(arguments, 3);
+        let times = file_times(arguments, 4)?;
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, true)?;
+        File::open(path)?.set_times(times)?;
+
+        Ok(())
+    }
+
+    fn fd_readdir(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_buf = arg_usize(arguments, 1);
+        let buf_len = arg_usize(arguments, 2);
+        let cookie = arg_u64(arguments, 3);
+        let ptr_bufused = arg_usize(arguments, 4);
+
+        // `.` and `..` are left out, since `..` of a preopened directory is outside the sandbox
+        let mut entries = fs::read_dir(&self.directory(fd)?.host_path)?
+            .map(|entry| {
+                let entry = entry?;
+                let filetype = entry.file_type().map_or(FILETYPE_UNKNOWN, filetype);
+                let name = entry.file_name().to_string_lossy().into_owned();
+                Ok((name, dir_entry_ino(&entry), filetype))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        // The cookie is the index of the next entry, so the order has to be the same every time
+        entries.sort();
+
+        let mut used = 0;
+
+        for (index, (name, ino, filetype)) in entries.iter().enumerate().skip(cookie as usize) {
+            let mut dirent = [0; SIZE_OF_DIRENT];
+            dirent[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+            dirent[8..16].copy_from_slice(&ino.to_le_bytes());
+            dirent[16..20].copy_from_slice(&(name.len() as u32).to_le_bytes());
+            dirent[20] = *filetype;
+
+            // When the buffer fills up, the last entry is cut off, and WASI libc reads it
+            // again starting from its cookie.
+            for bytes in [&dirent[..], name.as_bytes()] {
+                let len = bytes.len().min(buf_len - used);
+                memory[ptr_buf + used..][..len].copy_from_slice(&bytes[..len]);
+                used += len;
+            }
+
+            if used == buf_len {
+                break;
+            }
+        }
+
+        write_u32(memory, ptr_bufused, used as u32);
+
+        Ok(())
+    }
+
+    fn path_link(&mut self, arguments: &[Value], memory: &[u8]) -> Result<(), Errno> {
+        let old_fd = arg_usize(arguments, 0);
+        let follow_symlinks = arg_u32(arguments, 1) & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let old_ptr_path = arg_usize(arguments, 2);
+        let old_path_len = arg_usize(arguments, 3);
+        let new_fd = arg_usize(arguments, 4);
+        let new_ptr_path = arg_usize(arguments, 5);
+        let new_path_len = arg_usize(arguments, 6);
+
+        let old_path =
+            self.resolve_path(old_fd, memory, old_ptr_path, old_path_len, follow_symlinks)?;
+        let new_path = self.resolve_path(new_fd, memory, new_ptr_path, new_path_len, false)?;
+        fs::hard_link(old_path, new_path)?;
+
+        Ok(())
+    }
+
+    fn path_readlink(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_path = arg_usize(arguments, 1);
+        let path_len = arg_usize(arguments, 2);
+        let ptr_buf = arg_usize(arguments, 3);
+        let buf_len = arg_usize(arguments, 4);
+        let ptr_bufused = arg_usize(arguments, 5);
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, false)?;
+        let target = fs::read_link(path)?;
+        let target = target.to_string_lossy();
+
+        let len = target.len().min(buf_len);
+        memory[ptr_buf..][..len].copy_from_slice(&target.as_bytes()[..len]);
+        write_u32(memory, ptr_bufused, len as u32);
+
+        Ok(())
+    }
+
+    fn path_remove_directory(&mut self, arguments: &[Value], memory: &[u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_path = arg_usize(arguments, 1);
+        let path_len = arg_usize(arguments, 2);
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, false)?;
+
+        if path == self.directory(fd)?.sandbox {
+            return Err(Errno::Busy);
+        }
+        if !fs::symlink_metadata(&path)?.is_dir() {
+            return Err(Errno::Notdir);
+        }
+        if fs::read_dir(&path)?.next().is_some() {
+            return Err(Errno::Notempty);
+        }
+
+        fs::remove_dir(path)?;
+
+        Ok(())
+    }
+
+    fn path_rename(&mut self, arguments: &[Value], memory: &[u8]) -> Result<(), Errno> {
+        let old_fd = arg_usize(arguments, 0);
+        let old_ptr_path = arg_usize(arguments, 1);
+        let old_path_len = arg_usize(arguments, 2);
+        let new_fd = arg_usize(arguments, 3);
+        let new_ptr_path = arg_usize(arguments, 4);
+        let new_path_len = arg_usize(arguments, 5);
+
+        let old_path = self.resolve_path(old_fd, memory, old_ptr_path, old_path_len, false)?;
+        let new_path = self.resolve_path(new_fd, memory, new_ptr_path, new_path_len, false)?;
+        fs::rename(old_path, new_path)?;
+
+        Ok(())
+    }
+
+    fn path_unlink_file(&mut self, arguments: &[Value], memory: &[u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_path = arg_usize(arguments, 1);
+        let path_len = arg_usize(arguments, 2);
+
+        let path = self.resolve_path(fd, memory, ptr_path, path_len, false)?;
+
+        if fs::symlink_metadata(&path)?.is_dir() {
+            return Err(Errno::Isdir);
+        }
+
+        fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    /// Files are always ready to read and write, so only clocks ever make the program wait.
+    fn poll_oneoff(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let ptr_subscriptions = arg_usize(arguments, 0);
+        let ptr_events = arg_usize(arguments, 1);
+        let n_subscriptions = arg_usize(arguments, 2);
+        let ptr_nevents = arg_usize(arguments, 3);
+
+        if n_subscriptions == 0 {
+            return Err(Errno::Inval);
+        }
+
+        let mut ready = Vec::new();
+        let mut clocks = Vec::new();
+
+        for index in 0..n_subscriptions {
+            let ptr_subscription = ptr_subscriptions + SIZE_OF_SUBSCRIPTION * index;
+
+            if memory[ptr_subscription + 8] == EVENTTYPE_CLOCK {
+                let clock_id = read_u32(memory, ptr_subscription + 16);
+                let timeout = Duration::from_nanos(read_u64(memory, ptr_subscription + 24));
+                let flags = read_u16(memory, ptr_subscription + 40);
+
+                let wait = if flags & SUBCLOCKFLAGS_SUBSCRIPTION_CLOCK_ABSTIME != 0 {
+                    timeout.saturating_sub(self.clock_now(clock_id)?)
+                } else {
+                    timeout
+                };
+
+                clocks.push((index, wait));
+            } else {
+                ready.push(index);
+            }
+        }
+
+        if ready.is_empty() {
+            let shortest = clocks
+                .iter()
+                .map(|(_, wait)| *wait)
+                .min()
+                .unwrap_or_default();
+            thread::sleep(shortest);
+
+            ready = clocks
+                .iter()
+                .filter(|(_, wait)| *wait == shortest)
+                .map(|(index, _)| *index)
+                .collect();
+        }
+
+        for (n_event, index) in ready.iter().enumerate() {
+            let ptr_subscription = ptr_subscriptions + SIZE_OF_SUBSCRIPTION * index;
+            let ptr_event = ptr_events + SIZE_OF_EVENT * n_event;
+
+            // An event starts with the userdata of its subscription, and has the same type
+            memory[ptr_event..][..SIZE_OF_EVENT].fill(0);
+            memory.copy_within(ptr_subscription..ptr_subscription + 8, ptr_event);
+            memory[ptr_event + 10] = memory[ptr_subscription + 8];
+        }
+
+        write_u32(memory, ptr_nevents, ready.len() as u32);
+
+        Ok(())
+    }
+}
+
+fn is_drive_letter(prefix: &str) -> bool {
+    prefix.len() == 1 && prefix.as_bytes()[0].is_ascii_alphabetic()
+}
+
+fn errno(result: Result<(), Errno>) -> Option<Value> {
+    let errno = match result {
+        Ok(()) => Errno::Success,
+        Err(errno) => errno,
+    };
+
+    Some(Value::I32(errno as i32))
+}
+
+fn arg_u32(arguments: &[Value], index: usize) -> u32 {
+    arguments[index].expect_i32().unwrap() as u32
+}
+
+fn arg_usize(arguments: &[Value], index: usize) -> usize {
+    arg_u32(arguments, index) as usize
+}
+
+fn arg_u64(arguments: &[Value], index: usize) -> u64 {
+    arguments[index].expect_i64().unwrap() as u64
+}
+
+/// Read into an array of iovecs, stopping at the first one that isn't filled, like `readv`
+fn read_iovecs(
+    reader: &mut impl Read,
+    memory: &mut [u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+) -> io::Result<usize> {
+    let mut n_read = 0;
+
+    for i in 0..iovs_len {
+        // https://man7.org/linux/man-pages/man2/readv.2.html
+        // struct iovec {
+        //     void  *iov_base;    /* Starting address */
+        //     size_t iov_len;     /* Number of bytes to transfer */
+        // };
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        let n = reader.read(&mut memory[iov_base..][..iov_len])?;
+        n_read += n;
+
+        if n < iov_len {
+            break;
+        }
+    }
+
+    Ok(n_read)
+}
+
+fn write_iovecs(
+    writer: &mut impl Write,
+    memory: &[u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+) -> io::Result<usize> {
+    let mut n_written = 0;
+
+    for i in 0..iovs_len {
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        writer.write_all(&memory[iov_base..][..iov_len])?;
+        n_written += iov_len;
+    }
+
+    Ok(n_written)
+}
+
+/// The `fst_flags` argument says which of the two timestamps before it to use
+fn file_times(arguments: &[Value], first: usize) -> Result<FileTimes, Errno> {
+    let atim = arg_u64(arguments, first);
+    let mtim = arg_u64(arguments, first + 1);
+    let fst_flags = arg_u32(arguments, first + 2) as u16;
+
+    let now = SystemTime::now();
+    let mut times = FileTimes::new();
+
+    if fst_flags & FSTFLAGS_ATIM_NOW != 0 {
+        if fst_flags & FSTFLAGS_ATIM != 0 {
+            return Err(Errno::Inval);
+        }
+        times = times.set_accessed(now);
+    } else if fst_flags & FSTFLAGS_ATIM != 0 {
+        times = times.set_accessed(UNIX_EPOCH + Duration::from_nanos(atim));
+    }
+
+    if fst_flags & FSTFLAGS_MTIM_NOW != 0 {
+        if fst_flags & FSTFLAGS_MTIM != 0 {
+            return Err(Errno::Inval);
+        }
+        times = times.set_modified(now);
+    } else if fst_flags & FSTFLAGS_MTIM != 0 {
+        times = times.set_modified(UNIX_EPOCH + Duration::from_nanos(mtim));
+    }
+
+    Ok(times)
+}
+
+fn filetype(file_type: fs::FileType) -> u8 {
+    if file_type.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if file_type.is_file() {
+        FILETYPE_REGULAR_FILE
+    } else if file_type.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_UNKNOWN
+    }
+}
+
+fn nanos_since_epoch(time: io::Result<SystemTime>) -> u64 {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+#[cfg(unix)]
+fn dir_entry_ino(entry: &fs::DirEntry) -> u64 {
+    use std::os::unix::fs::DirEntryExt;
+    entry.ino()
+}
+
+#[cfg(not(unix))]
+fn dir_entry_ino(_entry: &fs::DirEntry) -> u64 {
+    0
+}
+
+/// struct filestat {
+///     dev: u64, ino: u64, filetype: u8, nlink: u64, size: u64, atim: u64, mtim: u64, ctim: u64
+/// }
+fn write_filestat(memory: &mut [u8], ptr_filestat: usize, metadata: &Metadata) {
+    #[cfg(unix)]
+    let (dev, ino, nlink, ctim) = {
+        use std::os::unix::fs::MetadataExt;
+        let ctim = metadata.ctime() as u64 * 1_000_000_000 + metadata.ctime_nsec() as u64;
+        (metadata.dev(), metadata.ino(), metadata.nlink(), ctim)
+    };
+    #[cfg(not(unix))]
+    let (dev, ino, nlink, ctim) = (0, 0, 1, nanos_since_epoch(metadata.modified()));
+
+    memory[ptr_filestat..][..SIZE_OF_FILESTAT].fill(0);
+    write_u64(memory, ptr_filestat, dev);
+    write_u64(memory, ptr_filestat + 8, ino);
+    memory[ptr_filestat + 16] = filetype(metadata.file_type());
+    write_u64(memory, ptr_filestat + 24, nlink);
+    write_u64(memory, ptr_filestat + 32, metadata.len());
+    write_u64(
+        memory,
+        ptr_filestat + 40,
+        nanos_since_epoch(metadata.accessed()),
+    );
+    write_u64(
+        memory,
+        ptr_filestat + 48,
+        nanos_since_epoch(metadata.modified()),
+    );
+    write_u64(memory, ptr_filestat + 56, ctim);
 }
 
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
@@ -355,6 +1230,22 @@ fn write_i32(memory: &mut [u8], addr: usize, value: i32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
 
+fn read_u16(memory: &[u8], addr: usize) -> u16 {
+    let mut bytes = [0; 2];
+    bytes.copy_from_slice(&memory[addr..][..2]);
+    u16::from_le_bytes(bytes)
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&memory[addr..][..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
+}
+
 /// Error codes returned by functions.
 /// Not all of these error codes are returned by the functions provided by this
 /// API; some are used in higher-level library layers, and others are provided
@@ -517,3 +1408,18 @@ pub enum Errno {
     /// Extension: Capabilities insufficient.
     Notcapable,
 }
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Errno::Noent,
+            io::ErrorKind::PermissionDenied => Errno::Access,
+            io::ErrorKind::AlreadyExists => Errno::Exist,
+            io::ErrorKind::InvalidInput => Errno::Inval,
+            io::ErrorKind::Interrupted => Errno::Intr,
+            io::ErrorKind::WouldBlock => Errno::Again,
+            io::ErrorKind::Unsupported => Errno::Notsup,
+            _ => Errno::Io,
+        }
+    }
+}